@@ -0,0 +1,245 @@
+use std::borrow::Cow;
+
+use crate::json_item::JsonItem;
+use crate::json_type::JsonType::JsonString;
+
+/// Conversion from a [`JsonItem`] to a typed value, used by accessors generated with [`json_accessors!`](crate::json_accessors).
+/// Returns `None` if the item is non-existent or does not hold a value of the requested type.
+///
+/// Implemented for `bool`, all integer types (checked against their range), `f32`, `f64`,
+/// `&str` (string content as written in the source, escape sequences included), `Cow<str>` (decoded string content)
+/// and `&JsonItem` (the item itself, if it exists).
+pub trait FromJsonItem<'a>: Sized {
+    /// Converts `item`, returning `None` if it does not hold a value of this type
+    fn from_item(item: &'a JsonItem) -> Option<Self>;
+}
+
+impl<'a> FromJsonItem<'a> for &'a JsonItem {
+    fn from_item(item: &'a JsonItem) -> Option<Self> {
+        item.exists().then_some(item)
+    }
+}
+
+impl<'a> FromJsonItem<'a> for &'a str {
+    fn from_item(item: &'a JsonItem) -> Option<Self> {
+        if item.json_type != JsonString {
+            None
+        } else {
+            item.as_str()
+        }
+    }
+}
+
+impl<'a> FromJsonItem<'a> for Cow<'a, str> {
+    fn from_item(item: &'a JsonItem) -> Option<Self> {
+        item.as_unescaped()
+    }
+}
+
+impl<'a> FromJsonItem<'a> for bool {
+    fn from_item(item: &'a JsonItem) -> Option<Self> {
+        item.as_bool()
+    }
+}
+
+impl<'a> FromJsonItem<'a> for f64 {
+    fn from_item(item: &'a JsonItem) -> Option<Self> {
+        item.as_f64()
+    }
+}
+
+impl<'a> FromJsonItem<'a> for f32 {
+    fn from_item(item: &'a JsonItem) -> Option<Self> {
+        item.as_f64().map(|value| value as f32)
+    }
+}
+
+macro_rules! from_integer {
+    ($($integer:ty),*) => {
+        $(
+            impl<'a> FromJsonItem<'a> for $integer {
+                fn from_item(item: &'a JsonItem) -> Option<Self> {
+                    item.as_i128().and_then(|value| <$integer>::try_from(value).ok())
+                }
+            }
+        )*
+    };
+}
+
+from_integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+// Accessors falling back to a default value, or to a value computed on demand, when a conversion returns `None`
+macro_rules! accessors_or {
+    ($($accessor:ident, $or:ident, $or_else:ident, $value:ty);* $(;)?) => {
+        impl JsonItem {
+            $(
+                #[doc = concat!("Returns the value of [`", stringify!($accessor), "`](JsonItem::", stringify!($accessor), "), or `default` if it is `None`")]
+                pub fn $or(&self, default: $value) -> $value {
+                    self.$accessor().unwrap_or(default)
+                }
+
+                #[doc = concat!("Returns the value of [`", stringify!($accessor), "`](JsonItem::", stringify!($accessor), "), or the result of `f` if it is `None`")]
+                pub fn $or_else<F: FnOnce() -> $value>(&self, f: F) -> $value {
+                    self.$accessor().unwrap_or_else(f)
+                }
+            )*
+        }
+    };
+}
+
+accessors_or!(as_bool, as_bool_or, as_bool_or_else, bool; as_f64, as_f64_or, as_f64_or_else, f64; as_i128, as_i128_or, as_i128_or_else, i128);
+
+impl JsonItem {
+    /// Returns the value of [`as_str`](JsonItem::as_str), or `default` if it is `None`
+    pub fn as_str_or<'a>(&'a self, default: &'a str) -> &'a str {
+        self.as_str().unwrap_or(default)
+    }
+
+    /// Returns the value of [`as_str`](JsonItem::as_str), or the result of `f` if it is `None`
+    pub fn as_str_or_else<'a, F: FnOnce() -> &'a str>(&'a self, f: F) -> &'a str {
+        self.as_str().unwrap_or_else(f)
+    }
+
+    /// Converts the item to `T`, see [`FromJsonItem`], returning `default` if it is missing or of another type,
+    /// so that each field of a configuration can be read in one call
+    ///
+    /// ```rust
+    /// let config = jsonic::parse("{\"port\": 8080, \"workers\": -1, \"name\": \"api\"}").unwrap();
+    ///
+    /// assert_eq!(config["port"].value_or(80_u16), 8080);
+    /// assert_eq!(config["workers"].value_or(4_usize), 4);
+    /// assert_eq!(config["timeout"].value_or(30_i64), 30);
+    /// assert_eq!(config["name"].value_or("default"), "api");
+    /// assert!(!config["debug"].as_bool_or(false));
+    /// ```
+    pub fn value_or<'a, T: FromJsonItem<'a>>(&'a self, default: T) -> T {
+        T::from_item(self).unwrap_or(default)
+    }
+
+    /// Converts the item to `T`, see [`FromJsonItem`], returning the result of `f` if it is missing or of another type
+    pub fn value_or_else<'a, T: FromJsonItem<'a>, F: FnOnce() -> T>(&'a self, f: F) -> T {
+        T::from_item(self).unwrap_or_else(f)
+    }
+}
+
+/// Generates a lightweight typed view over a [`JsonItem`](crate::json_item::JsonItem), with one accessor per field.
+/// Each field is declared with its type and the JSON Pointer (RFC 6901) of its value relative to the item.
+/// Accessors are lazy: nothing is converted until an accessor is called, and `&str` fields borrow the source text.
+/// They return `None` when the value is missing or of another type. Field types must implement
+/// [`FromJsonItem`](crate::accessors::FromJsonItem).
+///
+/// The generated struct borrows the item, and has `new(item)` and `item()` methods besides accessors.
+///
+/// # Example
+///
+/// ```rust
+/// jsonic::json_accessors! {
+///     /// Tweet from the search API
+///     pub struct Tweet {
+///         id: u64 = "/id",
+///         text: &str = "/text",
+///         author: &str = "/user/screen_name",
+///         retweeted: bool = "/retweeted",
+///     }
+/// }
+///
+/// let parsed = jsonic::parse("{\"id\": 42, \"text\": \"hi\", \"user\": {\"screen_name\": \"ann\"}}").unwrap();
+/// let tweet = Tweet::new(&parsed);
+///
+/// assert_eq!(tweet.id(), Some(42));
+/// assert_eq!(tweet.author(), Some("ann"));
+/// assert_eq!(tweet.retweeted(), None);
+/// ```
+#[macro_export]
+macro_rules! json_accessors {
+    ($(#[$meta:meta])* $vis:vis struct $name:ident { $($(#[$field_meta:meta])* $field:ident : $ty:ty = $pointer:literal),* $(,)? }) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy)]
+        $vis struct $name<'a> {
+            item: &'a $crate::json_item::JsonItem,
+        }
+
+        impl<'a> $name<'a> {
+            /// Creates a view over `item`
+            #[allow(dead_code)]
+            $vis fn new(item: &'a $crate::json_item::JsonItem) -> Self {
+                $name { item }
+            }
+
+            /// Item this view reads from
+            #[allow(dead_code)]
+            $vis fn item(&self) -> &'a $crate::json_item::JsonItem {
+                self.item
+            }
+
+            $(
+                $(#[$field_meta])*
+                // Lifetimes elided in `$ty` borrow from `self`, which lives no longer than the item
+                #[allow(dead_code, unknown_lints, mismatched_lifetime_syntaxes)]
+                $vis fn $field(&self) -> Option<$ty> {
+                    <$ty as $crate::accessors::FromJsonItem>::from_item(self.item.pointer($pointer))
+                }
+            )*
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use crate::json_item::JsonItem;
+    use crate::parse;
+
+    json_accessors! {
+        struct Event {
+            id: u8 = "/id",
+            name: &str = "/name",
+            label: Cow<str> = "/name",
+            score: f64 = "/stats/score",
+            first_tag: &str = "/tags/0",
+            stats: &JsonItem = "/stats",
+        }
+    }
+
+    #[test]
+    fn typed_accessors() {
+        let parsed = parse("{\"id\": 7, \"name\": \"a\\u0062c\", \"stats\": {\"score\": 1.5}, \"tags\": [\"x\"]}").unwrap();
+        let event = Event::new(&parsed);
+        assert_eq!(event.id(), Some(7));
+        assert_eq!(event.name(), Some("a\\u0062c"));
+        assert_eq!(event.label().as_deref(), Some("abc"));
+        assert_eq!(event.score(), Some(1.5));
+        assert_eq!(event.first_tag(), Some("x"));
+        assert_eq!(event.stats().map(|stats| stats.entries().unwrap().count()), Some(1));
+        assert!(std::ptr::eq(event.item(), &parsed));
+
+        let parsed = parse("{\"id\": 300, \"name\": 1, \"tags\": []}").unwrap();
+        let event = Event::new(&parsed);
+        assert_eq!((event.id(), event.name(), event.score(), event.first_tag()), (None, None, None, None));
+        assert!(event.stats().is_none());
+    }
+
+    #[test]
+    fn default_values() {
+        let parsed = parse("{\"a\": 1, \"b\": \"x\", \"c\": 2.5, \"d\": false}").unwrap();
+        assert_eq!((parsed["a"].as_i128_or(0), parsed["b"].as_i128_or(0), parsed["z"].as_i128_or_else(|| 3)), (1, 0, 3));
+        assert_eq!((parsed["c"].as_f64_or(0.0), parsed["z"].as_f64_or_else(|| 0.5)), (2.5, 0.5));
+        assert_eq!((parsed["d"].as_bool_or(true), parsed["a"].as_bool_or_else(|| true)), (false, true));
+        assert_eq!((parsed["b"].as_str_or("y"), parsed["z"].as_str_or("y"), parsed["z"].as_str_or_else(|| "w")), ("x", "y", "w"));
+        assert_eq!((parsed["a"].value_or(0_u8), parsed["c"].value_or(0_u8), parsed["a"].value_or_else(|| "?")), (1, 0, "?"));
+        assert_eq!(parsed["b"].value_or(Cow::Borrowed("")), "x");
+    }
+
+    #[test]
+    fn json_pointers() {
+        let parsed = parse("{\"a\": [10, {\"~k\": true}], \"\": 0}").unwrap();
+        assert!(std::ptr::eq(parsed.pointer(""), &parsed));
+        assert_eq!(parsed.pointer("/a/0").as_i128(), Some(10));
+        assert_eq!(parsed.pointer("/a/1/~0k").as_bool(), Some(true));
+        assert_eq!(parsed.pointer("/").as_i128(), Some(0));
+        for missing in ["a", "/b", "/a/01", "/a/+1", "/a/2", "/a/0/x", "/a/1/~0k/z"] {
+            assert!(!parsed.pointer(missing).exists(), "{}", missing);
+        }
+    }
+}
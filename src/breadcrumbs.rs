@@ -0,0 +1,129 @@
+// Structural breadcrumbs: with the `breadcrumbs` feature, the parser records the path and byte offset it is at,
+// and panics raised while parsing are re-raised with them appended to the message, to make bug reports actionable.
+// Without the feature, every hook is an empty inline function.
+
+#[cfg(feature = "breadcrumbs")]
+mod enabled {
+    use std::any::Any;
+    use std::cell::RefCell;
+    use std::fmt::Write;
+    use std::mem::{replace, take};
+    use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
+
+    enum Crumb {
+        Key(usize, usize),
+        Index(usize),
+    }
+
+    #[derive(Default)]
+    struct Trail {
+        path: Vec<Crumb>,
+        offset: usize,
+    }
+
+    thread_local! {
+        static TRAIL: RefCell<Trail> = RefCell::default();
+    }
+
+    pub(crate) fn enter_key(start: usize, len: usize) {
+        TRAIL.with_borrow_mut(|trail| trail.path.push(Crumb::Key(start, len)));
+    }
+
+    pub(crate) fn enter_index(index: usize) {
+        TRAIL.with_borrow_mut(|trail| trail.path.push(Crumb::Index(index)));
+    }
+
+    pub(crate) fn leave() {
+        TRAIL.with_borrow_mut(|trail| { trail.path.pop(); });
+    }
+
+    pub(crate) fn at(offset: usize) {
+        TRAIL.with_borrow_mut(|trail| trail.offset = offset);
+    }
+
+    fn message(payload: &(dyn Any + Send)) -> &str {
+        if let Some(message) = payload.downcast_ref::<&str>() {
+            message
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message
+        } else {
+            "parser panicked"
+        }
+    }
+
+    // Runs `parse` on `source` with a fresh trail, adding the breadcrumbs to the message of any panic
+    pub(crate) fn guard<T>(source: &str, parse: impl FnOnce() -> T) -> T {
+        let outer = TRAIL.with_borrow_mut(take);
+        let result = catch_unwind(AssertUnwindSafe(parse));
+        let trail = TRAIL.with_borrow_mut(|trail| replace(trail, outer));
+        match result {
+            Ok(value) => { value }
+            Err(payload) => {
+                let mut path = String::from("$");
+                for crumb in &trail.path {
+                    match crumb {
+                        Crumb::Key(start, len) => {
+                            let key = source.get(*start..*start + *len).unwrap_or("?");
+                            write!(path, "[\"{}\"]", key).unwrap();
+                        }
+                        Crumb::Index(index) => { write!(path, "[{}]", index).unwrap(); }
+                    }
+                }
+                let message = format!("{} (at path {}, byte offset {})", message(payload.as_ref()), path, trail.offset);
+                resume_unwind(Box::new(message))
+            }
+        }
+    }
+}
+
+#[cfg(feature = "breadcrumbs")]
+pub(crate) use enabled::{at, enter_index, enter_key, guard, leave};
+
+#[cfg(not(feature = "breadcrumbs"))]
+mod disabled {
+    #[inline(always)]
+    pub(crate) fn enter_key(_start: usize, _len: usize) {}
+
+    #[inline(always)]
+    pub(crate) fn enter_index(_index: usize) {}
+
+    #[inline(always)]
+    pub(crate) fn leave() {}
+
+    #[inline(always)]
+    pub(crate) fn at(_offset: usize) {}
+
+    #[inline(always)]
+    pub(crate) fn guard<T>(_source: &str, parse: impl FnOnce() -> T) -> T {
+        parse()
+    }
+}
+
+#[cfg(not(feature = "breadcrumbs"))]
+pub(crate) use disabled::{at, enter_index, enter_key, guard, leave};
+
+#[cfg(all(test, feature = "breadcrumbs"))]
+mod tests {
+    use std::panic::catch_unwind;
+
+    use crate::breadcrumbs::{at, enter_index, enter_key, guard, leave};
+
+    #[test]
+    fn panic_with_breadcrumbs() {
+        let source = "{\"a\": {\"b\": [1, 2, 3]}}";
+        let payload = catch_unwind(|| guard(source, || {
+            enter_key(2, 1);
+            enter_key(8, 1);
+            enter_index(0);
+            leave();
+            enter_index(2);
+            at(19);
+            panic!("invariant broken");
+        })).unwrap_err();
+        assert_eq!(payload.downcast_ref::<String>().unwrap(), "invariant broken (at path $[\"a\"][\"b\"][2], byte offset 19)");
+
+        // The trail is reset for every guarded parse
+        let payload = catch_unwind(|| guard(source, || -> () { panic!("again") })).unwrap_err();
+        assert_eq!(payload.downcast_ref::<String>().unwrap(), "again (at path $, byte offset 0)");
+    }
+}
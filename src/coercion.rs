@@ -0,0 +1,239 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use crate::json_item::JsonItem;
+use crate::json_type::JsonType::{Empty, JsonArray, JsonFalse, JsonMap, JsonNull, JsonNumber, JsonString, JsonTrue};
+
+// Name of the JSON type of an item in coercion reports
+fn type_name(item: &JsonItem) -> &'static str {
+    match item.json_type {
+        JsonNull => { "null" }
+        JsonTrue | JsonFalse => { "boolean" }
+        JsonString => { "string" }
+        JsonNumber => { "number" }
+        JsonMap => { "object" }
+        JsonArray => { "array" }
+        Empty => { "missing" }
+    }
+}
+
+/// Conversion from a [`JsonItem`] to a value, accepting values of other JSON types when they can be converted
+/// without loss, see [`Coercer`]. Implemented for `bool`, `f64`, `String` and integer types:
+/// * integers are read from numeric strings (e.g. `"42"`), integral floats (e.g. `42.0`) and booleans (as `0` or `1`)
+/// * `f64` values are read from numeric strings
+/// * `bool` values are read from the strings `"true"` and `"false"`, and from the numbers `0` and `1`
+/// * `String` values are read from numbers and booleans, as written in the source
+pub trait Coerce: Sized {
+    /// Name of the type in coercion reports
+    const NAME: &'static str;
+
+    /// Converts an item of the matching JSON type
+    fn exact(item: &JsonItem) -> Option<Self>;
+
+    /// Converts an item of another JSON type
+    fn coerce(item: &JsonItem) -> Option<Self>;
+}
+
+macro_rules! coerce_integer {
+    ($($integer:ty),*) => {
+        $(
+            impl Coerce for $integer {
+                const NAME: &'static str = "integer";
+
+                fn exact(item: &JsonItem) -> Option<Self> {
+                    item.as_i128().and_then(|value| <$integer>::try_from(value).ok())
+                }
+
+                fn coerce(item: &JsonItem) -> Option<Self> {
+                    let value = match item.json_type {
+                        JsonString => { item.as_unescaped()?.trim().parse::<i128>().ok()? }
+                        JsonNumber => {
+                            let value = item.as_f64()?;
+                            if value.fract() != 0.0 || value.abs() >= i128::MAX as f64 {
+                                return None;
+                            }
+                            value as i128
+                        }
+                        JsonTrue => { 1 }
+                        JsonFalse => { 0 }
+                        _ => { return None; }
+                    };
+                    <$integer>::try_from(value).ok()
+                }
+            }
+        )*
+    };
+}
+
+coerce_integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl Coerce for f64 {
+    const NAME: &'static str = "number";
+
+    fn exact(item: &JsonItem) -> Option<Self> {
+        item.as_f64()
+    }
+
+    fn coerce(item: &JsonItem) -> Option<Self> {
+        item.as_unescaped()?.trim().parse::<f64>().ok().filter(|value| value.is_finite())
+    }
+}
+
+impl Coerce for bool {
+    const NAME: &'static str = "boolean";
+
+    fn exact(item: &JsonItem) -> Option<Self> {
+        item.as_bool()
+    }
+
+    fn coerce(item: &JsonItem) -> Option<Self> {
+        match item.json_type {
+            JsonString => {
+                match item.as_unescaped()?.trim() {
+                    "true" => { Some(true) }
+                    "false" => { Some(false) }
+                    _ => { None }
+                }
+            }
+            JsonNumber => {
+                match item.as_i128()? {
+                    0 => { Some(false) }
+                    1 => { Some(true) }
+                    _ => { None }
+                }
+            }
+            _ => { None }
+        }
+    }
+}
+
+impl Coerce for String {
+    const NAME: &'static str = "string";
+
+    fn exact(item: &JsonItem) -> Option<Self> {
+        item.as_unescaped().map(|text| text.into_owned())
+    }
+
+    fn coerce(item: &JsonItem) -> Option<Self> {
+        match item.json_type {
+            JsonNumber | JsonTrue | JsonFalse => { item.as_str().map(str::to_owned) }
+            _ => { None }
+        }
+    }
+}
+
+/// Coercion performed by a [`Coercer`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coercion {
+    path: String,
+    from: &'static str,
+    to: &'static str,
+}
+
+impl Coercion {
+    /// JSON Pointer of the coerced value, as given to [`Coercer::get`]
+    pub fn get_path(&self) -> &str {
+        &self.path
+    }
+
+    /// JSON type of the value: `null`, `boolean`, `string`, `number`, `object` or `array`
+    pub fn get_from(&self) -> &'static str {
+        self.from
+    }
+
+    /// Type the value was converted to, see [`Coerce::NAME`]
+    pub fn get_to(&self) -> &'static str {
+        self.to
+    }
+}
+
+impl Display for Coercion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "'{}': {} coerced to {}", self.path, self.from, self.to)
+    }
+}
+
+/// Reads typed values, converting values of other JSON types when possible (e.g. the string `"42"` to an integer)
+/// and recording each conversion, so that the quality of an upstream feed can be measured instead of being hidden.
+/// A coercer can be used for any number of documents, its report covering them all.
+///
+/// # Example
+///
+/// ```rust
+/// use jsonic::coercion::Coercer;
+///
+/// let parsed = jsonic::parse("{\"id\": \"42\", \"price\": 9.5, \"active\": 1, \"tags\": []}").unwrap();
+/// let mut coercer = Coercer::new();
+///
+/// assert_eq!(coercer.get::<u32>(&parsed, "/id"), Some(42));
+/// assert_eq!(coercer.get::<f64>(&parsed, "/price"), Some(9.5));
+/// assert_eq!(coercer.get::<bool>(&parsed, "/active"), Some(true));
+/// assert_eq!(coercer.get::<String>(&parsed, "/tags"), None);
+///
+/// let report = coercer.coercions().iter().map(ToString::to_string).collect::<Vec<_>>();
+/// assert_eq!(report, ["'/id': string coerced to integer", "'/active': number coerced to boolean"]);
+/// ```
+#[derive(Debug, Default)]
+pub struct Coercer {
+    coercions: Vec<Coercion>,
+}
+
+impl Coercer {
+    /// Creates a coercer with an empty report
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads the value at `pointer` (RFC 6901) relative to `item` as a `T`, coercing it if it is of another JSON type.
+    /// Returns `None` if the value is missing or cannot be converted.
+    pub fn get<T: Coerce>(&mut self, item: &JsonItem, pointer: &str) -> Option<T> {
+        let value = item.pointer(pointer);
+        if let Some(exact) = T::exact(value) {
+            return Some(exact);
+        }
+        let coerced = T::coerce(value)?;
+        self.coercions.push(Coercion { path: pointer.to_owned(), from: type_name(value), to: T::NAME });
+        Some(coerced)
+    }
+
+    /// Coercions performed so far, in order
+    pub fn coercions(&self) -> &[Coercion] {
+        &self.coercions
+    }
+
+    /// Returns the coercions performed so far, emptying the report
+    pub fn take_coercions(&mut self) -> Vec<Coercion> {
+        std::mem::take(&mut self.coercions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::coercion::Coercer;
+    use crate::parse;
+
+    #[test]
+    fn coerce_values() {
+        let parsed = parse("{\"n\": [\" 7 \", 3.0, 3.5, true, \"x\", 300, null], \"b\": [\"false\", 0, 2], \"s\": [1e3, false, \"\\u0061\"]}").unwrap();
+        let mut coercer = Coercer::new();
+        assert_eq!(coercer.get::<i64>(&parsed, "/n/0"), Some(7));
+        assert_eq!(coercer.get::<i64>(&parsed, "/n/1"), Some(3));
+        assert_eq!(coercer.get::<i64>(&parsed, "/n/2"), None);
+        assert_eq!(coercer.get::<u8>(&parsed, "/n/3"), Some(1));
+        assert_eq!(coercer.get::<i64>(&parsed, "/n/4"), None);
+        assert_eq!(coercer.get::<u8>(&parsed, "/n/5"), None);
+        assert_eq!(coercer.get::<i64>(&parsed, "/n/6"), None);
+        assert_eq!(coercer.get::<f64>(&parsed, "/n/0"), Some(7.0));
+        assert_eq!(coercer.get::<bool>(&parsed, "/b/0"), Some(false));
+        assert_eq!(coercer.get::<bool>(&parsed, "/b/1"), Some(false));
+        assert_eq!(coercer.get::<bool>(&parsed, "/b/2"), None);
+        assert_eq!(coercer.get::<String>(&parsed, "/s/0").as_deref(), Some("1e3"));
+        assert_eq!(coercer.get::<String>(&parsed, "/s/1").as_deref(), Some("false"));
+        assert_eq!(coercer.get::<String>(&parsed, "/s/2").as_deref(), Some("a"));
+
+        let report = coercer.take_coercions();
+        let summary = report.iter().map(|c| (c.get_path(), c.get_from(), c.get_to())).collect::<Vec<_>>();
+        assert_eq!(summary, [("/n/0", "string", "integer"), ("/n/1", "number", "integer"), ("/n/3", "boolean", "integer"), ("/n/0", "string", "number"),
+                             ("/b/0", "string", "boolean"), ("/b/1", "number", "boolean"), ("/s/0", "number", "string"), ("/s/1", "boolean", "string")]);
+        assert!(coercer.coercions().is_empty());
+    }
+}
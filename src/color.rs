@@ -0,0 +1,190 @@
+use crate::json_item::JsonItem;
+use crate::serialize::{Indent, SerializeOptions};
+
+const RESET: &str = "\x1b[0m";
+
+/// ANSI escape sequences coloring the tokens of colorized JSON, see [`JsonItem::to_json_colored`].
+/// Each token is preceded by the sequence of its kind and followed by a reset, and empty sequences leave tokens as they are.
+///
+/// # Example
+///
+/// ```rust
+/// use jsonic::color::Palette;
+/// use jsonic::SerializeOptions;
+///
+/// let parsed = jsonic::parse("{\"ok\": true}").unwrap();
+/// let palette = Palette::new().key("\x1b[35m").punctuation("");
+///
+/// assert_eq!(parsed.to_json_colored_using(&SerializeOptions::new(), &palette), "{\x1b[35m\"ok\"\x1b[0m:\x1b[33mtrue\x1b[0m}");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette {
+    key: &'static str,
+    string: &'static str,
+    number: &'static str,
+    literal: &'static str,
+    punctuation: &'static str,
+    comment: &'static str,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Palette { key: "\x1b[1;34m", string: "\x1b[32m", number: "\x1b[36m", literal: "\x1b[33m", punctuation: "\x1b[1m", comment: "\x1b[2m" }
+    }
+}
+
+impl Palette {
+    /// Creates a palette with keys in bold blue, strings in green, numbers in cyan, `true`, `false` and `null` in yellow,
+    /// brackets, commas and colons in bold, and comments dimmed
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the escape sequence of object keys
+    pub fn key(mut self, sequence: &'static str) -> Self {
+        self.key = sequence;
+        self
+    }
+
+    /// Sets the escape sequence of string values
+    pub fn string(mut self, sequence: &'static str) -> Self {
+        self.string = sequence;
+        self
+    }
+
+    /// Sets the escape sequence of numbers
+    pub fn number(mut self, sequence: &'static str) -> Self {
+        self.number = sequence;
+        self
+    }
+
+    /// Sets the escape sequence of `true`, `false` and `null`
+    pub fn literal(mut self, sequence: &'static str) -> Self {
+        self.literal = sequence;
+        self
+    }
+
+    /// Sets the escape sequence of brackets, commas and colons
+    pub fn punctuation(mut self, sequence: &'static str) -> Self {
+        self.punctuation = sequence;
+        self
+    }
+
+    /// Sets the escape sequence of comments, written with [`SerializeOptions::comments`]
+    pub fn comment(mut self, sequence: &'static str) -> Self {
+        self.comment = sequence;
+        self
+    }
+}
+
+// Appends `token` to `output`, wrapped in `sequence` if any
+fn paint(token: &str, sequence: &str, output: &mut String) {
+    if sequence.is_empty() {
+        output.push_str(token);
+    } else {
+        output.push_str(sequence);
+        output.push_str(token);
+        output.push_str(RESET);
+    }
+}
+
+// Colors the tokens of JSON text written by the serializer, which holds standard JSON, whitespace and comments
+fn colorize(json: &str, palette: &Palette) -> String {
+    let bytes = json.as_bytes();
+    let mut output = String::with_capacity(json.len() * 2);
+    let mut index = 0;
+    while index < bytes.len() {
+        let start = index;
+        let sequence = match bytes[index] {
+            b'"' => {
+                index += 1;
+                while index < bytes.len() && bytes[index] != b'"' {
+                    index += if bytes[index] == b'\\' { 2 } else { 1 };
+                }
+                index = usize::min(index + 1, bytes.len());
+                let next = json[index..].trim_start_matches([' ', '\t']);
+                if next.starts_with(':') { palette.key } else { palette.string }
+            }
+            b'-' | b'0'..=b'9' => {
+                index += json[index..].find(|c: char| !matches!(c, '-' | '+' | '.' | 'e' | 'E' | '0'..='9')).unwrap_or(json.len() - index);
+                palette.number
+            }
+            b't' | b'f' | b'n' => {
+                index += json[index..].find(|c: char| !c.is_ascii_lowercase()).unwrap_or(json.len() - index);
+                palette.literal
+            }
+            b'/' if bytes.get(index + 1) == Some(&b'/') => {
+                index += json[index..].find('\n').unwrap_or(json.len() - index);
+                palette.comment
+            }
+            b'/' => {
+                index += json[index + 2..].find("*/").map_or(json.len() - index, |len| len + 4);
+                palette.comment
+            }
+            b'{' | b'}' | b'[' | b']' | b',' | b':' => {
+                index += 1;
+                palette.punctuation
+            }
+            _ => {
+                // Whitespace, copied as is
+                let len = json[index..].find(|c: char| !c.is_whitespace()).unwrap_or(json.len() - index);
+                index += if len > 0 { len } else { json[index..].chars().next().map_or(1, char::len_utf8) };
+                ""
+            }
+        };
+        paint(&json[start..index], sequence, &mut output);
+    }
+    output
+}
+
+impl JsonItem {
+    /// Returns the pretty-printed JSON text of the item, see [`to_json_pretty`](JsonItem::to_json_pretty),
+    /// colored with ANSI escape sequences for terminals, see [`Palette::new`] for the colors.
+    /// Meant for debugging output and command-line tools: check that the output is a terminal before coloring it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jsonic::Indent;
+    ///
+    /// let parsed = jsonic::parse("{\"id\": 7}").unwrap();
+    /// assert_eq!(parsed.to_json_colored(Indent::Spaces(2)), "\x1b[1m{\x1b[0m\n  \x1b[1;34m\"id\"\x1b[0m\x1b[1m:\x1b[0m \x1b[36m7\x1b[0m\n\x1b[1m}\x1b[0m");
+    /// ```
+    pub fn to_json_colored(&self, indent: Indent) -> String {
+        self.to_json_colored_using(&SerializeOptions::new().indent(indent), &Palette::new())
+    }
+
+    /// Returns the JSON text of the item written according to `options`, colored according to `palette`
+    pub fn to_json_colored_using(&self, options: &SerializeOptions, palette: &Palette) -> String {
+        colorize(&self.to_json_using(options), palette)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::color::Palette;
+    use crate::parse;
+    use crate::serialize::{Indent, SerializeOptions};
+
+    #[test]
+    fn colorize_tokens() {
+        let parsed = parse("{\"a\\\"b\": [\"x:\", -1.5e-3, null, false, {}], \"c\": \"\\\\x\"}").unwrap();
+        let palette = Palette::new().key("K").string("S").number("N").literal("L").punctuation("");
+        let colored = parsed.to_json_colored_using(&SerializeOptions::new(), &palette);
+        assert_eq!(colored.replace("\x1b[0m", "|"), "{K\"a\\\"b\"|:[S\"x:\"|,N-1.5e-3|,Lnull|,Lfalse|,{}],K\"c\"|:S\"\\\\x\"|}");
+        let plain = Palette::new().key("").string("").number("").literal("").punctuation("");
+        assert_eq!(parsed.to_json_colored_using(&SerializeOptions::new().indent(Indent::Tabs(1)), &plain), parsed.to_json_pretty(Indent::Tabs(1)));
+        assert_eq!(parse("[]").unwrap()[0].to_json_colored(Indent::default()), "\x1b[33mnull\x1b[0m");
+        #[cfg(feature = "extended")]
+        {
+            let parsed = crate::parse_jsonc("[1, /* one */ 2 // two\n]").unwrap();
+            let options = SerializeOptions::new().indent(Indent::Spaces(1)).comments(true);
+            let colored = parsed.to_json_colored_using(&options, &palette.comment("C"));
+            assert_eq!(colored.replace("\x1b[0m", "|"), "[\n N1|,\n C/* one */|\n N2| C// two|\n]");
+
+            let parser = crate::options::Parser::new(crate::options::ParseOptions::json5().materialize_depth(1));
+            let stubs = parser.parse("[{name: 'é', x: [1,]}]").unwrap();
+            assert_eq!(stubs.to_json_colored_using(&SerializeOptions::new(), &palette).replace("\x1b[0m", "|"), "[{K\"name\"|:S\"é\"|,K\"x\"|:[N1|]}]");
+        }
+    }
+}
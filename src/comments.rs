@@ -0,0 +1,227 @@
+use std::slice::from_raw_parts;
+use std::str::from_utf8_unchecked;
+
+use crate::document::JsonDocument;
+use crate::json_item::JsonItem;
+use crate::json_type::JsonType::{JsonArray, JsonMap, JsonString};
+use crate::key::Key;
+
+/// Comments of the source text attached to an item, see [`JsonItem::comments_of`].
+/// Comments are given as written, delimiters included, e.g. `// port` or `/* in ms */`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Comments<'a> {
+    before: Vec<&'a str>,
+    after: Vec<&'a str>,
+}
+
+impl<'a> Comments<'a> {
+    /// Comments preceding the item, down to the previous item or the opening bracket of its container,
+    /// those between a member key and its value included
+    pub fn get_before(&self) -> &[&'a str] {
+        &self.before
+    }
+
+    /// Comments following the item on the same line, after the comma if any
+    pub fn get_after(&self) -> &[&'a str] {
+        &self.after
+    }
+
+    /// Checks if there are no comments around the item
+    pub fn is_empty(&self) -> bool {
+        self.before.is_empty() && self.after.is_empty()
+    }
+}
+
+// Pointers to the start and end of the text of an item, quotes included
+fn bounds(item: &JsonItem) -> (*const u8, *const u8) {
+    let quote = usize::from(item.json_type == JsonString);
+    let start = item.slice.ptr.wrapping_sub(quote);
+    (start, start.wrapping_add(item.slice.len + 2 * quote))
+}
+
+// Pointers to the start and end of the text of a key, quotes included
+fn key_bounds(key: &Key) -> (*const u8, *const u8) {
+    // Keys follow a bracket, a comma or whitespace, so the byte before them belongs to the source
+    match unsafe { *key.slice.ptr.sub(1) } {
+        b'"' | b'\'' => { (key.slice.ptr.wrapping_sub(1), key.slice.ptr.wrapping_add(key.slice.len + 1)) }
+        _ => { (key.slice.ptr, key.slice.ptr.wrapping_add(key.slice.len)) }
+    }
+}
+
+// Source text between two pointers into the text of an item, empty if they are out of order
+fn between(_item: &JsonItem, start: *const u8, end: *const u8) -> &str {
+    if (start as usize) >= (end as usize) {
+        return "";
+    }
+    unsafe { from_utf8_unchecked(from_raw_parts(start, end as usize - start as usize)) }
+}
+
+// Comments in text separating tokens, with their offsets. Strings are skipped, as the text may hold members
+// dropped as duplicates.
+fn scan(text: &str) -> Vec<(usize, &str)> {
+    let bytes = text.as_bytes();
+    let mut comments = Vec::new();
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            quote @ (b'"' | b'\'') => {
+                index += 1;
+                while index < bytes.len() && bytes[index] != quote {
+                    index += if bytes[index] == b'\\' { 2 } else { 1 };
+                }
+                index += 1;
+            }
+            b'/' if bytes.get(index + 1) == Some(&b'/') => {
+                let end = text[index..].find('\n').map_or(text.len(), |len| index + len);
+                comments.push((index, text[index..end].trim_end_matches('\r')));
+                index = end;
+            }
+            b'/' if bytes.get(index + 1) == Some(&b'*') => {
+                let end = text[index + 2..].find("*/").map_or(text.len(), |len| index + len + 4);
+                comments.push((index, &text[index..end]));
+                index = end;
+            }
+            _ => { index += 1; }
+        }
+    }
+    comments
+}
+
+// Children of a container in source order, with their key if any and their comments,
+// and the comments left before its closing bracket
+pub(crate) type Layout<'a> = (Vec<(Option<&'a Key>, &'a JsonItem, Comments<'a>)>, Vec<&'a str>);
+
+// Layout of the comments of an object or array parsed from source text, `None` for other items and stubs,
+// whose children are not parsed
+pub(crate) fn layout(item: &JsonItem) -> Option<Layout<'_>> {
+    let children = match (item.json_type, item.slice.as_bytes().first()) {
+        _ if item.is_stub() => { return None; }
+        (JsonArray, Some(b'[')) => { item.elements()?.map(|element| (None, element, bounds(element).0)).collect::<Vec<_>>() }
+        (JsonMap, Some(b'{')) => {
+            let mut entries = item.entries()?.map(|(key, value)| (Some(key), value, key_bounds(key).0)).collect::<Vec<_>>();
+            entries.sort_by_key(|(_, _, start)| *start as usize);
+            entries
+        }
+        _ => { return None; }
+    };
+
+    let (open, close) = bounds(item);
+    let mut previous = open.wrapping_add(1);
+    let mut comments = vec![Comments::default(); children.len()];
+    for (index, (key, value, start)) in children.iter().enumerate() {
+        let gap = between(item, previous, *start);
+        for (offset, comment) in scan(gap) {
+            // Comments on the line of the previous child belong to it, unless the child starts on that line too
+            match index.checked_sub(1) {
+                Some(previous) if !gap[..offset].contains('\n') && gap[offset + comment.len()..].contains('\n') => {
+                    comments[previous].after.push(comment)
+                }
+                _ => { comments[index].before.push(comment) }
+            }
+        }
+        if let Some(key) = key {
+            comments[index].before.extend(scan(between(item, key_bounds(key).1, bounds(value).0)).into_iter().map(|(_, comment)| comment));
+        }
+        previous = bounds(value).1;
+    }
+    let gap = between(item, previous, close.wrapping_sub(1));
+    let mut inner = Vec::new();
+    for (offset, comment) in scan(gap) {
+        match comments.last_mut() {
+            Some(last) if !gap[..offset].contains('\n') => { last.after.push(comment) }
+            _ => { inner.push(comment) }
+        }
+    }
+    Some((children.into_iter().zip(comments).map(|((key, value, _), comments)| (key, value, comments)).collect(), inner))
+}
+
+impl JsonItem {
+    /// Returns the comments of the source text attached to `child`, an element or member value of this array
+    /// or object, or `None` if `child` is not one of them. Comments are only found in text parsed with comments
+    /// enabled, e.g. by [`parse_jsonc`](crate::parse_jsonc), and stubs have none until expanded.
+    /// Comments preceding a child are attached to it, except those on the line where the previous child ends,
+    /// when the child starts on a later line, which follow the previous child; see [`inner_comments`](JsonItem::inner_comments) for the comments after the last child.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let source = "{\n  // Editor\n  \"tabSize\": 4, // spaces\n  \"rulers\": [80, /* hard */ 120]\n  // \"wordWrap\": \"on\"\n}";
+    /// let settings = jsonic::parse_jsonc(source).unwrap();
+    ///
+    /// let comments = settings.comments_of(&settings["tabSize"]).unwrap();
+    /// assert_eq!((comments.get_before(), comments.get_after()), (&["// Editor"][..], &["// spaces"][..]));
+    /// assert_eq!(settings["rulers"].comments_of(&settings["rulers"][1]).unwrap().get_before(), ["/* hard */"]);
+    /// assert_eq!(settings.inner_comments(), ["// \"wordWrap\": \"on\""]);
+    /// ```
+    pub fn comments_of(&self, child: &JsonItem) -> Option<Comments<'_>> {
+        let (children, _) = layout(self)?;
+        children.into_iter().find(|(_, item, _)| std::ptr::eq(*item, child)).map(|(_, _, comments)| comments)
+    }
+
+    /// Returns the comments of the source text following the last element or member of this array or object,
+    /// on the lines before its closing bracket, or all its comments if it is empty, see [`comments_of`](JsonItem::comments_of)
+    pub fn inner_comments(&self) -> Vec<&str> {
+        layout(self).map(|(_, inner)| inner).unwrap_or_default()
+    }
+}
+
+impl JsonDocument {
+    /// Returns the comments of the source text preceding and following the root item of the document
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jsonic::options::{ParseOptions, Parser};
+    ///
+    /// let document = Parser::new(ParseOptions::jsonc()).parse_document("// Generated\n[1]\n/* end */".to_owned()).unwrap();
+    /// let comments = document.root_comments();
+    /// assert_eq!((comments.get_before(), comments.get_after()), (&["// Generated"][..], &["/* end */"][..]));
+    /// ```
+    pub fn root_comments(&self) -> Comments<'_> {
+        let source = self.source();
+        let (start, end) = bounds(self.root());
+        let start = start as usize - source.as_ptr() as usize;
+        let end = end as usize - source.as_ptr() as usize;
+        Comments {
+            before: scan(&source[..start]).into_iter().map(|(_, comment)| comment).collect(),
+            after: scan(&source[end..]).into_iter().map(|(_, comment)| comment).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::comments::Comments;
+    use crate::options::{DuplicateKeys, ParseOptions, Parser};
+    use crate::{parse, parse_jsonc, parse_json5};
+
+    #[test]
+    fn attach_comments() {
+        let parsed = parse_jsonc("{ // header\n  \"a\": /* key */ 1, // one\r\n  /* two */ \"b\": [ // open\n    2, /* x */ 3 // three\n  // end\n  ], \"c\": {} // c\n}").unwrap();
+        let comments = |item, child| parsed.pointer(item).comments_of(parsed.pointer(child)).unwrap();
+        assert_eq!(comments("", "/a").get_before(), ["// header", "/* key */"]);
+        assert_eq!(comments("", "/a").get_after(), ["// one"]);
+        assert_eq!(comments("", "/b").get_before(), ["/* two */"]);
+        assert_eq!(comments("", "/b").get_after(), [] as [&str; 0]);
+        assert_eq!(comments("/b", "/b/0").get_before(), ["// open"]);
+        assert!(comments("/b", "/b/0").get_after().is_empty());
+        assert_eq!((comments("/b", "/b/1").get_before(), comments("/b", "/b/1").get_after()), (&["/* x */"][..], &["// three"][..]));
+        assert_eq!(parsed["b"].inner_comments(), ["// end"]);
+        assert_eq!(comments("", "/c").get_after(), ["// c"]);
+        assert!(parsed.inner_comments().is_empty() && parsed["c"].inner_comments().is_empty());
+        assert!(parsed.comments_of(&parsed["b"][0]).is_none() && parsed["a"].comments_of(&parsed["a"]).is_none());
+
+        let parsed = parse_json5("[{a: '//', 'b': \"/*\"}, /* c */]").unwrap();
+        assert!(parsed[0].comments_of(&parsed[0]["a"]).unwrap().is_empty() && parsed[0].comments_of(&parsed[0]["b"]).unwrap().is_empty());
+        assert_eq!(parsed.comments_of(&parsed[0]).unwrap().get_after(), ["/* c */"]);
+        assert!(parse("[1, 2]").unwrap().inner_comments().is_empty());
+
+        let parser = Parser::new(ParseOptions::jsonc().duplicate_keys(DuplicateKeys::FirstWins));
+        let parsed = parser.parse("{\"a\": 1, \"a\": \"/* no */\", // a\n \"b\": 2}").unwrap();
+        assert_eq!(parsed.comments_of(&parsed["a"]).unwrap().get_after(), ["// a"]);
+        let stubs = Parser::new(ParseOptions::jsonc().materialize_depth(1)).parse("[[1 /* x */]]").unwrap();
+        assert!(stubs[0].inner_comments().is_empty() && stubs.comments_of(&stubs[0]).unwrap().is_empty());
+        let expanded = stubs[0].as_stub().unwrap().expand().unwrap();
+        assert_eq!(expanded.comments_of(&expanded[0]), Some(Comments { before: Vec::new(), after: vec!["/* x */"] }));
+    }
+}
@@ -0,0 +1,193 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::mem;
+
+#[cfg(feature = "serialize")]
+use crate::delta::{apply_delta, Delta, DeltaError};
+use crate::document::JsonDocument;
+use crate::json_item::JsonItem;
+use crate::number::FloatEquality;
+#[cfg(feature = "serialize")]
+use crate::options::ParseOptions;
+
+/// Errors returned by conditional updates, leaving the document unchanged
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConditionError {
+    /// Value at the pointer is not the expected one, e.g. the document was updated concurrently
+    Mismatch,
+    /// Delta does not apply to the document
+    #[cfg(feature = "serialize")]
+    Delta(DeltaError),
+}
+
+impl Display for ConditionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            ConditionError::Mismatch => { write!(f, "value does not match the expected one") }
+            #[cfg(feature = "serialize")]
+            ConditionError::Delta(error) => { Display::fmt(error, f) }
+        }
+    }
+}
+
+impl Error for ConditionError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ConditionError::Mismatch => { None }
+            #[cfg(feature = "serialize")]
+            ConditionError::Delta(error) => { Some(error) }
+        }
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl From<DeltaError> for ConditionError {
+    fn from(error: DeltaError) -> Self {
+        ConditionError::Delta(error)
+    }
+}
+
+// Whether the value at `pointer` equals `expected`, a non-existent item matching a missing value
+fn holds(doc: &JsonDocument, pointer: &str, expected: &JsonItem) -> bool {
+    doc.pointer(pointer).equals(expected, FloatEquality::Bitwise)
+}
+
+/// Conditional read: returns `doc` only if the value at `pointer` differs from `known`,
+/// e.g. to skip sending a document whose version the client already has.
+/// Values are compared as with [`JsonItem::equals`] and [`FloatEquality::Bitwise`].
+///
+/// # Arguments
+/// * `doc` - Stored document
+/// * `pointer` - JSON Pointer (RFC 6901) of the value to check, e.g. a version or an ETag
+/// * `known` - Value known by the reader
+///
+/// # Example
+///
+/// ```rust
+/// use jsonic::conditional::get_if_pointer_differs;
+/// use jsonic::document::ParseJsonExt;
+///
+/// let stored = "{\"version\": 3, \"name\": \"ann\"}".parse_json().unwrap();
+/// assert!(get_if_pointer_differs(&stored, "/version", &jsonic::parse_strict("3").unwrap()).is_none());
+/// assert!(get_if_pointer_differs(&stored, "/version", &jsonic::parse_strict("2").unwrap()).is_some());
+/// ```
+pub fn get_if_pointer_differs<'d>(doc: &'d JsonDocument, pointer: &str, known: &JsonItem) -> Option<&'d JsonDocument> {
+    (!holds(doc, pointer, known)).then_some(doc)
+}
+
+/// Compare-and-swap: replaces `doc` with `new_doc` only if the value at `pointer` equals `expected`,
+/// returning the previous document. Otherwise `doc` is left unchanged and `new_doc` is dropped.
+/// Holding `doc` mutably makes the check and the replacement a single step, for optimistic concurrency
+/// over documents stored behind a lock, e.g. in a key-value store.
+/// Values are compared as with [`JsonItem::equals`] and [`FloatEquality::Bitwise`],
+/// and a non-existent `expected` item matches a missing value.
+///
+/// # Arguments
+/// * `doc` - Stored document
+/// * `pointer` - JSON Pointer (RFC 6901) of the value to check, e.g. a version or an ETag
+/// * `expected` - Value the writer based its update on
+/// * `new_doc` - Replacement document
+///
+/// # Example
+///
+/// ```rust
+/// use jsonic::conditional::{replace_if_pointer_equals, ConditionError};
+/// use jsonic::document::ParseJsonExt;
+///
+/// let mut stored = "{\"version\": 3, \"name\": \"ann\"}".parse_json().unwrap();
+/// let expected = jsonic::parse_strict("3").unwrap();
+///
+/// let previous = replace_if_pointer_equals(&mut stored, "/version", &expected, "{\"version\": 4, \"name\": \"bob\"}".parse_json().unwrap());
+/// assert_eq!(previous.unwrap()["name"].as_str(), Some("ann"));
+///
+/// let stale = replace_if_pointer_equals(&mut stored, "/version", &expected, "{\"version\": 4, \"name\": \"cy\"}".parse_json().unwrap());
+/// assert_eq!(stale.unwrap_err(), ConditionError::Mismatch);
+/// assert_eq!(stored["name"].as_str(), Some("bob"));
+/// ```
+pub fn replace_if_pointer_equals(doc: &mut JsonDocument, pointer: &str, expected: &JsonItem, new_doc: JsonDocument) -> Result<JsonDocument, ConditionError> {
+    if !holds(doc, pointer, expected) {
+        return Err(ConditionError::Mismatch);
+    }
+    Ok(mem::replace(doc, new_doc))
+}
+
+/// Compare-and-patch: applies `delta` to `doc` only if the value at `pointer` equals `expected`, see [`replace_if_pointer_equals`].
+/// Either every operation of `delta` is applied or `doc` is left unchanged.
+/// The patched document holds compact JSON text, as returned by [`apply_delta`].
+///
+/// # Arguments
+/// * `doc` - Stored document
+/// * `pointer` - JSON Pointer (RFC 6901) of the value to check, e.g. a version or an ETag
+/// * `expected` - Value the writer based its update on
+/// * `delta` - Changes to apply, which usually update the checked value too
+///
+/// # Example
+///
+/// ```rust
+/// use jsonic::conditional::patch_if_pointer_equals;
+/// use jsonic::delta::delta;
+/// use jsonic::document::ParseJsonExt;
+///
+/// let mut stored = "{\"version\": 3, \"tags\": [\"a\"]}".parse_json().unwrap();
+/// let changes = delta(&stored, &jsonic::parse_strict("{\"version\": 4, \"tags\": [\"a\", \"b\"]}").unwrap());
+///
+/// patch_if_pointer_equals(&mut stored, "/version", &jsonic::parse_strict("3").unwrap(), &changes).unwrap();
+/// assert_eq!(stored.source(), "{\"version\":4,\"tags\":[\"a\",\"b\"]}");
+/// assert!(patch_if_pointer_equals(&mut stored, "/version", &jsonic::parse_strict("3").unwrap(), &changes).is_err());
+/// ```
+#[cfg(feature = "serialize")]
+pub fn patch_if_pointer_equals(doc: &mut JsonDocument, pointer: &str, expected: &JsonItem, delta: &Delta) -> Result<(), ConditionError> {
+    if !holds(doc, pointer, expected) {
+        return Err(ConditionError::Mismatch);
+    }
+    let source = apply_delta(doc, delta)?;
+    *doc = JsonDocument::parse(source, &ParseOptions::strict().max_depth(usize::MAX)).expect("patched JSON is valid");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::conditional::{get_if_pointer_differs, replace_if_pointer_equals, ConditionError};
+    use crate::document::ParseJsonExt;
+    use crate::parse_strict;
+
+    #[test]
+    fn compare_and_swap() {
+        let mut stored = "{\"meta\": {\"etag\": \"a\\u0062\"}, \"n\": 1}".parse_json().unwrap();
+        let etag = parse_strict("\"ab\"").unwrap();
+        assert!(get_if_pointer_differs(&stored, "/meta/etag", &etag).is_none());
+        assert!(get_if_pointer_differs(&stored, "/meta/other", &etag).is_some());
+
+        let next = "{\"meta\": {\"etag\": \"c\"}, \"n\": 2}".parse_json().unwrap();
+        assert_eq!(replace_if_pointer_equals(&mut stored, "/meta/etag", &parse_strict("\"c\"").unwrap(), next).unwrap_err(), ConditionError::Mismatch);
+        assert_eq!(stored["n"].as_i128(), Some(1));
+
+        let next = "{\"meta\": {\"etag\": \"c\"}, \"n\": 2}".parse_json().unwrap();
+        let previous = replace_if_pointer_equals(&mut stored, "/meta/etag", &etag, next).unwrap();
+        assert_eq!((previous["n"].as_i128(), stored["n"].as_i128()), (Some(1), Some(2)));
+
+        // A non-existent item expects the value to be missing
+        let absent = &parse_strict("[]").unwrap()[0];
+        let created = replace_if_pointer_equals(&mut stored, "/lock", absent, "{\"lock\": true}".parse_json().unwrap());
+        assert!(created.is_ok() && stored["lock"].as_bool() == Some(true));
+        assert!(replace_if_pointer_equals(&mut stored, "/lock", absent, "{}".parse_json().unwrap()).is_err());
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn compare_and_patch() {
+        use crate::conditional::patch_if_pointer_equals;
+        use crate::delta::{Delta, DeltaOp};
+
+        let mut stored = "{\"version\": 1.0, \"items\": [1, 2]}".parse_json().unwrap();
+        let remove = Delta::from_json("[[1, \"/missing\"], [0, \"/version\", 2]]").unwrap();
+        assert!(matches!(patch_if_pointer_equals(&mut stored, "/version", &parse_strict("1").unwrap(), &remove), Err(ConditionError::Delta(e)) if e.get_op() == 0));
+        assert_eq!(stored.source(), "{\"version\": 1.0, \"items\": [1, 2]}");
+
+        let truncate = Delta::from_json("[[2, \"/items\", 1], [0, \"/version\", 2]]").unwrap();
+        assert!(matches!(truncate.ops()[0], DeltaOp::Truncate { len: 1, .. }));
+        patch_if_pointer_equals(&mut stored, "/version", &parse_strict("1").unwrap(), &truncate).unwrap();
+        assert_eq!(stored.source(), "{\"version\":2,\"items\":[1]}");
+        assert_eq!(patch_if_pointer_equals(&mut stored, "/version", &parse_strict("1").unwrap(), &truncate), Err(ConditionError::Mismatch));
+    }
+}
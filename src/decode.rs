@@ -0,0 +1,188 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::io;
+
+use crate::decompress::decompress;
+use crate::document::read_source;
+use crate::json_error::JsonError;
+use crate::json_item::JsonItem;
+use crate::ndjson::{parse_lines, LineError};
+use crate::options::ParseOptions;
+use crate::parse_complete;
+use crate::seq::{parse_seq, RecordError};
+
+/// Data format selected from a media type, see [`decode`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// A single JSON document (`application/json`, `text/json`, `*/*+json`)
+    Json,
+    /// NDJSON records (`application/x-ndjson`, `application/ndjson`, `application/jsonl`, `application/x-jsonlines`)
+    JsonLines,
+    /// RFC 7464 JSON text sequence (`application/json-seq`, `*/*+json-seq`)
+    JsonSeq,
+}
+
+/// Errors returned by [`decode`]
+#[derive(Debug)]
+pub enum DecodeError {
+    /// The media type is not a JSON format, or names a compression whose feature is disabled
+    UnsupportedMediaType(String),
+    /// Data could not be decompressed, or is not valid UTF-8
+    Io(io::Error),
+    /// JSON document is invalid
+    Json(JsonError),
+    /// NDJSON record is invalid
+    Line(LineError),
+    /// JSON text sequence record is invalid
+    Record(RecordError),
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            DecodeError::UnsupportedMediaType(media_type) => { write!(f, "unsupported media type '{}'", media_type) }
+            DecodeError::Io(error) => { write!(f, "I/O error: {}", error) }
+            DecodeError::Json(error) => { Display::fmt(error, f) }
+            DecodeError::Line(error) => { Display::fmt(error, f) }
+            DecodeError::Record(error) => { Display::fmt(error, f) }
+        }
+    }
+}
+
+impl Error for DecodeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DecodeError::UnsupportedMediaType(_) => { None }
+            DecodeError::Io(error) => { Some(error) }
+            DecodeError::Json(error) => { Some(error) }
+            DecodeError::Line(error) => { Some(error) }
+            DecodeError::Record(error) => { Some(error) }
+        }
+    }
+}
+
+/// Records decoded by [`decode`], owning their source text
+#[derive(Debug)]
+pub struct Decoded {
+    format: Format,
+    records: Vec<JsonItem>,
+    source: String,
+}
+
+impl Decoded {
+    /// Format the data was decoded as
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    /// Decoded records, a single one for [`Format::Json`]
+    pub fn records(&self) -> &[JsonItem] {
+        &self.records
+    }
+
+    /// Source text of the records, decompressed
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+// Splits a media type into its format and whether it declares gzip compression. Parameters (e.g. `charset`) are ignored.
+fn media_format(content_type: &str) -> Option<(Format, bool)> {
+    let media_type = content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+    let (media_type, gzip) = match media_type.strip_suffix("+gzip") {
+        Some(media_type) => { (media_type, true) }
+        None => { (media_type.as_str(), false) }
+    };
+    let format = match media_type {
+        "application/gzip" | "application/x-gzip" => { return Some((Format::Json, true)); }
+        "application/json" | "text/json" => { Format::Json }
+        "application/x-ndjson" | "application/ndjson" | "application/jsonl" | "application/x-jsonlines" => { Format::JsonLines }
+        "application/json-seq" => { Format::JsonSeq }
+        _ if media_type.ends_with("+json-seq") => { Format::JsonSeq }
+        _ if media_type.ends_with("+json") => { Format::Json }
+        _ => { return None; }
+    };
+    Some((format, gzip))
+}
+
+/// Decodes an HTTP body according to its `Content-Type`, dispatching to JSON, NDJSON or JSON text sequence parsing.
+/// Gzip data, declared with a `+gzip` suffix or `application/gzip`, requires the `gzip` feature.
+/// With the `gzip` or `zstd` feature, compressed data is also detected and decompressed transparently.
+/// Fails on the first invalid record.
+///
+/// # Arguments
+/// * `bytes` - Body of the request or response
+/// * `content_type` - Media type of the body, parameters included
+///
+/// # Example
+///
+/// ```rust
+/// use jsonic::decode::Format;
+///
+/// let decoded = jsonic::decode(b"{\"id\": 1}\n{\"id\": 2}\n", "application/x-ndjson; charset=utf-8").unwrap();
+/// assert_eq!(decoded.format(), Format::JsonLines);
+/// assert_eq!(decoded.records()[1]["id"].as_i128(), Some(2));
+///
+/// assert!(jsonic::decode(b"<a/>", "text/xml").is_err());
+/// ```
+pub fn decode(bytes: &[u8], content_type: &str) -> Result<Decoded, DecodeError> {
+    let unsupported = || DecodeError::UnsupportedMediaType(content_type.to_owned());
+    let (format, gzip) = media_format(content_type).ok_or_else(unsupported)?;
+    if gzip && !cfg!(feature = "gzip") {
+        return Err(unsupported());
+    }
+    let source = read_source(decompress(bytes).map_err(DecodeError::Io)?, bytes.len()).map_err(DecodeError::Io)?;
+
+    // Items point into the heap buffer of `source`, which stays in place when it is moved into `Decoded`
+    let records = match format {
+        Format::Json => { vec![parse_complete(&source, &ParseOptions::JSON).map_err(DecodeError::Json)?] }
+        Format::JsonLines => { parse_lines(&source).collect::<Result<_, _>>().map_err(DecodeError::Line)? }
+        Format::JsonSeq => { parse_seq(&source).collect::<Result<_, _>>().map_err(DecodeError::Record)? }
+    };
+    Ok(Decoded { format, records, source })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::decode::{decode, media_format, DecodeError, Format};
+
+    #[test]
+    fn select_format() {
+        assert_eq!(media_format("application/json"), Some((Format::Json, false)));
+        assert_eq!(media_format(" Application/Problem+JSON ; charset=UTF-8"), Some((Format::Json, false)));
+        assert_eq!(media_format("application/x-ndjson+gzip"), Some((Format::JsonLines, true)));
+        assert_eq!(media_format("application/geo+json-seq"), Some((Format::JsonSeq, false)));
+        assert_eq!(media_format("application/gzip"), Some((Format::Json, true)));
+        assert_eq!(media_format("application/jsonp"), None);
+        assert_eq!(media_format(""), None);
+    }
+
+    #[test]
+    fn decode_formats() {
+        let decoded = decode(b" [1, 2] ", "application/json").unwrap();
+        assert_eq!((decoded.format(), decoded.records().len()), (Format::Json, 1));
+        assert_eq!(decoded.records()[0][1].as_i128(), Some(2));
+
+        let decoded = decode(b"\x1E{\"a\": 1}\n\x1E[2]\n", "application/json-seq").unwrap();
+        assert_eq!(decoded.records()[1][0].as_i128(), Some(2));
+
+        assert!(matches!(decode(b"[1] [2]", "application/json"), Err(DecodeError::Json(_))));
+        assert!(matches!(decode(b"[1]\n[2", "application/jsonl"), Err(DecodeError::Line(e)) if e.get_line() == 2));
+        assert!(matches!(decode(b"\x1E[", "application/json-seq"), Err(DecodeError::Record(_))));
+        assert!(matches!(decode(b"[\xFF]", "application/json"), Err(DecodeError::Io(_))));
+        assert!(matches!(decode(b"[]", "text/plain"), Err(DecodeError::UnsupportedMediaType(t)) if t == "text/plain"));
+    }
+
+    #[test]
+    #[cfg(feature = "gzip")]
+    fn decode_gzip() {
+        use std::io::Write;
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(b"{\"id\": 1}\n{\"id\": 2}\n").unwrap();
+        let compressed = encoder.finish().unwrap();
+        let decoded = decode(&compressed, "application/x-ndjson+gzip").unwrap();
+        assert_eq!(decoded.records().len(), 2);
+        assert_eq!(decoded.source(), "{\"id\": 1}\n{\"id\": 2}\n");
+    }
+}
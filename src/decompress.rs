@@ -0,0 +1,93 @@
+use std::io;
+use std::io::Read;
+#[cfg(any(feature = "gzip", feature = "zstd"))]
+use std::io::Cursor;
+
+#[cfg(feature = "gzip")]
+const GZIP_MAGIC: &[u8] = &[0x1F, 0x8B];
+#[cfg(feature = "zstd")]
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xB5, 0x2F, 0xFD];
+
+// Wraps `reader` in a decoder when its data starts with the magic number of an enabled compression format,
+// so that reading yields decompressed bytes
+#[cfg(any(feature = "gzip", feature = "zstd"))]
+pub(crate) fn decompress<'a, R: Read + 'a>(mut reader: R) -> io::Result<Box<dyn Read + 'a>> {
+    let mut magic = [0; 4];
+    let mut len = 0;
+    while len < magic.len() {
+        match reader.read(&mut magic[len..]) {
+            Ok(0) => { break; }
+            Ok(read) => { len += read; }
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+            Err(error) => { return Err(error); }
+        }
+    }
+    let head = &magic[..len];
+    let reader = Cursor::new(head.to_vec()).chain(reader);
+
+    #[cfg(feature = "gzip")]
+    if head.starts_with(GZIP_MAGIC) {
+        return Ok(Box::new(flate2::read::MultiGzDecoder::new(reader)));
+    }
+    #[cfg(feature = "zstd")]
+    if head.starts_with(ZSTD_MAGIC) {
+        let decoder = ruzstd::decoding::StreamingDecoder::new(reader)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        return Ok(Box::new(decoder));
+    }
+    Ok(Box::new(reader))
+}
+
+#[cfg(not(any(feature = "gzip", feature = "zstd")))]
+#[inline(always)]
+pub(crate) fn decompress<R: Read>(reader: R) -> io::Result<R> {
+    Ok(reader)
+}
+
+#[cfg(all(test, any(feature = "gzip", feature = "zstd")))]
+mod tests {
+    use std::io::{Cursor, Read};
+
+    use crate::decompress::decompress;
+    use crate::document::parse_reader_with_progress;
+
+    const JSON: &str = "{\"compressed\": [1, 2, 3]}";
+
+    fn read_all(data: &[u8]) -> String {
+        let mut output = String::new();
+        decompress(data).unwrap().read_to_string(&mut output).unwrap();
+        output
+    }
+
+    #[test]
+    fn pass_plain_data_through() {
+        assert_eq!(read_all(JSON.as_bytes()), JSON);
+        assert_eq!(read_all(b"[]"), "[]");
+        assert_eq!(read_all(b""), "");
+    }
+
+    #[test]
+    #[cfg(feature = "gzip")]
+    fn decompress_gzip() {
+        use std::io::Write;
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(JSON.as_bytes()).unwrap();
+        let compressed = encoder.finish().unwrap();
+        assert_eq!(read_all(&compressed), JSON);
+
+        let mut reports = Vec::new();
+        let document = parse_reader_with_progress(Cursor::new(&compressed), Some(compressed.len() as u64), |p| reports.push(p)).unwrap();
+        assert_eq!(document["compressed"][2].as_i128(), Some(3));
+        assert_eq!(reports.last().unwrap().get_processed(), compressed.len() as u64);
+    }
+
+    #[test]
+    #[cfg(feature = "zstd")]
+    fn decompress_zstd() {
+        let compressed = ruzstd::encoding::compress_to_vec(JSON.as_bytes(), ruzstd::encoding::CompressionLevel::Fastest);
+        assert_eq!(read_all(&compressed), JSON);
+        let document = parse_reader_with_progress(Cursor::new(&compressed), None, |_| {}).unwrap();
+        assert_eq!(document["compressed"][0].as_i128(), Some(1));
+    }
+}
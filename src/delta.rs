@@ -0,0 +1,658 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::ops::Range;
+
+use crate::document::JsonDocument;
+use crate::json_error::{ErrorKind, JsonError};
+use crate::json_item::JsonItem;
+use crate::json_type::JsonType::{JsonArray, JsonMap, JsonNumber, JsonString};
+use crate::key::Key;
+use crate::number::FloatEquality;
+use crate::parse;
+use crate::layout::{write_laid_out, Layout};
+use crate::serialize::write_compact;
+use crate::text::{escape_into, unescape};
+
+const SET: i128 = 0;
+const REMOVE: i128 = 1;
+const TRUNCATE: i128 = 2;
+
+/// Operation of a [`Delta`]. Paths are JSON Pointers (RFC 6901), the empty path designating the whole document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeltaOp {
+    /// Sets the value at `path` to the compact JSON text `value`, adding an object member
+    /// or appending an array element if the path designates the array length
+    Set { path: String, value: String },
+    /// Removes the object member at `path`
+    Remove { path: String },
+    /// Shortens the array at `path` to `len` elements
+    Truncate { path: String, len: usize },
+}
+
+/// Compact patch turning a document into the next one, see [`delta`] and [`apply_delta`].
+///
+/// Its JSON form, produced by [`Delta::to_json`], is an array of operations, each one written as an array:
+/// `[0, path, value]` for [`DeltaOp::Set`], `[1, path]` for [`DeltaOp::Remove`] and `[2, path, len]` for [`DeltaOp::Truncate`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Delta {
+    ops: Vec<DeltaOp>,
+}
+
+impl Delta {
+    /// Operations of the delta, in application order
+    pub fn ops(&self) -> &[DeltaOp] {
+        &self.ops
+    }
+
+    /// Checks if the delta has no operations, i.e. both documents are equal
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// Returns the compact JSON form of the delta
+    pub fn to_json(&self) -> String {
+        let mut output = String::from("[");
+        for (index, op) in self.ops.iter().enumerate() {
+            if index > 0 {
+                output.push(',');
+            }
+            match op {
+                DeltaOp::Set { path, value } => {
+                    output.push_str("[0,");
+                    escape_into(path, &mut output);
+                    output.push(',');
+                    output.push_str(value);
+                }
+                DeltaOp::Remove { path } => {
+                    output.push_str("[1,");
+                    escape_into(path, &mut output);
+                }
+                DeltaOp::Truncate { path, len } => {
+                    output.push_str("[2,");
+                    escape_into(path, &mut output);
+                    output.push(',');
+                    output.push_str(&len.to_string());
+                }
+            }
+            output.push(']');
+        }
+        output.push(']');
+        output
+    }
+
+    /// Parses the JSON form of a delta, as produced by [`Delta::to_json`]
+    ///
+    /// # Arguments
+    /// * `source` - Text content to be parsed
+    pub fn from_json(source: &str) -> Result<Delta, JsonError> {
+        let bytes = source.as_bytes();
+        let parsed = parse(source)?;
+        let invalid = |item: &JsonItem| JsonError::with_kind(item.slice.offset_in(bytes), ErrorKind::UnexpectedValue).located(bytes);
+        let mut ops = Vec::new();
+        for op in parsed.elements().ok_or_else(|| invalid(&parsed))? {
+            let path = match &op[1] {
+                path if path.json_type == JsonString => { path.as_unescaped().ok_or_else(|| invalid(path))?.into_owned() }
+                _ => { return Err(invalid(op)); }
+            };
+            let arity = op.elements().map_or(0, Iterator::count);
+            ops.push(match (op[0].as_i128(), arity) {
+                (Some(SET), 3) => {
+                    let mut value = String::new();
+                    write_compact(&op[2], &mut value);
+                    DeltaOp::Set { path, value }
+                }
+                (Some(REMOVE), 2) => { DeltaOp::Remove { path } }
+                (Some(TRUNCATE), 3) if op[2].json_type == JsonNumber => {
+                    let len = op[2].as_i128().and_then(|len| usize::try_from(len).ok()).ok_or_else(|| invalid(&op[2]))?;
+                    DeltaOp::Truncate { path, len }
+                }
+                _ => { return Err(invalid(op)); }
+            });
+        }
+        Ok(Delta { ops })
+    }
+}
+
+/// Error returned by [`apply_delta`] when an operation does not fit the document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeltaError {
+    op: usize,
+}
+
+impl DeltaError {
+    /// Index of the first operation that does not fit the document
+    pub fn get_op(&self) -> usize {
+        self.op
+    }
+}
+
+impl Display for DeltaError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "delta operation {} does not apply to the document", self.op)
+    }
+}
+
+impl Error for DeltaError {}
+
+fn compact(item: &JsonItem) -> String {
+    let mut output = String::new();
+    write_compact(item, &mut output);
+    output
+}
+
+fn key_text(key: &Key) -> String {
+    unescape(key.as_str()).map_or_else(|| key.as_str().to_owned(), |key| key.into_owned())
+}
+
+fn push_segment(path: &str, segment: &str) -> String {
+    format!("{}/{}", path, segment.replace('~', "~0").replace('/', "~1"))
+}
+
+fn diff(prev: &JsonItem, next: &JsonItem, path: &str, floats: FloatEquality, ops: &mut Vec<DeltaOp>) {
+    match (&prev.json_type, &next.json_type) {
+        (JsonMap, JsonMap) => {
+            let prev_entries = prev.entries().unwrap().map(|(k, v)| (key_text(k), v)).collect::<Vec<_>>();
+            let next_entries = next.entries().unwrap().map(|(k, v)| (key_text(k), v)).collect::<Vec<_>>();
+            for (key, _) in &prev_entries {
+                if !next_entries.iter().any(|(k, _)| k == key) {
+                    ops.push(DeltaOp::Remove { path: push_segment(path, key) });
+                }
+            }
+            for (key, value) in &next_entries {
+                match prev_entries.iter().find(|(k, _)| k == key) {
+                    Some((_, previous)) => { diff(previous, value, &push_segment(path, key), floats, ops) }
+                    None => { ops.push(DeltaOp::Set { path: push_segment(path, key), value: compact(value) }) }
+                }
+            }
+        }
+        (JsonArray, JsonArray) => {
+            let prev_elements = prev.elements().unwrap().collect::<Vec<_>>();
+            let next_elements = next.elements().unwrap().collect::<Vec<_>>();
+            for (index, (previous, element)) in prev_elements.iter().zip(&next_elements).enumerate() {
+                diff(previous, element, &push_segment(path, &index.to_string()), floats, ops);
+            }
+            if next_elements.len() < prev_elements.len() {
+                ops.push(DeltaOp::Truncate { path: path.to_owned(), len: next_elements.len() });
+            }
+            for (index, element) in next_elements.iter().enumerate().skip(prev_elements.len()) {
+                ops.push(DeltaOp::Set { path: push_segment(path, &index.to_string()), value: compact(element) });
+            }
+        }
+        (JsonNumber, JsonNumber) if prev.equals(next, floats) => {}
+        _ => {
+            let value = compact(next);
+            if compact(prev) != value {
+                ops.push(DeltaOp::Set { path: path.to_owned(), value });
+            }
+        }
+    }
+}
+
+/// Computes the delta turning `prev` into `next`, made of the values that changed, the object members that were removed,
+/// and the arrays that were shortened. Meant for pipelines sending near-identical documents repeatedly,
+/// where the delta is much smaller than the next document.
+///
+/// # Example
+///
+/// ```rust
+/// use jsonic::delta::{apply_delta, delta};
+///
+/// let prev = jsonic::parse("{\"host\": \"a\", \"cpu\": [0.5, 0.7], \"up\": true}").unwrap();
+/// let next = jsonic::parse("{\"host\": \"a\", \"cpu\": [0.5, 0.9, 0.1], \"up\": true}").unwrap();
+///
+/// let patch = delta(&prev, &next);
+/// assert_eq!(patch.to_json(), "[[0,\"/cpu/1\",0.9],[0,\"/cpu/2\",0.1]]");
+/// assert_eq!(apply_delta(&prev, &patch).unwrap(), "{\"host\":\"a\",\"cpu\":[0.5,0.9,0.1],\"up\":true}");
+/// ```
+pub fn delta(prev: &JsonItem, next: &JsonItem) -> Delta {
+    delta_with(prev, next, FloatEquality::Bitwise)
+}
+
+/// Same as [`delta`], but numbers considered equal according to `floats` are left unchanged,
+/// so that rounding noise in computed values does not show up in the delta.
+///
+/// # Example
+///
+/// ```rust
+/// use jsonic::delta::delta_with;
+/// use jsonic::number::FloatEquality;
+///
+/// let prev = jsonic::parse("{\"ratio\": 0.3, \"count\": 7}").unwrap();
+/// let next = jsonic::parse("{\"ratio\": 0.30000000000000004, \"count\": 8}").unwrap();
+///
+/// assert_eq!(delta_with(&prev, &next, FloatEquality::Epsilon(1e-9)).to_json(), "[[0,\"/count\",8]]");
+/// ```
+pub fn delta_with(prev: &JsonItem, next: &JsonItem, floats: FloatEquality) -> Delta {
+    let mut ops = Vec::new();
+    diff(prev, next, "", floats, &mut ops);
+    Delta { ops }
+}
+
+// Edits to apply to an item and its descendants
+#[derive(Default)]
+struct Edit<'d> {
+    op: usize,
+    set: Option<&'d str>,
+    remove: bool,
+    truncate: Option<usize>,
+    children: Vec<(String, Edit<'d>)>,
+}
+
+impl<'d> Edit<'d> {
+    fn child(&self, segment: &str) -> Option<&Edit<'d>> {
+        self.children.iter().find(|(s, _)| s == segment).map(|(_, edit)| edit)
+    }
+
+    fn descend(&mut self, path: &str, op: usize) -> Result<&mut Edit<'d>, DeltaError> {
+        let mut edit = self;
+        if path.is_empty() {
+            return Ok(edit);
+        }
+        let segments = path.strip_prefix('/').ok_or(DeltaError { op })?;
+        for segment in segments.split('/') {
+            let segment = segment.replace("~1", "/").replace("~0", "~");
+            let position = match edit.children.iter().position(|(s, _)| *s == segment) {
+                Some(position) => { position }
+                None => {
+                    edit.children.push((segment, Edit { op, ..Edit::default() }));
+                    edit.children.len() - 1
+                }
+            };
+            edit = &mut edit.children[position].1;
+        }
+        Ok(edit)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.set.is_none() && !self.remove && self.truncate.is_none() && self.children.is_empty()
+    }
+}
+
+fn write_edited(item: &JsonItem, edit: &Edit, output: &mut String) -> Result<(), DeltaError> {
+    if let Some(value) = edit.set {
+        output.push_str(value);
+        return Ok(());
+    }
+    match item.json_type {
+        JsonMap => {
+            if edit.truncate.is_some() {
+                return Err(DeltaError { op: edit.op });
+            }
+            output.push('{');
+            let mut first = true;
+            let mut write_key = |key: &str, output: &mut String| {
+                if !first {
+                    output.push(',');
+                }
+                first = false;
+                escape_into(key, output);
+                output.push(':');
+            };
+            let mut seen = Vec::new();
+            for (key, value) in item.entries().unwrap() {
+                let key = key_text(key);
+                match edit.child(&key) {
+                    Some(child) if child.remove => {}
+                    Some(child) => {
+                        write_key(&key, output);
+                        write_edited(value, child, output)?;
+                    }
+                    None => {
+                        write_key(&key, output);
+                        write_compact(value, output);
+                    }
+                }
+                seen.push(key);
+            }
+            for (key, child) in &edit.children {
+                if !seen.contains(key) {
+                    let value = child.set.ok_or(DeltaError { op: child.op })?;
+                    write_key(key, output);
+                    output.push_str(value);
+                }
+            }
+            output.push('}');
+        }
+        JsonArray => {
+            let elements = item.elements().unwrap().collect::<Vec<_>>();
+            let len = edit.truncate.unwrap_or(elements.len());
+            if len > elements.len() {
+                return Err(DeltaError { op: edit.op });
+            }
+            let mut indexed = Vec::with_capacity(edit.children.len());
+            for (segment, child) in &edit.children {
+                let index = segment.parse::<usize>().map_err(|_| DeltaError { op: child.op })?;
+                if child.remove || (index >= len && child.set.is_none()) {
+                    return Err(DeltaError { op: child.op });
+                }
+                indexed.push((index, child));
+            }
+            indexed.sort_by_key(|(index, _)| *index);
+            output.push('[');
+            for (index, element) in elements.iter().take(len).enumerate() {
+                if index > 0 {
+                    output.push(',');
+                }
+                match indexed.iter().find(|(i, _)| *i == index) {
+                    Some((_, child)) => { write_edited(element, child, output)?; }
+                    None => { write_compact(element, output); }
+                }
+            }
+            for (next, (index, child)) in (len..).zip(indexed.iter().filter(|(index, _)| *index >= len)) {
+                if *index != next {
+                    return Err(DeltaError { op: child.op });
+                }
+                if next > 0 {
+                    output.push(',');
+                }
+                output.push_str(child.set.unwrap());
+            }
+            output.push(']');
+        }
+        _ => {
+            if !edit.is_empty() {
+                return Err(DeltaError { op: edit.op });
+            }
+            write_compact(item, output);
+        }
+    }
+    Ok(())
+}
+
+// Edits to apply to the root item, gathered by path
+fn edits(delta: &Delta) -> Result<Edit<'_>, DeltaError> {
+    let mut root = Edit::default();
+    for (op, delta_op) in delta.ops.iter().enumerate() {
+        match delta_op {
+            DeltaOp::Set { path, value } => {
+                let edit = root.descend(path, op)?;
+                edit.op = op;
+                edit.set = Some(value);
+                edit.children.clear();
+                edit.truncate = None;
+            }
+            DeltaOp::Remove { path } => {
+                if path.is_empty() {
+                    return Err(DeltaError { op });
+                }
+                let edit = root.descend(path, op)?;
+                edit.op = op;
+                edit.remove = true;
+            }
+            DeltaOp::Truncate { path, len } => {
+                let edit = root.descend(path, op)?;
+                edit.op = op;
+                edit.truncate = Some(*len);
+            }
+        }
+    }
+    Ok(root)
+}
+
+/// Applies `delta` to `prev`, returning the compact JSON text of the resulting document.
+/// New object members are added after existing ones.
+///
+/// # Errors
+/// If an operation does not fit `prev`, e.g. it removes a missing member or designates a child of a scalar.
+pub fn apply_delta(prev: &JsonItem, delta: &Delta) -> Result<String, DeltaError> {
+    let root = edits(delta)?;
+    let mut output = String::new();
+    write_edited(prev, &root, &mut output)?;
+    Ok(output)
+}
+
+// Writes the container at `range` with `members`, each one given with the index of the original member it stands for,
+// if any, `ends` being the offsets where the original members end. The separators recorded in `layout` before the first
+// original member, after the last one and between them are kept, new members being separated as the last two original
+// ones, or by a comma followed by the whitespace after the bracket.
+fn write_layout(layout: &Layout<'_>, range: Range<usize>, ends: &[usize], members: Vec<(Option<usize>, String)>, output: &mut String) {
+    let text = layout.text(range.clone());
+    let (open, close) = (&text[..1], &text[text.len() - 1..]);
+    let inner = layout.after(range.start + 1).unwrap_or("");
+    output.push_str(open);
+    let last = match ends.last() {
+        Some(last) => { *last }
+        None => {
+            output.push_str(&members.into_iter().map(|(_, text)| text).collect::<Vec<_>>().join(","));
+            output.push_str(inner);
+            output.push_str(close);
+            return;
+        }
+    };
+    let separator = |index: usize| { layout.after(ends[index]).unwrap_or(",") };
+    let default_separator = match ends.len() {
+        1 => {
+            if inner.bytes().all(|b| b.is_ascii_whitespace()) { format!(",{}", inner) } else { ",".to_owned() }
+        }
+        len => { separator(len - 2).to_owned() }
+    };
+    output.push_str(inner);
+    let mut previous = None;
+    for (index, (original, text)) in members.into_iter().enumerate() {
+        if index > 0 {
+            match previous {
+                Some(previous) if previous + 1 < ends.len() => { output.push_str(separator(previous)); }
+                _ => { output.push_str(&default_separator); }
+            }
+        }
+        output.push_str(&text);
+        previous = original;
+    }
+    output.push_str(layout.after(last).unwrap_or(""));
+    output.push_str(close);
+}
+
+fn write_preserved(item: &JsonItem, edit: &Edit, layout: &Layout<'_>, output: &mut String) -> Result<(), DeltaError> {
+    if let Some(value) = edit.set {
+        output.push_str(value);
+        return Ok(());
+    }
+    let range = layout.range(item).ok_or(DeltaError { op: edit.op })?;
+    if edit.is_empty() {
+        write_laid_out(item, layout, output);
+        return Ok(());
+    }
+    if item.is_stub() {
+        let (expanded, layout) = layout.expand(item).map_err(|_| DeltaError { op: edit.op })?;
+        return write_preserved(&expanded, edit, &layout, output);
+    }
+    match item.json_type {
+        JsonMap => {
+            if edit.truncate.is_some() {
+                return Err(DeltaError { op: edit.op });
+            }
+            // Members in source order, with the range of their key and the offset where their value ends
+            let mut entries = item.entries().unwrap().map(|(key, value)| {
+                (layout.key_range(key), layout.range(value).unwrap().end, key_text(key), value)
+            }).collect::<Vec<_>>();
+            entries.sort_by_key(|(key_range, ..)| key_range.start);
+            let colon = entries.last().and_then(|(key_range, ..)| layout.after(key_range.end)).unwrap_or(":");
+
+            let mut members = Vec::with_capacity(entries.len());
+            for (index, (key_range, _, key, value)) in entries.iter().enumerate() {
+                let child = edit.child(key);
+                if child.is_some_and(|child| child.remove) {
+                    continue;
+                }
+                let mut text = layout.text(key_range.clone()).to_owned();
+                text.push_str(layout.after(key_range.end).unwrap_or(":"));
+                match child {
+                    Some(child) => { write_preserved(value, child, layout, &mut text)?; }
+                    None => { write_laid_out(value, layout, &mut text); }
+                }
+                members.push((Some(index), text));
+            }
+            for (key, child) in &edit.children {
+                if !entries.iter().any(|(_, _, k, _)| k == key) {
+                    let mut text = String::new();
+                    escape_into(key, &mut text);
+                    text.push_str(colon);
+                    text.push_str(child.set.ok_or(DeltaError { op: child.op })?);
+                    members.push((None, text));
+                }
+            }
+            let ends = entries.into_iter().map(|(_, end, ..)| end).collect::<Vec<_>>();
+            write_layout(layout, range, &ends, members, output);
+        }
+        JsonArray => {
+            let elements = item.elements().unwrap().collect::<Vec<_>>();
+            let len = edit.truncate.unwrap_or(elements.len());
+            if len > elements.len() {
+                return Err(DeltaError { op: edit.op });
+            }
+            let mut indexed = Vec::with_capacity(edit.children.len());
+            for (segment, child) in &edit.children {
+                let index = segment.parse::<usize>().map_err(|_| DeltaError { op: child.op })?;
+                if child.remove || (index >= len && child.set.is_none()) {
+                    return Err(DeltaError { op: child.op });
+                }
+                indexed.push((index, child));
+            }
+            indexed.sort_by_key(|(index, _)| *index);
+
+            let mut members = Vec::with_capacity(len + indexed.len());
+            for (index, element) in elements.iter().take(len).enumerate() {
+                let mut text = String::new();
+                match indexed.iter().find(|(i, _)| *i == index) {
+                    Some((_, child)) => { write_preserved(element, child, layout, &mut text)?; }
+                    None => { write_laid_out(element, layout, &mut text); }
+                }
+                members.push((Some(index), text));
+            }
+            for (next, (index, child)) in (len..).zip(indexed.iter().filter(|(index, _)| *index >= len)) {
+                if *index != next {
+                    return Err(DeltaError { op: child.op });
+                }
+                members.push((None, child.set.unwrap().to_owned()));
+            }
+            let ends = elements.iter().map(|element| layout.range(element).unwrap().end).collect::<Vec<_>>();
+            write_layout(layout, range, &ends, members, output);
+        }
+        _ => { return Err(DeltaError { op: edit.op }); }
+    }
+    Ok(())
+}
+
+/// Applies `delta` to `doc` like [`apply_delta`], but writes everything the delta leaves unchanged with the layout
+/// recorded while parsing `doc` with [`ParseOptions::preserve_layout`](crate::options::ParseOptions::preserve_layout),
+/// see [`JsonDocument::to_json_preserving`]: whitespace, comments, number and string spellings and member order are
+/// kept byte for byte. Only the values set by the delta are written as compact JSON, so that tools can change a field
+/// of a hand-written file without reformatting it. With an empty delta, the source text is returned as is.
+/// Without a recorded layout, the layout is recovered from the spans of the items of `doc`.
+///
+/// New members and elements are separated from the previous ones as the last two members of their container are,
+/// and removed members are dropped along with the separator following them, or preceding them for the last one.
+///
+/// # Errors
+/// If an operation does not fit `doc`, see [`apply_delta`].
+///
+/// # Example
+///
+/// ```rust
+/// use jsonic::delta::{apply_delta_preserving, Delta, DeltaOp};
+/// use jsonic::options::{ParseOptions, Parser};
+///
+/// let parser = Parser::new(ParseOptions::new().preserve_layout(true));
+/// let config = parser.parse_document("{\n  \"port\": 8080,\n  \"hosts\": [\"a\", \"b\"],\n  \"debug\": true\n}\n".to_owned()).unwrap();
+/// assert_eq!(apply_delta_preserving(&config, &Delta::default()).unwrap(), config.source());
+///
+/// let patch = Delta::from_json("[[0, \"/port\", 9090], [0, \"/hosts/2\", \"c\"], [1, \"/debug\"]]").unwrap();
+/// let edited = apply_delta_preserving(&config, &patch).unwrap();
+/// assert_eq!(edited, "{\n  \"port\": 9090,\n  \"hosts\": [\"a\", \"b\", \"c\"]\n}\n");
+/// ```
+pub fn apply_delta_preserving(doc: &JsonDocument, delta: &Delta) -> Result<String, DeltaError> {
+    let root = edits(delta)?;
+    let layout = doc.layout();
+    let range = layout.range(doc.root()).unwrap();
+    let mut output = String::from(layout.after(0).unwrap_or(""));
+    write_preserved(doc.root(), &root, &layout, &mut output)?;
+    output.push_str(layout.after(range.end).unwrap_or(""));
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::delta::{apply_delta, apply_delta_preserving, delta, delta_with, Delta, DeltaOp};
+    use crate::document::ParseJsonExt;
+    use crate::number::FloatEquality;
+    use crate::options::{ParseOptions, Parser};
+    use crate::parse;
+
+    fn round_trip(prev: &str, next: &str) -> Delta {
+        let (prev, next) = (parse(prev).unwrap(), parse(next).unwrap());
+        let patch = delta(&prev, &next);
+        let mut expected = String::new();
+        crate::serialize::write_compact(&next, &mut expected);
+        assert_eq!(apply_delta(&prev, &patch).unwrap(), expected);
+        assert_eq!(Delta::from_json(&patch.to_json()).unwrap(), patch);
+        patch
+    }
+
+    #[test]
+    fn diff_and_apply() {
+        assert!(round_trip("{\"a\": [1, 2]}", "{\"a\":[1,2]}").is_empty());
+        let patch = round_trip("{\"a\": 1, \"b\": {\"c\": [1, 2, 3], \"d\": null}, \"e/~\": 0}", "{\"a\": 1, \"b\": {\"c\": [1, 5]}, \"e/~\": 0, \"f\": \"x\"}");
+        assert_eq!(patch.ops(), &[
+            DeltaOp::Remove { path: "/b/d".to_owned() },
+            DeltaOp::Set { path: "/b/c/1".to_owned(), value: "5".to_owned() },
+            DeltaOp::Truncate { path: "/b/c".to_owned(), len: 2 },
+            DeltaOp::Set { path: "/f".to_owned(), value: "\"x\"".to_owned() },
+        ]);
+        round_trip("{\"e/~\": {\"x\": 1}}", "{\"e/~\": {\"x\": 2}}");
+        round_trip("[1, {\"a\": []}]", "[1, {\"a\": [true, [false]]}, 3]");
+        round_trip("[1, 2]", "{\"a\": 1}");
+        round_trip("[[1, 2], 3]", "[[], 3]");
+    }
+
+    #[test]
+    fn reject_invalid_deltas() {
+        let prev = parse("{\"a\": [1, 2], \"b\": 3}").unwrap();
+        for (json, op) in [("[[1,\"/c\"]]", 0), ("[[0,\"/b/x\",1]]", 0), ("[[0,\"/a/3\",1]]", 0), ("[[0,\"/a/2\",1],[2,\"/a\",5]]", 1), ("[[0,\"a\",1]]", 0)] {
+            let patch = Delta::from_json(json).unwrap();
+            assert_eq!(apply_delta(&prev, &patch).unwrap_err().get_op(), op, "{}", json);
+        }
+        for json in ["{}", "[[3,\"/a\"]]", "[[0,\"/a\"]]", "[[2,\"/a\",-1]]", "[[1,2]]"] {
+            assert!(Delta::from_json(json).is_err(), "{}", json);
+        }
+    }
+
+    #[test]
+    fn ignore_float_noise() {
+        let prev = parse("[0.3, 1e-3, 2, -0.0]").unwrap();
+        let next = parse("[0.30000000000000004, 1.0e-3, 2.0, 0]").unwrap();
+        assert_eq!(delta(&prev, &next).ops().len(), 2);
+        assert_eq!(delta_with(&prev, &next, FloatEquality::Ulps(1)).ops().len(), 0);
+        assert_eq!(delta_with(&prev, &next, FloatEquality::Epsilon(1e-20)).ops(), &[DeltaOp::Set { path: "/0".to_owned(), value: "0.30000000000000004".to_owned() }]);
+    }
+
+    #[test]
+    fn preserve_layout() {
+        let source = " {\"a\" :1.50,\n\t\"b\": {\"c\": [ 1 ], \"d\": \"\\u0041\"},\n\t\"e\": [],\n\t\"f\": {}\n} \n";
+        let parser = Parser::new(ParseOptions::new().preserve_layout(true));
+        let doc = parser.parse_document(source.to_owned()).unwrap();
+        let apply = |patch: &str| apply_delta_preserving(&doc, &Delta::from_json(patch).unwrap());
+        assert_eq!(apply("[]").unwrap(), source);
+        assert_eq!(apply("[[0,\"/b/c/0\",2],[0,\"/b/c/1\",3]]").unwrap(), source.replace("[ 1 ]", "[ 2, 3 ]"));
+        assert_eq!(apply("[[1,\"/a\"],[1,\"/b/d\"]]").unwrap(), " {\"b\": {\"c\": [ 1 ]},\n\t\"e\": [],\n\t\"f\": {}\n} \n");
+        assert_eq!(apply("[[1,\"/b\"],[0,\"/g\",null]]").unwrap(), " {\"a\" :1.50,\n\t\"e\": [],\n\t\"f\": {},\n\t\"g\": null\n} \n");
+        assert_eq!(apply("[[0,\"/e/0\",1],[0,\"/f/x\",1],[2,\"/b/c\",0]]").unwrap(), source.replace("[ 1 ]", "[  ]").replace("[]", "[1]").replace("{}", "{\"x\":1}"));
+        assert_eq!(apply("[[0,\"\",0]]").unwrap(), " 0 \n");
+        for (patch, op) in [("[[1,\"/x\"]]", 0), ("[[0,\"/a/x\",1]]", 0), ("[[2,\"/b\",0]]", 0), ("[[0,\"/e/1\",1]]", 0)] {
+            assert_eq!(apply(patch).unwrap_err().get_op(), op, "{}", patch);
+        }
+
+        let doc = parser.parse_document("[\n  {\"a\": [1]}\n]".to_owned()).unwrap();
+        let patch = Delta::from_json("[[0,\"/1\",2],[0,\"/0/a/1\",3]]").unwrap();
+        assert_eq!(apply_delta_preserving(&doc, &patch).unwrap(), "[\n  {\"a\": [1,3]},\n  2\n]");
+        let doc = "[\n  {\"a\": [1]}\n]".parse_json().unwrap();
+        assert_eq!(apply_delta_preserving(&doc, &patch).unwrap(), "[\n  {\"a\": [1,3]},\n  2\n]");
+        #[cfg(feature = "extended")]
+        {
+            let source = String::from("{\n  // port\n  port: 80, /* x */ 'tags': [[1], 2,],\n}");
+            let doc = Parser::new(ParseOptions::json5().materialize_depth(1).preserve_layout(true)).parse_document(source.clone()).unwrap();
+            assert_eq!(apply_delta_preserving(&doc, &Delta::default()).unwrap(), source);
+            let patch = Delta::from_json("[[0,\"/port\",81],[0,\"/tags/0/1\",1],[0,\"/tags/2\",3]]").unwrap();
+            assert_eq!(apply_delta_preserving(&doc, &patch).unwrap(), "{\n  // port\n  port: 81, /* x */ 'tags': [[1,1], 2, 3,],\n}");
+        }
+    }
+}
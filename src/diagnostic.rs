@@ -0,0 +1,171 @@
+use std::fmt::Write;
+use std::ops::Range;
+
+use crate::json_error::{location, ErrorKind, JsonError};
+
+/// Part of the source text pointed at by a diagnostic, with a message, see [`JsonError::get_labels`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Label {
+    range: Range<usize>,
+    message: String,
+    primary: bool,
+}
+
+impl Label {
+    /// Byte range of the labeled text, empty at the end of data
+    pub fn get_range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// Message shown next to the labeled text
+    pub fn get_message(&self) -> &str {
+        &self.message
+    }
+
+    /// Whether the label points at the error itself, rather than at related text
+    pub fn is_primary(&self) -> bool {
+        self.primary
+    }
+}
+
+impl JsonError {
+    /// Returns a stable identifier of the kind of error, such as `jsonic::unexpected_character`
+    pub fn get_code(&self) -> &'static str {
+        match self.get_kind() {
+            ErrorKind::UnexpectedCharacter => { "jsonic::unexpected_character" }
+            ErrorKind::UnexpectedEof => { "jsonic::unexpected_eof" }
+            ErrorKind::UnterminatedString => { "jsonic::unterminated_string" }
+            ErrorKind::UnterminatedComment => { "jsonic::unterminated_comment" }
+            ErrorKind::InvalidNumber => { "jsonic::invalid_number" }
+            ErrorKind::InvalidEscape => { "jsonic::invalid_escape" }
+            ErrorKind::TrailingData => { "jsonic::trailing_data" }
+            ErrorKind::UnexpectedValue => { "jsonic::unexpected_value" }
+            ErrorKind::DepthLimitExceeded => { "jsonic::depth_limit_exceeded" }
+            ErrorKind::SizeLimitExceeded => { "jsonic::size_limit_exceeded" }
+            ErrorKind::NodeLimitExceeded => { "jsonic::node_limit_exceeded" }
+            ErrorKind::KeyLimitExceeded => { "jsonic::key_limit_exceeded" }
+            ErrorKind::StringLengthLimitExceeded => { "jsonic::string_length_limit_exceeded" }
+            ErrorKind::DuplicateKey => { "jsonic::duplicate_key" }
+        }
+    }
+
+    /// Returns a hint on fixing the data or the parsing options, if there is one for this kind of error
+    pub fn get_help(&self) -> Option<&'static str> {
+        match self.get_kind() {
+            ErrorKind::UnterminatedString => { Some("add the closing '\"'") }
+            ErrorKind::UnterminatedComment => { Some("close the comment with '*/'") }
+            ErrorKind::InvalidNumber => { Some("JSON numbers have no leading zeros nor '+' sign, and digits on both sides of '.'") }
+            ErrorKind::InvalidEscape => { Some("valid escapes are \\\", \\\\, \\/, \\b, \\f, \\n, \\r, \\t and \\uXXXX") }
+            ErrorKind::TrailingData => { Some("a document holds a single root value, parse the data as NDJSON for one value per line") }
+            ErrorKind::DepthLimitExceeded => { Some("raise the limit with ParseOptions::max_depth") }
+            ErrorKind::SizeLimitExceeded => { Some("raise the limit with ParseOptions::max_input_size") }
+            ErrorKind::NodeLimitExceeded => { Some("raise the limit with ParseOptions::max_nodes") }
+            ErrorKind::KeyLimitExceeded => { Some("raise the limit with ParseOptions::max_keys") }
+            ErrorKind::StringLengthLimitExceeded => { Some("raise the limit with ParseOptions::max_string_len") }
+            ErrorKind::DuplicateKey => { Some("remove one of the members, or choose another ParseOptions::duplicate_keys policy") }
+            _ => { None }
+        }
+    }
+
+    /// Returns the parts of `source` to point at when reporting the error, `source` being the text it was raised on:
+    /// the character at the error index, or both occurrences of the key for [`ErrorKind::DuplicateKey`] errors.
+    /// With [`get_code`](JsonError::get_code) and [`get_help`](JsonError::get_help), labels map directly onto
+    /// diagnostic reporting crates such as `miette` or `ariadne`.
+    pub fn get_labels(&self, source: &str) -> Vec<Label> {
+        if let Some((first, second)) = self.get_duplicate_spans() {
+            return vec![
+                Label { range: first, message: "first occurrence".to_owned(), primary: false },
+                Label { range: second, message: "duplicate key".to_owned(), primary: true },
+            ];
+        }
+        let start = usize::min(self.get_index(), source.len());
+        let length = source.get(start..).and_then(|rest| rest.chars().next()).map_or(0, char::len_utf8);
+        let message = match (self.get_expected(), self.get_kind()) {
+            (Some(expected), _) => { format!("expected {}", expected) }
+            (None, ErrorKind::UnexpectedEof) => { "data ends here".to_owned() }
+            (None, ErrorKind::UnterminatedString) => { "string is not closed".to_owned() }
+            (None, ErrorKind::UnterminatedComment) => { "comment starts here".to_owned() }
+            (None, ErrorKind::TrailingData) => { "data after the root value".to_owned() }
+            (None, kind) => { kind.description().trim_start_matches("JSON ").to_owned() }
+        };
+        vec![Label { range: start..start + length, message, primary: true }]
+    }
+
+    /// Renders the error as a compiler-style report for terminals: code and description, location in the file `name`,
+    /// lines of `source` with the [labels](JsonError::get_labels) underlined, and help if any.
+    /// `source` must be the text the error was raised on.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let source = "{\n  \"a\": 1,\n  \"a\": 2\n}";
+    /// let options = jsonic::ParseOptions::new().duplicate_keys(jsonic::options::DuplicateKeys::Error);
+    /// let error = jsonic::Parser::new(options).parse(source).unwrap_err();
+    ///
+    /// assert_eq!(error.report(source, "config.json"), "\
+    /// error[jsonic::duplicate_key]: JSON duplicate key
+    ///  --> config.json:3:4
+    ///   |
+    /// 2 |   \"a\": 1,
+    ///   |    - first occurrence
+    /// 3 |   \"a\": 2
+    ///   |    ^ duplicate key
+    ///   = help: remove one of the members, or choose another ParseOptions::duplicate_keys policy
+    /// ");
+    /// ```
+    pub fn report(&self, source: &str, name: &str) -> String {
+        let mut labels = self.get_labels(source);
+        labels.sort_by_key(|label| label.range.start);
+        let lines = labels.iter().map(|label| location(source.as_bytes(), label.range.start)).collect::<Vec<_>>();
+        let width = lines.iter().map(|(line, _, _)| line.to_string().len()).max().unwrap_or(1);
+
+        let mut report = format!("error[{}]: {}\n", self.get_code(), self.get_kind().description());
+        let (line, column, _) = location(source.as_bytes(), self.get_index());
+        let _ = writeln!(report, "{:width$}--> {}:{}:{}", "", name, line, column, width = width);
+        let _ = writeln!(report, "{:width$} |", "", width = width);
+        let mut previous = 0;
+        for (label, (line, column, line_start)) in labels.iter().zip(lines) {
+            let line_end = source[line_start..].find('\n').map_or(source.len(), |end| line_start + end);
+            let text = source[line_start..line_end].trim_end_matches('\r');
+            if line != previous {
+                let text = text.chars().map(|c| if c.is_control() { ' ' } else { c }).collect::<String>();
+                let _ = writeln!(report, "{:>width$} | {}", line, text, width = width);
+                previous = line;
+            }
+            let underlined = source.get(label.range.start..usize::min(label.range.end, line_start + text.len())).map_or(0, |text| text.chars().count());
+            let marker = if label.primary { "^" } else { "-" }.repeat(usize::max(underlined, 1));
+            let _ = writeln!(report, "{:width$} | {:indent$}{} {}", "", "", marker, label.message, width = width, indent = column - 1);
+        }
+        if let Some(help) = self.get_help() {
+            let _ = writeln!(report, "{:width$} = help: {}", "", help, width = width);
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse, parse_strict};
+
+    #[test]
+    fn label_errors() {
+        let source = "[1,\n\t2 3]";
+        let error = parse(source).unwrap_err();
+        assert_eq!(error.get_code(), "jsonic::unexpected_character");
+        assert!(error.get_help().is_none());
+        let labels = error.get_labels(source);
+        assert_eq!(labels.len(), 1);
+        assert_eq!((labels[0].get_range(), labels[0].get_message(), labels[0].is_primary()), (7..8, "expected ',' or ']'", true));
+        assert_eq!(error.report(source, "a.json"), "error[jsonic::unexpected_character]: JSON unexpected character\n --> a.json:2:4\n  |\n2 |  2 3]\n  |    ^ expected ',' or ']'\n");
+
+        let source = "[\"é";
+        let error = parse(source).unwrap_err();
+        assert_eq!(error.get_labels(source)[0].get_range(), 4..4);
+        assert!(error.report(source, "b.json").ends_with("1 | [\"é\n  |    ^ string is not closed\n  = help: add the closing '\"'\n"));
+
+        let source = "[1] [2]";
+        let error = parse_strict(source).unwrap_err();
+        assert_eq!(error.get_labels(source)[0].get_message(), "data after the root value");
+        assert_eq!(error.get_labels("")[0].get_range(), 0..0);
+    }
+}
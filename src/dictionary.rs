@@ -0,0 +1,177 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::json_item::JsonItem;
+use crate::json_type::JsonType::JsonString;
+use crate::key::Key;
+use crate::text::unescape;
+
+const DEFAULT_MAX_ENTRIES: usize = 64 * 1024;
+const DEFAULT_MAX_BYTES: usize = 4 * 1024 * 1024;
+
+#[derive(Default)]
+struct Entries {
+    strings: HashSet<Arc<str>>,
+    bytes: usize,
+}
+
+/// Thread-safe string interner shared across many parsed documents.
+/// When ingesting homogeneous event streams, records extracted from each document keep asking for the same keys
+/// and enumeration-like string values; interning them makes every record share a single allocation per distinct string,
+/// so steady-state memory no longer grows with the number of records.
+///
+/// The dictionary holds at most `max_entries` strings totalling at most `max_bytes` bytes. Once either cap is reached,
+/// strings that are not already interned are returned as fresh, unshared allocations.
+/// Clones are handles to the same dictionary.
+///
+/// # Example
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use jsonic::dictionary::SharedDictionary;
+///
+/// let dictionary = SharedDictionary::new();
+/// let first = jsonic::parse("{\"level\": \"warn\"}").unwrap();
+/// let second = jsonic::parse("{\"level\": \"warn\"}").unwrap();
+///
+/// let a = dictionary.string_value(&first["level"]).unwrap();
+/// let b = dictionary.string_value(&second["level"]).unwrap();
+/// assert!(Arc::ptr_eq(&a, &b));
+/// assert_eq!(dictionary.len(), 1);
+/// ```
+#[derive(Clone)]
+pub struct SharedDictionary {
+    entries: Arc<Mutex<Entries>>,
+    max_entries: usize,
+    max_bytes: usize,
+}
+
+impl SharedDictionary {
+    /// Creates a dictionary with default caps (65,536 strings, 4 MiB)
+    pub fn new() -> Self {
+        Self::with_limits(DEFAULT_MAX_ENTRIES, DEFAULT_MAX_BYTES)
+    }
+
+    /// Creates a dictionary holding at most `max_entries` strings totalling at most `max_bytes` bytes
+    pub fn with_limits(max_entries: usize, max_bytes: usize) -> Self {
+        SharedDictionary { entries: Arc::default(), max_entries, max_bytes }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Entries> {
+        // Entries are always left consistent, so a poisoned lock can be recovered
+        self.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Returns the shared copy of `text`, interning it if the caps allow
+    pub fn intern(&self, text: &str) -> Arc<str> {
+        let mut entries = self.lock();
+        if let Some(shared) = entries.strings.get(text) {
+            return shared.clone();
+        }
+        let shared = Arc::<str>::from(text);
+        if entries.strings.len() < self.max_entries && entries.bytes + text.len() <= self.max_bytes {
+            entries.bytes += text.len();
+            entries.strings.insert(shared.clone());
+        }
+        shared
+    }
+
+    /// Returns the shared copy of the text of `key`, with escape sequences decoded
+    pub fn key(&self, key: &Key) -> Arc<str> {
+        let raw = key.as_str();
+        self.intern(&unescape(raw).unwrap_or_else(|| raw.into()))
+    }
+
+    /// If the item is a string, returns the shared copy of its text, with escape sequences decoded.
+    /// Otherwise, returns `None`.
+    pub fn string_value(&self, item: &JsonItem) -> Option<Arc<str>> {
+        if item.json_type != JsonString {
+            None
+        } else {
+            Some(self.intern(&item.as_unescaped()?))
+        }
+    }
+
+    /// Number of interned strings
+    pub fn len(&self) -> usize {
+        self.lock().strings.len()
+    }
+
+    /// Checks if no string is interned
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Total size in bytes of interned strings
+    pub fn bytes(&self) -> usize {
+        self.lock().bytes
+    }
+
+    /// Forgets all interned strings. Copies handed out so far stay valid.
+    pub fn clear(&self) {
+        *self.lock() = Entries::default();
+    }
+}
+
+impl Default for SharedDictionary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::thread;
+
+    use crate::dictionary::SharedDictionary;
+    use crate::parse;
+
+    #[test]
+    fn intern_keys_and_values() {
+        let dictionary = SharedDictionary::new();
+        let documents = ["{\"k\\u0065y\": \"v\", \"n\": 1}", "{\"key\": \"v\", \"n\": 2}"];
+        let mut keys = Vec::new();
+        for document in documents {
+            let parsed = parse(document).unwrap();
+            for (key, value) in parsed.entries().unwrap() {
+                keys.push(dictionary.key(key));
+                assert_eq!(dictionary.string_value(value).is_some(), key.as_str() != "n");
+            }
+        }
+        assert_eq!(&*keys[0], "key");
+        assert!(Arc::ptr_eq(&keys[0], &keys[2]));
+        assert!(Arc::ptr_eq(&keys[1], &keys[3]));
+        assert_eq!(dictionary.len(), 3);
+        assert_eq!(dictionary.bytes(), 5);
+    }
+
+    #[test]
+    fn respect_caps() {
+        let dictionary = SharedDictionary::with_limits(2, 100);
+        dictionary.intern("a");
+        dictionary.intern("b");
+        let c = dictionary.intern("c");
+        assert!(!Arc::ptr_eq(&c, &dictionary.intern("c")));
+        assert!(Arc::ptr_eq(&dictionary.intern("a"), &dictionary.intern("a")));
+        assert_eq!(dictionary.len(), 2);
+
+        let dictionary = SharedDictionary::with_limits(100, 4);
+        dictionary.intern("abc");
+        dictionary.intern("de");
+        assert_eq!((dictionary.len(), dictionary.bytes()), (1, 3));
+        dictionary.clear();
+        assert!(dictionary.is_empty());
+    }
+
+    #[test]
+    fn share_across_threads() {
+        let dictionary = SharedDictionary::new();
+        let handles = (0..4).map(|_| {
+            let dictionary = dictionary.clone();
+            thread::spawn(move || dictionary.intern("shared"))
+        }).collect::<Vec<_>>();
+        let interned = handles.into_iter().map(|handle| handle.join().unwrap()).collect::<Vec<_>>();
+        assert!(interned.windows(2).all(|w| Arc::ptr_eq(&w[0], &w[1])));
+    }
+}
@@ -0,0 +1,347 @@
+use std::fmt::Write;
+
+use crate::json_item::JsonItem;
+use crate::json_type::JsonType::{Empty, JsonArray, JsonMap, JsonNumber};
+use crate::number::FloatEquality;
+use crate::serialize::{write_compact, write_string};
+
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const CYAN: &str = "\x1b[36m";
+const RESET: &str = "\x1b[0m";
+
+// Document a line of the rendering belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Both,
+    Prev,
+    Next,
+}
+
+// Line of the pretty-printed documents, indented, without separators.
+// Lines of the same change share `change`, the index of its first line.
+struct Row {
+    side: Side,
+    text: String,
+    change: usize,
+}
+
+// Pretty-prints `item` as lines of one document, `prefix` being the key of object members
+fn push_value(item: &JsonItem, indent: usize, prefix: &str, side: Side, rows: &mut Vec<Row>) {
+    let (open, close, members) = match (item.elements(), item.entries()) {
+        (Some(elements), _) => { ("[", "]", elements.map(|element| (String::new(), element)).collect::<Vec<_>>()) }
+        (_, Some(entries)) => { ("{", "}", entries.map(|(key, value)| (key_prefix(key.as_str()), value)).collect::<Vec<_>>()) }
+        _ => {
+            let mut text = format!("{:indent$}{}", "", prefix, indent = indent);
+            write_compact(item, &mut text);
+            rows.push(Row { side, text, change: 0 });
+            return;
+        }
+    };
+    if members.is_empty() {
+        rows.push(Row { side, text: format!("{:indent$}{}{}{}", "", prefix, open, close, indent = indent), change: 0 });
+        return;
+    }
+    rows.push(Row { side, text: format!("{:indent$}{}{}", "", prefix, open, indent = indent), change: 0 });
+    for (key, value) in members {
+        push_value(value, indent + 2, &key, side, rows);
+    }
+    rows.push(Row { side, text: format!("{:indent$}{}", "", close, indent = indent), change: 0 });
+}
+
+// Pretty-prints a value removed from `prev` and its replacement added to `next`, either one being optional
+fn push_change(prev: Option<&JsonItem>, next: Option<&JsonItem>, indent: usize, prefix: &str, rows: &mut Vec<Row>) {
+    let start = rows.len();
+    if let Some(prev) = prev {
+        push_value(prev, indent, prefix, Side::Prev, rows);
+    }
+    if let Some(next) = next {
+        push_value(next, indent, prefix, Side::Next, rows);
+    }
+    rows[start..].iter_mut().for_each(|row| row.change = start);
+}
+
+// Key of an object member followed by a colon, as written in the rendering
+fn key_prefix(key: &str) -> String {
+    let mut prefix = String::new();
+    write_string(key, &mut prefix);
+    prefix.push_str(": ");
+    prefix
+}
+
+// Pretty-prints both items as aligned lines, matching object members by key and array elements by index, as `delta` does
+fn compare(prev: &JsonItem, next: &JsonItem, indent: usize, prefix: &str, floats: FloatEquality, rows: &mut Vec<Row>) {
+    let (open, close) = match (&prev.json_type, &next.json_type) {
+        (JsonMap, JsonMap) => { ("{", "}") }
+        (JsonArray, JsonArray) => { ("[", "]") }
+        (JsonNumber, JsonNumber) if prev.equals(next, floats) => {
+            push_value(prev, indent, prefix, Side::Both, rows);
+            return;
+        }
+        _ => {
+            let (mut prev_text, mut next_text) = (String::new(), String::new());
+            write_compact(prev, &mut prev_text);
+            write_compact(next, &mut next_text);
+            if prev_text == next_text {
+                push_value(prev, indent, prefix, Side::Both, rows);
+            } else {
+                push_change(Some(prev), Some(next), indent, prefix, rows);
+            }
+            return;
+        }
+    };
+    let empty = |item: &JsonItem| item.elements().map_or(0, Iterator::count) + item.entries().map_or(0, Iterator::count) == 0;
+    if empty(prev) && empty(next) {
+        push_value(prev, indent, prefix, Side::Both, rows);
+        return;
+    }
+    rows.push(Row { side: Side::Both, text: format!("{:indent$}{}{}", "", prefix, open, indent = indent), change: 0 });
+    if let (Some(prev_elements), Some(next_elements)) = (prev.elements(), next.elements()) {
+        let (prev_elements, next_elements) = (prev_elements.collect::<Vec<_>>(), next_elements.collect::<Vec<_>>());
+        for (previous, element) in prev_elements.iter().zip(&next_elements) {
+            compare(previous, element, indent + 2, "", floats, rows);
+        }
+        for previous in prev_elements.iter().skip(next_elements.len()) {
+            push_change(Some(previous), None, indent + 2, "", rows);
+        }
+        for element in next_elements.iter().skip(prev_elements.len()) {
+            push_change(None, Some(element), indent + 2, "", rows);
+        }
+    } else if let (Some(prev_entries), Some(next_entries)) = (prev.entries(), next.entries()) {
+        let next_entries = next_entries.collect::<Vec<_>>();
+        for (key, previous) in prev_entries {
+            let prefix = key_prefix(key.as_str());
+            match next_entries.iter().find(|(k, _)| k.as_str() == key.as_str()) {
+                Some((_, value)) => { compare(previous, value, indent + 2, &prefix, floats, rows) }
+                None => { push_change(Some(previous), None, indent + 2, &prefix, rows) }
+            }
+        }
+        for (key, value) in &next_entries {
+            if prev[key.as_str()].json_type == Empty {
+                push_change(None, Some(value), indent + 2, &key_prefix(key.as_str()), rows);
+            }
+        }
+    }
+    rows.push(Row { side: Side::Both, text: format!("{:indent$}{}", "", close, indent = indent), change: 0 });
+}
+
+// Cuts `text` to `width` characters, marking cuts with an ellipsis
+fn cut(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_owned();
+    }
+    let mut cut = text.chars().take(width.saturating_sub(1)).collect::<String>();
+    cut.push('…');
+    cut
+}
+
+// Cuts `text` to `width` characters, padding it with spaces if shorter
+fn fit(text: &str, width: usize) -> String {
+    format!("{:width$}", cut(text, width), width = width)
+}
+
+/// Renders the differences between two documents as text, for `jsondiff`-style tools: as a unified diff
+/// with [`unified`](DiffRenderer::unified), or in two columns with [`side_by_side`](DiffRenderer::side_by_side).
+/// Both documents are pretty-printed one value per line, without separators, and aligned structurally:
+/// object members are matched by key and array elements by index, as in [`delta`](crate::delta::delta),
+/// so that reordered keys and reformatted text do not show up as changes.
+///
+/// # Example
+///
+/// ```rust
+/// use jsonic::diff::DiffRenderer;
+///
+/// let prev = jsonic::parse("{\"host\": \"a\", \"cpu\": [0.5, 0.7], \"up\": true}").unwrap();
+/// let next = jsonic::parse("{\"up\": true, \"host\": \"a\", \"cpu\": [0.5, 0.9]}").unwrap();
+///
+/// assert_eq!(DiffRenderer::new().context(1).unified(&prev, &next), "\
+/// --- prev
+/// +++ next
+/// @@ -4,3 +4,3 @@
+///      0.5
+/// -    0.7
+/// +    0.9
+///    ]
+/// ");
+/// ```
+#[derive(Debug, Clone)]
+pub struct DiffRenderer {
+    context: usize,
+    width: usize,
+    color: bool,
+    labels: (String, String),
+    floats: FloatEquality,
+}
+
+impl Default for DiffRenderer {
+    fn default() -> Self {
+        DiffRenderer { context: 3, width: 40, color: false, labels: ("prev".to_owned(), "next".to_owned()), floats: FloatEquality::Bitwise }
+    }
+}
+
+impl DiffRenderer {
+    /// Creates a renderer with 3 lines of context, 40-character columns, no colors and documents labeled `prev` and `next`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of unchanged lines shown around changes in unified diffs
+    pub fn context(mut self, lines: usize) -> Self {
+        self.context = lines;
+        self
+    }
+
+    /// Sets the width in characters of both columns of side-by-side renderings, longer lines being cut
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Enables ANSI colors: removed lines in red, added lines in green and hunk headers in cyan
+    pub fn color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Sets the names of both documents in the header of unified diffs
+    pub fn labels(mut self, prev: &str, next: &str) -> Self {
+        self.labels = (prev.to_owned(), next.to_owned());
+        self
+    }
+
+    /// Sets how floats are compared, numbers considered equal being shown as unchanged
+    pub fn floats(mut self, floats: FloatEquality) -> Self {
+        self.floats = floats;
+        self
+    }
+
+    fn rows(&self, prev: &JsonItem, next: &JsonItem) -> Vec<Row> {
+        let mut rows = Vec::new();
+        compare(prev, next, 0, "", self.floats, &mut rows);
+        rows
+    }
+
+    // Wraps `text` in `color` if colors are enabled
+    fn paint(&self, text: &str, color: &str) -> String {
+        if self.color {
+            format!("{}{}{}", color, text, RESET)
+        } else {
+            text.to_owned()
+        }
+    }
+
+    /// Renders the differences from `prev` to `next` as a unified diff, the way `diff -u` does for text files.
+    /// Returns an empty string if both documents are equal.
+    pub fn unified(&self, prev: &JsonItem, next: &JsonItem) -> String {
+        let rows = self.rows(prev, next);
+        let mut output = String::new();
+        // Ranges of rows shown, each one holding changes and the context around them
+        let mut hunks: Vec<(usize, usize)> = Vec::new();
+        for (index, _) in rows.iter().enumerate().filter(|(_, row)| row.side != Side::Both) {
+            let (start, end) = (index.saturating_sub(self.context), usize::min(rows.len(), index + self.context + 1));
+            match hunks.last_mut() {
+                Some((_, last)) if start <= *last => { *last = end; }
+                _ => { hunks.push((start, end)); }
+            }
+        }
+        if hunks.is_empty() {
+            return output;
+        }
+        let _ = writeln!(output, "--- {}\n+++ {}", self.labels.0, self.labels.1);
+        for (start, end) in hunks {
+            let lines_before = |side: Side| rows[..start].iter().filter(|row| row.side != side).count();
+            let lines_in = |side: Side| rows[start..end].iter().filter(|row| row.side != side).count();
+            // Like `diff`, empty ranges are numbered after the line preceding them
+            let range = |before: usize, count: usize| format!("{},{}", if count == 0 { before } else { before + 1 }, count);
+            let header = format!("@@ -{} +{} @@", range(lines_before(Side::Next), lines_in(Side::Next)), range(lines_before(Side::Prev), lines_in(Side::Prev)));
+            let _ = writeln!(output, "{}", self.paint(&header, CYAN));
+            for row in &rows[start..end] {
+                let line = match row.side {
+                    Side::Both => { format!(" {}", row.text) }
+                    Side::Prev => { self.paint(&format!("-{}", row.text), RED) }
+                    Side::Next => { self.paint(&format!("+{}", row.text), GREEN) }
+                };
+                let _ = writeln!(output, "{}", line);
+            }
+        }
+        output
+    }
+
+    /// Renders both documents in full, side by side: `prev` on the left and `next` on the right, separated by a marker
+    /// telling whether the line is unchanged (` `), changed (`|`), removed (`<`) or added (`>`).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jsonic::diff::DiffRenderer;
+    ///
+    /// let prev = jsonic::parse("{\"a\": 1, \"b\": \"old\"}").unwrap();
+    /// let next = jsonic::parse("{\"b\": \"new\", \"c\": null}").unwrap();
+    ///
+    /// assert_eq!(DiffRenderer::new().width(12).side_by_side(&prev, &next), "\
+    /// {              {
+    ///   \"a\": 1     <
+    ///   \"b\": \"old\" |   \"b\": \"new\"
+    ///              >   \"c\": null
+    /// }              }
+    /// ");
+    /// ```
+    pub fn side_by_side(&self, prev: &JsonItem, next: &JsonItem) -> String {
+        let rows = self.rows(prev, next);
+        let mut output = String::new();
+        let mut index = 0;
+        while index < rows.len() {
+            if rows[index].side == Side::Both {
+                let _ = writeln!(output, "{}   {}", fit(&rows[index].text, self.width), cut(&rows[index].text, self.width));
+                index += 1;
+                continue;
+            }
+            // Lines of a value and its replacement are shown as changed, side by side
+            let change = rows[index].change;
+            let removed = rows[index..].iter().take_while(|row| row.side == Side::Prev && row.change == change).count();
+            let added = rows[index + removed..].iter().take_while(|row| row.side == Side::Next && row.change == change).count();
+            for line in 0..usize::max(removed, added) {
+                let left = (line < removed).then(|| &rows[index + line].text);
+                let right = (line < added).then(|| &rows[index + removed + line].text);
+                let (marker, left, right) = match (left, right) {
+                    (Some(left), Some(right)) => { ("|", self.paint(&fit(left, self.width), RED), self.paint(&cut(right, self.width), GREEN)) }
+                    (Some(left), None) => { ("<", self.paint(&fit(left, self.width), RED), String::new()) }
+                    (None, Some(right)) => { (">", fit("", self.width), self.paint(&cut(right, self.width), GREEN)) }
+                    (None, None) => { unreachable!() }
+                };
+                let _ = writeln!(output, "{}", format!("{} {} {}", left, marker, right).trim_end());
+            }
+            index += removed + added;
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::diff::DiffRenderer;
+    use crate::number::FloatEquality;
+    use crate::parse;
+
+    #[test]
+    fn render_unified() {
+        let prev = parse("{\"a\": [1, 2, 3], \"b\": {\"c\": 1.0}, \"d\": {\"e\": 1}, \"f\": 1, \"g\": 2, \"h\": 3, \"i\": 4, \"j\": [\"x\"]}").unwrap();
+        let next = parse("{\"a\": [1, 2], \"b\": {\"c\": 1.0000000001}, \"d\": 5, \"f\": 1, \"g\": 2, \"h\": 3, \"i\": 4, \"j\": [\"y\", {}], \"k\": []}").unwrap();
+        let renderer = DiffRenderer::new().context(1).labels("a.json", "b.json").floats(FloatEquality::Epsilon(1e-6));
+        assert_eq!(renderer.unified(&prev, &next), "--- a.json\n+++ b.json\n@@ -4,3 +4,2 @@\n     2\n-    3\n   ]\n@@ -9,5 +8,3 @@\n   }\n-  \"d\": {\n-    \"e\": 1\n-  }\n+  \"d\": 5\n   \"f\": 1\n@@ -17,4 +14,6 @@\n   \"j\": [\n-    \"x\"\n+    \"y\"\n+    {}\n   ]\n+  \"k\": []\n }\n");
+        assert_eq!(renderer.unified(&prev, &prev), "");
+
+        let colored = DiffRenderer::new().color(true).unified(&parse("[1]").unwrap(), &parse("[]").unwrap());
+        assert_eq!(colored, "--- prev\n+++ next\n\x1b[36m@@ -1,3 +1,2 @@\x1b[0m\n [\n\x1b[31m-  1\x1b[0m\n ]\n");
+    }
+
+    #[test]
+    fn render_side_by_side() {
+        let prev = parse("[\"a very long string\", 2, 3]").unwrap();
+        let next = parse("[\"a very long string\", 4]").unwrap();
+        let rendered = DiffRenderer::new().width(8).side_by_side(&prev, &next);
+        assert_eq!(rendered, "[          [\n  \"a ve…     \"a ve…\n  2      |   4\n  3      <\n]          ]\n");
+        let colored = DiffRenderer::new().width(3).color(true).side_by_side(&parse("[1]").unwrap(), &parse("[2]").unwrap());
+        assert_eq!(colored, "[     [\n\x1b[31m  1\x1b[0m | \x1b[32m  2\x1b[0m\n]     ]\n");
+    }
+}
@@ -0,0 +1,452 @@
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter, Result as FmtResult};
+use std::fs::File;
+use std::io;
+use std::io::Read;
+#[cfg(feature = "serialize")]
+use std::io::Write;
+use std::ops::{Deref, Range};
+use std::path::Path;
+#[cfg(feature = "bytes")]
+use std::str::from_utf8_unchecked;
+
+use crate::decompress::decompress;
+use crate::generics::ArrayIterator;
+use crate::json_error::JsonError;
+use crate::json_item::JsonItem;
+use crate::parse_root_recording;
+use crate::options::ParseOptions;
+#[cfg(feature = "serialize")]
+use crate::serialize::write_compact;
+
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
+// Buffer holding the source text of a document, whose heap data stays in place when it is moved
+enum Source {
+    Text(String),
+    // Shared network buffer, checked to be valid UTF-8
+    #[cfg(feature = "bytes")]
+    Bytes(bytes::Bytes),
+}
+
+impl Source {
+    fn as_str(&self) -> &str {
+        match self {
+            Source::Text(text) => { text }
+            #[cfg(feature = "bytes")]
+            Source::Bytes(bytes) => { unsafe { from_utf8_unchecked(bytes) } }
+        }
+    }
+}
+
+/// A parsed JSON document owning its source text.
+/// Dereferences to the root [`JsonItem`], so it can be indexed and queried directly.
+pub struct JsonDocument {
+    root: JsonItem,
+    source: Source,
+    // Byte ranges of the text separating tokens, recorded with `ParseOptions::preserve_layout`
+    #[cfg_attr(not(feature = "serialize"), allow(dead_code))]
+    separators: Vec<Range<usize>>,
+}
+
+impl JsonDocument {
+    pub(crate) fn parse(source: String, options: &ParseOptions) -> Result<Self, JsonError> {
+        Self::parse_source(Source::Text(source), options)
+    }
+
+    fn parse_source(source: Source, options: &ParseOptions) -> Result<Self, JsonError> {
+        // Items point into the heap buffer of `source`, which stays in place when the document is moved
+        let mut separators = Vec::new();
+        let root = parse_root_recording(source.as_str(), options, options.preserve_layout.then_some(&mut separators))?;
+        Ok(JsonDocument { root, source, separators })
+    }
+
+    /// Root item of the document
+    pub fn root(&self) -> &JsonItem {
+        &self.root
+    }
+
+    /// Source text of the document
+    pub fn source(&self) -> &str {
+        self.source.as_str()
+    }
+
+    #[cfg(feature = "serialize")]
+    pub(crate) fn separators(&self) -> &[Range<usize>] {
+        &self.separators
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl JsonItem {
+    /// Copies this item into a document owning its text, so that it can outlive the text it was parsed from.
+    /// The text is written as compact standard JSON, and payloads attached by recognizers are not kept.
+    /// A non-existent item gives a `null` document.
+    /// Returns an error if the written text cannot be parsed back, so that a document always holds valid JSON.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let document = {
+    ///     let source = String::from("{\"user\": {\"name\": \"ann\"}}");
+    ///     jsonic::parse(&source).unwrap()["user"].to_document().unwrap()
+    /// };
+    /// assert_eq!(document.source(), "{\"name\":\"ann\"}");
+    /// ```
+    pub fn to_document(&self) -> Result<JsonDocument, JsonError> {
+        let mut source = String::new();
+        write_compact(self, &mut source);
+        JsonDocument::parse(source, &ParseOptions::strict().max_depth(usize::MAX))
+    }
+}
+
+impl Deref for JsonDocument {
+    type Target = JsonItem;
+
+    fn deref(&self) -> &Self::Target {
+        &self.root
+    }
+}
+
+/// Iterates over the elements of the root item, see [`JsonItem`]
+impl<'a> IntoIterator for &'a JsonDocument {
+    type Item = &'a JsonItem;
+    type IntoIter = ArrayIterator<'a, JsonItem>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.root.into_iter()
+    }
+}
+
+impl Debug for JsonDocument {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        Debug::fmt(&self.root, f)
+    }
+}
+
+/// Errors occurring while reading and parsing JSON data
+#[derive(Debug)]
+pub enum ReadError {
+    /// Data could not be read, or is not valid UTF-8
+    Io(io::Error),
+    /// Data is not valid JSON
+    Json(JsonError),
+}
+
+impl Display for ReadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            ReadError::Io(error) => { write!(f, "I/O error: {}", error) }
+            ReadError::Json(error) => { Display::fmt(error, f) }
+        }
+    }
+}
+
+impl Error for ReadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ReadError::Io(error) => { Some(error) }
+            ReadError::Json(error) => { Some(error) }
+        }
+    }
+}
+
+impl From<io::Error> for ReadError {
+    fn from(error: io::Error) -> Self {
+        ReadError::Io(error)
+    }
+}
+
+impl From<JsonError> for ReadError {
+    fn from(error: JsonError) -> Self {
+        ReadError::Json(error)
+    }
+}
+
+/// Reading or writing progress, as reported to progress callbacks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    processed: u64,
+    total: Option<u64>,
+}
+
+impl Progress {
+    /// Number of bytes processed so far
+    pub fn get_processed(&self) -> u64 {
+        self.processed
+    }
+
+    /// Total number of bytes to process, if known
+    pub fn get_total(&self) -> Option<u64> {
+        self.total
+    }
+}
+
+// Reader reporting the number of bytes read from the underlying source
+struct ProgressReader<R, F> {
+    reader: R,
+    processed: u64,
+    total: Option<u64>,
+    progress: F,
+}
+
+impl<R: Read, F: FnMut(Progress)> Read for ProgressReader<R, F> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.reader.read(buf)?;
+        if read > 0 {
+            self.processed += read as u64;
+            (self.progress)(Progress { processed: self.processed, total: self.total });
+        }
+        Ok(read)
+    }
+}
+
+/// Writer reporting the number of bytes written to the underlying destination to a progress callback after each write,
+/// so that the progress of serialization can be shown, e.g. by wrapping the destination of a
+/// [`JsonWriter`](crate::writer::JsonWriter) in it
+///
+/// # Example
+///
+/// ```rust
+/// use jsonic::document::ProgressWriter;
+/// use jsonic::writer::JsonWriter;
+///
+/// let mut written = 0;
+/// let mut writer = JsonWriter::new(ProgressWriter::new(Vec::new(), None, |progress| written = progress.get_processed()));
+/// writer.value_str("text").unwrap();
+/// let output = writer.finish().unwrap().into_inner();
+/// assert_eq!((output.len(), written), (6, 6));
+/// ```
+#[cfg(feature = "serialize")]
+pub struct ProgressWriter<W, F> {
+    writer: W,
+    processed: u64,
+    total: Option<u64>,
+    progress: F,
+}
+
+#[cfg(feature = "serialize")]
+impl<W: Write, F: FnMut(Progress)> ProgressWriter<W, F> {
+    /// Creates a writer reporting the bytes written to `writer` to `progress`, with `total` as total if known
+    pub fn new(writer: W, total: Option<u64>, progress: F) -> Self {
+        ProgressWriter { writer, processed: 0, total, progress }
+    }
+
+    /// Returns the underlying writer
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl<W: Write, F: FnMut(Progress)> Write for ProgressWriter<W, F> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.writer.write(buf)?;
+        if written > 0 {
+            self.processed += written as u64;
+            (self.progress)(Progress { processed: self.processed, total: self.total });
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+pub(crate) fn read_source<R: Read>(mut reader: R, capacity: usize) -> io::Result<String> {
+    let mut buffer = Vec::with_capacity(capacity + READ_CHUNK_SIZE);
+    loop {
+        let start = buffer.len();
+        buffer.resize(start + READ_CHUNK_SIZE, 0);
+        let result = reader.read(&mut buffer[start..]);
+        buffer.truncate(start + *result.as_ref().unwrap_or(&0));
+        match result {
+            Ok(0) => { break; }
+            Ok(_) => {}
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+            Err(error) => { return Err(error); }
+        }
+    }
+    String::from_utf8(buffer).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+}
+
+/// Reads all data from `reader` and parses it as JSON.
+/// With the `gzip` or `zstd` feature, compressed data is detected and decompressed transparently.
+///
+/// # Arguments
+/// * `reader` - Source of the text content to be parsed
+pub fn parse_reader<R: Read>(reader: R) -> Result<JsonDocument, ReadError> {
+    parse_reader_with_progress(reader, None, |_| {})
+}
+
+/// Reads all data from `reader` and parses it as JSON, calling `progress` after each chunk read.
+/// For compressed data, progress is reported in bytes of compressed data read.
+///
+/// # Arguments
+/// * `reader` - Source of the text content to be parsed
+/// * `total` - Total size of the data in bytes, if known, passed on to `progress`
+/// * `progress` - Progress callback
+pub fn parse_reader_with_progress<R: Read, F: FnMut(Progress)>(reader: R, total: Option<u64>, progress: F) -> Result<JsonDocument, ReadError> {
+    let reader = ProgressReader { reader, processed: 0, total, progress };
+    let source = read_source(decompress(reader)?, total.map_or(0, |t| t as usize))?;
+    Ok(JsonDocument::parse(source, &ParseOptions::JSON)?)
+}
+
+/// Reads and parses a JSON file.
+/// With the `gzip` or `zstd` feature, `.json.gz` and `.json.zst` files are decompressed transparently.
+///
+/// # Arguments
+/// * `path` - Path of the file to be parsed
+pub fn parse_file<P: AsRef<Path>>(path: P) -> Result<JsonDocument, ReadError> {
+    parse_file_with_progress(path, |_| {})
+}
+
+/// Reads and parses a JSON file, calling `progress` after each chunk read, with the file size as total
+///
+/// # Arguments
+/// * `path` - Path of the file to be parsed
+/// * `progress` - Progress callback
+///
+/// # Example
+///
+/// ```rust,no_run
+/// let document = jsonic::document::parse_file_with_progress("data.json", |progress| {
+///     if let Some(total) = progress.get_total() {
+///         eprint!("\r{}%", progress.get_processed() * 100 / total.max(1));
+///     }
+/// });
+/// ```
+pub fn parse_file_with_progress<P: AsRef<Path>, F: FnMut(Progress)>(path: P, progress: F) -> Result<JsonDocument, ReadError> {
+    let file = File::open(path)?;
+    let total = file.metadata().ok().map(|metadata| metadata.len());
+    parse_reader_with_progress(file, total, progress)
+}
+
+/// Parses a shared byte buffer as JSON without copying it, e.g. the body of an HTTP request or response
+/// received with `hyper` or `reqwest`. The document holds a reference to the buffer, which is released
+/// when the document is dropped. Fails with [`ReadError::Io`] for bytes which are not valid UTF-8.
+///
+/// # Arguments
+/// * `bytes` - Buffer holding the text content to be parsed
+///
+/// # Example
+///
+/// ```rust
+/// let body = bytes::Bytes::from_static(b"{\"status\": \"ok\"}");
+/// let document = jsonic::document::parse_bytes(body.clone()).unwrap();
+/// assert_eq!(document["status"].as_str(), Some("ok"));
+/// assert_eq!(document.source().as_ptr(), body.as_ptr());
+/// ```
+#[cfg(feature = "bytes")]
+pub fn parse_bytes(bytes: bytes::Bytes) -> Result<JsonDocument, ReadError> {
+    std::str::from_utf8(&bytes).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+    Ok(JsonDocument::parse_source(Source::Bytes(bytes), &ParseOptions::JSON)?)
+}
+
+/// Parsing of text and bytes into owned documents with method syntax, e.g. `"[1, 2]".parse_json()`.
+/// Implemented for `str` and `[u8]`, so that it also applies to `String`, `Vec<u8>` and byte string literals.
+///
+/// # Example
+///
+/// ```rust
+/// use jsonic::document::ParseJsonExt;
+///
+/// let document = "{\"a\": [1, 2]}".parse_json().unwrap();
+/// assert_eq!(document["a"][1].as_i128(), Some(2));
+///
+/// let body: Vec<u8> = b"[true]".to_vec();
+/// assert_eq!(body.parse_json().unwrap()[0].as_bool(), Some(true));
+/// ```
+pub trait ParseJsonExt {
+    /// Parses the content as JSON into a document holding a copy of it.
+    /// Fails with [`ReadError::Io`] for bytes which are not valid UTF-8, and [`ReadError::Json`] for invalid JSON.
+    fn parse_json(&self) -> Result<JsonDocument, ReadError>;
+}
+
+impl ParseJsonExt for str {
+    fn parse_json(&self) -> Result<JsonDocument, ReadError> {
+        Ok(JsonDocument::parse(self.to_owned(), &ParseOptions::JSON)?)
+    }
+}
+
+impl ParseJsonExt for [u8] {
+    fn parse_json(&self) -> Result<JsonDocument, ReadError> {
+        let source = String::from_utf8(self.to_vec()).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        Ok(JsonDocument::parse(source, &ParseOptions::JSON)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::document::{parse_reader, parse_reader_with_progress, ParseJsonExt, ReadError};
+
+    #[test]
+    fn parse_owned_document() {
+        let document = parse_reader(Cursor::new("{\"a\": [1, 2]}")).unwrap();
+        let moved = document;
+        assert_eq!(moved["a"][1].as_i128(), Some(2));
+        assert_eq!((&moved).into_iter().count(), 0);
+        assert_eq!(parse_reader(Cursor::new("[1, 2]")).unwrap().into_iter().filter_map(|item| item.as_i128()).sum::<i128>(), 3);
+        assert_eq!(moved.source(), "{\"a\": [1, 2]}");
+    }
+
+    #[test]
+    fn report_progress() {
+        let json = format!("[{}0]", "0,".repeat(100_000));
+        let mut reports = Vec::new();
+        let document = parse_reader_with_progress(Cursor::new(&json), Some(json.len() as u64), |progress| reports.push(progress));
+        assert!(document.is_ok());
+        assert!(reports.len() > 1);
+        assert!(reports.windows(2).all(|w| w[0].get_processed() < w[1].get_processed()));
+        assert_eq!(reports.last().unwrap().get_processed(), json.len() as u64);
+        assert_eq!(reports.last().unwrap().get_total(), Some(json.len() as u64));
+    }
+
+    #[test]
+    #[cfg(feature = "serialize")]
+    fn copy_to_document() {
+        let parsed = crate::parse("{\"a\": [1, {\"b\": \"x\"}], \"c\": 2}").unwrap();
+        let document = parsed["a"].to_document().unwrap();
+        drop(parsed);
+        assert_eq!(document.source(), "[1,{\"b\":\"x\"}]");
+        assert_eq!(document[1]["b"].as_str(), Some("x"));
+        assert_eq!(crate::parse("[2.5]").unwrap()[0].to_document().unwrap().as_f64(), Some(2.5));
+        assert!(crate::parse("{}").unwrap()["missing"].to_document().unwrap().is_null());
+    }
+
+    #[test]
+    fn parse_with_methods() {
+        let source = String::from("[1, {\"b\": null}]");
+        let document = source.parse_json().unwrap();
+        drop(source);
+        assert!(document[1]["b"].is_null());
+        assert_eq!(b"[2]".parse_json().unwrap()[0].as_i128(), Some(2));
+        assert!(matches!("[1".parse_json(), Err(ReadError::Json(_))));
+        assert!(matches!(b"[\"\xFF\"]".parse_json(), Err(ReadError::Io(_))));
+    }
+
+    #[test]
+    #[cfg(feature = "bytes")]
+    fn parse_shared_bytes() {
+        let buffer = bytes::Bytes::from(b"[1, {\"b\": \"x\"}]".to_vec());
+        let document = crate::document::parse_bytes(buffer.slice(4..14)).unwrap();
+        assert_eq!((document.source(), document.source().as_ptr()), ("{\"b\": \"x\"}", buffer[4..].as_ptr()));
+        drop(buffer);
+        let moved = document;
+        assert_eq!(moved["b"].as_str(), Some("x"));
+        assert!(matches!(crate::document::parse_bytes(bytes::Bytes::from_static(b"\"\xFF\"")), Err(ReadError::Io(_))));
+        assert!(matches!(crate::document::parse_bytes(bytes::Bytes::new()), Err(ReadError::Json(_))));
+    }
+
+    #[test]
+    fn read_errors() {
+        assert!(matches!(parse_reader(Cursor::new(b"[\xff]")), Err(ReadError::Io(_))));
+        assert!(matches!(parse_reader(Cursor::new("[1,")), Err(ReadError::Json(_))));
+        let error = parse_reader(Cursor::new("[1,")).unwrap_err();
+        let source = std::error::Error::source(&error).and_then(|source| source.downcast_ref::<crate::json_error::JsonError>());
+        assert_eq!(source.map(|source| source.get_index()), Some(3));
+    }
+}
@@ -0,0 +1,282 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use crate::json_item::JsonItem;
+use crate::json_type::JsonType;
+use crate::json_type::JsonType::{Empty, JsonArray, JsonFalse, JsonMap, JsonNull, JsonNumber, JsonString, JsonTrue};
+use crate::tracking::push_token;
+
+// Shape of a document: the paths it holds, array elements sharing the reference token `*`, with the types found at each
+type Shape = BTreeMap<String, Vec<JsonType>>;
+
+// Type compared between documents, both booleans being the same type
+fn kind(json_type: JsonType) -> JsonType {
+    match json_type {
+        JsonFalse => { JsonTrue }
+        other => { other }
+    }
+}
+
+fn type_name(json_type: JsonType) -> &'static str {
+    match json_type {
+        JsonNull => { "null" }
+        JsonTrue | JsonFalse => { "boolean" }
+        JsonString => { "string" }
+        JsonNumber => { "number" }
+        JsonMap => { "object" }
+        JsonArray => { "array" }
+        Empty => { "missing" }
+    }
+}
+
+fn collect(item: &JsonItem, path: &mut String, shape: &mut Shape) {
+    let types = shape.entry(path.clone()).or_default();
+    if !types.contains(&kind(item.json_type)) {
+        types.push(kind(item.json_type));
+    }
+    let len = path.len();
+    if let Some(elements) = item.elements() {
+        path.push_str("/*");
+        for element in elements {
+            collect(element, path, shape);
+        }
+    } else if let Some(entries) = item.entries() {
+        for (key, value) in entries {
+            push_token(path, key.as_str());
+            collect(value, path, shape);
+            path.truncate(len);
+        }
+    }
+    path.truncate(len);
+}
+
+// Path of the parent of the item at `path`, and whether the item is an object member
+fn parent(path: &str) -> (&str, bool) {
+    let slash = path.rfind('/').unwrap_or(0);
+    (&path[..slash], &path[slash..] != "/*")
+}
+
+/// Deviation of a document from the shape learned by a [`DriftMonitor`]. Paths are JSON Pointers (RFC 6901)
+/// in which array elements are designated by `*`, e.g. `/users/*/name`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Drift {
+    /// An item at a path never seen while learning, e.g. a new object key
+    NewPath(String),
+    /// An item of a type never seen at its path while learning
+    ChangedType { path: String, expected: Vec<JsonType>, found: JsonType },
+    /// An object member present in every document learned is missing while its parent is present
+    MissingField(String),
+}
+
+impl Display for Drift {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Drift::NewPath(path) => { write!(f, "new path '{}'", path) }
+            Drift::ChangedType { path, expected, found } => {
+                let expected = expected.iter().map(|t| type_name(*t)).collect::<Vec<_>>().join(" or ");
+                write!(f, "'{}' is {} instead of {}", path, type_name(*found), expected)
+            }
+            Drift::MissingField(path) => { write!(f, "missing field '{}'", path) }
+        }
+    }
+}
+
+// Learned facts about a path: number of documents holding it and types found
+#[derive(Debug, Clone)]
+struct PathProfile {
+    documents: usize,
+    types: Vec<JsonType>,
+}
+
+/// Early warning of schema drift in a stream of documents, e.g. records of an NDJSON feed or messages of a queue.
+///
+/// The monitor first learns the shape of a number of documents, see [`warmup`](DriftMonitor::warmup): the paths
+/// they hold, the types found at each path and the object members always present. Each later document is then
+/// compared with this profile by [`observe`](DriftMonitor::observe), which returns its deviations, and the monitor
+/// is drifting when the share of deviating documents among the last ones exceeds a threshold, see
+/// [`is_drifting`](DriftMonitor::is_drifting).
+///
+/// # Example
+///
+/// ```rust
+/// use jsonic::drift::{Drift, DriftMonitor};
+///
+/// let mut monitor = DriftMonitor::new().warmup(2).window(2).threshold(0.5);
+/// for record in ["{\"id\": 1, \"tags\": [\"a\"]}", "{\"id\": 2, \"tags\": []}", "{\"id\": 3, \"tags\": [\"b\"]}"] {
+///     assert!(monitor.observe(&jsonic::parse(record).unwrap()).is_empty());
+/// }
+///
+/// let drifts = monitor.observe(&jsonic::parse("{\"id\": \"4\", \"tags\": [], \"extra\": true}").unwrap());
+/// assert_eq!(drifts[0].to_string(), "new path '/extra'");
+/// assert_eq!(drifts[1].to_string(), "'/id' is string instead of number");
+/// assert!(!monitor.is_drifting());
+/// assert_eq!(monitor.observe(&jsonic::parse("{\"tags\": []}").unwrap()), [Drift::MissingField("/id".to_owned())]);
+/// assert!(monitor.is_drifting());
+/// ```
+#[derive(Debug, Clone)]
+pub struct DriftMonitor {
+    warmup: usize,
+    window: usize,
+    threshold: f64,
+    learned: usize,
+    profile: BTreeMap<String, PathProfile>,
+    // Whether each of the last documents observed after learning deviated, oldest first
+    recent: VecDeque<bool>,
+}
+
+impl Default for DriftMonitor {
+    fn default() -> Self {
+        DriftMonitor { warmup: 100, window: 100, threshold: 0.05, learned: 0, profile: BTreeMap::new(), recent: VecDeque::new() }
+    }
+}
+
+impl DriftMonitor {
+    /// Creates a monitor learning from 100 documents, then drifting when more than 5% of the last 100 documents deviate
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of documents learned before any is checked (default: 100)
+    pub fn warmup(mut self, documents: usize) -> Self {
+        self.warmup = documents;
+        self
+    }
+
+    /// Sets the number of last documents the share of deviating ones is computed over (default: 100, at least 1)
+    pub fn window(mut self, documents: usize) -> Self {
+        self.window = documents.max(1);
+        self
+    }
+
+    /// Sets the share of deviating documents over the window above which the monitor is drifting, from 0 to 1 (default: 0.05)
+    pub fn threshold(mut self, ratio: f64) -> Self {
+        self.threshold = ratio;
+        self
+    }
+
+    /// Checks if the monitor is still learning the shape of documents
+    pub fn is_learning(&self) -> bool {
+        self.learned < self.warmup
+    }
+
+    /// Learns the shape of `item` while learning, otherwise returns its deviations from the learned shape,
+    /// in path order, and records whether it deviated
+    pub fn observe(&mut self, item: &JsonItem) -> Vec<Drift> {
+        let mut shape = Shape::new();
+        collect(item, &mut String::new(), &mut shape);
+        if self.is_learning() {
+            self.learn(shape);
+            return Vec::new();
+        }
+
+        let mut drifts = Vec::new();
+        for (path, types) in &shape {
+            match self.profile.get(path) {
+                None => {
+                    // Descendants of a new path are not reported, only the path itself
+                    if self.profile.contains_key(parent(path).0) {
+                        drifts.push(Drift::NewPath(path.clone()));
+                    }
+                }
+                Some(profile) => {
+                    for found in types.iter().filter(|t| !profile.types.contains(t)) {
+                        drifts.push(Drift::ChangedType { path: path.clone(), expected: profile.types.clone(), found: *found });
+                    }
+                }
+            }
+        }
+        for (path, profile) in &self.profile {
+            let (parent_path, member) = parent(path);
+            if member && !shape.contains_key(path) && shape.get(parent_path).is_some_and(|types| types.contains(&JsonMap))
+                && self.profile.get(parent_path).is_some_and(|parent| parent.documents == profile.documents) {
+                drifts.push(Drift::MissingField(path.clone()));
+            }
+        }
+        drifts.sort_by(|a, b| Self::path(a).cmp(Self::path(b)));
+
+        if self.recent.len() == self.window {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(!drifts.is_empty());
+        drifts
+    }
+
+    fn path(drift: &Drift) -> &str {
+        match drift {
+            Drift::NewPath(path) | Drift::MissingField(path) | Drift::ChangedType { path, .. } => { path }
+        }
+    }
+
+    fn learn(&mut self, shape: Shape) {
+        for (path, types) in shape {
+            let profile = self.profile.entry(path).or_insert_with(|| PathProfile { documents: 0, types: Vec::new() });
+            profile.documents += 1;
+            for found in types {
+                if !profile.types.contains(&found) {
+                    profile.types.push(found);
+                }
+            }
+        }
+        self.learned += 1;
+    }
+
+    /// Share of deviating documents among the last ones observed after learning, 0 if there are none
+    pub fn get_drift_rate(&self) -> f64 {
+        match self.recent.len() {
+            0 => { 0.0 }
+            len => { self.recent.iter().filter(|deviated| **deviated).count() as f64 / len as f64 }
+        }
+    }
+
+    /// Checks if the share of deviating documents exceeds the threshold, once a full window of documents has been observed
+    /// after learning
+    pub fn is_drifting(&self) -> bool {
+        self.recent.len() == self.window && self.get_drift_rate() > self.threshold
+    }
+
+    /// Forgets the learned shape and the last documents, so that the next documents are learned, e.g. after accepting a drift
+    pub fn reset(&mut self) {
+        self.learned = 0;
+        self.profile.clear();
+        self.recent.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::drift::{Drift, DriftMonitor};
+    use crate::json_type::JsonType::{JsonNull, JsonNumber, JsonString, JsonTrue};
+    use crate::parse;
+
+    #[test]
+    fn detect_drift() {
+        let mut monitor = DriftMonitor::new().warmup(3).window(4).threshold(0.25);
+        for record in ["{\"a\": 1, \"b\": [{\"c\": true}], \"d\": null}", "{\"a\": 2, \"b\": [], \"d\": \"x\"}", "{\"a\": 3, \"b\": [{\"c\": false, \"e\": 1}]}"] {
+            assert!(monitor.observe(&parse(record).unwrap()).is_empty());
+        }
+        assert!(!monitor.is_learning());
+
+        assert!(monitor.observe(&parse("{\"a\": 4, \"b\": [{\"c\": true}, {\"c\": false}], \"d\": \"y\"}").unwrap()).is_empty());
+        let drifts = monitor.observe(&parse("{\"b\": [{\"c\": 1, \"f\": {\"g\": 1}}], \"d\": 1, \"a/\": 0}").unwrap());
+        assert_eq!(drifts, [
+            Drift::MissingField("/a".to_owned()),
+            Drift::NewPath("/a~1".to_owned()),
+            Drift::ChangedType { path: "/b/*/c".to_owned(), expected: vec![JsonTrue], found: JsonNumber },
+            Drift::NewPath("/b/*/f".to_owned()),
+            Drift::ChangedType { path: "/d".to_owned(), expected: vec![JsonNull, JsonString], found: JsonNumber },
+        ]);
+        assert_eq!(drifts[4].to_string(), "'/d' is number instead of null or string");
+        assert!(monitor.observe(&parse("{\"a\": 5, \"b\": {}}").unwrap())[0].to_string().starts_with("'/b' is object"));
+        assert_eq!((monitor.get_drift_rate(), monitor.is_drifting()), (2.0 / 3.0, false));
+        assert!(monitor.observe(&parse("{\"a\": 6, \"b\": []}").unwrap()).is_empty());
+        assert!(monitor.is_drifting());
+        for a in [7, 8] {
+            assert!(monitor.observe(&parse(&format!("{{\"a\": {}, \"b\": []}}", a)).unwrap()).is_empty());
+        }
+        assert_eq!((monitor.get_drift_rate(), monitor.is_drifting()), (0.25, false));
+
+        monitor.reset();
+        assert!(monitor.is_learning() && monitor.get_drift_rate() == 0.0);
+        assert!(monitor.observe(&parse("[1]").unwrap()).is_empty());
+    }
+}
@@ -0,0 +1,166 @@
+// Handling of objects holding the same key more than once, according to `ParseOptions::duplicate_keys`
+
+use std::mem::replace;
+use std::ops::Range;
+
+use crate::generics::Container::Array;
+use crate::json_error::JsonError;
+use crate::json_item::JsonItem;
+use crate::json_type::JsonType::JsonString;
+use crate::key::Key;
+use crate::options::DuplicateKeys;
+use crate::slice::Slice;
+
+// Below this size, duplicates are found by comparing all pairs of keys rather than sorting them
+const PAIRWISE_THRESHOLD: usize = 16;
+
+// For each key, index of the first key equal to it, or `None` if all keys are distinct
+fn first_occurrences<T>(entries: &[T], key: impl Fn(&T) -> &Key) -> Option<Vec<usize>> {
+    let mut first = (0..entries.len()).collect::<Vec<_>>();
+    let mut found = false;
+    if entries.len() <= PAIRWISE_THRESHOLD {
+        for index in 1..entries.len() {
+            if let Some(previous) = entries[..index].iter().position(|entry| key(entry) == key(&entries[index])) {
+                first[index] = previous;
+                found = true;
+            }
+        }
+    } else {
+        let mut order = (0..entries.len()).collect::<Vec<_>>();
+        order.sort_by(|&a, &b| key(&entries[a]).cmp(key(&entries[b])).then(a.cmp(&b)));
+        for pair in order.windows(2) {
+            if key(&entries[pair[0]]) == key(&entries[pair[1]]) {
+                first[pair[1]] = first[pair[0]];
+                found = true;
+            }
+        }
+    }
+    found.then_some(first)
+}
+
+// Byte range of the source text of a value, quotes included for strings
+fn value_span(bytes: &[u8], item: &JsonItem) -> (usize, usize) {
+    let start = item.slice.offset_in(bytes);
+    if item.json_type == JsonString {
+        (start - 1, start + item.slice.len + 1)
+    } else {
+        (start, start + item.slice.len)
+    }
+}
+
+fn key_span(bytes: &[u8], key: &Key) -> Range<usize> {
+    let start = key.slice.offset_in(bytes);
+    start..start + key.slice.len
+}
+
+// Checks that the keys of an object skipped over are distinct, when duplicate keys are errors
+pub(crate) fn check(bytes: &[u8], keys: &[Key]) -> Result<(), JsonError> {
+    match first_occurrences(keys, |key| key) {
+        Some(first) => {
+            let second = (0..keys.len()).find(|&index| first[index] != index).unwrap();
+            Err(JsonError::duplicate_key(key_span(bytes, &keys[first[second]]), key_span(bytes, &keys[second])))
+        }
+        None => { Ok(()) }
+    }
+}
+
+// Applies `policy` to the entries of an object
+#[inline(always)]
+pub(crate) fn resolve(bytes: &[u8], map: Vec<(Key, JsonItem)>, policy: DuplicateKeys) -> Result<Vec<(Key, JsonItem)>, JsonError> {
+    let first = match first_occurrences(&map, |(key, _)| key) {
+        Some(first) => { first }
+        None => { return Ok(map); }
+    };
+    if policy == DuplicateKeys::Error {
+        let second = (0..map.len()).find(|&index| first[index] != index).unwrap();
+        return Err(JsonError::duplicate_key(key_span(bytes, &map[first[second]].0), key_span(bytes, &map[second].0)));
+    }
+
+    let mut entries = Vec::with_capacity(map.len());
+    let mut positions = vec![(0, false); map.len()];
+    for (index, (key, value)) in map.into_iter().enumerate() {
+        if first[index] == index {
+            positions[index] = (entries.len(), false);
+            entries.push((key, value));
+            continue;
+        }
+        let (position, collected) = &mut positions[first[index]];
+        let kept = &mut entries[*position].1;
+        match policy {
+            DuplicateKeys::LastWins => { *kept = value; }
+            DuplicateKeys::Collect => {
+                let (_, end) = value_span(bytes, &value);
+                if *collected {
+                    let start = kept.slice.offset_in(bytes);
+                    kept.slice = Slice::from_bytes(bytes, start, end);
+                    if let Some(Array(array)) = &mut kept.container {
+                        array.push(value);
+                    }
+                } else {
+                    let (start, _) = value_span(bytes, kept);
+                    let previous = replace(kept, JsonItem::empty());
+                    *kept = JsonItem::new_array(Slice::from_bytes(bytes, start, end), Some(vec![previous, value]));
+                    *collected = true;
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::json_error::ErrorKind;
+    use crate::options::{DuplicateKeys, ParseOptions, Parser};
+
+    const JSON: &str = "{\"a\": 1, \"b\": {\"c\": true, \"c\": null}, \"a\": \"two\", \"d\": [], \"a\": [3]}";
+
+    fn parse(policy: DuplicateKeys, json: &str) -> crate::json_item::JsonItem {
+        Parser::new(ParseOptions::new().duplicate_keys(policy)).parse(json).unwrap()
+    }
+
+    #[test]
+    fn keep_one_value() {
+        let parsed = parse(DuplicateKeys::LastWins, JSON);
+        assert_eq!(parsed.entries().unwrap().map(|(k, _)| k.as_str()).collect::<Vec<_>>(), ["a", "b", "d"]);
+        assert_eq!(parsed["a"][0].as_i128(), Some(3));
+        assert!(parsed["b"]["c"].is_null());
+
+        let parsed = parse(DuplicateKeys::FirstWins, JSON);
+        assert_eq!(parsed.entries().unwrap().count(), 3);
+        assert_eq!(parsed["a"].as_i128(), Some(1));
+        assert_eq!(parsed["b"]["c"].as_bool(), Some(true));
+    }
+
+    #[test]
+    fn collect_values() {
+        let parsed = parse(DuplicateKeys::Collect, JSON);
+        assert_eq!(parsed["a"].elements().unwrap().count(), 3);
+        assert_eq!(parsed["a"][1].as_str(), Some("two"));
+        assert_eq!(parsed["a"][2][0].as_i128(), Some(3));
+        assert_eq!(parsed["a"].as_str().map(|s| (s.starts_with('1'), s.ends_with("[3]"))), Some((true, true)));
+        assert_eq!(parsed["b"]["c"].as_str(), Some("true, \"c\": null"));
+        assert_eq!(parsed["d"].elements().unwrap().count(), 0);
+    }
+
+    #[test]
+    fn reject_duplicates() {
+        let error = Parser::new(ParseOptions::new().duplicate_keys(DuplicateKeys::Error)).parse(JSON).unwrap_err();
+        assert_eq!(error.get_kind(), ErrorKind::DuplicateKey);
+        assert_eq!(error.get_duplicate_spans(), Some((16..17, 27..28)));
+        assert_eq!(error.get_index(), 27);
+        assert!(Parser::new(ParseOptions::new().duplicate_keys(DuplicateKeys::Error)).parse("{\"a\": {\"a\": 1}}").is_ok());
+    }
+
+    #[test]
+    fn large_objects() {
+        let entries = (0..100).map(|n| format!("\"k{}\": {}", n % 40, n)).collect::<Vec<_>>();
+        let json = format!("{{{}}}", entries.join(", "));
+        let parsed = parse(DuplicateKeys::LastWins, &json);
+        assert_eq!(parsed.entries().unwrap().count(), 40);
+        assert_eq!(parsed["k5"].as_i128(), Some(85));
+        assert_eq!(parse(DuplicateKeys::FirstWins, &json)["k5"].as_i128(), Some(5));
+        assert_eq!(parse(DuplicateKeys::Collect, &json)["k39"][1].as_i128(), Some(79));
+    }
+}
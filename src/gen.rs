@@ -0,0 +1,162 @@
+use std::fmt::Write;
+
+use crate::number::write_f64_shortest;
+use crate::rng::Rng;
+use crate::text::escape_into;
+
+const STRING_CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789 _-";
+
+/// Shape of generated JSON values, see [`Generator`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    /// `null`
+    Null,
+    /// `true` or `false`
+    Bool,
+    /// Integer in `min..=max`
+    Integer { min: i64, max: i64 },
+    /// Float in `[min, max)`
+    Float { min: f64, max: f64 },
+    /// ASCII string with a length in `min_len..=max_len`
+    String { min_len: usize, max_len: usize },
+    /// Array with a length in `min_len..=max_len`, every element having shape `items`
+    Array { items: Box<Shape>, min_len: usize, max_len: usize },
+    /// Object with the given keys, in order, each value having the associated shape
+    Object(Vec<(String, Shape)>),
+    /// Any one of the given shapes, picked at random for every value
+    OneOf(Vec<Shape>),
+}
+
+/// Deterministic generator of random JSON documents conforming to a [`Shape`], for benchmarks, fuzzing corpora and load tests.
+/// A given seed always produces the same sequence of documents.
+///
+/// # Example
+///
+/// ```rust
+/// use jsonic::gen::{Generator, Shape};
+///
+/// let shape = Shape::Array {
+///     items: Box::new(Shape::Object(vec![
+///         ("id".to_owned(), Shape::Integer { min: 1, max: 1000 }),
+///         ("name".to_owned(), Shape::String { min_len: 1, max_len: 12 }),
+///         ("score".to_owned(), Shape::OneOf(vec![Shape::Float { min: 0.0, max: 1.0 }, Shape::Null])),
+///     ])),
+///     min_len: 10,
+///     max_len: 100,
+/// };
+///
+/// let document = Generator::new(42).generate(&shape);
+/// let parsed = jsonic::parse(&document).unwrap();
+/// assert!(parsed[0]["id"].as_i128().is_some());
+/// assert_eq!(document, Generator::new(42).generate(&shape));
+/// ```
+pub struct Generator {
+    rng: Rng,
+}
+
+impl Generator {
+    /// Creates a generator seeded with `seed`
+    pub fn new(seed: u64) -> Self {
+        Generator { rng: Rng::new(seed) }
+    }
+
+    /// Generates the text of a value conforming to `shape`.
+    /// Use a [`Shape::Array`] or [`Shape::Object`] root to get a document accepted by [`parse`](crate::parse).
+    pub fn generate(&mut self, shape: &Shape) -> String {
+        let mut output = String::new();
+        self.write(shape, &mut output);
+        output
+    }
+
+    /// Appends the text of a value conforming to `shape` to `output`
+    pub fn write(&mut self, shape: &Shape, output: &mut String) {
+        match shape {
+            Shape::Null => { output.push_str("null") }
+            Shape::Bool => { output.push_str(if self.rng.below(2) == 0 { "false" } else { "true" }) }
+            Shape::Integer { min, max } => { write!(output, "{}", self.rng.between(*min, *max)).unwrap() }
+            Shape::Float { min, max } => { write_f64_shortest(min + (max - min) * self.rng.unit(), output) }
+            Shape::String { min_len, max_len } => {
+                let len = self.len(*min_len, *max_len);
+                output.push('"');
+                output.extend((0..len).map(|_| STRING_CHARS[self.rng.below(STRING_CHARS.len())] as char));
+                output.push('"');
+            }
+            Shape::Array { items, min_len, max_len } => {
+                let len = self.len(*min_len, *max_len);
+                output.push('[');
+                for index in 0..len {
+                    if index > 0 {
+                        output.push(',');
+                    }
+                    self.write(items, output);
+                }
+                output.push(']');
+            }
+            Shape::Object(fields) => {
+                output.push('{');
+                for (index, (key, value)) in fields.iter().enumerate() {
+                    if index > 0 {
+                        output.push(',');
+                    }
+                    escape_into(key, output);
+                    output.push(':');
+                    self.write(value, output);
+                }
+                output.push('}');
+            }
+            Shape::OneOf(shapes) => {
+                match shapes.len() {
+                    0 => { output.push_str("null") }
+                    len => {
+                        let index = self.rng.below(len);
+                        self.write(&shapes[index], output)
+                    }
+                }
+            }
+        }
+    }
+
+    fn len(&mut self, min_len: usize, max_len: usize) -> usize {
+        min_len + self.rng.below(max_len.saturating_sub(min_len) + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::gen::{Generator, Shape};
+    use crate::parse;
+
+    fn shape() -> Shape {
+        Shape::Object(vec![
+            ("flag".to_owned(), Shape::Bool),
+            ("count".to_owned(), Shape::Integer { min: -5, max: 5 }),
+            ("ratio".to_owned(), Shape::Float { min: 1.5, max: 2.5 }),
+            ("quote\"d\n".to_owned(), Shape::OneOf(vec![Shape::Null, Shape::String { min_len: 3, max_len: 3 }])),
+            ("list".to_owned(), Shape::Array { items: Box::new(Shape::Integer { min: i64::MIN, max: i64::MAX }), min_len: 2, max_len: 4 }),
+        ])
+    }
+
+    #[test]
+    fn conform_to_shape() {
+        let mut generator = Generator::new(1);
+        for _ in 0..100 {
+            let document = generator.generate(&shape());
+            let parsed = parse(&document).unwrap();
+            assert!(parsed["flag"].as_bool().is_some());
+            assert!((-5..=5).contains(&parsed["count"].as_i128().unwrap()));
+            assert!((1.5..2.5).contains(&parsed["ratio"].as_f64().unwrap()));
+            let quoted = &parsed["quote\\\"d\\n"];
+            assert!(quoted.is_null() || quoted.as_str().unwrap().len() == 3);
+            assert!((2..=4).contains(&parsed["list"].elements().unwrap().count()));
+        }
+    }
+
+    #[test]
+    fn generate_deterministically() {
+        let first = (0..5).map(|_| Generator::new(9).generate(&shape())).collect::<Vec<_>>();
+        assert!(first.iter().all(|document| document == &first[0]));
+        let mut generator = Generator::new(9);
+        assert_eq!(generator.generate(&shape()), first[0]);
+        assert_ne!(generator.generate(&shape()), first[0]);
+    }
+}
@@ -1,14 +1,23 @@
 use std::collections::{btree_map, BTreeMap};
+use std::iter::FusedIterator;
 use std::slice::Iter;
 
 use crate::generics::IterArray::{IterArrayEmpty, IterArrayVec};
 use crate::generics::IterMap::{IterMapBTree, IterMapEmpty, IterMapVec};
+use crate::json_item::JsonItem;
+use crate::key::Key;
+use crate::plugin::Attachment;
+use crate::stub::StubOptions;
 
 #[derive(Debug)]
 pub(crate) enum Container<K, V> {
     Array(Vec<V>),
     MapVec(Vec<(K, V)>),
     MapBTree(BTreeMap<K, V>),
+    // Data attached to a scalar by a recognizer
+    Attached(Attachment),
+    // Options of the parse which left an object or array unparsed, used to expand it
+    Stub(StubOptions),
 }
 
 pub(crate) enum IterArray<'a, V> {
@@ -30,8 +39,28 @@ impl<'a, V> Iterator for ArrayIterator<'a, V> {
             IterArrayVec(iter_vec) => { iter_vec.next() }
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.iter {
+            IterArrayEmpty() => { (0, Some(0)) }
+            IterArrayVec(iter_vec) => { iter_vec.size_hint() }
+        }
+    }
 }
 
+impl<'a, V> DoubleEndedIterator for ArrayIterator<'a, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match &mut self.iter {
+            IterArrayEmpty() => { None }
+            IterArrayVec(iter_vec) => { iter_vec.next_back() }
+        }
+    }
+}
+
+impl<'a, V> ExactSizeIterator for ArrayIterator<'a, V> {}
+
+impl<'a, V> FusedIterator for ArrayIterator<'a, V> {}
+
 impl<'a, V> ToOwned for ArrayIterator<'a, V> {
     type Owned = Self;
 
@@ -43,6 +72,7 @@ impl<'a, V> ToOwned for ArrayIterator<'a, V> {
     }
 }
 
+#[allow(clippy::enum_variant_names)]
 pub(crate) enum IterMap<'a, K, V> {
     IterMapEmpty(),
     IterMapVec(Iter<'a, (K, V)>),
@@ -69,8 +99,30 @@ impl<'a, K, V> Iterator for MapIterator<'a, K, V> {
             IterMapBTree(iter_map) => { iter_map.next() }
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.iter {
+            IterMapEmpty() => { (0, Some(0)) }
+            IterMapVec(iter_vec) => { iter_vec.size_hint() }
+            IterMapBTree(iter_map) => { iter_map.size_hint() }
+        }
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for MapIterator<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match &mut self.iter {
+            IterMapEmpty() => { None }
+            IterMapVec(iter_vec) => { iter_vec.next_back().map(|(k, v)| (k, v)) }
+            IterMapBTree(iter_map) => { iter_map.next_back() }
+        }
+    }
 }
 
+impl<'a, K, V> ExactSizeIterator for MapIterator<'a, K, V> {}
+
+impl<'a, K, V> FusedIterator for MapIterator<'a, K, V> {}
+
 impl<'a, K, V> ToOwned for MapIterator<'a, K, V> {
     type Owned = Self;
 
@@ -81,4 +133,85 @@ impl<'a, K, V> ToOwned for MapIterator<'a, K, V> {
             IterMapBTree(iter_btree) => { MapIterator { iter: IterMapBTree(iter_btree.to_owned()) } }
         }
     }
-}
\ No newline at end of file
+}
+
+/// Object keys iterator, see [`JsonItem::keys`](crate::json_item::JsonItem::keys)
+pub struct KeyIterator<'a, K, V> {
+    pub(crate) entries: MapIterator<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for KeyIterator<'a, K, V> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries.next().map(|(k, _)| k)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for KeyIterator<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.entries.next_back().map(|(k, _)| k)
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for KeyIterator<'a, K, V> {}
+
+impl<'a, K, V> FusedIterator for KeyIterator<'a, K, V> {}
+
+/// Object values iterator, see [`JsonItem::values`](crate::json_item::JsonItem::values)
+pub struct ValueIterator<'a, K, V> {
+    pub(crate) entries: MapIterator<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for ValueIterator<'a, K, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries.next().map(|(_, v)| v)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for ValueIterator<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.entries.next_back().map(|(_, v)| v)
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for ValueIterator<'a, K, V> {}
+
+impl<'a, K, V> FusedIterator for ValueIterator<'a, K, V> {}
+
+/// Object entries iterator with keys as text, see [`JsonItem::pairs`](crate::json_item::JsonItem::pairs)
+pub struct PairIterator<'a> {
+    pub(crate) entries: MapIterator<'a, Key, JsonItem>,
+}
+
+impl<'a> Iterator for PairIterator<'a> {
+    type Item = (&'a str, &'a JsonItem);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries.next().map(|(k, v)| (k.as_str(), v))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+impl<'a> DoubleEndedIterator for PairIterator<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.entries.next_back().map(|(k, v)| (k.as_str(), v))
+    }
+}
+
+impl<'a> ExactSizeIterator for PairIterator<'a> {}
+
+impl<'a> FusedIterator for PairIterator<'a> {}
@@ -0,0 +1,167 @@
+use std::borrow::Cow;
+
+use crate::json_item::JsonItem;
+use crate::json_type::JsonType::{Empty, JsonArray, JsonFalse, JsonMap, JsonNull, JsonNumber, JsonString, JsonTrue};
+use crate::number::JsonNumberValue;
+#[cfg(feature = "sha256")]
+use crate::sha256::Sha256;
+use crate::text::unescape;
+
+// Receiver of the bytes encoding the logical value of an item
+trait Sink {
+    fn update(&mut self, bytes: &[u8]);
+}
+
+// 64-bit FNV-1a, whose output is the same on every platform and release
+struct Fnv(u64);
+
+impl Sink for Fnv {
+    fn update(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ *byte as u64).wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+#[cfg(feature = "sha256")]
+impl Sink for Sha256 {
+    fn update(&mut self, bytes: &[u8]) {
+        Sha256::update(self, bytes);
+    }
+}
+
+fn text(raw: &str) -> Cow<'_, str> {
+    unescape(raw).unwrap_or(Cow::Borrowed(raw))
+}
+
+fn feed_text<S: Sink>(text: &str, sink: &mut S) {
+    sink.update(&(text.len() as u64).to_le_bytes());
+    sink.update(text.as_bytes());
+}
+
+// Feeds the logical value of `item` to `sink`: a type tag, then numbers as `f64` bits, strings unescaped,
+// and members sorted by unescaped key, lengths being given before strings and containers
+fn feed<S: Sink>(item: &JsonItem, sink: &mut S) {
+    if let Some(stub) = item.as_stub() {
+        return feed(&stub.expanded(), sink);
+    }
+    match item.json_type {
+        Empty => { sink.update(b"_") }
+        JsonNull => { sink.update(b"n") }
+        JsonTrue => { sink.update(b"t") }
+        JsonFalse => { sink.update(b"f") }
+        JsonNumber => {
+            // Integers equal as floats are equal items, see `FloatEquality::eq_number`
+            match item.as_number() {
+                Some(number) => {
+                    sink.update(b"#");
+                    sink.update(&JsonNumberValue::to_f64(number).to_bits().to_le_bytes());
+                }
+                None => {
+                    sink.update(b"?");
+                    feed_text(item.slice.as_str(), sink);
+                }
+            }
+        }
+        JsonString => {
+            sink.update(b"\"");
+            feed_text(&text(item.slice.as_str()), sink);
+        }
+        JsonArray => {
+            let elements = item.elements().unwrap();
+            sink.update(b"[");
+            sink.update(&(elements.len() as u64).to_le_bytes());
+            for element in elements {
+                feed(element, sink);
+            }
+        }
+        JsonMap => {
+            let mut members = item.entries().unwrap().map(|(key, value)| (text(key.as_str()), value)).collect::<Vec<_>>();
+            members.sort_by(|(a, _), (b, _)| a.cmp(b));
+            sink.update(b"{");
+            sink.update(&(members.len() as u64).to_le_bytes());
+            for (key, value) in members {
+                feed_text(&key, sink);
+                feed(value, sink);
+            }
+        }
+    }
+}
+
+impl JsonItem {
+    /// Returns a 64-bit hash of the logical value of the item, ignoring formatting, key order and escape sequences,
+    /// so that equal items (see [`PartialEq`]) have equal hashes, e.g. to deduplicate documents, build cache keys
+    /// or detect changes. Numbers are hashed as `f64`, like `1` and `1.0`, which are equal.
+    ///
+    /// The hash is the same on every platform and release, so that it can be stored. It is not meant to resist
+    /// deliberate collisions, see `structural_sha256` with the `sha256` feature for that.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let a = jsonic::parse("{\"id\": 7, \"tags\": [\"x\", \"y\"]}").unwrap();
+    /// let b = jsonic::parse("{\n  \"tags\": [\"\\u0078\", \"y\"],\n  \"id\": 7.0\n}").unwrap();
+    ///
+    /// assert_eq!(a.structural_hash(), b.structural_hash());
+    /// assert_ne!(a.structural_hash(), a["tags"].structural_hash());
+    /// ```
+    pub fn structural_hash(&self) -> u64 {
+        let mut hasher = Fnv(0xcbf29ce484222325);
+        feed(self, &mut hasher);
+        // Final mix spreading every input bit over the output, FNV-1a alone leaving the high bits weak
+        let mut hash = hasher.0;
+        hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d049bb133111eb);
+        hash ^ (hash >> 31)
+    }
+
+    /// Returns the SHA-256 digest of the logical value of the item, see [`structural_hash`](JsonItem::structural_hash),
+    /// for content addressing where collisions must be infeasible. The digest is not that of the canonical JSON text.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let a = jsonic::parse("{\"id\": 7, \"name\": \"ann\"}").unwrap();
+    /// let b = jsonic::parse("{\"name\": \"ann\", \"id\": 7}").unwrap();
+    ///
+    /// assert_eq!(a.structural_sha256(), b.structural_sha256());
+    /// assert_ne!(a.structural_sha256(), jsonic::parse("{\"id\": 7, \"name\": \"bob\"}").unwrap().structural_sha256());
+    /// ```
+    #[cfg(feature = "sha256")]
+    pub fn structural_sha256(&self) -> [u8; 32] {
+        let mut digest = Sha256::new();
+        feed(self, &mut digest);
+        digest.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::options::{DuplicateKeys, ParseOptions, Parser};
+    use crate::parse;
+
+    #[test]
+    fn hash_structure() {
+        let items = ["null", "true", "false", "0", "-0.0", "1", "\"1\"", "[]", "{}", "[[]]", "[null]", "{\"\": null}", "[1, 2]", "[2, 1]",
+                     "{\"a\": 1, \"b\": 2}", "{\"a\": 2, \"b\": 1}", "{\"ab\": \"\"}", "{\"a\": \"b\"}", "[\"ab\", \"\"]", "[\"a\", \"b\"]"];
+        let hashes = items.iter().map(|json| parse(&format!("[{}]", json)).unwrap()[0].structural_hash()).collect::<Vec<_>>();
+        for (index, hash) in hashes.iter().enumerate() {
+            assert!(!hashes[index + 1..].contains(hash), "{}", items[index]);
+        }
+        assert!(!hashes.contains(&parse("[]").unwrap()[0].structural_hash()));
+
+        let a = parse("{\"k\": [1e0, {\"y\": \"\\u00e9\", \"x\": 1.5}], \"j\": true}").unwrap();
+        let b = parse("{\"j\":true,\"k\":[1,{\"x\":15e-1,\"y\":\"é\"}]}").unwrap();
+        assert_eq!(a, b);
+        assert_eq!(a.structural_hash(), b.structural_hash());
+        let stubs = Parser::new(ParseOptions::new().materialize_depth(1)).parse("{\"k\": [1, {\"x\": 1.5, \"y\": \"é\"}], \"j\": true}").unwrap();
+        assert_eq!(stubs.structural_hash(), a.structural_hash());
+        let duplicates = Parser::new(ParseOptions::new().duplicate_keys(DuplicateKeys::LastWins)).parse("{\"j\": 0, \"k\": [1, {\"x\": 1.5, \"y\": \"é\"}], \"j\": true}").unwrap();
+        assert_eq!(duplicates.structural_hash(), a.structural_hash());
+        #[cfg(feature = "sha256")]
+        {
+            assert_eq!(a.structural_sha256(), b.structural_sha256());
+            assert_ne!(a.structural_sha256(), a["k"].structural_sha256());
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use std::ffi::OsString;
+use std::fs::File;
+use std::io;
+use std::io::{BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::document::{read_source, JsonDocument, ReadError};
+use crate::options::ParseOptions;
+
+const MAGIC: &[u8; 8] = b"JSONICX1";
+
+// Error on an index file which does not match its data file
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// Returns the path of the index file of the NDJSON file at `data`, written by [`build`]: the same path with `.idx` appended
+pub fn index_path<P: AsRef<Path>>(data: P) -> PathBuf {
+    let mut path = OsString::from(data.as_ref());
+    path.push(".idx");
+    PathBuf::from(path)
+}
+
+/// Scans the NDJSON (newline-delimited JSON) file at `data` once and writes the byte range of each record,
+/// i.e. of each non-blank line, to the index file at [`index_path`]`(data)`, returning its path.
+/// Records are not parsed: invalid ones are reported when read with [`IndexedFile::record`].
+///
+/// The index file holds a magic number, the size of the data file, the number of records and two little-endian
+/// 64-bit integers per record, its offset and length.
+///
+/// # Arguments
+/// * `data` - Path of the NDJSON file to index
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use jsonic::index::{build, IndexedFile};
+///
+/// let index = build("events.ndjson").unwrap();
+/// let events = IndexedFile::open("events.ndjson", index).unwrap();
+/// let last = events.record(events.len() - 1).unwrap().unwrap();
+/// println!("{:?}", last["type"].as_str());
+/// ```
+pub fn build<P: AsRef<Path>>(data: P) -> io::Result<PathBuf> {
+    let mut reader = BufReader::new(File::open(&data)?);
+    let mut ranges = Vec::new();
+    let (mut offset, mut line) = (0u64, Vec::new());
+    loop {
+        line.clear();
+        let read = reader.read_until(b'\n', &mut line)?;
+        if read == 0 {
+            break;
+        }
+        let text = line.strip_suffix(b"\n").unwrap_or(&line);
+        // Blank lines (e.g. a final newline) are not records
+        if !text.iter().all(u8::is_ascii_whitespace) {
+            ranges.push((offset, text.len() as u64));
+        }
+        offset += read as u64;
+    }
+
+    let path = index_path(&data);
+    let mut writer = BufWriter::new(File::create(&path)?);
+    writer.write_all(MAGIC)?;
+    writer.write_all(&offset.to_le_bytes())?;
+    writer.write_all(&(ranges.len() as u64).to_le_bytes())?;
+    for (start, len) in ranges {
+        writer.write_all(&start.to_le_bytes())?;
+        writer.write_all(&len.to_le_bytes())?;
+    }
+    writer.flush()?;
+    Ok(path)
+}
+
+/// NDJSON file opened with its index, giving random access to records by number without reading the rest of the file,
+/// see [`build`]. The data file is opened read-only, and records can be read from several threads.
+#[derive(Debug)]
+pub struct IndexedFile {
+    file: Mutex<File>,
+    ranges: Vec<(u64, u64)>,
+}
+
+impl IndexedFile {
+    /// Opens the NDJSON file at `data` with the index file at `index`, written by [`build`].
+    /// Fails with an [`io::ErrorKind::InvalidData`] error if `index` is not an index file,
+    /// or if it is stale, i.e. the size of the data file changed since it was built.
+    pub fn open<P: AsRef<Path>, Q: AsRef<Path>>(data: P, index: Q) -> io::Result<IndexedFile> {
+        let file = File::open(data)?;
+        let mut reader = BufReader::new(File::open(index)?);
+        let mut magic = [0; 8];
+        reader.read_exact(&mut magic).map_err(|_| invalid("not a jsonic index file"))?;
+        if &magic != MAGIC {
+            return Err(invalid("not a jsonic index file"));
+        }
+        if read_u64(&mut reader)? != file.metadata()?.len() {
+            return Err(invalid("stale index file, the data file changed since it was indexed"));
+        }
+        let count = read_u64(&mut reader)?;
+        let mut ranges = Vec::with_capacity(usize::try_from(count).unwrap_or(0).min(1 << 20));
+        for _ in 0..count {
+            ranges.push((read_u64(&mut reader)?, read_u64(&mut reader)?));
+        }
+        Ok(IndexedFile { file: Mutex::new(file), ranges })
+    }
+
+    /// Number of records
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// Checks if the file has no records
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Reads and parses record `number`, starting at 0, or returns `None` if there is no such record
+    pub fn record(&self, number: usize) -> Result<Option<JsonDocument>, ReadError> {
+        let (offset, len) = match self.ranges.get(number) {
+            Some(range) => { *range }
+            None => { return Ok(None); }
+        };
+        let source = {
+            let mut file = self.file.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            file.seek(SeekFrom::Start(offset))?;
+            read_source((&mut *file).take(len), len as usize)?
+        };
+        Ok(Some(JsonDocument::parse(source, &ParseOptions::JSON)?))
+    }
+
+    /// Reads record `number` and returns the item designated by `pointer` (RFC 6901) in it as a document,
+    /// or `None` if there is no such record or item
+    pub fn get(&self, number: usize, pointer: &str) -> Result<Option<JsonDocument>, ReadError> {
+        match self.record(number)? {
+            Some(record) if record.pointer(pointer).exists() => { Ok(Some(record.pointer(pointer).to_document()?)) }
+            _ => { Ok(None) }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::io::ErrorKind;
+
+    use crate::document::ReadError;
+    use crate::index::{build, index_path, IndexedFile};
+
+    #[test]
+    fn read_records() {
+        let data = std::env::temp_dir().join(format!("jsonic-index-{}.ndjson", std::process::id()));
+        fs::write(&data, "{\"id\": 1}\n\n  \r\n[2, {\"a\": \"é\"}]\r\n{\"id\": \n{\"id\": 4}").unwrap();
+        let index = build(&data).unwrap();
+        assert_eq!(index, index_path(&data));
+
+        let file = IndexedFile::open(&data, &index).unwrap();
+        assert_eq!(file.len(), 4);
+        assert_eq!(file.record(0).unwrap().unwrap()["id"].as_i128(), Some(1));
+        assert_eq!(file.get(1, "/1/a").unwrap().unwrap().as_str(), Some("é"));
+        assert!(file.get(1, "/2").unwrap().is_none());
+        assert!(matches!(file.record(2), Err(ReadError::Json(_))));
+        assert_eq!(file.record(3).unwrap().unwrap().source(), "{\"id\": 4}");
+        assert!(file.record(4).unwrap().is_none());
+
+        fs::write(&data, "{}\n").unwrap();
+        assert_eq!(IndexedFile::open(&data, &index).unwrap_err().kind(), ErrorKind::InvalidData);
+        assert_eq!(IndexedFile::open(&data, &data).unwrap_err().kind(), ErrorKind::InvalidData);
+        build(&data).unwrap();
+        assert_eq!(IndexedFile::open(&data, &index).unwrap().len(), 1);
+        fs::remove_file(&data).unwrap();
+        fs::remove_file(&index).unwrap();
+    }
+}
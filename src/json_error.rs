@@ -1,44 +1,314 @@
+use std::error::Error;
 use std::fmt::{Display, Formatter, Result};
+use std::ops::Range;
 use std::str::from_utf8;
+use std::sync::OnceLock;
 
 const EXTRACT_PADDING: usize = 8;
+const SNIPPET_PADDING: usize = 32;
+
+/// Kinds of parsing errors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// A character cannot start or continue the expected token, e.g. a missing comma or colon
+    UnexpectedCharacter,
+    /// Data ends before the root value is complete
+    UnexpectedEof,
+    /// A string has no closing quote
+    UnterminatedString,
+    /// A block comment has no closing `*/`
+    UnterminatedComment,
+    /// A number does not follow the grammar, e.g. `01` or `1.` with [`parse_strict`](crate::parse_strict)
+    InvalidNumber,
+    /// A string holds an invalid escape sequence, with [`parse_strict`](crate::parse_strict)
+    InvalidEscape,
+    /// Something other than whitespace follows the root value
+    TrailingData,
+    /// Data is valid JSON, but a value does not have the expected type or structure
+    UnexpectedValue,
+    /// Containers are nested deeper than allowed by [`ParseOptions::max_depth`](crate::options::ParseOptions::max_depth)
+    DepthLimitExceeded,
+    /// Source text is larger than allowed by [`ParseOptions::max_input_size`](crate::options::ParseOptions::max_input_size)
+    SizeLimitExceeded,
+    /// Document holds more values than allowed by [`ParseOptions::max_nodes`](crate::options::ParseOptions::max_nodes)
+    NodeLimitExceeded,
+    /// Document holds more object members than allowed by [`ParseOptions::max_keys`](crate::options::ParseOptions::max_keys)
+    KeyLimitExceeded,
+    /// A string or key is longer than allowed by [`ParseOptions::max_string_len`](crate::options::ParseOptions::max_string_len)
+    StringLengthLimitExceeded,
+    /// An object holds the same key twice, with [`DuplicateKeys::Error`](crate::options::DuplicateKeys::Error)
+    DuplicateKey,
+}
+
+impl ErrorKind {
+    /// Whether the error comes from malformed data, rather than from a limit or a duplicate key policy
+    pub fn is_syntax(self) -> bool {
+        matches!(self, ErrorKind::UnexpectedCharacter | ErrorKind::UnexpectedEof | ErrorKind::UnterminatedString | ErrorKind::UnterminatedComment
+            | ErrorKind::InvalidNumber | ErrorKind::InvalidEscape | ErrorKind::TrailingData)
+    }
+
+    // Short description, opening error messages
+    pub(crate) fn description(self) -> &'static str {
+        match self {
+            ErrorKind::UnexpectedCharacter => { "JSON unexpected character" }
+            ErrorKind::UnexpectedEof => { "JSON unexpected end of data" }
+            ErrorKind::UnterminatedString => { "JSON unterminated string" }
+            ErrorKind::UnterminatedComment => { "JSON unterminated comment" }
+            ErrorKind::InvalidNumber => { "JSON invalid number" }
+            ErrorKind::InvalidEscape => { "JSON invalid escape sequence" }
+            ErrorKind::TrailingData => { "JSON trailing data" }
+            ErrorKind::UnexpectedValue => { "JSON unexpected value" }
+            ErrorKind::DepthLimitExceeded => { "JSON nesting depth limit exceeded" }
+            ErrorKind::SizeLimitExceeded => { "JSON input size limit exceeded" }
+            ErrorKind::NodeLimitExceeded => { "JSON value count limit exceeded" }
+            ErrorKind::KeyLimitExceeded => { "JSON key count limit exceeded" }
+            ErrorKind::StringLengthLimitExceeded => { "JSON string length limit exceeded" }
+            ErrorKind::DuplicateKey => { "JSON duplicate key" }
+        }
+    }
+}
 
 /// Parsing errors
 #[derive(Debug)]
 pub struct JsonError {
     index: usize,
-    extract: Option<String>,
+    kind: ErrorKind,
+    expected: Option<&'static str>,
+    // Boxed to keep errors small, as most are not about duplicates
+    duplicate: Option<Box<(Range<usize>, Range<usize>)>>,
+    // Found when the error is returned to the caller, see `located`, so that errors built and dropped while parsing cost nothing
+    location: Option<Box<Location>>,
+}
+
+// Line, column and source text around an error
+#[derive(Debug)]
+struct Location {
+    line: usize,
+    column: usize,
+    context: Context,
+}
+
+// Source text around an error, copied when the error is located. The extract and the snippet are built from it when first needed.
+#[derive(Debug)]
+struct Context {
+    window: Box<[u8]>,
+    extract_range: Range<usize>,
+    extract: OnceLock<Option<String>>,
+    // Part of the error line in `window`, whether it is cut at its start and end, and the number of characters before the error in it
+    line_range: Range<usize>,
+    cut: (bool, bool),
+    caret: usize,
+}
+
+fn is_lead_byte(b: u8) -> bool {
+    b & 0xC0 != 0x80
+}
+
+// Line and column of byte `index`, both starting at 1, and index of the start of the line.
+// Lines end with `\n`, and columns count UTF-8 lead bytes.
+pub(crate) fn location(bytes: &[u8], index: usize) -> (usize, usize, usize) {
+    let before = &bytes[..usize::min(index, bytes.len())];
+    let line_start = before.iter().rposition(|&b| b == b'\n').map_or(0, |newline| newline + 1);
+    let line = before[..line_start].iter().filter(|&&b| b == b'\n').count() + 1;
+    let column = before[line_start..].iter().filter(|&&b| is_lead_byte(b)).count() + 1;
+    (line, column, line_start)
+}
+
+// Copies the text around byte `index`: up to `EXTRACT_PADDING` bytes on each side for the extract, and the part of
+// the line holding it with up to `SNIPPET_PADDING` characters on each side of it for the snippet
+fn context(bytes: &[u8], index: usize, line_start: usize) -> Context {
+    let index = usize::min(index, bytes.len());
+    let (mut start, mut caret) = (index, 0);
+    while start > line_start && caret < SNIPPET_PADDING {
+        start -= 1;
+        caret += usize::from(is_lead_byte(bytes[start]));
+    }
+    let (mut end, mut after) = (index, 0);
+    while end < bytes.len() && bytes[end] != b'\n' {
+        if is_lead_byte(bytes[end]) {
+            if after > SNIPPET_PADDING {
+                break;
+            }
+            after += 1;
+        }
+        end += 1;
+    }
+    let extract_range = index.saturating_sub(EXTRACT_PADDING)..usize::min(bytes.len(), index + EXTRACT_PADDING);
+    let window = usize::min(start, extract_range.start)..usize::max(end, extract_range.end);
+    Context {
+        window: bytes[window.clone()].into(),
+        extract_range: extract_range.start - window.start..extract_range.end - window.start,
+        extract: OnceLock::new(),
+        line_range: start - window.start..end - window.start,
+        cut: (start > line_start, end < bytes.len() && bytes[end] != b'\n'),
+        caret,
+    }
+}
+
+impl Context {
+    fn extract(&self) -> &Option<String> {
+        self.extract.get_or_init(|| from_utf8(&self.window[self.extract_range.clone()]).ok().map(str::to_owned))
+    }
+
+    // Writes the line holding the error and a caret under it, prefixed with the line number, as in compiler diagnostics.
+    // Control characters are replaced with spaces, and cut ends are marked with `...`.
+    fn write_snippet(&self, f: &mut Formatter<'_>, line: usize) -> Result {
+        let text = match from_utf8(&self.window[self.line_range.clone()]) {
+            Ok(text) => { text.trim_end_matches('\r') }
+            Err(_) => { return Ok(()); }
+        };
+        let (cut_start, cut_end) = self.cut;
+        let width = line.to_string().len();
+        write!(f, "\n{:>width$} | {}", line, if cut_start { "..." } else { "" }, width = width)?;
+        for c in text.chars() {
+            write!(f, "{}", if c.is_control() { ' ' } else { c })?;
+        }
+        let caret = self.caret + if cut_start { 3 } else { 0 };
+        write!(f, "{}\n{:>width$} | {:caret$}^", if cut_end { "..." } else { "" }, "", "", width = width, caret = caret)
+    }
 }
 
 impl JsonError {
+    // Error on an unexpected character, or on the end of data if `index` is past it
     pub(crate) fn new(bytes: &[u8], index: usize) -> Self {
-        let extract = match from_utf8(&bytes[isize::max(0, index as isize - EXTRACT_PADDING as isize) as usize..usize::min(bytes.len(), index + EXTRACT_PADDING)]) {
-            Ok(extract) => { Some(extract.to_owned()) }
-            Err(_) => { None }
-        };
-        return JsonError {
-            index,
-            extract,
-        };
+        let kind = if index < bytes.len() { ErrorKind::UnexpectedCharacter } else { ErrorKind::UnexpectedEof };
+        Self::with_kind(index, kind)
     }
 
+    pub(crate) fn with_kind(index: usize, kind: ErrorKind) -> Self {
+        JsonError { index, kind, expected: None, duplicate: None, location: None }
+    }
+
+    // Error on the `second` occurrence of a key, both ranges spanning key text
+    pub(crate) fn duplicate_key(first: Range<usize>, second: Range<usize>) -> Self {
+        let mut error = Self::with_kind(second.start, ErrorKind::DuplicateKey);
+        error.duplicate = Some(Box::new((first, second)));
+        error
+    }
+
+    // Adds what the parser expected instead, to an error on an unexpected character or end of data which does not tell it yet
+    #[cold]
+    pub(crate) fn expecting(mut self, expected: &'static str) -> Self {
+        if self.expected.is_none() && matches!(self.kind, ErrorKind::UnexpectedCharacter | ErrorKind::UnexpectedEof) {
+            self.expected = Some(expected);
+        }
+        self
+    }
+
+    // Finds the line, column and source text of the error in `bytes`, the source text it was built from.
+    // Called where errors leave the crate, as errors own no source text.
+    #[cold]
+    pub(crate) fn located(mut self, bytes: &[u8]) -> Self {
+        if self.location.is_none() {
+            let (line, column, line_start) = location(bytes, self.index);
+            self.location = Some(Box::new(Location { line, column, context: context(bytes, self.index, line_start) }));
+        }
+        self
+    }
 
     /// Get error index (position) in source content
     pub fn get_index(&self) -> usize {
         self.index
     }
 
+    /// Get error line in source content, starting at 1, or `None` if the error was not located in its source
+    pub fn get_line(&self) -> Option<usize> {
+        self.location.as_ref().map(|location| location.line)
+    }
+
+    /// Get error column in source content, starting at 1 and counted in characters, or `None` if the error was not
+    /// located in its source, see [`get_line`](JsonError::get_line)
+    pub fn get_column(&self) -> Option<usize> {
+        self.location.as_ref().map(|location| location.column)
+    }
+
+    /// Returns the kind of error
+    pub fn get_kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// For errors on an unexpected character or end of data, returns what the parser expected instead if known,
+    /// e.g. `',' or '}'` after an object member
+    pub fn get_expected(&self) -> Option<&'static str> {
+        self.expected
+    }
+
+    /// For [`ErrorKind::DuplicateKey`] errors, returns the byte ranges of the text of both occurrences of the key
+    pub fn get_duplicate_spans(&self) -> Option<(Range<usize>, Range<usize>)> {
+        self.duplicate.as_deref().cloned()
+    }
+
     /// Returns an optional text extract near the error index
     pub fn get_extract(&self) -> &Option<String> {
-        &self.extract
+        match &self.location {
+            Some(location) => { location.context.extract() }
+            None => { &None }
+        }
     }
 }
 
 impl Display for JsonError {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        match &self.extract {
-            Some(extract) => { write!(f, "JSON error near '{}': index {} in data", extract, self.index) }
-            None => { write!(f, "JSON error at index {} in data", self.index) }
+        write!(f, "{}", self.kind.description())?;
+        if let Some(expected) = self.expected {
+            write!(f, " (expected {})", expected)?;
+        }
+        let location = match &self.location {
+            Some(location) => { location }
+            None => { return write!(f, " at index {} in data", self.index); }
+        };
+        match location.context.extract() {
+            Some(extract) => { write!(f, " near '{}': line {}, column {} (index {}) in data", extract, location.line, location.column, self.index)?; }
+            None => { write!(f, " at line {}, column {} (index {}) in data", location.line, location.column, self.index)?; }
         }
+        location.context.write_snippet(f, location.line)
+    }
+}
+
+impl Error for JsonError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::json_error::JsonError;
+
+    #[test]
+    fn locate_errors() {
+        let bytes = "{\n  \"é\": [1,\n  x]\n}".as_bytes();
+        let error = JsonError::new(bytes, 16).located(bytes);
+        assert_eq!((error.get_line(), error.get_column()), (Some(3), Some(3)));
+        assert_eq!(error.to_string(), "JSON unexpected character near ': [1,\n  x]\n}': line 3, column 3 (index 16) in data\n3 |   x]\n  |   ^");
+        let error = JsonError::new(bytes, 10).located(bytes);
+        assert_eq!((error.get_line(), error.get_column()), (Some(2), Some(8)));
+        let error = JsonError::new(bytes, 0).located(bytes);
+        assert_eq!((error.get_line(), error.get_column()), (Some(1), Some(1)));
+        assert_eq!(JsonError::new(bytes, bytes.len()).located(bytes).get_line(), Some(4));
+        let error = JsonError::new(bytes, 16);
+        assert_eq!((error.get_line(), error.get_column()), (None, None));
+        assert_eq!(error.get_extract(), &None);
+        assert_eq!(error.to_string(), "JSON unexpected character at index 16 in data");
+    }
+
+    #[test]
+    fn render_snippets() {
+        let snippet = |json: &str, index: usize| {
+            let rendered = JsonError::new(json.as_bytes(), index).located(json.as_bytes()).to_string();
+            let lines = rendered.split('\n').collect::<Vec<_>>();
+            lines[lines.len() - 2..].join("\n")
+        };
+        assert_eq!(snippet("[1,\t?]", 4), "1 | [1, ?]\n  |     ^");
+        assert_eq!(snippet("[1,\r\n", 5), "2 | \n  | ^");
+        let long = format!("[{}x{}]", "é,".repeat(50), "0,".repeat(50));
+        assert_eq!(snippet(&long, 151), format!("1 | ...{}x{}...\n  | {}^", "é,".repeat(16), "0,".repeat(16), " ".repeat(35)));
+        assert_eq!(snippet("\n\n  x", 4), "3 |   x\n  |   ^");
+        assert_eq!(JsonError::new(b"[\"\xFF\"]", 1).located(b"[\"\xFF\"]").to_string().lines().count(), 1);
+    }
+
+    #[test]
+    fn compose_errors() {
+        let error: Box<dyn std::error::Error + Send + Sync> = Box::new(JsonError::new(b"[1 2]", 3).located(b"[1 2]"));
+        assert!(error.source().is_none());
+        let error = error.downcast::<JsonError>().unwrap();
+        assert_eq!(error.get_extract().as_deref(), Some("[1 2]"));
     }
-}
\ No newline at end of file
+}
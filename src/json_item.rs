@@ -1,24 +1,84 @@
+use std::any::Any;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::ops::Index;
+use std::sync::Arc;
 
-use crate::generics::{ArrayIterator, Container, MapIterator};
-use crate::generics::Container::{Array, MapBTree, MapVec};
+use crate::generics::{ArrayIterator, Container, KeyIterator, MapIterator, PairIterator, ValueIterator};
+use crate::generics::Container::{Array, Attached, MapBTree, MapVec, Stub};
 use crate::generics::IterArray::{IterArrayEmpty, IterArrayVec};
 use crate::generics::IterMap::{IterMapBTree, IterMapEmpty, IterMapVec};
 use crate::json_type::JsonType;
-use crate::json_type::JsonType::{Empty, JsonArray, JsonFalse, JsonMap, JsonNull, JsonNumber, JsonTrue};
+use crate::json_type::JsonType::{Empty, JsonArray, JsonFalse, JsonMap, JsonNull, JsonNumber, JsonString, JsonTrue};
 use crate::key::Key;
+use crate::number::{FloatEquality, JsonNumberValue, parse_integer, parse_number_text};
+use crate::options::ParseOptions;
 use crate::slice::Slice;
+use crate::stub::StubOptions;
+use crate::text::{truncate, unescape};
 
 const KEEP_VEC_THRESHOLD: usize = 64;
 
-static EMPTY_ITEM: JsonItem = JsonItem::empty();
+pub(crate) static EMPTY_ITEM: JsonItem = JsonItem::empty();
+
+/// State of an item as seen by PATCH-style APIs, see [`JsonItem::field_state`]
+#[derive(Debug, PartialEq)]
+pub enum FieldState<'a> {
+    /// The item does not exist, e.g. the field was omitted
+    Missing,
+    /// The item is a JSON `null`
+    Null,
+    /// The item holds any other value
+    Present(&'a JsonItem),
+}
+
+/// Intent of a JSON merge-patch field: leave the target as is, clear it, or set it to a value.
+/// See [`JsonItem::update`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Update<T> {
+    /// The field was omitted, the target is left unchanged
+    Missing,
+    /// The field was set to `null`, the target is cleared
+    Null,
+    /// The field was set to a value
+    Value(T),
+}
+
+impl<T> Update<T> {
+    /// Maps the value of an `Update::Value`, leaving other states unchanged
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Update<U> {
+        match self {
+            Update::Missing => { Update::Missing }
+            Update::Null => { Update::Null }
+            Update::Value(value) => { Update::Value(f(value)) }
+        }
+    }
+
+    /// Applies the update to an optional field: `Missing` keeps it, `Null` clears it and `Value` replaces it
+    pub fn apply(self, target: &mut Option<T>) {
+        match self {
+            Update::Missing => {}
+            Update::Null => { *target = None; }
+            Update::Value(value) => { *target = Some(value); }
+        }
+    }
+}
+
+/// Entries of an object as stored after parsing, see [`JsonItem::as_object`]
+#[derive(Debug, Clone, Copy)]
+pub enum Members<'a> {
+    /// Entries of objects with few keys, in source order
+    Ordered(&'a [(Key, JsonItem)]),
+    /// Entries of objects with many keys, sorted in an internal order of keys suited to lookups, not by key text
+    Indexed(&'a BTreeMap<Key, JsonItem>),
+}
 
 /// Container for a JSON element, i.e. can contain a JSON null, bool, string, number, object or array.
 #[derive(Debug)]
 pub struct JsonItem {
     pub(crate) slice: Slice,
     pub(crate) json_type: JsonType,
-    container: Option<Container<Key, JsonItem>>,
+    pub(crate) container: Option<Container<Key, JsonItem>>,
 }
 
 impl JsonItem {
@@ -40,14 +100,18 @@ impl JsonItem {
                 let container = if map.len() <= KEEP_VEC_THRESHOLD {
                     MapVec(map)
                 } else {
-                    MapBTree(map.into_iter().map(|(k, v)| (k, v)).collect())
+                    MapBTree(map.into_iter().collect())
                 };
                 JsonItem { slice, json_type: JsonMap, container: Some(container) }
             }
         }
     }
 
-    const fn empty() -> Self {
+    pub(crate) fn new_stub(slice: Slice, json_type: JsonType, options: Arc<ParseOptions>) -> Self {
+        JsonItem { slice, json_type, container: Some(Stub(StubOptions(options))) }
+    }
+
+    pub(crate) const fn empty() -> Self {
         JsonItem { slice: Slice::empty(), json_type: Empty, container: None }
     }
 
@@ -62,22 +126,82 @@ impl JsonItem {
         }
     }
 
+    /// If the item is a string, returns its text content with escape sequences decoded, borrowing the source when there are none.
+    /// Besides standard JSON escapes, JSON5 ones are decoded: `\'`, `\v`, `\0`, `\xHH`, and line continuations,
+    /// i.e. a `\` followed by a line break, which is removed so that long strings can be split over several lines.
+    /// Returns `None` if the item is not a string or contains a malformed `\u` or `\x` escape.
+    pub fn as_unescaped(&self) -> Option<Cow<'_, str>> {
+        if self.json_type != JsonString {
+            None
+        } else {
+            unescape(self.slice.as_str())
+        }
+    }
+
+    /// If the item is a string, returns its text content cut down to at most `max_chars` characters.
+    /// The cut never splits a UTF-8 sequence or an escape sequence (an escape counts as one character),
+    /// and with the `grapheme` feature enabled, never splits a grapheme cluster either.
+    /// Otherwise, returns `None`.
+    pub fn truncate_string_value(&self, max_chars: usize) -> Option<&str> {
+        if self.json_type != JsonString {
+            None
+        } else {
+            Some(truncate(self.slice.as_str(), max_chars))
+        }
+    }
+
+    /// Reads the item as a merge-patch field, converting values with `convert`.
+    /// Returns `None` if the item holds a value that `convert` rejects.
+    ///
+    /// ```rust
+    /// use jsonic::json_item::{JsonItem, Update};
+    ///
+    /// let mut nickname = Some("jo".to_owned());
+    /// let mut age = Some(41);
+    /// let patch = jsonic::parse("{\"nickname\": null, \"age\": 42}").unwrap();
+    ///
+    /// patch["nickname"].update(|item| item.as_unescaped().map(|s| s.into_owned())).unwrap().apply(&mut nickname);
+    /// patch["age"].update(JsonItem::as_i128).unwrap().apply(&mut age);
+    /// assert_eq!((nickname, age), (None, Some(42)));
+    ///
+    /// assert_eq!(patch["email"].update(JsonItem::as_bool), Some(Update::Missing));
+    /// assert_eq!(patch["age"].update(JsonItem::as_bool), None);
+    /// ```
+    pub fn update<'a, T>(&'a self, convert: impl FnOnce(&'a JsonItem) -> Option<T>) -> Option<Update<T>> {
+        match self.field_state() {
+            FieldState::Missing => { Some(Update::Missing) }
+            FieldState::Null => { Some(Update::Null) }
+            FieldState::Present(item) => { convert(item).map(Update::Value) }
+        }
+    }
+
     /// Tries to convert item to `f64`. If the conversion fails, returns `None`.
     pub fn as_f64(&self) -> Option<f64> {
         if self.json_type != JsonNumber {
             None
         } else {
-            self.as_str()?.parse::<f64>().ok()
+            let text = self.as_str()?;
+            text.parse::<f64>().ok().or_else(|| parse_integer(text).map(|integer| integer as f64))
         }
     }
 
-    /// Tries to convert item to an `i128` integer. If the conversion fails, returns `None`.
+    /// Tries to convert item to an `i128` integer (hexadecimal JSON5 literals included). If the conversion fails, returns `None`.
     /// Resulting `i128` can then be converted to other integer types as required.
     pub fn as_i128(&self) -> Option<i128> {
         if self.json_type != JsonNumber {
             None
         } else {
-            self.as_str()?.parse::<i128>().ok()
+            parse_integer(self.as_str()?)
+        }
+    }
+
+    /// Tries to convert item to an integer, or a float if it is not integral. If the item is not a number, returns `None`.
+    /// See [`parse_json_number`](crate::number::parse_json_number), extended number syntaxes being accepted as parsed.
+    pub fn as_number(&self) -> Option<JsonNumberValue> {
+        if self.json_type != JsonNumber {
+            None
+        } else {
+            parse_number_text(self.as_str()?)
         }
     }
 
@@ -90,7 +214,16 @@ impl JsonItem {
         }
     }
 
-    /// Checks if item is a JSON null
+    /// Returns the payload attached to the item by a [`Recognizer`](crate::plugin::Recognizer) if it is of type `T`.
+    /// Otherwise, returns `None`.
+    pub fn payload<T: Any>(&self) -> Option<&T> {
+        match &self.container {
+            Some(Attached(payload)) => { payload.downcast_ref() }
+            _ => { None }
+        }
+    }
+
+    /// Checks if item is a JSON null. Non-existent items are not null, see [`is_missing`](JsonItem::is_missing).
     pub fn is_null(&self) -> bool {
         self.json_type == JsonNull
     }
@@ -100,41 +233,363 @@ impl JsonItem {
         self.json_type != Empty
     }
 
+    /// Checks if item is non-existent, e.g. an object member that is absent, as opposed to one set to `null`
+    ///
+    /// ```rust
+    /// let patch = jsonic::parse("{\"nickname\": null}").unwrap();
+    ///
+    /// assert!(patch["nickname"].is_null() && !patch["nickname"].is_missing());
+    /// assert!(patch["email"].is_missing() && !patch["email"].is_null());
+    /// ```
+    pub fn is_missing(&self) -> bool {
+        self.json_type == Empty
+    }
+
+    /// Returns whether the item is missing, `null`, or holds a value.
+    /// PATCH-style APIs use this to tell an omitted field from a field explicitly set to `null`.
+    ///
+    /// ```rust
+    /// use jsonic::json_item::FieldState;
+    ///
+    /// let patch = jsonic::parse("{\"nickname\": null, \"age\": 42}").unwrap();
+    ///
+    /// assert_eq!(patch["email"].field_state(), FieldState::Missing);
+    /// assert_eq!(patch["nickname"].field_state(), FieldState::Null);
+    /// assert!(matches!(patch["age"].field_state(), FieldState::Present(age) if age.as_i128() == Some(42)));
+    /// ```
+    pub fn field_state(&self) -> FieldState<'_> {
+        match self.json_type {
+            Empty => { FieldState::Missing }
+            JsonNull => { FieldState::Null }
+            _ => { FieldState::Present(self) }
+        }
+    }
+
     /// Returns item's type
     pub fn get_type(&self) -> &JsonType {
         &self.json_type
     }
 
-    /// If the item is an array, returns an iterator over array elements. If the array is empty (`[]`), an empty iterator is returned.
-    /// Otherwise, returns `None`.
-    pub fn elements(&self) -> Option<ArrayIterator<JsonItem>> {
-        if let Some(container) = &self.container {
-            if let Array(array) = container {
-                return Some(ArrayIterator { iter: IterArrayVec(array.iter()) });
+    /// Returns the item designated by a JSON Pointer (RFC 6901) relative to this item, e.g. `/users/0/name`,
+    /// the empty pointer designating the item itself. Keys are compared with object keys as written in the source.
+    /// If there is no such item, or the pointer does not start with `/`, a non-existent item is returned.
+    ///
+    /// ```rust
+    /// let parsed = jsonic::parse("{\"users\": [{\"name\": \"ann\"}], \"a/b\": 1}").unwrap();
+    ///
+    /// assert_eq!(parsed.pointer("/users/0/name").as_str(), Some("ann"));
+    /// assert_eq!(parsed.pointer("/a~1b").as_i128(), Some(1));
+    /// assert!(!parsed.pointer("/users/1").exists());
+    /// ```
+    pub fn pointer(&self, pointer: &str) -> &JsonItem {
+        if pointer.is_empty() {
+            return self;
+        }
+        let segments = match pointer.strip_prefix('/') {
+            Some(segments) => { segments }
+            None => { return &EMPTY_ITEM; }
+        };
+        let mut item = self;
+        for segment in segments.split('/') {
+            let segment = if segment.contains('~') { Cow::Owned(segment.replace("~1", "/").replace("~0", "~")) } else { Cow::Borrowed(segment) };
+            item = match item.json_type {
+                JsonArray => {
+                    match segment.parse::<usize>() {
+                        Ok(index) if segment.bytes().all(|b| b.is_ascii_digit()) && (segment.len() == 1 || !segment.starts_with('0')) => { &item[index] }
+                        _ => { &EMPTY_ITEM }
+                    }
+                }
+                _ => { &item[segment.as_ref()] }
+            };
+            if item.json_type == Empty {
+                break;
             }
-        } else {
-            if self.json_type == JsonArray {
-                return Some(ArrayIterator { iter: IterArrayEmpty() });
+        }
+        item
+    }
+
+    /// Returns the object member `key`, matched as with indexing by `&str`, or `None` if there is no such member
+    ///
+    /// ```rust
+    /// let parsed = jsonic::parse("{\"user\": {\"name\": \"ann\"}}").unwrap();
+    ///
+    /// assert_eq!(parsed.get("user").and_then(|user| user.get("name")?.as_str()), Some("ann"));
+    /// assert!(parsed.get("group").is_none());
+    /// ```
+    pub fn get(&self, key: &str) -> Option<&JsonItem> {
+        Some(&self[key]).filter(|item| item.exists())
+    }
+
+    /// Checks if the item is an object holding the key `key`, matched as with indexing by `&str`
+    pub fn contains_key(&self, key: &str) -> bool {
+        let key = Key::from_str(key);
+        match &self.container {
+            Some(MapVec(map)) => { map.iter().any(|(k, _)| key.eq(k)) }
+            Some(MapBTree(map)) => { map.contains_key(&key) }
+            _ => { false }
+        }
+    }
+
+    /// Returns the array element at `index`, or `None` if there is no such element
+    pub fn get_index(&self, index: usize) -> Option<&JsonItem> {
+        Some(&self[index]).filter(|item| item.exists())
+    }
+
+    /// Returns the array element `n` places from the end, `get_back(0)` being the last one,
+    /// or `None` if there is no such element
+    ///
+    /// ```rust
+    /// let parsed = jsonic::parse("{\"events\": [\"start\", \"tick\", \"stop\"]}").unwrap();
+    ///
+    /// assert_eq!(parsed["events"].get_back(0).and_then(|last| last.as_str()), Some("stop"));
+    /// assert!(parsed["events"].get_back(3).is_none());
+    /// ```
+    pub fn get_back(&self, n: usize) -> Option<&JsonItem> {
+        match &self.container {
+            Some(Array(array)) => { array.iter().nth_back(n) }
+            _ => { None }
+        }
+    }
+
+    /// Returns the object members `keys`, as [`get`](JsonItem::get) does for each one, walking the object once
+    /// instead of once per key, which is faster when extracting many fields from a large object
+    ///
+    /// ```rust
+    /// let parsed = jsonic::parse("{\"id\": 7, \"name\": \"ann\", \"tags\": []}").unwrap();
+    ///
+    /// let [id, name, email] = parsed.get_many(["id", "name", "email"]);
+    /// assert_eq!((id.and_then(|id| id.as_i128()), name.and_then(|name| name.as_str())), (Some(7), Some("ann")));
+    /// assert!(email.is_none());
+    /// ```
+    pub fn get_many<const N: usize>(&self, keys: [&str; N]) -> [Option<&JsonItem>; N] {
+        let mut found = [None; N];
+        match &self.container {
+            Some(MapVec(map)) => {
+                let keys = keys.map(Key::from_str);
+                let mut missing = N;
+                for (k, v) in map {
+                    for (key, slot) in keys.iter().zip(found.iter_mut()) {
+                        if slot.is_none() && key.eq(k) {
+                            *slot = Some(v);
+                            missing -= 1;
+                        }
+                    }
+                    if missing == 0 {
+                        break;
+                    }
+                }
+            }
+            Some(MapBTree(map)) => {
+                for (key, slot) in keys.iter().zip(found.iter_mut()) {
+                    *slot = map.get(&Key::from_str(key));
+                }
             }
+            _ => {}
+        }
+        found
+    }
+
+    /// If the item is an array, returns an iterator over array elements. If the array is empty (`[]`), an empty iterator is returned.
+    /// Otherwise, returns `None`, as for stubs, whose children are unknown until expanded.
+    pub fn elements(&self) -> Option<ArrayIterator<'_, JsonItem>> {
+        match &self.container {
+            Some(Array(array)) => { Some(ArrayIterator { iter: IterArrayVec(array.iter()) }) }
+            None if self.json_type == JsonArray => { Some(ArrayIterator { iter: IterArrayEmpty() }) }
+            _ => { None }
         }
-        None
     }
 
     /// If the item is an object, returns an iterator over object entries. If the object contains no entries (`{}`), an empty iterator is returned.
+    /// Otherwise, returns `None`, as for stubs, whose children are unknown until expanded.
+    pub fn entries(&self) -> Option<MapIterator<'_, Key, JsonItem>> {
+        match &self.container {
+            Some(MapVec(map)) => { Some(MapIterator { iter: IterMapVec(map.iter()) }) }
+            Some(MapBTree(map)) => { Some(MapIterator { iter: IterMapBTree(map.iter()) }) }
+            None if self.json_type == JsonMap => { Some(MapIterator { iter: IterMapEmpty() }) }
+            _ => { None }
+        }
+    }
+
+    /// Returns the number of elements of an array or of entries of an object, or `None` for other items
+    /// and for stubs, whose children are unknown until expanded
+    ///
+    /// ```rust
+    /// let parsed = jsonic::parse("{\"tags\": [\"a\", \"b\"], \"meta\": {}, \"name\": \"x\"}").unwrap();
+    ///
+    /// assert_eq!((parsed.len(), parsed["tags"].len(), parsed["name"].len()), (Some(3), Some(2), None));
+    /// assert_eq!(parsed["meta"].is_empty(), Some(true));
+    /// ```
+    pub fn len(&self) -> Option<usize> {
+        match &self.container {
+            Some(Array(array)) => { Some(array.len()) }
+            Some(MapVec(map)) => { Some(map.len()) }
+            Some(MapBTree(map)) => { Some(map.len()) }
+            Some(Attached(_) | Stub(_)) => { None }
+            None => { matches!(self.json_type, JsonArray | JsonMap).then_some(0) }
+        }
+    }
+
+    /// Checks if an array or object has no elements or entries, see [`len`](JsonItem::len)
+    pub fn is_empty(&self) -> Option<bool> {
+        self.len().map(|len| len == 0)
+    }
+
+    /// If the item is an object, returns an iterator over its keys, in the order of [`entries`](JsonItem::entries).
     /// Otherwise, returns `None`.
-    pub fn entries(&self) -> Option<MapIterator<Key, JsonItem>> {
-        if let Some(container) = &self.container {
-            return match container {
-                MapVec(map) => { Some(MapIterator { iter: IterMapVec(map.iter()) }) }
-                MapBTree(map) => { Some(MapIterator { iter: IterMapBTree(map.iter()) }) }
-                _ => { None }
-            };
-        } else {
-            if self.json_type == JsonMap {
-                return Some(MapIterator { iter: IterMapEmpty() });
+    pub fn keys(&self) -> Option<KeyIterator<'_, Key, JsonItem>> {
+        self.entries().map(|entries| KeyIterator { entries })
+    }
+
+    /// If the item is an object, returns an iterator over its values, in the order of [`entries`](JsonItem::entries).
+    /// Otherwise, returns `None`.
+    pub fn values(&self) -> Option<ValueIterator<'_, Key, JsonItem>> {
+        self.entries().map(|entries| ValueIterator { entries })
+    }
+
+    /// If the item is an object, returns an iterator over its entries, in the order of [`entries`](JsonItem::entries),
+    /// with keys as text, as written in the source (escape sequences included). Otherwise, returns `None`.
+    ///
+    /// ```rust
+    /// let parsed = jsonic::parse("{\"host\": \"localhost\", \"port\": 80}").unwrap();
+    ///
+    /// for (key, value) in parsed.pairs().unwrap() {
+    ///     assert!(key == "host" || value.as_i128() == Some(80));
+    /// }
+    /// assert_eq!(parsed.pairs().unwrap().rev().next().map(|(key, _)| key), Some("port"));
+    /// ```
+    pub fn pairs(&self) -> Option<PairIterator<'_>> {
+        self.entries().map(|entries| PairIterator { entries })
+    }
+
+    /// If the item is an array, returns its elements as a slice, so that the slice API applies, e.g. `split_at`,
+    /// `chunks` or `binary_search_by`. Otherwise, returns `None`, as for stubs.
+    ///
+    /// ```rust
+    /// let parsed = jsonic::parse("[1, 3, 5, 7]").unwrap();
+    ///
+    /// let elements = parsed.as_array().unwrap();
+    /// assert_eq!(elements.binary_search_by_key(&5, |element| element.as_i128().unwrap()), Ok(2));
+    /// assert_eq!(elements.split_last().unwrap().1.len(), 3);
+    /// ```
+    pub fn as_array(&self) -> Option<&[JsonItem]> {
+        match &self.container {
+            Some(Array(array)) => { Some(array) }
+            None if self.json_type == JsonArray => { Some(&[]) }
+            _ => { None }
+        }
+    }
+
+    /// If the item is an object, returns its entries in the collection they are stored in, so that its API applies:
+    /// objects with few keys keep them in a slice, in source order, and others in a [`BTreeMap`].
+    /// Otherwise, returns `None`, as for stubs.
+    ///
+    /// ```rust
+    /// use jsonic::json_item::Members;
+    ///
+    /// let parsed = jsonic::parse("{\"a\": 1, \"b\": 2}").unwrap();
+    ///
+    /// match parsed.as_object().unwrap() {
+    ///     Members::Ordered(entries) => { assert_eq!(entries[1].0.as_str(), "b") }
+    ///     Members::Indexed(_) => { unreachable!() }
+    /// }
+    /// ```
+    pub fn as_object(&self) -> Option<Members<'_>> {
+        match &self.container {
+            Some(MapVec(map)) => { Some(Members::Ordered(map)) }
+            Some(MapBTree(map)) => { Some(Members::Indexed(map)) }
+            None if self.json_type == JsonMap => { Some(Members::Ordered(&[])) }
+            _ => { None }
+        }
+    }
+
+    /// Deep comparison with another item: object entries are compared regardless of their order, strings and keys
+    /// regardless of how they are escaped, and numbers by value, floats according to `floats`.
+    /// Payloads attached by recognizers are ignored.
+    ///
+    /// ```rust
+    /// use jsonic::number::FloatEquality;
+    ///
+    /// let expected = jsonic::parse("{\"total\": 0.3, \"unit\": \"\\u20ac\"}").unwrap();
+    /// let computed = jsonic::parse("{\"unit\": \"€\", \"total\": 0.30000000000000004}").unwrap();
+    ///
+    /// assert!(!expected.equals(&computed, FloatEquality::Bitwise));
+    /// assert!(expected.equals(&computed, FloatEquality::Ulps(4)));
+    /// ```
+    pub fn equals(&self, other: &JsonItem, floats: FloatEquality) -> bool {
+        // Stubs are compared once expanded
+        if let Some(stub) = self.as_stub() {
+            return stub.expanded().equals(other, floats);
+        }
+        if let Some(stub) = other.as_stub() {
+            return self.equals(&stub.expanded(), floats);
+        }
+        match (&self.json_type, &other.json_type) {
+            (JsonNumber, JsonNumber) => {
+                match (self.as_number(), other.as_number()) {
+                    (Some(a), Some(b)) => { floats.eq_number(a, b) }
+                    _ => { self.slice.as_str() == other.slice.as_str() }
+                }
+            }
+            (JsonString, JsonString) => { unescaped(self.slice.as_str()) == unescaped(other.slice.as_str()) }
+            (JsonArray, JsonArray) => {
+                let (mut a, mut b) = (self.elements().unwrap(), other.elements().unwrap());
+                loop {
+                    match (a.next(), b.next()) {
+                        (Some(x), Some(y)) if x.equals(y, floats) => {}
+                        (None, None) => { return true; }
+                        _ => { return false; }
+                    }
+                }
+            }
+            (JsonMap, JsonMap) => {
+                let (a, b) = (sorted_entries(self), sorted_entries(other));
+                a.len() == b.len() && a.iter().zip(&b).all(|((ka, va), (kb, vb))| ka == kb && va.equals(vb, floats))
+            }
+            (a, b) => { a == b }
+        }
+    }
+}
+
+// Text of a string or key with escapes decoded, as is if an escape is malformed
+fn unescaped(raw: &str) -> Cow<'_, str> {
+    unescape(raw).unwrap_or(Cow::Borrowed(raw))
+}
+
+fn sorted_entries(item: &JsonItem) -> Vec<(Cow<'_, str>, &JsonItem)> {
+    let mut entries = item.entries().unwrap().map(|(key, value)| (unescaped(key.as_str()), value)).collect::<Vec<_>>();
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+    entries
+}
+
+/// Deep comparison with bitwise float equality, see [`JsonItem::equals`]
+impl PartialEq for JsonItem {
+    fn eq(&self, other: &Self) -> bool {
+        self.equals(other, FloatEquality::Bitwise)
+    }
+}
+
+impl Drop for JsonItem {
+    fn drop(&mut self) {
+        // Nested containers are moved to a work stack before being dropped,
+        // so that dropping deeply nested documents does not overflow the call stack
+        let mut pending = match self.container.take() {
+            Some(Attached(_) | Stub(_)) | None => { return; }
+            Some(container) => { vec![container] }
+        };
+        while let Some(container) = pending.pop() {
+            match container {
+                Array(array) => { array.into_iter().for_each(|item| unnest(item, &mut pending)); }
+                MapVec(map) => { map.into_iter().for_each(|(_, item)| unnest(item, &mut pending)); }
+                MapBTree(map) => { map.into_values().for_each(|item| unnest(item, &mut pending)); }
+                Attached(_) | Stub(_) => {}
             }
         }
-        None
+    }
+}
+
+fn unnest(mut item: JsonItem, pending: &mut Vec<Container<Key, JsonItem>>) {
+    if let Some(container) = item.container.take() {
+        pending.push(container);
     }
 }
 
@@ -142,10 +597,8 @@ impl Index<usize> for JsonItem {
     type Output = JsonItem;
 
     fn index(&self, index: usize) -> &Self::Output {
-        if let Some(container) = &self.container {
-            if let Array(array) = container {
-                return array.get(index).unwrap_or(&EMPTY_ITEM);
-            }
+        if let Some(Array(array)) = &self.container {
+            return array.get(index).unwrap_or(&EMPTY_ITEM);
         }
         &EMPTY_ITEM
     }
@@ -171,4 +624,69 @@ impl Index<&str> for JsonItem {
         }
         &EMPTY_ITEM
     }
-}
\ No newline at end of file
+}
+
+impl Index<&String> for JsonItem {
+    type Output = JsonItem;
+
+    fn index(&self, key: &String) -> &Self::Output {
+        &self[key.as_str()]
+    }
+}
+
+impl Index<String> for JsonItem {
+    type Output = JsonItem;
+
+    fn index(&self, key: String) -> &Self::Output {
+        &self[key.as_str()]
+    }
+}
+
+impl Index<Cow<'_, str>> for JsonItem {
+    type Output = JsonItem;
+
+    fn index(&self, key: Cow<'_, str>) -> &Self::Output {
+        &self[&*key]
+    }
+}
+
+/// Iterates over the elements of an array, as [`JsonItem::elements`] does, and over nothing for other items
+///
+/// ```rust
+/// let parsed = jsonic::parse("{\"ids\": [3, 4]}").unwrap();
+///
+/// let mut total = 0;
+/// for id in &parsed["ids"] {
+///     total += id.as_i128().unwrap();
+/// }
+/// assert_eq!(total, 7);
+/// assert_eq!((&parsed).into_iter().count(), 0);
+/// ```
+impl<'a> IntoIterator for &'a JsonItem {
+    type Item = &'a JsonItem;
+    type IntoIter = ArrayIterator<'a, JsonItem>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.elements().unwrap_or(ArrayIterator { iter: IterArrayEmpty() })
+    }
+}
+
+/// Iterates over the entries of an object, as [`JsonItem::entries`] does
+///
+/// ```rust
+/// let parsed = jsonic::parse("{\"a\": 1, \"b\": 2}").unwrap();
+///
+/// let keys = parsed.as_object().unwrap().into_iter().map(|(key, _)| key.as_str()).collect::<Vec<_>>();
+/// assert_eq!(keys, ["a", "b"]);
+/// ```
+impl<'a> IntoIterator for Members<'a> {
+    type Item = (&'a Key, &'a JsonItem);
+    type IntoIter = MapIterator<'a, Key, JsonItem>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        match self {
+            Members::Ordered(map) => { MapIterator { iter: IterMapVec(map.iter()) } }
+            Members::Indexed(map) => { MapIterator { iter: IterMapBTree(map.iter()) } }
+        }
+    }
+}
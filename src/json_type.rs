@@ -16,7 +16,7 @@
 /// if let Ok(parsed) = jsonic::parse(json) {
 ///     assert!(parsed["c"].get_type() == &Empty);
 /// }
-#[derive(PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum JsonType {
     JsonNull,
     JsonTrue,
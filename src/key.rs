@@ -20,7 +20,9 @@ fn hash(bytes: &[u8]) -> u64 {
     hash
 }
 
-/// A struct representing JSON object keys
+/// A struct representing JSON object keys.
+/// Keys are not copied: like string values, they point into the source text, escape sequences included,
+/// so that their size does not depend on their length and no small-string optimization applies.
 #[derive(Debug)]
 pub struct Key {
     pub(crate) slice: Slice,
@@ -0,0 +1,249 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
+use crate::document::JsonDocument;
+use crate::json_error::JsonError;
+use crate::json_item::JsonItem;
+use crate::json_type::JsonType::{JsonArray, JsonMap};
+use crate::key::Key;
+use crate::serialize::write_compact;
+
+// Text separating the tokens of a document, as recorded while parsing with `ParseOptions::preserve_layout`
+// or recovered from the spans of its items: byte ranges of its source in increasing order, each one starting
+// where a token ends
+pub(crate) struct Layout<'a> {
+    source: &'a str,
+    separators: Cow<'a, [Range<usize>]>,
+    // Whether the separators were recorded while parsing, rather than recovered from spans
+    recorded: bool,
+}
+
+impl<'a> Layout<'a> {
+    // Text recorded after the token ending at byte `end`, if any
+    pub(crate) fn after(&self, end: usize) -> Option<&'a str> {
+        let index = self.separators.binary_search_by_key(&end, |separator| separator.start).ok()?;
+        Some(&self.source[self.separators[index].clone()])
+    }
+
+    // Source text in `range`
+    pub(crate) fn text(&self, range: Range<usize>) -> &'a str {
+        &self.source[range]
+    }
+
+    // Byte range of the text of `item`, quotes included
+    pub(crate) fn range(&self, item: &JsonItem) -> Option<Range<usize>> {
+        item.span(self.source).map(|span| span.get_range())
+    }
+
+    // Byte range of the text of `key`, quotes included
+    pub(crate) fn key_range(&self, key: &Key) -> Range<usize> {
+        let start = key.slice.offset_in(self.source.as_bytes());
+        match self.source.as_bytes()[start - 1] {
+            b'"' | b'\'' => { start - 1..start + key.slice.len + 1 }
+            _ => { start..start + key.slice.len }
+        }
+    }
+
+    // Layout of `root` recovered from the spans of its items and keys, for documents whose layout was not recorded.
+    // Text between tokens is taken as is, so members dropped as duplicate keys are kept as part of it.
+    fn from_spans(source: &'a str, root: &JsonItem) -> Self {
+        let mut layout = Layout { source, separators: Cow::Owned(Vec::new()), recorded: false };
+        let mut tokens = Vec::new();
+        let mut stack = vec![root];
+        while let Some(item) = stack.pop() {
+            let range = match layout.range(item) {
+                Some(range) => { range }
+                None => { continue; }
+            };
+            match (item.json_type, source.as_bytes()[range.start]) {
+                _ if item.is_stub() => { tokens.push(range) }
+                (JsonArray, b'[') | (JsonMap, b'{') => {
+                    tokens.push(range.start..range.start + 1);
+                    tokens.push(range.end - 1..range.end);
+                    stack.extend(item.elements().into_iter().flatten());
+                    for (key, value) in item.entries().into_iter().flatten() {
+                        tokens.push(layout.key_range(key));
+                        stack.push(value);
+                    }
+                }
+                _ => { tokens.push(range) }
+            }
+        }
+        tokens.sort_by_key(|token| token.start);
+
+        let (mut separators, mut end) = (Vec::new(), 0);
+        for token in tokens {
+            if token.start > end {
+                separators.push(end..token.start);
+            }
+            end = end.max(token.end);
+        }
+        if source.len() > end {
+            separators.push(end..source.len());
+        }
+        layout.separators = Cow::Owned(separators);
+        layout
+    }
+
+    // Expands the stub `item`, with the layout recorded for its text
+    pub(crate) fn expand(&self, item: &JsonItem) -> Result<(JsonItem, Layout<'a>), JsonError> {
+        let offset = self.range(item).map_or(0, |range| range.start);
+        let (expanded, separators) = item.as_stub().expect("item is a stub").expand_recording()?;
+        let separators = separators.into_iter().map(|separator| separator.start + offset..separator.end + offset).collect::<Vec<_>>();
+        Ok((expanded, Layout { source: self.source, separators: Cow::Owned(separators), recorded: true }))
+    }
+}
+
+impl JsonDocument {
+    pub(crate) fn layout(&self) -> Layout<'_> {
+        match self.separators() {
+            [] => { Layout::from_spans(self.source(), self.root()) }
+            separators => { Layout { source: self.source(), separators: Cow::Borrowed(separators), recorded: true } }
+        }
+    }
+
+    /// Returns the text of the document written with the layout recorded while parsing it, see
+    /// [`ParseOptions::preserve_layout`](crate::options::ParseOptions::preserve_layout): whitespace, comments, commas
+    /// and colons are written as recorded between tokens, and tokens as spelled in the source, so that the source text
+    /// is reproduced byte for byte. Members dropped as duplicate keys are not written. Without a recorded layout,
+    /// the text between tokens is recovered from the spans of the items, which reproduces the source text as well,
+    /// dropped members being kept as part of that text.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jsonic::options::{ParseOptions, Parser};
+    ///
+    /// let source = "{\n  \"port\": 8080,\n  \"hosts\": [ \"a\" , \"b\" ]\n}\n";
+    /// let document = Parser::new(ParseOptions::new().preserve_layout(true)).parse_document(source.to_owned()).unwrap();
+    /// assert_eq!(document.to_json_preserving(), source);
+    ///
+    /// let document = Parser::new(ParseOptions::new()).parse_document(source.to_owned()).unwrap();
+    /// assert_eq!(document.to_json_preserving(), source);
+    /// ```
+    pub fn to_json_preserving(&self) -> String {
+        let layout = self.layout();
+        let mut output = String::from(layout.after(0).unwrap_or(""));
+        write_laid_out(self.root(), &layout, &mut output);
+        if let Some(range) = layout.range(self.root()) {
+            output.push_str(layout.after(range.end).unwrap_or(""));
+        }
+        output
+    }
+}
+
+// Pending output of `write_laid_out`: an item to write, or text
+enum Work<'i, 'a> {
+    Item(&'i JsonItem),
+    Text(&'a str),
+}
+
+// Writes `item` with the separators recorded in `layout` and its tokens as spelled in the source.
+// Separators which were not recorded are written as in compact JSON, and stubs as their source text,
+// as well as objects and arrays when the layout was recovered from spans.
+// Nested items are written from a stack of pending work rather than by recursion, so that deep documents do not
+// exhaust the call stack.
+pub(crate) fn write_laid_out(item: &JsonItem, layout: &Layout<'_>, output: &mut String) {
+    let mut stack = vec![Work::Item(item)];
+    while let Some(work) = stack.pop() {
+        let item = match work {
+            Work::Item(item) => { item }
+            Work::Text(text) => {
+                output.push_str(text);
+                continue;
+            }
+        };
+        let range = match layout.range(item) {
+            Some(range) => { range }
+            None => {
+                write_compact(item, output);
+                continue;
+            }
+        };
+        let text = layout.text(range.clone());
+        let children = match (item.json_type, text.as_bytes()[0]) {
+            _ if item.is_stub() => {
+                output.push_str(text);
+                continue;
+            }
+            (JsonArray, b'[') | (JsonMap, b'{') if !layout.recorded => {
+                output.push_str(text);
+                continue;
+            }
+            (JsonArray, b'[') => { item.elements().into_iter().flatten().map(|element| (None, element)).collect::<Vec<_>>() }
+            (JsonMap, b'{') => {
+                let mut entries = item.entries().into_iter().flatten().map(|(key, value)| (Some(layout.key_range(key)), value)).collect::<Vec<_>>();
+                entries.sort_by_key(|(key, _)| key.as_ref().map(|key| key.start));
+                entries
+            }
+            // Values collected from duplicate keys have no brackets of their own
+            (JsonArray | JsonMap, _) => {
+                write_compact(item, output);
+                continue;
+            }
+            _ => {
+                output.push_str(text);
+                continue;
+            }
+        };
+
+        // Work for the container in writing order, pushed in reverse
+        let mut pending = vec![Work::Text(&text[..1])];
+        let mut end = range.start + 1;
+        for (index, (key, value)) in children.into_iter().enumerate() {
+            pending.push(Work::Text(layout.after(end).unwrap_or(if index > 0 { "," } else { "" })));
+            if let Some(key) = key {
+                pending.push(Work::Text(layout.text(key.clone())));
+                pending.push(Work::Text(layout.after(key.end).unwrap_or(":")));
+            }
+            pending.push(Work::Item(value));
+            end = layout.range(value).map_or(end, |range| range.end);
+        }
+        pending.push(Work::Text(layout.after(end).unwrap_or("")));
+        pending.push(Work::Text(&text[text.len() - 1..]));
+        stack.extend(pending.into_iter().rev());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::options::{ParseOptions, Parser};
+
+    #[test]
+    fn reproduce_source() {
+        let parser = Parser::new(ParseOptions::new().preserve_layout(true));
+        for source in ["[]", " { } ", "\t[ 1 ,2, [ ], {\"a\" :{}} ]\n", "{\"a\": 1.50, \"b\": [\"\\u0041\", -0e+1], \"c\": {\"d\": null}}\r\n"] {
+            assert_eq!(parser.parse_document(source.to_owned()).unwrap().to_json_preserving(), source);
+        }
+        let strict = Parser::new(ParseOptions::strict().preserve_layout(true));
+        assert_eq!(strict.parse_document(" \"text\" ".to_owned()).unwrap().to_json_preserving(), " \"text\" ");
+
+        let source = "{\n  \"a\": [1, [2, 3]],\n  \"b\": {\"c\": [ ]}\n}";
+        let stubs = Parser::new(ParseOptions::new().materialize_depth(1)).parse_document(source.to_owned()).unwrap();
+        assert_eq!(stubs.to_json_preserving(), source);
+
+        let stubs = Parser::new(ParseOptions::new().materialize_depth(1).preserve_layout(true)).parse_document(source.to_owned()).unwrap();
+        assert_eq!(stubs.to_json_preserving(), source);
+
+        let duplicates = "{\"a\": 1, \"b\": [2], \"a\": 3}";
+        let unrecorded = Parser::new(ParseOptions::new()).parse_document(duplicates.to_owned()).unwrap();
+        assert_eq!(unrecorded.to_json_preserving(), duplicates);
+    }
+
+    #[test]
+    fn reproduce_deep_source() {
+        let source = format!("{}{}", "[ ".repeat(200_000), " ]".repeat(200_000));
+        let document = Parser::new(ParseOptions::new().max_depth(usize::MAX).preserve_layout(true)).parse_document(source.clone()).unwrap();
+        assert_eq!(document.to_json_preserving(), source);
+    }
+
+    #[cfg(feature = "extended")]
+    #[test]
+    fn reproduce_extended_syntax() {
+        let source = "// settings\n{\n  port: 0x50, /* http */\n  'hosts': ['a', \"b\",], // trailing\n  debug: True,\n}\n/* end */";
+        let document = Parser::new(ParseOptions::lenient().preserve_layout(true)).parse_document(source.to_owned()).unwrap();
+        assert_eq!(document.to_json_preserving(), source);
+        let document = Parser::new(ParseOptions::lenient()).parse_document(source.to_owned()).unwrap();
+        assert_eq!(document.to_json_preserving(), source);
+    }
+}
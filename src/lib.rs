@@ -1,16 +1,140 @@
-use crate::json_error::JsonError;
-use crate::json_item::JsonItem;
-use crate::json_type::JsonType::{JsonFalse, JsonNull, JsonNumber, JsonString, JsonTrue};
+//! Fast, small JSON parsing library.
+//!
+//! # Features
+//!
+//! The core parser ([`parse`], [`json_item`], [`document`], [`options::Parser`]) is always compiled.
+//! Parsed values are [`JsonItem`]s borrowing their source text, or [`JsonDocument`]s owning it and dereferencing to their root item;
+//! these and the other core types are re-exported at the crate root, and [`prelude`] gathers everything needed for typical usage.
+//! Optional parts sit behind Cargo features, so that embedded users can keep binaries small:
+//!
+//! | Feature       | Default | Enables                                                                                         |
+//! |---------------|---------|-------------------------------------------------------------------------------------------------|
+//! | `extended`    | yes     | `parse_jsonc`, `parse_json5`, `parse_lenient`, the grammar presets and setters of [`options::ParseOptions`], the `quantity` module reading strings such as `"10s"` as quantities, and the `comments` module attaching source comments to items and keeping them in pretty-printed output |
+//! | `serialize`   | yes     | JSON output with [`to_json`](json_item::JsonItem::to_json) and its [pretty-printed](json_item::JsonItem::to_json_pretty), HTML-safe and sorted-key variants, [`to_json_preserving`](document::JsonDocument::to_json_preserving), the `delta`, `query`, `diff`, `merge`, `signing`, `table`, `writer` and `minify` modules, and `conditional::patch_if_pointer_equals` |
+//! | `streams`     | yes     | the `ndjson`, `seq`, `stream`, `quarantine` and `decode` modules, and the `index` module giving random access to NDJSON records |
+//! | `rayon`       | no      | `ndjson::parse_lines_parallel`, parsing NDJSON records across threads (implies `streams`)       |
+//! | `gzip`        | no      | transparent decompression of gzip data in `document::parse_reader`, `document::parse_file` and `decode` |
+//! | `zstd`        | no      | transparent decompression of Zstandard data in `document::parse_reader` and `document::parse_file` |
+//! | `bytes`       | no      | `document::parse_bytes`, parsing shared `bytes::Bytes` buffers without copying them              |
+//! | `fast-numbers`| no      | number output with `ryu` and `itoa`, faster than the standard library formatting with the same text |
+//! | `grapheme`    | no      | grapheme-aware [`truncate_string_value`](json_item::JsonItem::truncate_string_value)            |
+//! | `sha256`      | no      | [`structural_sha256`](json_item::JsonItem::structural_sha256), a SHA-256 digest of documents ignoring formatting and key order |
+//! | `color`       | no      | the `color` module and [`to_json_colored`](json_item::JsonItem::to_json_colored), pretty-printing JSON colored with ANSI escape sequences (implies `serialize`) |
+//! | `gen`         | no      | the `gen` module, generating random documents from a shape spec                                 |
+//! | `test-util`   | no      | the `test_util` module and seeded shuffling of parsed items                                     |
+//! | `breadcrumbs` | no      | path and byte offset in the message of panics raised while parsing                              |
+//! | `schema`      | no      | the `schema` module, validating documents against named JSON schemas compiled once             |
+//! | `units`       | no      | the `units` module and [`as_duration`](json_item::JsonItem::as_duration) and `as_byte_size` accessors reading config values such as `"1h30m"` or `"10MiB"` |
+//! | `diagnostics` | no      | codes, help, labels and terminal reports for [`JsonError`]s, ready to feed to diagnostic crates such as `miette` |
+//!
+//! Without `extended`, the code handling grammar extensions is compiled out of the parser.
+//! For the smallest footprint, depend on `jsonic` with `default-features = false` and build with
+//! `opt-level = "z"`, `lto = true`, `codegen-units = 1` and `panic = "abort"`.
+
+use std::ops::Range;
+use std::sync::Arc;
+
+pub use crate::document::JsonDocument;
+pub use crate::json_error::{ErrorKind, JsonError};
+pub use crate::json_item::JsonItem;
+pub use crate::options::{ParseOptions, Parser};
+pub use crate::recovery::parse_with_recovery;
+use crate::json_type::JsonType;
+use crate::json_type::JsonType::{JsonArray, JsonFalse, JsonMap, JsonNull, JsonNumber, JsonString, JsonTrue};
 use crate::key::Key;
+use crate::number::is_json_number;
+use crate::options::DuplicateKeys;
 use crate::slice::Slice;
 
 pub mod json_error;
 pub mod slice;
+pub mod span;
 pub mod json_item;
 
 pub mod json_type;
 pub mod key;
 pub mod generics;
+pub mod number;
+pub mod document;
+pub mod dictionary;
+pub mod accessors;
+pub mod coercion;
+pub mod conditional;
+pub mod plain;
+pub mod prelude;
+pub mod recovery;
+pub mod tracking;
+pub mod lookup;
+pub mod path_index;
+pub mod drift;
+pub mod stub;
+pub mod tagged;
+#[cfg(feature = "serialize")]
+pub mod delta;
+#[cfg(feature = "serialize")]
+pub mod query;
+#[cfg(feature = "serialize")]
+pub mod minify;
+#[cfg(feature = "serialize")]
+pub mod diff;
+#[cfg(feature = "serialize")]
+pub mod merge;
+#[cfg(feature = "serialize")]
+pub mod signing;
+#[cfg(feature = "serialize")]
+pub mod table;
+#[cfg(feature = "serialize")]
+pub mod writer;
+#[cfg(feature = "color")]
+pub mod color;
+#[cfg(feature = "serialize")]
+pub use crate::minify::minify;
+#[cfg(feature = "serialize")]
+pub use crate::serialize::{Escaping, Indent, SerializeOptions};
+pub mod options;
+pub mod plugin;
+#[cfg(feature = "extended")]
+pub mod quantity;
+#[cfg(feature = "extended")]
+pub mod comments;
+#[cfg(feature = "streams")]
+pub mod ndjson;
+#[cfg(feature = "streams")]
+pub mod seq;
+#[cfg(feature = "streams")]
+pub mod stream;
+#[cfg(feature = "streams")]
+pub mod quarantine;
+#[cfg(feature = "streams")]
+pub mod decode;
+#[cfg(feature = "streams")]
+pub mod index;
+#[cfg(feature = "streams")]
+pub use crate::decode::decode;
+mod breadcrumbs;
+mod decompress;
+mod duplicates;
+mod hash;
+#[cfg(feature = "serialize")]
+mod layout;
+#[cfg(feature = "serialize")]
+mod serialize;
+#[cfg(feature = "sha256")]
+mod sha256;
+mod syntax;
+mod text;
+#[cfg(feature = "gen")]
+pub mod gen;
+#[cfg(any(feature = "test-util", feature = "gen"))]
+mod rng;
+#[cfg(feature = "test-util")]
+pub mod test_util;
+#[cfg(feature = "schema")]
+pub mod schema;
+#[cfg(feature = "units")]
+pub mod units;
+#[cfg(feature = "diagnostics")]
+pub mod diagnostic;
 
 const DEFAULT_VEC_CAPACITY: usize = 2;
 
@@ -23,189 +147,736 @@ fn shift_index(item: &JsonItem) -> usize {
     }
 }
 
+// Index of the byte following the source text of `item`
+#[inline(always)]
+fn end_index(bytes: &[u8], item: &JsonItem) -> usize {
+    let start = item.slice.offset_in(bytes);
+    if item.json_type == JsonString {
+        start + item.slice.len + 1
+    } else {
+        start + item.slice.len
+    }
+}
+
+// Records the bytes from `start` to `end` as text separating tokens, when separators are recorded
+#[inline(always)]
+fn record_separator(separators: &mut Option<&mut Vec<Range<usize>>>, start: usize, end: usize) {
+    if let Some(separators) = separators {
+        if start < end {
+            separators.push(start..end);
+        }
+    }
+}
+
+#[inline(always)]
+fn skip_spaces(bytes: &[u8], index: usize, options: &ParseOptions) -> Result<usize, JsonError> {
+    next_token(bytes, index, options)?.ok_or_else(|| JsonError::new(bytes, bytes.len()))
+}
+
+// Index of the first byte at or after `index` which is neither whitespace nor part of a comment, or `None` at the end
+// of data. Unlike `skip_spaces`, no error is built at the end, so that checking for trailing data costs nothing.
 #[inline(always)]
-fn skip_spaces(bytes: &[u8], mut index: usize) -> Result<usize, JsonError> {
+fn next_token(bytes: &[u8], mut index: usize, options: &ParseOptions) -> Result<Option<usize>, JsonError> {
     while index < bytes.len() {
         match bytes[index] {
             b' ' | b'\n' | b'\r' | b'\t' => {}
-            _ => { return Ok(index); }
+            b'/' if options.syntax.comments() => {
+                index = skip_comment(bytes, index)?;
+                continue;
+            }
+            0x0B | 0x0C | 0xC2 | 0xE2 | 0xEF if options.syntax.extended_whitespace() => {
+                match extended_space_len(&bytes[index..]) {
+                    0 => { return Ok(Some(index)); }
+                    len => { index += len; }
+                }
+                continue;
+            }
+            _ => { return Ok(Some(index)); }
         }
         index += 1;
     }
-    Err(JsonError::new(bytes, index))
+    Ok(None)
 }
 
 #[inline(always)]
-fn parse_null(bytes: &[u8], index: usize) -> Result<JsonItem, JsonError> {
-    if index + 3 < bytes.len() {
-        if bytes[index + 1] == b'u' && bytes[index + 2] == b'l' && bytes[index + 3] == b'l' {
-            return Ok(JsonItem::new(Slice::from_bytes(bytes, index, index + 4), JsonNull));
+fn extended_space_len(bytes: &[u8]) -> usize {
+    match bytes {
+        [0x0B | 0x0C, ..] => { 1 }
+        [0xC2, 0xA0, ..] => { 2 }
+        [0xE2, 0x80, 0xA8 | 0xA9, ..] | [0xEF, 0xBB, 0xBF, ..] => { 3 }
+        _ => { 0 }
+    }
+}
+
+#[inline(always)]
+fn skip_comment(bytes: &[u8], index: usize) -> Result<usize, JsonError> {
+    match bytes.get(index + 1) {
+        Some(b'/') => {
+            match bytes[index + 2..].iter().position(|&b| b == b'\n') {
+                Some(end) => { Ok(index + 2 + end + 1) }
+                None => { Ok(bytes.len()) }
+            }
         }
+        Some(b'*') => {
+            match bytes[index + 2..].windows(2).position(|w| w == b"*/") {
+                Some(end) => { Ok(index + 2 + end + 2) }
+                None => { Err(JsonError::with_kind(index, ErrorKind::UnterminatedComment)) }
+            }
+        }
+        _ => { Err(JsonError::new(bytes, index)) }
     }
-    Err(JsonError::new(bytes, index))
+}
+
+// Error on a literal which does not match `word`, at the end of data if it is cut short
+#[cold]
+fn literal_error(bytes: &[u8], index: usize, word: &[u8]) -> JsonError {
+    if word.starts_with(&bytes[index..]) {
+        JsonError::new(bytes, bytes.len())
+    } else {
+        JsonError::new(bytes, index)
+    }
+}
+
+#[inline(always)]
+fn parse_null(bytes: &[u8], index: usize) -> Result<JsonItem, JsonError> {
+    if index + 3 < bytes.len() && bytes[index + 1] == b'u' && bytes[index + 2] == b'l' && bytes[index + 3] == b'l' {
+        return Ok(JsonItem::new(Slice::from_bytes(bytes, index, index + 4), JsonNull));
+    }
+    Err(literal_error(bytes, index, b"null"))
 }
 
 #[inline(always)]
 fn parse_true(bytes: &[u8], index: usize) -> Result<JsonItem, JsonError> {
-    if index + 3 < bytes.len() {
-        if bytes[index + 1] == b'r' && bytes[index + 2] == b'u' && bytes[index + 3] == b'e' {
-            return Ok(JsonItem::new(Slice::from_bytes(bytes, index, index + 4), JsonTrue));
-        }
+    if index + 3 < bytes.len() && bytes[index + 1] == b'r' && bytes[index + 2] == b'u' && bytes[index + 3] == b'e' {
+        return Ok(JsonItem::new(Slice::from_bytes(bytes, index, index + 4), JsonTrue));
     }
-    Err(JsonError::new(bytes, index))
+    Err(literal_error(bytes, index, b"true"))
 }
 
 #[inline(always)]
 fn parse_false(bytes: &[u8], index: usize) -> Result<JsonItem, JsonError> {
-    if index + 4 < bytes.len() {
-        if bytes[index + 1] == b'a' && bytes[index + 2] == b'l' && bytes[index + 3] == b's' && bytes[index + 4] == b'e' {
-            return Ok(JsonItem::new(Slice::from_bytes(bytes, index, index + 5), JsonFalse));
-        }
+    if index + 4 < bytes.len() && bytes[index + 1] == b'a' && bytes[index + 2] == b'l' && bytes[index + 3] == b's' && bytes[index + 4] == b'e' {
+        return Ok(JsonItem::new(Slice::from_bytes(bytes, index, index + 5), JsonFalse));
+    }
+    Err(literal_error(bytes, index, b"false"))
+}
+
+#[inline(always)]
+fn parse_word(bytes: &[u8], index: usize, word: &[u8], json_type: JsonType) -> Result<JsonItem, JsonError> {
+    if bytes[index..].starts_with(word) {
+        return Ok(JsonItem::new(Slice::from_bytes(bytes, index, index + word.len()), json_type));
     }
-    Err(JsonError::new(bytes, index))
+    Err(literal_error(bytes, index, word))
 }
 
 #[inline(always)]
-fn parse_number(bytes: &[u8], mut index: usize) -> Result<JsonItem, JsonError> {
+fn parse_number(bytes: &[u8], mut index: usize, options: &ParseOptions) -> Result<JsonItem, JsonError> {
     let mark = index;
-    index += 1;
-    while index < bytes.len() {
-        match bytes[index] {
-            b'0'..=b'9' | b'+' | b'-' | b'.' | b'e' | b'E' => {}
-            _ => {
-                return Ok(JsonItem::new(Slice::from_bytes(bytes, mark, index), JsonNumber));
+    let start = if bytes[index] == b'+' || bytes[index] == b'-' { index + 1 } else { index };
+    if options.syntax.extended_numbers() {
+        for word in [b"Infinity".as_slice(), b"NaN".as_slice()] {
+            if bytes[start..].starts_with(word) {
+                return Ok(JsonItem::new(Slice::from_bytes(bytes, mark, start + word.len()), JsonNumber));
             }
         }
+    }
+    if options.syntax.hex_numbers() && (bytes[start..].starts_with(b"0x") || bytes[start..].starts_with(b"0X")) {
+        index = start + 2;
+        while index < bytes.len() && bytes[index].is_ascii_hexdigit() {
+            index += 1;
+        }
+        if index == start + 2 {
+            return Err(JsonError::with_kind(index, ErrorKind::InvalidNumber));
+        }
+        return Ok(JsonItem::new(Slice::from_bytes(bytes, mark, index), JsonNumber));
+    }
+    index += 1;
+    while index < bytes.len() && matches!(bytes[index], b'0'..=b'9' | b'+' | b'-' | b'.' | b'e' | b'E') {
         index += 1;
     }
-    Err(JsonError::new(bytes, index))
+    let slice = Slice::from_bytes(bytes, mark, index);
+    if options.strict && !is_json_number(slice.as_str()) {
+        return Err(JsonError::with_kind(mark, ErrorKind::InvalidNumber));
+    }
+    Ok(JsonItem::new(slice, JsonNumber))
 }
 
 #[inline(always)]
-fn parse_string(bytes: &[u8], mut index: usize) -> Result<JsonItem, JsonError> {
+fn parse_string(bytes: &[u8], mut index: usize, quote: u8) -> Result<JsonItem, JsonError> {
     index += 1;
     let mark = index;
     let mut b = 0;
     while index < bytes.len() {
         let p = b;
         b = bytes[index];
-        if b == b'"' {
-            if p != b'\\' {
+        if b == quote && p != b'\\' {
+            return Ok(JsonItem::new(Slice::from_bytes(bytes, mark, index), JsonString));
+        }
+        index += 1;
+    }
+    Err(JsonError::with_kind(index, ErrorKind::UnterminatedString))
+}
+
+#[inline(always)]
+fn parse_quoted(bytes: &[u8], index: usize, quote: u8, options: &ParseOptions) -> Result<JsonItem, JsonError> {
+    if options.strict {
+        parse_string_strict(bytes, index, quote)
+    } else {
+        parse_string(bytes, index, quote)
+    }
+}
+
+// Same as `parse_string`, also checking escape sequences and rejecting control characters
+#[inline(always)]
+fn parse_string_strict(bytes: &[u8], mut index: usize, quote: u8) -> Result<JsonItem, JsonError> {
+    index += 1;
+    let mark = index;
+    while index < bytes.len() {
+        match bytes[index] {
+            b if b == quote => {
                 return Ok(JsonItem::new(Slice::from_bytes(bytes, mark, index), JsonString));
             }
+            0x00..=0x1F => { return Err(JsonError::new(bytes, index)); }
+            b'\\' => {
+                match bytes.get(index + 1) {
+                    Some(b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't') => { index += 1; }
+                    Some(b'\'') if quote == b'\'' => { index += 1; }
+                    Some(b'u') if bytes.get(index + 2..index + 6).is_some_and(|hex| hex.iter().all(u8::is_ascii_hexdigit)) => { index += 5; }
+                    _ => { return Err(JsonError::with_kind(index, ErrorKind::InvalidEscape)); }
+                }
+            }
+            _ => {}
         }
         index += 1;
     }
-    Err(JsonError::new(bytes, index))
+    Err(JsonError::with_kind(index, ErrorKind::UnterminatedString))
 }
 
 #[inline(always)]
-fn parse_item(bytes: &[u8], index: usize) -> Result<JsonItem, JsonError> {
-    return match bytes[index] {
-        b'n' => { Ok(parse_null(bytes, index)?) }
-        b't' => { Ok(parse_true(bytes, index)?) }
-        b'f' => { Ok(parse_false(bytes, index)?) }
-        b'+' | b'-' | b'0'..=b'9' => { Ok(parse_number(bytes, index)?) }
-        b'"' => { Ok(parse_string(bytes, index)?) }
-        b'{' => { Ok(parse_map(bytes, index)?) }
-        b'[' => { Ok(parse_array(bytes, index)?) }
+fn parse_identifier(bytes: &[u8], mut index: usize) -> Result<(Key, usize), JsonError> {
+    let mark = index;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'a'..=b'z' | b'A'..=b'Z' | b'_' | b'$' | 0x80..=0xFF => {}
+            b'0'..=b'9' if index > mark => {}
+            b'\\' if index + 1 < bytes.len() && bytes[index + 1] == b'u' => { index += 1; }
+            _ => { break; }
+        }
+        index += 1;
+    }
+    if index == mark {
+        return Err(JsonError::new(bytes, index));
+    }
+    Ok((Key::from_slice(Slice::from_bytes(bytes, mark, index)), index))
+}
+
+#[inline(always)]
+fn parse_key(bytes: &[u8], index: usize, options: &ParseOptions) -> Result<(Key, usize), JsonError> {
+    match bytes[index] {
+        b'"' => {
+            let key = parse_quoted(bytes, index, b'"', options)?;
+            let next = index + shift_index(&key);
+            Ok((Key::from_slice(key.slice), next))
+        }
+        b'\'' if options.syntax.single_quotes() => {
+            let key = parse_quoted(bytes, index, b'\'', options)?;
+            let next = index + shift_index(&key);
+            Ok((Key::from_slice(key.slice), next))
+        }
+        _ if options.syntax.unquoted_keys() => { parse_identifier(bytes, index) }
+        _ => { Err(JsonError::new(bytes, index)) }
+    }
+}
+
+#[inline(always)]
+fn parse_scalar(bytes: &[u8], index: usize, options: &ParseOptions) -> Result<JsonItem, JsonError> {
+    match bytes[index] {
+        b'n' => { parse_null(bytes, index) }
+        b't' => { parse_true(bytes, index) }
+        b'f' => { parse_false(bytes, index) }
+        b'+' | b'-' | b'0'..=b'9' => { parse_number(bytes, index, options) }
+        b'T' if options.syntax.python_literals() => { parse_word(bytes, index, b"True", JsonTrue) }
+        b'F' if options.syntax.python_literals() => { parse_word(bytes, index, b"False", JsonFalse) }
+        b'N' if options.syntax.python_literals() && bytes[index..].starts_with(b"None") => { parse_word(bytes, index, b"None", JsonNull) }
+        b'.' | b'I' | b'N' if options.syntax.extended_numbers() => { parse_number(bytes, index, options) }
+        b'"' => { parse_quoted(bytes, index, b'"', options) }
+        b'\'' if options.syntax.single_quotes() => { parse_quoted(bytes, index, b'\'', options) }
         _ => {
             Err(JsonError::new(bytes, index))
         }
-    };
+    }
 }
 
-#[inline(always)]
-fn parse_map(bytes: &[u8], mut index: usize) -> Result<JsonItem, JsonError> {
-    let mark = index;
+// Object or array being parsed, waiting on the work stack for its closing bracket
+enum Frame {
+    Map { mark: usize, map: Option<Vec<(Key, JsonItem)>>, key: Option<Key> },
+    Array { mark: usize, array: Option<Vec<JsonItem>> },
+}
+
+impl Frame {
+    #[inline(always)]
+    fn open(bytes: &[u8], index: usize, options: &ParseOptions, depth: usize) -> Result<Frame, JsonError> {
+        if depth > options.max_depth {
+            return Err(JsonError::with_kind(index, ErrorKind::DepthLimitExceeded));
+        }
+        if bytes[index] == b'{' {
+            Ok(Frame::Map { mark: index, map: None, key: None })
+        } else {
+            Ok(Frame::Array { mark: index, array: None })
+        }
+    }
+
+    #[inline(always)]
+    fn closing(&self) -> u8 {
+        match self {
+            Frame::Map { .. } => { b'}' }
+            Frame::Array { .. } => { b']' }
+        }
+    }
+
+    // What may follow an element: a comma or the closing bracket
+    fn expected_next(&self) -> &'static str {
+        match self {
+            Frame::Map { .. } => { "',' or '}'" }
+            Frame::Array { .. } => { "',' or ']'" }
+        }
+    }
+
+    // What may follow the opening bracket
+    fn expected_first(&self) -> &'static str {
+        match self {
+            Frame::Map { .. } => { "a key or '}'" }
+            Frame::Array { .. } => { "a value or ']'" }
+        }
+    }
+
+    #[inline(always)]
+    fn is_empty(&self) -> bool {
+        match self {
+            Frame::Map { map, .. } => { map.is_none() }
+            Frame::Array { array, .. } => { array.is_none() }
+        }
+    }
+
+    // Stores a value, under the pending key for objects
+    #[inline(always)]
+    fn store(&mut self, item: JsonItem) {
+        match self {
+            Frame::Map { map, key, .. } => {
+                let key = key.take().unwrap();
+                map.get_or_insert_with(|| Vec::with_capacity(DEFAULT_VEC_CAPACITY)).push((key, item));
+            }
+            Frame::Array { array, .. } => {
+                array.get_or_insert_with(|| Vec::with_capacity(DEFAULT_VEC_CAPACITY)).push(item);
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn close(self, bytes: &[u8], index: usize, options: &ParseOptions) -> Result<JsonItem, JsonError> {
+        match self {
+            Frame::Map { mark, map, .. } => {
+                let map = match map {
+                    Some(map) if map.len() > 1 => { Some(duplicates::resolve(bytes, map, options.duplicate_keys)?) }
+                    map => { map }
+                };
+                Ok(JsonItem::new_map(Slice::from_bytes(bytes, mark, index + 1), map))
+            }
+            Frame::Array { mark, array } => { Ok(JsonItem::new_array(Slice::from_bytes(bytes, mark, index + 1), array)) }
+        }
+    }
+}
+
+// Skips the object or array starting at byte `index`, at depth `depth`, without building it, and returns the index
+// following its closing bracket. Its syntax, duplicate keys and limits are checked as `parse_container` does, its values
+// and keys being added to `nodes` and `keys`, so that the stub expands without error.
+fn skip_container(bytes: &[u8], mut index: usize, options: &ParseOptions, depth: usize, nodes: &mut usize, keys: &mut usize) -> Result<usize, JsonError> {
+    let limits = &options.limits;
+    if depth > options.max_depth {
+        return Err(JsonError::with_kind(index, ErrorKind::DepthLimitExceeded));
+    }
+    // Open containers, `true` for objects, with the keys read so far when duplicate keys are errors
+    let check_keys = options.duplicate_keys == DuplicateKeys::Error;
+    let mut maps = vec![bytes[index] == b'{'];
+    let mut open_keys: Vec<Vec<Key>> = Vec::new();
+    if check_keys && maps[0] {
+        open_keys.push(Vec::new());
+    }
+    let mut empty = true;
     index += 1;
-    let mut map = None;
     loop {
+        let map = *maps.last().unwrap();
+        let (closing, first, next) = if map { (b'}', "a key or '}'", "',' or '}'") } else { (b']', "a value or ']'", "',' or ']'") };
+
         // Spaces
-        index = skip_spaces(bytes, index)?;
+        index = skip_spaces(bytes, index, options).map_err(|e| e.expecting(if empty { first } else { next }))?;
 
         // Check ending
-        match bytes[index] {
-            b'}' => {
-                return Ok(JsonItem::new_map(Slice::from_bytes(bytes, mark, index + 1), map));
+        let mut end = bytes[index] == closing;
+        if !end {
+            match bytes[index] {
+                b',' if !(options.strict && empty) => {
+                    index = skip_spaces(bytes, index + 1, options).map_err(|e| e.expecting(if map { "a key" } else { "a value" }))?;
+                    end = options.syntax.trailing_commas() && !empty && bytes[index] == closing;
+                }
+                _ => {
+                    if !empty || bytes[index] == b',' {
+                        return Err(JsonError::new(bytes, index).expecting(if empty { first } else { next }));
+                    }
+                }
             }
-            b',' => {
-                index = skip_spaces(bytes, index + 1)?;
+        }
+        if end {
+            if check_keys && map {
+                duplicates::check(bytes, &open_keys.pop().unwrap())?;
             }
-            _ => {
-                if !map.is_none() {
-                    return Err(JsonError::new(bytes, index));
-                }
+            maps.pop();
+            index += 1;
+            if maps.is_empty() {
+                return Ok(index);
             }
+            empty = false;
+            continue;
         }
 
         // Key
-        let key = parse_string(bytes, index)?;
-        index += shift_index(&key);
+        if map {
+            *keys += 1;
+            if *keys > limits.keys {
+                return Err(JsonError::with_kind(index, ErrorKind::KeyLimitExceeded));
+            }
+            let (key, next) = parse_key(bytes, index, options).map_err(|e| e.expecting("a key"))?;
+            if key.slice.len > limits.string_len {
+                return Err(JsonError::with_kind(index, ErrorKind::StringLengthLimitExceeded));
+            }
+            if let Some(open) = open_keys.last_mut() {
+                open.push(key);
+            }
 
-        // Separator
-        index = skip_spaces(bytes, index)?;
-        if bytes[index] != b':' {
-            return Err(JsonError::new(bytes, index));
-        } else {
-            index = skip_spaces(bytes, index + 1)?;
+            // Separator
+            index = skip_spaces(bytes, next, options).map_err(|e| e.expecting("':' after key"))?;
+            if bytes[index] != b':' {
+                return Err(JsonError::new(bytes, index).expecting("':' after key"));
+            }
+            index = skip_spaces(bytes, index + 1, options).map_err(|e| e.expecting("a value"))?;
         }
 
         // Value
-        let item = parse_item(bytes, index)?;
-        index += shift_index(&item);
-
-        // Store
-        if let Some(m) = &mut map {
-            m.push((Key::from_slice(key.slice), item));
-        } else {
-            let mut m = Vec::with_capacity(DEFAULT_VEC_CAPACITY);
-            m.push((Key::from_slice(key.slice), item));
-            map = Some(m);
+        *nodes += 1;
+        if *nodes > limits.nodes {
+            return Err(JsonError::with_kind(index, ErrorKind::NodeLimitExceeded));
+        }
+        match bytes[index] {
+            b'{' | b'[' => {
+                if depth + maps.len() > options.max_depth {
+                    return Err(JsonError::with_kind(index, ErrorKind::DepthLimitExceeded));
+                }
+                maps.push(bytes[index] == b'{');
+                if check_keys && bytes[index] == b'{' {
+                    open_keys.push(Vec::new());
+                }
+                empty = true;
+                index += 1;
+            }
+            _ => {
+                let item = parse_scalar(bytes, index, options).map_err(|e| e.expecting("a value"))?;
+                if item.json_type == JsonString && item.slice.len > limits.string_len {
+                    return Err(JsonError::with_kind(index, ErrorKind::StringLengthLimitExceeded));
+                }
+                index += shift_index(&item);
+                empty = false;
+            }
         }
     }
 }
 
+// Parses the object or array starting at byte `index`. Open containers are kept on an explicit work stack
+// instead of recursing, so that nesting is only bounded by `options.max_depth` and available memory.
 #[inline(always)]
-fn parse_array(bytes: &[u8], mut index: usize) -> Result<JsonItem, JsonError> {
-    let mark = index;
-    let mut array = None;
+fn parse_container(bytes: &[u8], mut index: usize, options: &ParseOptions, mut separators: Option<&mut Vec<Range<usize>>>) -> Result<JsonItem, JsonError> {
+    let limits = &options.limits;
+    if limits.nodes == 0 {
+        return Err(JsonError::with_kind(index, ErrorKind::NodeLimitExceeded));
+    }
+    let (mut nodes, mut keys) = (1, 0);
+    // Options kept by stubs, shared between them
+    let mut stub_options = None;
+    let mut stack = vec![Frame::open(bytes, index, options, 1)?];
     index += 1;
+    let mut next_report = options.progress.as_ref().map_or(usize::MAX, |progress| index - 1 + progress.interval);
     loop {
+        // Progress
+        if index >= next_report {
+            let progress = options.progress.as_ref().unwrap();
+            (progress.callback)(index);
+            next_report = index + progress.interval;
+        }
+
+        // Depth of a child container
+        let depth = stack.len() + 1;
+        let frame = stack.last_mut().unwrap();
+        let closing = frame.closing();
+        let start = index;
+
         // Spaces
-        index = skip_spaces(bytes, index)?;
+        index = skip_spaces(bytes, index, options).map_err(|e| e.expecting(if frame.is_empty() { frame.expected_first() } else { frame.expected_next() }))?;
 
         // Check ending
+        let mut end = bytes[index] == closing;
+        if !end {
+            match bytes[index] {
+                b',' if !(options.strict && frame.is_empty()) => {
+                    index = skip_spaces(bytes, index + 1, options).map_err(|e| {
+                        match frame {
+                            Frame::Map { .. } => { e.expecting("a key") }
+                            Frame::Array { .. } => { e.expecting("a value") }
+                        }
+                    })?;
+                    end = options.syntax.trailing_commas() && !frame.is_empty() && bytes[index] == closing;
+                }
+                _ => {
+                    if !frame.is_empty() || bytes[index] == b',' {
+                        return Err(JsonError::new(bytes, index).expecting(if frame.is_empty() { frame.expected_first() } else { frame.expected_next() }));
+                    }
+                }
+            }
+        }
+        record_separator(&mut separators, start, index);
+        if end {
+            let item = stack.pop().unwrap().close(bytes, index, options)?;
+            index += 1;
+            match stack.last_mut() {
+                Some(parent) => {
+                    breadcrumbs::leave();
+                    parent.store(item);
+                    continue;
+                }
+                None => {
+                    if let Some(progress) = &options.progress {
+                        (progress.callback)(index);
+                    }
+                    return Ok(item);
+                }
+            }
+        }
+
+        // Key
+        match frame {
+            Frame::Map { key, .. } => {
+                keys += 1;
+                if keys > limits.keys {
+                    return Err(JsonError::with_kind(index, ErrorKind::KeyLimitExceeded));
+                }
+                let (k, next) = parse_key(bytes, index, options).map_err(|e| e.expecting("a key"))?;
+                if k.slice.len > limits.string_len {
+                    return Err(JsonError::with_kind(index, ErrorKind::StringLengthLimitExceeded));
+                }
+                index = next;
+
+                // Separator
+                index = skip_spaces(bytes, index, options).map_err(|e| e.expecting("':' after key"))?;
+                if bytes[index] != b':' {
+                    return Err(JsonError::new(bytes, index).expecting("':' after key"));
+                } else {
+                    index = skip_spaces(bytes, index + 1, options).map_err(|e| e.expecting("a value"))?;
+                }
+                record_separator(&mut separators, next, index);
+                breadcrumbs::enter_key(k.slice.offset_in(bytes), k.slice.len);
+                *key = Some(k);
+            }
+            Frame::Array { array, .. } => {
+                breadcrumbs::enter_index(array.as_ref().map_or(0, Vec::len));
+            }
+        }
+
+        // Value
+        breadcrumbs::at(index);
+        nodes += 1;
+        if nodes > limits.nodes {
+            return Err(JsonError::with_kind(index, ErrorKind::NodeLimitExceeded));
+        }
         match bytes[index] {
-            b']' => {
-                return Ok(JsonItem::new_array(Slice::from_bytes(bytes, mark, index + 1), array));
+            b'{' | b'[' if depth > options.materialize_depth => {
+                let end = skip_container(bytes, index, options, depth, &mut nodes, &mut keys)?;
+                let json_type = if bytes[index] == b'{' { JsonMap } else { JsonArray };
+                let stub_options = stub_options.get_or_insert_with(|| Arc::new(ParseOptions { progress: None, ..options.clone() }));
+                let stub = JsonItem::new_stub(Slice::from_bytes(bytes, index, end), json_type, stub_options.clone());
+                index = end;
+                breadcrumbs::leave();
+                frame.store(stub);
             }
-            b',' => {
-                index = skip_spaces(bytes, index + 1)?;
+            b'{' | b'[' => {
+                let child = Frame::open(bytes, index, options, depth)?;
+                stack.push(child);
+                index += 1;
             }
             _ => {
-                if !array.is_none() {
-                    return Err(JsonError::new(bytes, index));
+                let item = parse_scalar(bytes, index, options).map_err(|e| e.expecting("a value"))?;
+                if item.json_type == JsonString && item.slice.len > limits.string_len {
+                    return Err(JsonError::with_kind(index, ErrorKind::StringLengthLimitExceeded));
                 }
+                index += shift_index(&item);
+                breadcrumbs::leave();
+                frame.store(options.recognizers.apply(item));
             }
         }
+    }
+}
 
-        // Item
-        let item = parse_item(bytes, index)?;
-        index += shift_index(&item);
+// Checks the strict JSON object or array starting at byte `index` without building it, and returns the index
+// following its closing bracket. Errors are those `parse_container` returns. Open containers are tracked as bits,
+// set for objects, which is enough for the 128 levels allowed by `ParseOptions::STRICT`, so that nothing is allocated
+// unless an error is returned.
+fn check_container(bytes: &[u8], mut index: usize) -> Result<usize, JsonError> {
+    let options = &ParseOptions::STRICT;
+    let mut maps = u128::from(bytes[index] == b'{');
+    let mut depth = 1;
+    let mut empty = true;
+    index += 1;
+    loop {
+        let map = (maps >> (depth - 1)) & 1 == 1;
+        let (closing, first, next) = if map { (b'}', "a key or '}'", "',' or '}'") } else { (b']', "a value or ']'", "',' or ']'") };
+
+        // Spaces
+        index = skip_spaces(bytes, index, options).map_err(|e| e.expecting(if empty { first } else { next }))?;
 
-        // Store
-        if let Some(a) = &mut array {
-            a.push(item);
+        // Check ending
+        if bytes[index] == closing {
+            index += 1;
+            depth -= 1;
+            if depth == 0 {
+                return Ok(index);
+            }
+            empty = false;
+            continue;
+        }
+        if empty {
+            if bytes[index] == b',' {
+                return Err(JsonError::new(bytes, index).expecting(first));
+            }
+        } else if bytes[index] == b',' {
+            index = skip_spaces(bytes, index + 1, options).map_err(|e| e.expecting(if map { "a key" } else { "a value" }))?;
         } else {
-            let mut a = Vec::with_capacity(DEFAULT_VEC_CAPACITY);
-            a.push(item);
-            array = Some(a);
+            return Err(JsonError::new(bytes, index).expecting(next));
+        }
+
+        // Key
+        if map {
+            if bytes[index] != b'"' {
+                return Err(JsonError::new(bytes, index).expecting("a key"));
+            }
+            index += shift_index(&parse_string_strict(bytes, index, b'"').map_err(|e| e.expecting("a key"))?);
+
+            // Separator
+            index = skip_spaces(bytes, index, options).map_err(|e| e.expecting("':' after key"))?;
+            if bytes[index] != b':' {
+                return Err(JsonError::new(bytes, index).expecting("':' after key"));
+            }
+            index = skip_spaces(bytes, index + 1, options).map_err(|e| e.expecting("a value"))?;
         }
+
+        // Value
+        match bytes[index] {
+            b'{' | b'[' => {
+                if depth + 1 > options.max_depth {
+                    return Err(JsonError::with_kind(index, ErrorKind::DepthLimitExceeded));
+                }
+                if bytes[index] == b'{' {
+                    maps |= 1 << depth;
+                } else {
+                    maps &= !(1 << depth);
+                }
+                depth += 1;
+                empty = true;
+                index += 1;
+            }
+            _ => {
+                index += shift_index(&parse_scalar(bytes, index, options).map_err(|e| e.expecting("a value"))?);
+                empty = false;
+            }
+        }
+    }
+}
+
+// Checks the strict JSON root value of `bytes` without building it, and returns the index following it
+fn check_root(bytes: &[u8]) -> Result<usize, JsonError> {
+    let options = &ParseOptions::STRICT;
+    let index = skip_spaces(bytes, 0, options).map_err(|e| e.expecting("a value"))?;
+    match bytes[index] {
+        b'{' | b'[' => { check_container(bytes, index) }
+        _ => { Ok(index + shift_index(&parse_scalar(bytes, index, options).map_err(|e| e.expecting("a value"))?)) }
+    }
+}
+
+#[inline(always)]
+pub(crate) fn parse_root(source: &str, options: &ParseOptions) -> Result<JsonItem, JsonError> {
+    parse_root_recording(source, options, None)
+}
+
+// Parses a root item like `parse_root`, appending to `separators` the byte ranges of the text separating tokens
+// in source order, the text before and after the root item included
+#[inline(always)]
+pub(crate) fn parse_root_recording(source: &str, options: &ParseOptions, mut separators: Option<&mut Vec<Range<usize>>>) -> Result<JsonItem, JsonError> {
+    let bytes = source.as_bytes();
+    if source.len() > options.limits.input_size {
+        return Err(JsonError::with_kind(options.limits.input_size, ErrorKind::SizeLimitExceeded).located(bytes));
+    }
+    let item = parse_from_recording(source, 0, options, separators.as_deref_mut())?;
+    if options.strict {
+        expect_end(bytes, end_index(bytes, &item), options)?;
+    }
+    if separators.is_some() {
+        record_separator(&mut separators, end_index(bytes, &item), bytes.len());
+    }
+    Ok(item)
+}
+
+// Parses a root item starting at or after byte `index`, error indices being relative to the start of `source`
+#[inline(always)]
+pub(crate) fn parse_root_from(source: &str, index: usize, options: &ParseOptions) -> Result<JsonItem, JsonError> {
+    parse_from_recording(source, index, options, None)
+}
+
+// Parses a root item like `parse_root_from`, appending to `separators` the byte ranges of the text separating tokens
+// in source order, the text before the root item included
+#[inline(always)]
+pub(crate) fn parse_from_recording(source: &str, index: usize, options: &ParseOptions, mut separators: Option<&mut Vec<Range<usize>>>) -> Result<JsonItem, JsonError> {
+    let bytes = source.as_bytes();
+    breadcrumbs::guard(source, || {
+        let expected = if options.strict { "a value" } else { "'{' or '['" };
+        let start = index;
+        let index = skip_spaces(bytes, index, options).map_err(|e| e.expecting(expected))?;
+        record_separator(&mut separators, start, index);
+        match bytes[index] {
+            b'{' | b'[' => { parse_container(bytes, index, options, separators) }
+            _ if options.strict => { Ok(options.recognizers.apply(parse_scalar(bytes, index, options).map_err(|e| e.expecting(expected))?)) }
+            _ => { Err(JsonError::new(bytes, index).expecting(expected)) }
+        }
+    }).map_err(|error| error.located(bytes))
+}
+
+// Checks that only whitespace and comments follow byte `index`
+#[inline(always)]
+fn expect_end(bytes: &[u8], index: usize, options: &ParseOptions) -> Result<(), JsonError> {
+    match next_token(bytes, index, options) {
+        Ok(None) => { Ok(()) }
+        Ok(Some(index)) => { Err(JsonError::with_kind(index, ErrorKind::TrailingData).located(bytes)) }
+        Err(error) => { Err(error.located(bytes)) }
     }
 }
 
+// Parses a root item that may only be followed by whitespace
+#[cfg(feature = "streams")]
+#[inline(always)]
+pub(crate) fn parse_complete(source: &str, options: &ParseOptions) -> Result<JsonItem, JsonError> {
+    let bytes = source.as_bytes();
+    let item = parse_root(source, options)?;
+    expect_end(bytes, end_index(bytes, &item), options)?;
+    Ok(item)
+}
+
 /// Main library function. Parses JSON data.
+/// Use a [`Parser`] to parse with custom [`ParseOptions`].
 ///
 /// # Arguments
 /// * `source` - Text content to be parsed
@@ -213,32 +884,182 @@ fn parse_array(bytes: &[u8], mut index: usize) -> Result<JsonItem, JsonError> {
 /// # Example
 ///
 /// ```rust
-/// fn main() {
-///    let json = "{\"jsonic\": \"Fast, small JSON parsing library for rust with no dependencies\"}";
+/// let json = "{\"jsonic\": \"Fast, small JSON parsing library for rust with no dependencies\"}";
 ///
-///    match jsonic::parse(json) {
-///        Ok(parsed) => { println!("Describe jsonic? {:?}", parsed["jsonic"].as_str()); }
-///        Err(error) => { eprintln!("{}", error); }
-///    }
+/// match jsonic::parse(json) {
+///     Ok(parsed) => { println!("Describe jsonic? {:?}", parsed["jsonic"].as_str()); }
+///     Err(error) => { eprintln!("{}", error); }
 /// }
+/// ```
 pub fn parse(source: &str) -> Result<JsonItem, JsonError> {
+    parse_root(source, &ParseOptions::JSON)
+}
+
+/// Parses JSON data strictly following RFC 8259, so that it can be used as a validator.
+/// Unlike [`parse`], the root can be any value (e.g. `"text"` or `42`), and the following are rejected:
+/// numbers not matching the JSON grammar (e.g. `01`, `1.`, `+1`), control characters and invalid escape sequences
+/// in strings, leading commas, and anything but whitespace after the root value.
+/// As with [`parse`], nesting is limited to 128 levels, use [`ParseOptions::strict`] to change it.
+///
+/// # Arguments
+/// * `source` - Text content to be parsed
+///
+/// # Example
+///
+/// ```rust
+/// assert_eq!(jsonic::parse_strict(" 42 ").unwrap().as_i128(), Some(42));
+/// assert!(jsonic::parse_strict("{\"a\": [1, 2]}").is_ok());
+/// assert!(jsonic::parse_strict("[01]").is_err());
+/// assert!(jsonic::parse_strict("[\"\\x41\"]").is_err());
+/// assert!(jsonic::parse_strict("{} {}").is_err());
+/// ```
+pub fn parse_strict(source: &str) -> Result<JsonItem, JsonError> {
+    parse_root(source, &ParseOptions::STRICT)
+}
+
+/// Checks that `source` is JSON data strictly following RFC 8259, without building any item, so that nothing
+/// is allocated for valid data, e.g. to check request bodies before forwarding them. Accepts and rejects the same data as
+/// [`parse_strict`], with the same errors.
+///
+/// # Arguments
+/// * `source` - Text content to be checked
+///
+/// # Example
+///
+/// ```rust
+/// assert!(jsonic::validate("{\"ids\": [1, 2], \"next\": null}").is_ok());
+/// assert!(jsonic::validate(" \"text\" ").is_ok());
+/// assert_eq!(jsonic::validate("[1, 2,]").unwrap_err().get_index(), 6);
+/// ```
+pub fn validate(source: &str) -> Result<(), JsonError> {
     let bytes = source.as_bytes();
-    let mut index = 0_usize;
-    index = skip_spaces(bytes, index)?;
-    return match bytes[index] {
-        b'{' => { parse_map(bytes, index) }
-        b'[' => { parse_array(bytes, index) }
-        _ => { Err(JsonError::new(bytes, index)) }
-    };
+    let index = check_root(bytes).map_err(|error| error.located(bytes))?;
+    expect_end(bytes, index, &ParseOptions::STRICT)
+}
+
+/// Parses JSONC data, i.e. JSON with `//` and `/* */` comments and trailing commas,
+/// as found in VS Code-style `settings.json` or `tsconfig.json` files. Other JSON5 extensions are rejected.
+///
+/// # Arguments
+/// * `source` - Text content to be parsed
+///
+/// # Example
+///
+/// ```rust
+/// let jsonc = "{
+///     // Editor settings
+///     \"editor.tabSize\": 4, /* spaces */
+///     \"files.exclude\": [\"target\",],
+/// }";
+///
+/// let parsed = jsonic::parse_jsonc(jsonc).unwrap();
+/// assert_eq!(parsed["editor.tabSize"].as_i128(), Some(4));
+/// assert!(jsonic::parse_jsonc("{unquoted: 1}").is_err());
+/// ```
+#[cfg(feature = "extended")]
+pub fn parse_jsonc(source: &str) -> Result<JsonItem, JsonError> {
+    parse_root(source, &ParseOptions::JSONC)
+}
+
+/// Parses JSON data leniently, to recover data from almost-JSON sources.
+/// Accepts everything [`parse_json5`] does, plus Python's `True`, `False` and `None` literals,
+/// read as `true`, `false` and `null`, so that the output of Python's `str(dict)` can be ingested.
+///
+/// # Arguments
+/// * `source` - Text content to be parsed
+///
+/// # Example
+///
+/// ```rust
+/// let dump = "{'name': 'jsonic', 'fast': True, 'deps': None}";
+///
+/// let parsed = jsonic::parse_lenient(dump).unwrap();
+/// assert_eq!(parsed["fast"].as_bool(), Some(true));
+/// assert!(parsed["deps"].is_null());
+/// ```
+#[cfg(feature = "extended")]
+pub fn parse_lenient(source: &str) -> Result<JsonItem, JsonError> {
+    parse_root(source, &ParseOptions::LENIENT)
+}
+
+/// Parses JSON5 data (<https://spec.json5.org>).
+/// On top of standard JSON, accepts comments, trailing commas, single-quoted strings, unquoted keys,
+/// leading `+` and leading/trailing decimal points in numbers, hexadecimal integers (`0x1F`), `Infinity`, `NaN`
+/// and strings spanning several lines, either with raw line breaks or `\` line continuations.
+///
+/// As with [`parse`], string values are returned as written in the source by [`JsonItem::as_str`],
+/// use [`JsonItem::as_unescaped`] to get the decoded text, with line continuations removed.
+///
+/// # Arguments
+/// * `source` - Text content to be parsed
+///
+/// # Example
+///
+/// ```rust
+/// let json5 = "{
+///     // Comments are allowed
+///     name: 'jsonic',
+///     ratio: .5,
+///     mask: 0xFF,
+///     tags: ['fast', 'small',],
+/// }";
+///
+/// let parsed = jsonic::parse_json5(json5).unwrap();
+/// assert_eq!(parsed["name"].as_str(), Some("jsonic"));
+/// assert_eq!(parsed["ratio"].as_f64(), Some(0.5));
+/// assert_eq!(parsed["mask"].as_i128(), Some(255));
+/// assert_eq!(parsed["tags"][1].as_str(), Some("small"));
+/// ```
+#[cfg(feature = "extended")]
+pub fn parse_json5(source: &str) -> Result<JsonItem, JsonError> {
+    parse_root(source, &ParseOptions::JSON5)
 }
 
 #[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::bool_assert_comparison)]
 mod tests {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+
+    use crate::json_error::ErrorKind;
+    use crate::options::{ParseOptions, Parser};
     use crate::parse;
+    #[cfg(feature = "extended")]
+    use crate::{parse_json5, parse_jsonc, parse_lenient};
 
     const CORRECT_JSON: &str = " {\n\"test\": \"why not?\",\"b\": true,\"another one\":  \"hey#çà@â&éè\" \r ,\"obj2\":{\"k\":{\"k2\":\"v\"}}, \"num\":4.2344, \"int\":-234,  \"obj\":{\"a\":\"b\", \"c\":\"d\"}, \"arr\":[1,2,3],\"bool\":false, \"exp\":3.3e-21, \"exp2\":-4.5e-213,\"exp3\":3.7391238e+24,\"depth\":[\"a\",[\"b\",\"c\"]],\"emp_a\":[],\"emp_m\":{}}  ";
     const INCORRECT_JSON: &str = "{\"test\": \"num\", \"int\":234[] ,,}";
 
+    // Counts the allocations of each thread, so that tests running in parallel do not disturb each other
+    struct CountingAllocator;
+
+    thread_local! {
+        static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    fn allocations() -> usize {
+        ALLOCATIONS.with(Cell::get)
+    }
+
+    // Source of an object of `len` members "k0": 0, "k1": 1..., large enough to be indexed
+    fn large_object(len: usize) -> String {
+        format!("{{{}}}", (0..len).map(|index| format!("\"k{}\": {}", index, index)).collect::<Vec<_>>().join(","))
+    }
+
     #[test]
     fn parse_correct() {
         match parse(CORRECT_JSON) {
@@ -341,6 +1162,82 @@ mod tests {
         }
     }
 
+    #[test]
+    fn iterate_keys_and_values() {
+        use crate::json_item::JsonItem;
+        use crate::key::Key;
+
+        let parsed = parse(CORRECT_JSON).unwrap();
+        assert_eq!(parsed["obj"].keys().unwrap().map(Key::as_str).collect::<Vec<_>>(), ["a", "c"]);
+        assert_eq!(parsed["obj"].values().unwrap().filter_map(JsonItem::as_str).collect::<Vec<_>>(), ["b", "d"]);
+        assert!(parsed["emp_m"].keys().unwrap().next().is_none() && parsed["arr"].values().is_none());
+    }
+
+    #[test]
+    fn container_len() {
+        let parsed = parse(CORRECT_JSON).unwrap();
+        assert_eq!((parsed["obj"].len(), parsed["arr"].len(), parsed["emp_a"].len(), parsed["int"].len()), (Some(2), Some(3), Some(0), None));
+        assert_eq!((parsed["emp_m"].is_empty(), parsed["obj"].is_empty(), parsed["x"].is_empty()), (Some(true), Some(false), None));
+    }
+
+    #[test]
+    fn structural_accessors() {
+        use crate::json_item::{JsonItem, Members};
+
+        let parsed = parse(CORRECT_JSON).unwrap();
+        assert!(matches!(parsed["obj"].as_object(), Some(Members::Ordered([(a, _), _])) if a.as_str() == "a"));
+        assert!(matches!(parsed["emp_m"].as_object(), Some(Members::Ordered([]))));
+        assert!(parsed["arr"].as_object().is_none());
+        assert_eq!(parsed["arr"].as_array().map(<[JsonItem]>::len), Some(3));
+        assert_eq!(parsed["emp_a"].as_array(), Some(&[][..]));
+        assert_eq!(parsed["obj"].as_array(), None);
+        let source = large_object(100);
+        let large = parse(&source).unwrap();
+        assert!(matches!(large.as_object(), Some(Members::Indexed(map)) if map.len() == 100));
+    }
+
+    #[test]
+    fn into_iterator() {
+        let parsed = parse(CORRECT_JSON).unwrap();
+        let source = large_object(100);
+        let large = parse(&source).unwrap();
+        assert_eq!(large.as_object().unwrap().into_iter().filter(|(_, v)| v.as_i128() < Some(10)).count(), 10);
+        assert_eq!(parsed["obj"].as_object().unwrap().into_iter().map(|(k, _)| k.as_str()).collect::<Vec<_>>(), ["a", "c"]);
+        assert_eq!((&parsed["arr"]).into_iter().count(), 3);
+        assert_eq!((&parsed["obj"]).into_iter().count(), 0);
+        assert_eq!((&parsed["x"]).into_iter().count(), 0);
+    }
+
+    #[test]
+    fn iterator_traits() {
+        use crate::key::Key;
+
+        let parsed = parse(CORRECT_JSON).unwrap();
+        let source = large_object(100);
+        let large = parse(&source).unwrap();
+        assert_eq!(parsed["arr"].elements().unwrap().rev().map(|v| v.as_i128().unwrap()).collect::<Vec<_>>(), [3, 2, 1]);
+        assert_eq!(parsed["obj"].entries().unwrap().len(), 2);
+        assert_eq!(parsed["emp_a"].elements().unwrap().len(), 0);
+        assert_eq!(large.values().unwrap().len(), 100);
+        assert_eq!(parsed["obj"].keys().unwrap().next_back().map(Key::as_str), Some("c"));
+        let mut entries = large.entries().unwrap();
+        assert_eq!(entries.nth_back(98).map(|(key, _)| key.as_str()), Some("k1"));
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries.next().map(|(key, _)| key.as_str()), Some("k0"));
+        assert!(entries.next_back().is_none());
+    }
+
+    #[test]
+    fn iterate_pairs() {
+        let parsed = parse(CORRECT_JSON).unwrap();
+        assert_eq!(parsed["obj"].pairs().unwrap().map(|(k, v)| (k, v.as_str().unwrap())).collect::<Vec<_>>(), [("a", "b"), ("c", "d")]);
+        assert_eq!(parsed["emp_m"].pairs().unwrap().len(), 0);
+        assert!(parsed["arr"].pairs().is_none());
+        let source = large_object(100);
+        let large = parse(&source).unwrap();
+        assert_eq!(large.pairs().unwrap().find(|(k, _)| *k == "k42").and_then(|(_, v)| v.as_i128()), Some(42));
+    }
+
     #[test]
     fn parse_array() {
         match parse(CORRECT_JSON) {
@@ -422,6 +1319,152 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_too_deep() {
+        let hostile = "[".repeat(1_000_000);
+        assert_eq!(parse(&hostile).unwrap_err().get_kind(), ErrorKind::DepthLimitExceeded);
+        let nested = format!("{}{}", "[".repeat(128), "]".repeat(128));
+        assert!(parse(&nested).is_ok());
+        let nested = format!("{}{}", "{\"a\":".repeat(129), "}".repeat(129));
+        assert_eq!(parse(&nested).unwrap_err().get_kind(), ErrorKind::DepthLimitExceeded);
+    }
+
+    #[test]
+    fn parse_deep_iteratively() {
+        use crate::options::{ParseOptions, Parser};
+
+        let depth = 500_000;
+        let parser = Parser::new(ParseOptions::new().max_depth(usize::MAX));
+        let nested = format!("{}1{}", "{\"a\": [".repeat(depth), "]}".repeat(depth));
+        let parsed = parser.parse(&nested).unwrap();
+        let mut item = &parsed;
+        for _ in 0..depth {
+            item = &item["a"][0];
+        }
+        assert_eq!(item.as_i128(), Some(1));
+        let unbalanced = format!("{}{}", "[".repeat(depth), "]".repeat(depth - 1));
+        assert!(parser.parse(&unbalanced).is_err());
+    }
+
+    #[test]
+    fn deep_equality() {
+        use crate::number::FloatEquality;
+
+        let parsed = parse(CORRECT_JSON).unwrap();
+        let reordered = parse("{\"emp_m\":{},\"emp_a\":[],\"depth\":[\"a\",[\"b\",\"c\"]],\"exp3\":3.7391238e24,\"exp2\":-4.5e-213,\"exp\":33e-22,\"bool\":false,\"arr\":[1,2.0,3],\"obj\":{\"c\":\"d\",\"a\":\"b\"},\"int\":-234,\"num\":4.2344,\"obj2\":{\"k\":{\"k2\":\"v\"}},\"another one\":\"hey#çà@â&éè\",\"b\":true,\"t\\u0065st\":\"why not\\u003f\"}").unwrap();
+        assert_eq!(parsed, reordered);
+        assert_ne!(parsed, parse("{}").unwrap());
+        assert_ne!(parsed["arr"], parse("[1, 2, 3, 4]").unwrap());
+        assert_ne!(parsed["num"], parse("[4.23440001]").unwrap()[0]);
+        assert!(parsed["num"].equals(&parse("[4.23440001]").unwrap()[0], FloatEquality::Epsilon(1e-6)));
+        assert_eq!(parsed["missing"], parsed["other"]);
+        assert_ne!(parsed["b"], parsed["bool"]);
+    }
+
+    #[test]
+    fn parse_strict_conformance() {
+        use crate::options::Preset;
+        use crate::parse_strict;
+
+        let pinned = Parser::new(ParseOptions::preset(Preset::V1Strict));
+        let accepted = [
+            "[]", "{}", " [1] ", "\"\"", "\"a\\\\\"", "-0", "0.5e-3", "1E+2", "null", "true", "false",
+            "[\"\\u00e9\\/\\b\\f\\n\\r\\t\\\"\"]", "{\"a\":{\"b\":[null,{}]}}", "[-1.25e10,\"\\uD834\\uDD1E\"]", "\t\r\n[]\n",
+        ];
+        for json in accepted {
+            assert!(parse_strict(json).is_ok() && pinned.parse(json).is_ok(), "{}", json);
+        }
+        let rejected = [
+            "", " ", "[", "[1,]", "[,1]", "{,\"a\":1}", "[1 2]", "[01]", "[1.]", "[.5]", "[+1]", "[1e]", "[-]", "[0x1]",
+            "[NaN]", "[\"a\tb\"]", "[\"\\x41\"]", "[\"\\u12\"]", "[\"\\'\"]", "['a']", "[\"a\"", "{\"a\" 1}",
+            "{a: 1}", "[] []", "1 2", "nul", "[true false]", "\u{FEFF}[]", "[1] x", "\"\u{1}\"", "[\"\\\"]",
+        ];
+        for json in rejected {
+            assert!(parse_strict(json).is_err() && pinned.parse(json).is_err(), "{:?}", json);
+        }
+        assert_eq!(parse_strict("\"a\\\\\"").unwrap().as_unescaped().as_deref(), Some("a\\"));
+        assert_eq!(parse_strict(&format!("{}{}", "[".repeat(129), "]".repeat(129))).unwrap_err().get_kind(), ErrorKind::DepthLimitExceeded);
+        assert!(parse("[,1]").is_ok());
+    }
+
+    #[test]
+    fn validate_strict() {
+        use crate::{parse_strict, validate};
+
+        let samples = [
+            "[]", "{}", " [1] ", "\"\"", "\"a\\\\\"", "-0", "0.5e-3", "null", "{\"a\":{\"b\":[null,{}]}}", "[-1.25e10,\"\\uD834\\uDD1E\"]",
+            "[{}, [[]], {\"a\": []}]", CORRECT_JSON, "", " ", "[", "[1,]", "[,1]", "{,\"a\":1}", "[1 2]", "[01]", "{\"a\" 1}",
+            "{a: 1}", "{\"a\": 1,}", "[] []", "1 2", "nul", "[\"\\x41\"]", "[1] x", "{\"a\": [1}", "[{]", "{\"a\": {\"b\": 1]}", "{\"a\":",
+        ];
+        let outcome = |result: Result<(), crate::json_error::JsonError>| result.map_err(|error| (error.get_kind(), error.get_index(), error.to_string()));
+        for json in samples {
+            assert_eq!(outcome(validate(json)), outcome(parse_strict(json).map(|_| ())), "{:?}", json);
+        }
+        let nested = |pairs| format!("{}1{}", "{\"a\":[".repeat(pairs), "]}".repeat(pairs));
+        for json in [nested(64), nested(65), format!("{}{}", "[".repeat(129), "]".repeat(129))] {
+            assert_eq!(outcome(validate(&json)), outcome(parse_strict(&json).map(|_| ())));
+        }
+        assert!(validate(&nested(64)).is_ok());
+        assert_eq!(validate(&nested(65)).unwrap_err().get_kind(), ErrorKind::DepthLimitExceeded);
+    }
+
+    #[test]
+    fn validate_without_allocating() {
+        use crate::validate;
+
+        for json in [CORRECT_JSON, "\"text\"", " -1.5e3 \n", "[{\"a\": [true, null, {}]}, \"\\u00e9\"]"] {
+            let before = allocations();
+            let result = validate(json);
+            let count = allocations() - before;
+            assert!(result.is_ok() && count == 0, "{:?}: {} allocations", json, count);
+        }
+        assert!(allocations() > 0);
+    }
+
+    #[test]
+    fn classify_errors() {
+        use crate::parse_strict;
+
+        let kind = |result: Result<_, crate::json_error::JsonError>| result.map(|_| ()).map_err(|error| (error.get_kind(), error.get_index()));
+        assert_eq!(kind(parse("[1 2]")), Err((ErrorKind::UnexpectedCharacter, 3)));
+        assert_eq!(kind(parse("{\"a\" 1}")), Err((ErrorKind::UnexpectedCharacter, 5)));
+        assert_eq!(kind(parse("[1, ")), Err((ErrorKind::UnexpectedEof, 4)));
+        assert_eq!(kind(parse("[tru")), Err((ErrorKind::UnexpectedEof, 4)));
+        assert_eq!(kind(parse("[trux]")), Err((ErrorKind::UnexpectedCharacter, 1)));
+        assert_eq!(kind(parse("[\"ab")), Err((ErrorKind::UnterminatedString, 4)));
+        assert_eq!(kind(parse_strict("[01]")), Err((ErrorKind::InvalidNumber, 1)));
+        assert_eq!(kind(parse_strict("[\"\\x41\"]")), Err((ErrorKind::InvalidEscape, 2)));
+        assert_eq!(kind(parse_strict("[1] x")), Err((ErrorKind::TrailingData, 4)));
+        assert!(ErrorKind::TrailingData.is_syntax() && !ErrorKind::DepthLimitExceeded.is_syntax());
+        #[cfg(feature = "extended")]
+        {
+            assert_eq!(kind(parse_jsonc("[1 /* x")), Err((ErrorKind::UnterminatedComment, 3)));
+            assert_eq!(kind(parse_json5("[0x]")), Err((ErrorKind::InvalidNumber, 3)));
+        }
+    }
+
+    #[test]
+    fn expected_tokens() {
+        use crate::parse_strict;
+
+        let expected = |result: Result<_, crate::json_error::JsonError>| result.map(|_| ()).unwrap_err().get_expected();
+        assert_eq!(expected(parse("{\"a\": 1 \"b\": 2}")), Some("',' or '}'"));
+        assert_eq!(expected(parse("[1, 2")), Some("',' or ']'"));
+        assert_eq!(expected(parse("{\"a\" 1}")), Some("':' after key"));
+        assert_eq!(expected(parse("{\"a\"")), Some("':' after key"));
+        assert_eq!(expected(parse("{\"a\": }")), Some("a value"));
+        assert_eq!(expected(parse("{\"a\": 1, 2}")), Some("a key"));
+        assert_eq!(expected(parse("{")), Some("a key or '}'"));
+        assert_eq!(expected(parse_strict("[,1]")), Some("a value or ']'"));
+        assert_eq!(expected(parse_strict("[1,]")), Some("a value"));
+        assert_eq!(expected(parse("1")), Some("'{' or '['"));
+        assert_eq!(expected(parse_strict("")), Some("a value"));
+        assert_eq!(expected(parse("[\"ab")), None);
+        assert_eq!(expected(parse_strict("[1] x")), None);
+        let error = parse("[1 2]").unwrap_err();
+        assert!(error.to_string().starts_with("JSON unexpected character (expected ',' or ']') near '[1 2]': line 1, column 4"), "{}", error);
+    }
+
     #[test]
     fn missing_key() {
         match parse(CORRECT_JSON) {
@@ -434,6 +1477,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn index_owned_keys() {
+        use std::borrow::Cow;
+
+        let parsed = parse(CORRECT_JSON).unwrap();
+        let key = format!("{}{}", "ob", "j");
+        assert_eq!(parsed[&key]["a"].as_str(), Some("b"));
+        assert_eq!(parsed[key.clone()]["c"].as_str(), Some("d"));
+        assert_eq!(parsed[Cow::Borrowed("obj")], parsed[Cow::<str>::Owned(key)]);
+        assert!(!parsed[String::from("x")].exists() && !parsed["arr"][String::from("0")].exists());
+        let escaped = parse("{\"a\\u0062\": 1}").unwrap();
+        assert!(escaped[escaped.keys().unwrap().next().unwrap().as_str().to_owned()].as_i128() == Some(1));
+    }
+
     #[test]
     fn missing_key_get_value() {
         match parse(CORRECT_JSON) {
@@ -449,6 +1506,76 @@ mod tests {
         }
     }
 
+    #[test]
+    fn field_states() {
+        use crate::json_item::FieldState;
+
+        let parsed = parse("{\"null\": null, \"zero\": 0, \"empty\": \"\"}").unwrap();
+        assert!(parsed["absent"].is_missing());
+        assert!(!parsed["null"].is_missing());
+        assert!(parsed["null"].is_null());
+        assert_eq!(parsed["absent"].field_state(), FieldState::Missing);
+        assert_eq!(parsed["absent"]["deeper"].field_state(), FieldState::Missing);
+        assert_eq!(parsed["null"].field_state(), FieldState::Null);
+        assert_eq!(parsed["zero"].field_state(), FieldState::Present(&parsed["zero"]));
+        assert_eq!(parsed["empty"].field_state(), FieldState::Present(&parsed["empty"]));
+    }
+
+    #[test]
+    fn optional_access() {
+        let parsed = parse("{\"a\": [null, {\"b\": 1}], \"c\": \"x\"}").unwrap();
+        assert!(parsed.get("a").and_then(|a| a.get_index(0)).is_some_and(|item| item.is_null()));
+        assert_eq!(parsed.get("a").and_then(|a| a.get_index(1)?.get("b")?.as_i128()), Some(1));
+        assert!(parsed.get("b").is_none());
+        assert!(parsed.contains_key("a"));
+        assert!(!parsed.contains_key("b"));
+        assert!(!parsed["c"].contains_key("c"));
+        assert!(parsed.get_index(0).is_none());
+        assert!(parsed["a"].get_index(2).is_none());
+        assert!(parsed["c"].get("c").is_none());
+
+        let source = large_object(200);
+        let large = parse(&source).unwrap();
+        assert!(large.contains_key("k150"));
+        assert!(!large.contains_key("k200"));
+        assert_eq!(parsed["a"].get_back(0), Some(&parsed["a"][1]));
+        assert!(parsed["a"].get_back(usize::MAX).is_none());
+        assert!(parsed["c"].get_back(0).is_none());
+    }
+
+    #[test]
+    fn get_many() {
+        use crate::json_item::JsonItem;
+
+        let small = parse("{\"a\": 1, \"b\": 2, \"a\": 3}").unwrap();
+        let [a, b, c, again] = small.get_many(["a", "b", "c", "a"]).map(|item| item.and_then(JsonItem::as_i128));
+        assert_eq!((a, b, c, again), (Some(3), Some(2), None, Some(3)));
+        let source = large_object(200);
+        let large = parse(&source).unwrap();
+        assert_eq!(large.get_many(["k199", "k0", "x"]).map(|item| item.and_then(JsonItem::as_i128)), [Some(199), Some(0), None]);
+        assert_eq!(parse("[{\"0\": 1}]").unwrap().get_many(["0"]), [None]);
+    }
+
+    #[test]
+    fn merge_patch_updates() {
+        use crate::json_item::{JsonItem, Update};
+
+        let patch = parse("{\"name\": \"x\", \"tags\": null, \"size\": \"big\"}").unwrap();
+        assert_eq!(patch["name"].update(JsonItem::as_str), Some(Update::Value("x")));
+        assert_eq!(patch["tags"].update(JsonItem::as_str), Some(Update::Null));
+        assert_eq!(patch["other"].update(JsonItem::as_str), Some(Update::Missing));
+        assert_eq!(patch["size"].update(JsonItem::as_i128), None);
+        assert_eq!(patch["name"].update(JsonItem::as_str).unwrap().map(str::len), Update::Value(1));
+
+        let mut target = Some(1);
+        Update::Missing.apply(&mut target);
+        assert_eq!(target, Some(1));
+        Update::Value(2).apply(&mut target);
+        assert_eq!(target, Some(2));
+        Update::Null.apply(&mut target);
+        assert_eq!(target, None);
+    }
+
     #[test]
     fn empty_array_iterator() {
         match parse(CORRECT_JSON) {
@@ -482,4 +1609,145 @@ mod tests {
             }
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    #[cfg(feature = "extended")]
+    fn parse_json5_syntax() {
+        let json5 = "// leading comment\n{unquoted: 'single', \"quoted\": /* inline */ +1, $id_2: [.5, 5., -Infinity, NaN,],\n\"nested\": {a: 'it\\'s', }, }";
+        match parse_json5(json5) {
+            Ok(parsed) => {
+                assert_eq!(parsed["unquoted"].as_str(), Some("single"));
+                assert_eq!(parsed["quoted"].as_i128(), Some(1));
+                assert_eq!(parsed["$id_2"][0].as_f64(), Some(0.5));
+                assert_eq!(parsed["$id_2"][1].as_f64(), Some(5.0));
+                assert_eq!(parsed["$id_2"][2].as_f64(), Some(f64::NEG_INFINITY));
+                assert!(parsed["$id_2"][3].as_f64().unwrap().is_nan());
+                assert_eq!(parsed["$id_2"].elements().unwrap().count(), 4);
+                assert_eq!(parsed["nested"]["a"].as_str(), Some("it\\'s"));
+            }
+            Err(error) => {
+                assert!(false, "{}", error.to_string());
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "extended")]
+    fn parse_json5_rejected_by_parse() {
+        for json in ["{a: 1}", "{\"a\": 1,}", "['a']", "[1, /* c */ 2]", "[.5]"] {
+            assert!(parse(json).is_err(), "{}", json);
+            assert!(parse_json5(json).is_ok(), "{}", json);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "extended")]
+    fn parse_json5_hex() {
+        match parse_json5("[0x1F, -0XfF, +0x0, 0x7fffffffffffffffffffffffffffffff]") {
+            Ok(parsed) => {
+                assert_eq!(parsed[0].as_i128(), Some(31));
+                assert_eq!(parsed[1].as_i128(), Some(-255));
+                assert_eq!(parsed[1].as_f64(), Some(-255.0));
+                assert_eq!(parsed[2].as_i128(), Some(0));
+                assert_eq!(parsed[3].as_i128(), Some(i128::MAX));
+            }
+            Err(error) => {
+                assert!(false, "{}", error.to_string());
+            }
+        }
+        assert!(parse("[0x1F]").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "extended")]
+    fn parse_json5_multiline_strings() {
+        match parse_json5("{text: 'first \\\n  second \\\r\nthird', raw: \"line 1\nline 2\", other: \"\\'\\x41\\u00e9\\ud83d\\ude00\\t\"}") {
+            Ok(parsed) => {
+                assert_eq!(parsed["text"].as_unescaped().unwrap(), "first   second third");
+                assert_eq!(parsed["raw"].as_unescaped().unwrap(), "line 1\nline 2");
+                assert_eq!(parsed["other"].as_unescaped().unwrap(), "'Aé😀\t");
+            }
+            Err(error) => {
+                assert!(false, "{}", error.to_string());
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "extended")]
+    fn parse_json5_incorrect() {
+        for json5 in ["[1,,]", "[,]", "{,}", "{a: 1 /* unterminated }", "{1a: 2}", "[0x]", "[0xG1]"] {
+            assert!(parse_json5(json5).is_err(), "{}", json5);
+        }
+    }
+
+    #[test]
+    fn truncate_string_value() {
+        match parse(r#"["h\u00e9llo", "\ud83d\ude00!", "çà@", 12]"#) {
+            Ok(parsed) => {
+                assert_eq!(parsed[0].truncate_string_value(2), Some("h\\u00e9"));
+                assert_eq!(parsed[0].truncate_string_value(0), Some(""));
+                assert_eq!(parsed[0].truncate_string_value(100), Some("h\\u00e9llo"));
+                assert_eq!(parsed[1].truncate_string_value(1), Some("\\ud83d\\ude00"));
+                assert_eq!(parsed[2].truncate_string_value(2), Some("çà"));
+                assert_eq!(parsed[3].truncate_string_value(1), None);
+            }
+            Err(error) => {
+                assert!(false, "{}", error.to_string());
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "grapheme")]
+    fn truncate_string_value_graphemes() {
+        match parse("[\"e\u{301}tat\"]") {
+            Ok(parsed) => {
+                assert_eq!(parsed[0].truncate_string_value(1), Some(""));
+                assert_eq!(parsed[0].truncate_string_value(2), Some("e\u{301}"));
+            }
+            Err(error) => {
+                assert!(false, "{}", error.to_string());
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "extended")]
+    fn parse_jsonc_syntax() {
+        match parse_jsonc("{\n  // comment\n  \"a\": [1, 2,], /* block\n comment */ \"b\": {\"c\": true,},\n}") {
+            Ok(parsed) => {
+                assert_eq!(parsed["a"][1].as_i128(), Some(2));
+                assert_eq!(parsed["b"]["c"].as_bool(), Some(true));
+            }
+            Err(error) => {
+                assert!(false, "{}", error.to_string());
+            }
+        }
+        for json in ["{a: 1}", "['a']", "[.5]", "[Infinity]"] {
+            assert!(parse_jsonc(json).is_err(), "{}", json);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "extended")]
+    fn parse_python_literals() {
+        match parse_lenient("{'a': True, 'b': False, 'c': None, 'd': NaN, 'e': [None]}") {
+            Ok(parsed) => {
+                assert_eq!(parsed["a"].as_bool(), Some(true));
+                assert_eq!(parsed["b"].as_bool(), Some(false));
+                assert!(parsed["c"].is_null());
+                assert!(parsed["d"].as_f64().unwrap().is_nan());
+                assert!(parsed["e"][0].is_null());
+            }
+            Err(error) => {
+                assert!(false, "{}", error.to_string());
+            }
+        }
+        assert!(parse_json5("[True]").is_err());
+        assert!(parse_lenient("[Nope]").is_err());
+        let pinned = Parser::new(ParseOptions::preset(crate::options::Preset::V1Lenient));
+        assert!(pinned.parse("{a: [True, 0x1F, .5, 'b',], // x\n}").unwrap()["a"][0].as_bool() == Some(true));
+        assert!(pinned.parse("1").is_err());
+    }
+}
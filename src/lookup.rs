@@ -0,0 +1,414 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::ops::Deref;
+
+use crate::json_item::{JsonItem, EMPTY_ITEM};
+use crate::json_type::JsonType;
+use crate::json_type::JsonType::{Empty, JsonArray, JsonFalse, JsonMap, JsonNull, JsonNumber, JsonString, JsonTrue};
+use crate::tracking::push_token;
+
+/// Key or index requested from an item, see [`MissingPath`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    /// Object member key
+    Key(String),
+    /// Array element index
+    Index(usize),
+}
+
+/// Key or index usable as a segment of the paths given to [`JsonItem::get_path`]:
+/// `&str` and `String` keys select object members, `usize` indices select array elements
+pub trait JsonIndex {
+    /// Returns the child of `item` designated by this segment, or a non-existent item if there is none
+    fn index_into<'a>(&self, item: &'a JsonItem) -> &'a JsonItem;
+}
+
+impl JsonIndex for &str {
+    fn index_into<'a>(&self, item: &'a JsonItem) -> &'a JsonItem {
+        &item[*self]
+    }
+}
+
+impl JsonIndex for String {
+    fn index_into<'a>(&self, item: &'a JsonItem) -> &'a JsonItem {
+        &item[self.as_str()]
+    }
+}
+
+impl JsonIndex for usize {
+    fn index_into<'a>(&self, item: &'a JsonItem) -> &'a JsonItem {
+        &item[*self]
+    }
+}
+
+impl JsonIndex for Segment {
+    fn index_into<'a>(&self, item: &'a JsonItem) -> &'a JsonItem {
+        match self {
+            Segment::Key(key) => { &item[key.as_str()] }
+            Segment::Index(index) => { &item[*index] }
+        }
+    }
+}
+
+/// Sequence of keys and indices given to [`JsonItem::get_path`]: a tuple of up to 8 [`JsonIndex`] segments
+/// of any types, e.g. `("users", 0, "name")`, or an array or slice of segments of the same type, or a reference to a path
+pub trait JsonPath {
+    /// Returns the descendant of `item` designated by this path, or a non-existent item if there is none
+    fn resolve<'a>(&self, item: &'a JsonItem) -> &'a JsonItem;
+}
+
+macro_rules! path_tuple {
+    ($($segment:ident),+) => {
+        impl<$($segment: JsonIndex),+> JsonPath for ($($segment,)+) {
+            #[allow(non_snake_case)]
+            fn resolve<'a>(&self, item: &'a JsonItem) -> &'a JsonItem {
+                let ($($segment,)+) = self;
+                $(let item = $segment.index_into(item);)+
+                item
+            }
+        }
+    };
+}
+
+path_tuple!(A);
+path_tuple!(A, B);
+path_tuple!(A, B, C);
+path_tuple!(A, B, C, D);
+path_tuple!(A, B, C, D, E);
+path_tuple!(A, B, C, D, E, F);
+path_tuple!(A, B, C, D, E, F, G);
+path_tuple!(A, B, C, D, E, F, G, H);
+
+impl<T: JsonIndex> JsonPath for [T] {
+    fn resolve<'a>(&self, item: &'a JsonItem) -> &'a JsonItem {
+        self.iter().fold(item, |item, segment| segment.index_into(item))
+    }
+}
+
+impl<P: JsonPath + ?Sized> JsonPath for &P {
+    fn resolve<'a>(&self, item: &'a JsonItem) -> &'a JsonItem {
+        (**self).resolve(item)
+    }
+}
+
+impl<T: JsonIndex, const N: usize> JsonPath for [T; N] {
+    fn resolve<'a>(&self, item: &'a JsonItem) -> &'a JsonItem {
+        self[..].resolve(item)
+    }
+}
+
+// Name of a JSON type in error messages, with an article
+fn type_name(json_type: JsonType) -> &'static str {
+    match json_type {
+        JsonNull => { "null" }
+        JsonTrue | JsonFalse => { "a boolean" }
+        JsonString => { "a string" }
+        JsonNumber => { "a number" }
+        JsonMap => { "an object" }
+        JsonArray => { "an array" }
+        Empty => { "missing" }
+    }
+}
+
+// Writes the message of a missing key or index, mentioning the type of the parent if it cannot hold the segment
+fn write_missing(f: &mut Formatter<'_>, segment: &Segment, parent_path: &str, parent_type: JsonType) -> FmtResult {
+    let expected = match segment {
+        Segment::Key(key) => {
+            write!(f, "missing key '{}'", key)?;
+            JsonMap
+        }
+        Segment::Index(index) => {
+            write!(f, "missing index {}", index)?;
+            JsonArray
+        }
+    };
+    match parent_path {
+        "" => { write!(f, " under the root")?; }
+        path => { write!(f, " under {}", path)?; }
+    }
+    if parent_type != expected {
+        write!(f, ", which is {}", type_name(parent_type))?;
+    }
+    Ok(())
+}
+
+/// First key or index missing along a chain of lookups, see [`JsonItem::lookup`]
+#[derive(Debug, Clone)]
+pub struct MissingPath<'a> {
+    parent: &'a JsonItem,
+    parent_path: String,
+    segment: Segment,
+}
+
+impl<'a> MissingPath<'a> {
+    /// Last item found, which lacks the requested key or index. Its location in the source is given by
+    /// [`JsonItem::span`](crate::json_item::JsonItem::span).
+    pub fn get_parent(&self) -> &'a JsonItem {
+        self.parent
+    }
+
+    /// JSON Pointer of the last item found, relative to the item the lookup started from
+    pub fn get_parent_path(&self) -> &str {
+        &self.parent_path
+    }
+
+    /// Key or index missing from the last item found
+    pub fn get_segment(&self) -> &Segment {
+        &self.segment
+    }
+}
+
+impl Display for MissingPath<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write_missing(f, &self.segment, &self.parent_path, self.parent.json_type)
+    }
+}
+
+impl Error for MissingPath<'_> {}
+
+/// Error returned by [`JsonItem::try_get`], telling which part of the path failed and why
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessError {
+    path: String,
+    parent_path: String,
+    segment: Segment,
+    found: JsonType,
+}
+
+impl AccessError {
+    /// Path given to [`JsonItem::try_get`]
+    pub fn get_path(&self) -> &str {
+        &self.path
+    }
+
+    /// JSON Pointer of the last item found along the path
+    pub fn get_parent_path(&self) -> &str {
+        &self.parent_path
+    }
+
+    /// Key or index missing from the last item found
+    pub fn get_segment(&self) -> &Segment {
+        &self.segment
+    }
+
+    /// Type of the last item found, e.g. [`JsonType::JsonString`] when a key is requested from a string
+    pub fn get_found(&self) -> JsonType {
+        self.found
+    }
+}
+
+impl Display for AccessError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "cannot get '{}': ", self.path)?;
+        write_missing(f, &self.segment, &self.parent_path, self.found)
+    }
+}
+
+impl Error for AccessError {}
+
+/// Chain of lookups remembering where it broke, see [`JsonItem::lookup`].
+/// Dereferences to the item found, which does not exist if a key or index is missing along the chain.
+#[derive(Debug, Clone)]
+pub struct Lookup<'a> {
+    item: &'a JsonItem,
+    path: String,
+    missing: Option<MissingPath<'a>>,
+}
+
+impl<'a> Lookup<'a> {
+    fn child(mut self, item: &'a JsonItem, segment: Segment) -> Lookup<'a> {
+        if self.missing.is_some() {
+            return Lookup { item, ..self };
+        }
+        if !item.exists() {
+            self.missing = Some(MissingPath { parent: self.item, parent_path: self.path.clone(), segment });
+            return Lookup { item, ..self };
+        }
+        match &segment {
+            Segment::Key(key) => { push_token(&mut self.path, key); }
+            Segment::Index(index) => { push_token(&mut self.path, &index.to_string()); }
+        }
+        Lookup { item, ..self }
+    }
+
+    /// Member of an object, matched as with indexing by `&str`
+    pub fn get(self, key: &str) -> Lookup<'a> {
+        let item = &self.item[key];
+        self.child(item, Segment::Key(key.to_owned()))
+    }
+
+    /// Element of an array
+    pub fn at(self, index: usize) -> Lookup<'a> {
+        let item = &self.item[index];
+        self.child(item, Segment::Index(index))
+    }
+
+    /// Descendant designated by a JSON Pointer (RFC 6901) relative to this item, looked up one reference token at a time.
+    /// A pointer not starting with `/` is reported missing as a whole.
+    pub fn pointer(self, pointer: &str) -> Lookup<'a> {
+        let tokens = match pointer.strip_prefix('/') {
+            Some(tokens) => { tokens }
+            None if pointer.is_empty() => { return self; }
+            None => { return self.child(&EMPTY_ITEM, Segment::Key(pointer.to_owned())); }
+        };
+        tokens.split('/').fold(self, |lookup, token| {
+            let token = token.replace("~1", "/").replace("~0", "~");
+            match (&lookup.item.json_type, token.parse::<usize>()) {
+                (JsonArray, Ok(index)) if token.bytes().all(|b| b.is_ascii_digit()) && (token.len() == 1 || !token.starts_with('0')) => {
+                    lookup.at(index)
+                }
+                _ => { lookup.get(&token) }
+            }
+        })
+    }
+
+    /// Item found, which does not exist if a key or index is missing along the chain
+    pub fn item(&self) -> &'a JsonItem {
+        self.item
+    }
+
+    /// JSON Pointer of the item found, or of the last item found if a key or index is missing along the chain
+    pub fn get_path(&self) -> &str {
+        &self.path
+    }
+
+    /// First key or index missing along the chain, if any
+    pub fn missing(&self) -> Option<&MissingPath<'a>> {
+        self.missing.as_ref()
+    }
+
+    /// Returns the item found, or the first key or index missing along the chain
+    pub fn found(self) -> Result<&'a JsonItem, MissingPath<'a>> {
+        match self.missing {
+            Some(missing) => { Err(missing) }
+            None => { Ok(self.item) }
+        }
+    }
+}
+
+impl Deref for Lookup<'_> {
+    type Target = JsonItem;
+
+    fn deref(&self) -> &Self::Target {
+        self.item
+    }
+}
+
+impl JsonItem {
+    /// Starts a chain of lookups which, unlike indexing, remembers the first key or index missing along the chain,
+    /// so that error messages can tell where it broke rather than just that the result does not exist.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let parsed = jsonic::parse("{\"a\": {\"c\": [1, 2]}}").unwrap();
+    ///
+    /// assert!(!parsed["a"]["b"][3].exists());
+    /// let error = parsed.lookup().get("a").get("b").at(3).found().unwrap_err();
+    /// assert_eq!(error.to_string(), "missing key 'b' under /a");
+    ///
+    /// assert_eq!(parsed.lookup().pointer("/a/c/1").found().unwrap().as_i128(), Some(2));
+    /// ```
+    pub fn lookup(&self) -> Lookup<'_> {
+        Lookup { item: self, path: String::new(), missing: None }
+    }
+
+    /// Returns the descendant designated by a sequence of keys and indices, or `None` if there is no such item.
+    /// Segments of different types can be mixed in a tuple, and are resolved in a single walk down the tree,
+    /// without building or parsing a path string. See [`JsonPath`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let parsed = jsonic::parse("{\"users\": [{\"name\": \"ann\", \"tags\": [\"admin\"]}]}").unwrap();
+    ///
+    /// assert_eq!(parsed.get_path(("users", 0, "name")).and_then(|name| name.as_str()), Some("ann"));
+    /// assert_eq!(parsed.get_path(("users", 0, "tags", 0)).and_then(|tag| tag.as_str()), Some("admin"));
+    /// assert!(parsed.get_path(("users", 1)).is_none());
+    /// assert!(parsed.get_path(["users", "name"]).is_none());
+    /// ```
+    pub fn get_path<P: JsonPath>(&self, path: P) -> Option<&JsonItem> {
+        Some(path.resolve(self)).filter(|item| item.exists())
+    }
+
+    /// Returns the item designated by a JSON Pointer (RFC 6901) relative to this item, as [`pointer`](JsonItem::pointer) does,
+    /// or an error telling the path, the first key or index missing along it, and the type of the item lacking it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jsonic::json_type::JsonType;
+    ///
+    /// let config = jsonic::parse("{\"server\": {\"listen\": \"0.0.0.0:80\"}}").unwrap();
+    ///
+    /// assert_eq!(config.try_get("/server/listen").unwrap().as_str(), Some("0.0.0.0:80"));
+    /// let error = config.try_get("/server/listen/port").unwrap_err();
+    /// assert_eq!(error.get_found(), JsonType::JsonString);
+    /// assert_eq!(error.to_string(), "cannot get '/server/listen/port': missing key 'port' under /server/listen, which is a string");
+    /// ```
+    pub fn try_get(&self, path: &str) -> Result<&JsonItem, AccessError> {
+        self.lookup().pointer(path).found().map_err(|missing| AccessError {
+            path: path.to_owned(),
+            parent_path: missing.parent_path,
+            segment: missing.segment,
+            found: missing.parent.json_type,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::json_type::JsonType::{JsonArray, JsonMap};
+    use crate::lookup::Segment;
+    use crate::parse;
+
+    #[test]
+    fn report_missing() {
+        let source = "{\"a/b\": [{\"c\": \"x\"}], \"d\": 1}";
+        let parsed = parse(source).unwrap();
+        let lookup = parsed.lookup().get("a/b").at(0).get("c");
+        assert!(lookup.missing().is_none());
+        assert_eq!((lookup.get_path(), lookup.as_str()), ("/a~1b/0/c", Some("x")));
+
+        let lookup = parsed.lookup().get("a/b").at(2).get("c");
+        assert!(!lookup.exists());
+        let missing = lookup.missing().unwrap();
+        assert_eq!((missing.get_parent_path(), missing.get_segment()), ("/a~1b", &Segment::Index(2)));
+        assert_eq!(missing.get_parent().span(source).unwrap().get_range(), 8..20);
+        assert_eq!(missing.to_string(), "missing index 2 under /a~1b");
+
+        assert_eq!(parsed.lookup().get("e").found().unwrap_err().to_string(), "missing key 'e' under the root");
+        assert_eq!(parsed.lookup().get("d").get("f").found().unwrap_err().to_string(), "missing key 'f' under /d, which is a number");
+        assert_eq!(parsed.lookup().pointer("/a~1b/0/d").found().unwrap_err().to_string(), "missing key 'd' under /a~1b/0");
+        assert_eq!(parsed.lookup().pointer("/a~1b/01").found().unwrap_err().get_segment(), &Segment::Key("01".to_owned()));
+        assert_eq!(parsed.lookup().pointer("").found().ok(), Some(&parsed));
+        assert_eq!(parsed.lookup().pointer("d").missing().map(|missing| missing.get_segment().clone()), Some(Segment::Key("d".to_owned())));
+    }
+
+    #[test]
+    fn try_get_paths() {
+        let parsed = parse("{\"a\": [{\"b\": null}]}").unwrap();
+        assert!(parsed.try_get("/a/0/b").unwrap().is_null());
+        let error = parsed.try_get("/a/1/b").unwrap_err();
+        assert_eq!((error.get_path(), error.get_parent_path(), error.get_segment(), error.get_found()), ("/a/1/b", "/a", &Segment::Index(1), JsonArray));
+        assert_eq!(error.to_string(), "cannot get '/a/1/b': missing index 1 under /a");
+        let error = parsed.try_get("/a/x").unwrap_err();
+        assert_eq!(error.to_string(), "cannot get '/a/x': missing key 'x' under /a, which is an array");
+        assert_eq!(parsed.try_get("/c").unwrap_err().get_found(), JsonMap);
+        assert_eq!(parsed.try_get("/a/0/b/c").unwrap_err().to_string(), "cannot get '/a/0/b/c': missing key 'c' under /a/0/b, which is null");
+    }
+
+    #[test]
+    fn get_mixed_paths() {
+        let parsed = parse("{\"a\": [{\"b\": [null, {\"c\": 1}]}], \"1\": 2}").unwrap();
+        assert_eq!(parsed.get_path(("a", 0, "b", 1, "c")).and_then(|c| c.as_i128()), Some(1));
+        assert!(parsed.get_path(("a", 0, "b", 0)).unwrap().is_null());
+        assert_eq!(parsed.get_path((String::from("1"),)).and_then(|item| item.as_i128()), Some(2));
+        assert!(parsed.get_path(("a", "0")).is_none() && parsed.get_path((1,)).is_none() && parsed.get_path(("a", 0, "b", 2)).is_none());
+
+        let segments = [Segment::Key("a".to_owned()), Segment::Index(0), Segment::Key("b".to_owned())];
+        assert_eq!(parsed.get_path(&segments[..]), Some(&parsed["a"][0]["b"]));
+        assert_eq!(parsed.get_path([0usize; 0]), Some(&parsed));
+        assert_eq!(parsed.get_path(["a"]).map(|a| a.get_type()), Some(&JsonArray));
+    }
+}
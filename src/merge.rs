@@ -0,0 +1,249 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use crate::json_item::JsonItem;
+use crate::json_type::JsonType::{JsonArray, JsonMap};
+use crate::number::FloatEquality;
+use crate::serialize::{write_compact, write_string};
+use crate::tracking::push_token;
+
+/// How [`merge3_with`] resolves values changed differently on both sides
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Resolution {
+    /// Replaces the value with a marker object holding all versions: `{"<<<<<<< ours": .., "||||||| base": .., ">>>>>>> theirs": ..}`,
+    /// absent versions being left out, so that conflicts can be fixed by hand like in text merges
+    #[default]
+    Markers,
+    /// Keeps our version
+    Ours,
+    /// Keeps their version
+    Theirs,
+}
+
+/// Value changed differently on both sides of a three-way merge. Versions are compact JSON text, `None` when absent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    path: String,
+    base: Option<String>,
+    ours: Option<String>,
+    theirs: Option<String>,
+}
+
+impl Conflict {
+    /// JSON Pointer of the value
+    pub fn get_path(&self) -> &str {
+        &self.path
+    }
+
+    /// Value in the common ancestor
+    pub fn get_base(&self) -> Option<&str> {
+        self.base.as_deref()
+    }
+
+    /// Value on our side
+    pub fn get_ours(&self) -> Option<&str> {
+        self.ours.as_deref()
+    }
+
+    /// Value on their side
+    pub fn get_theirs(&self) -> Option<&str> {
+        self.theirs.as_deref()
+    }
+}
+
+impl Display for Conflict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let version = |value: &Option<String>| value.clone().unwrap_or_else(|| "(absent)".to_owned());
+        write!(f, "'{}': ours {}, theirs {}, base {}", self.path, version(&self.ours), version(&self.theirs), version(&self.base))
+    }
+}
+
+/// Result of a three-way merge, see [`merge3`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Merge {
+    merged: String,
+    conflicts: Vec<Conflict>,
+}
+
+impl Merge {
+    /// Compact JSON text of the merged document, conflicts being resolved as requested
+    pub fn get_merged(&self) -> &str {
+        &self.merged
+    }
+
+    /// Values changed differently on both sides, in document order
+    pub fn get_conflicts(&self) -> &[Conflict] {
+        &self.conflicts
+    }
+
+    /// Checks if the merge has no conflicts
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+fn compact(item: &JsonItem) -> String {
+    let mut output = String::new();
+    write_compact(item, &mut output);
+    output
+}
+
+// Whether both versions are equal, absent versions being equal to each other only
+fn same(a: Option<&JsonItem>, b: Option<&JsonItem>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => { a.equals(b, FloatEquality::Bitwise) }
+        (None, None) => { true }
+        _ => { false }
+    }
+}
+
+// Member `key` of `item`, if both exist
+fn member<'a>(item: Option<&'a JsonItem>, key: &str) -> Option<&'a JsonItem> {
+    item.map(|item| &item[key]).filter(|member| member.exists())
+}
+
+struct Merger {
+    resolution: Resolution,
+    conflicts: Vec<Conflict>,
+}
+
+impl Merger {
+    // Merged compact text of a value, `None` if it is absent from the result
+    fn merge(&mut self, base: Option<&JsonItem>, ours: Option<&JsonItem>, theirs: Option<&JsonItem>, path: &str) -> Option<String> {
+        if same(ours, theirs) || same(theirs, base) {
+            return ours.map(compact);
+        }
+        if same(ours, base) {
+            return theirs.map(compact);
+        }
+        let base_type = base.map(|base| &base.json_type);
+        match (ours, theirs) {
+            (Some(ours), Some(theirs)) if ours.json_type == JsonMap && theirs.json_type == JsonMap && base_type.is_none_or(|t| *t == JsonMap) => {
+                let mut output = String::from("{");
+                let mut keys = ours.entries().unwrap().map(|(key, _)| key.as_str()).collect::<Vec<_>>();
+                keys.extend(theirs.entries().unwrap().map(|(key, _)| key.as_str()).filter(|key| !ours[*key].exists()));
+                keys.extend(base.and_then(JsonItem::entries).into_iter().flatten().map(|(key, _)| key.as_str())
+                    .filter(|key| !ours[*key].exists() && !theirs[*key].exists()));
+                for key in keys {
+                    let mut child = path.to_owned();
+                    push_token(&mut child, key);
+                    if let Some(value) = self.merge(member(base, key), member(Some(ours), key), member(Some(theirs), key), &child) {
+                        if output.len() > 1 {
+                            output.push(',');
+                        }
+                        write_string(key, &mut output);
+                        output.push(':');
+                        output.push_str(&value);
+                    }
+                }
+                output.push('}');
+                Some(output)
+            }
+            (Some(ours), Some(theirs)) if ours.json_type == JsonArray && theirs.json_type == JsonArray && base_type == Some(&JsonArray) => {
+                // Elements are merged by index when no side changed the length, otherwise the array is one conflict
+                let len = base.and_then(JsonItem::elements).map_or(0, Iterator::count);
+                if ours.elements().unwrap().count() != len || theirs.elements().unwrap().count() != len {
+                    return self.conflict(base, Some(ours), Some(theirs), path);
+                }
+                let mut output = String::from("[");
+                for index in 0..len {
+                    if index > 0 {
+                        output.push(',');
+                    }
+                    let merged = self.merge(base.map(|base| &base[index]), Some(&ours[index]), Some(&theirs[index]), &format!("{}/{}", path, index));
+                    output.push_str(merged.as_deref().unwrap_or("null"));
+                }
+                output.push(']');
+                Some(output)
+            }
+            _ => { self.conflict(base, ours, theirs, path) }
+        }
+    }
+
+    fn conflict(&mut self, base: Option<&JsonItem>, ours: Option<&JsonItem>, theirs: Option<&JsonItem>, path: &str) -> Option<String> {
+        let (base, ours, theirs) = (base.map(compact), ours.map(compact), theirs.map(compact));
+        let resolved = match self.resolution {
+            Resolution::Ours => { ours.clone() }
+            Resolution::Theirs => { theirs.clone() }
+            Resolution::Markers => {
+                let versions = [("<<<<<<< ours", &ours), ("||||||| base", &base), (">>>>>>> theirs", &theirs)];
+                let members = versions.iter().filter_map(|(marker, value)| value.as_ref().map(|value| format!("\"{}\":{}", marker, value)));
+                Some(format!("{{{}}}", members.collect::<Vec<_>>().join(",")))
+            }
+        };
+        self.conflicts.push(Conflict { path: path.to_owned(), base, ours, theirs });
+        resolved
+    }
+}
+
+/// Merges the changes made to `base` in `ours` and in `theirs`, structurally: object members are merged by key
+/// and, in arrays whose length did not change, elements are merged by index. A value changed on one side only
+/// takes that side's version, and a value changed the same way on both sides is kept as is. Other changes are
+/// conflicts, replaced in the merged document with marker objects holding all versions, see [`Resolution::Markers`].
+///
+/// # Example
+///
+/// ```rust
+/// use jsonic::merge::merge3;
+///
+/// let base = jsonic::parse("{\"replicas\": 2, \"image\": \"app:1\", \"env\": {\"LOG\": \"info\"}}").unwrap();
+/// let ours = jsonic::parse("{\"replicas\": 3, \"image\": \"app:2\", \"env\": {\"LOG\": \"info\"}}").unwrap();
+/// let theirs = jsonic::parse("{\"replicas\": 2, \"image\": \"app:3\", \"env\": {\"LOG\": \"debug\"}}").unwrap();
+///
+/// let merge = merge3(&base, &ours, &theirs);
+/// assert_eq!(merge.get_merged(), "{\"replicas\":3,\"image\":{\"<<<<<<< ours\":\"app:2\",\"||||||| base\":\"app:1\",\">>>>>>> theirs\":\"app:3\"},\"env\":{\"LOG\":\"debug\"}}");
+/// assert_eq!(merge.get_conflicts()[0].to_string(), "'/image': ours \"app:2\", theirs \"app:3\", base \"app:1\"");
+/// ```
+pub fn merge3(base: &JsonItem, ours: &JsonItem, theirs: &JsonItem) -> Merge {
+    merge3_with(base, ours, theirs, Resolution::Markers)
+}
+
+/// Same as [`merge3`], but conflicts are resolved according to `resolution`. They are reported all the same.
+///
+/// # Example
+///
+/// ```rust
+/// use jsonic::merge::{merge3_with, Resolution};
+///
+/// let base = jsonic::parse("{\"a\": 1, \"b\": 1}").unwrap();
+/// let ours = jsonic::parse("{\"a\": 2}").unwrap();
+/// let theirs = jsonic::parse("{\"a\": 3, \"b\": 1, \"c\": 1}").unwrap();
+///
+/// let merge = merge3_with(&base, &ours, &theirs, Resolution::Theirs);
+/// assert_eq!((merge.get_merged(), merge.get_conflicts().len()), ("{\"a\":3,\"c\":1}", 1));
+/// ```
+pub fn merge3_with(base: &JsonItem, ours: &JsonItem, theirs: &JsonItem, resolution: Resolution) -> Merge {
+    let mut merger = Merger { resolution, conflicts: Vec::new() };
+    let [base, ours, theirs] = [base, ours, theirs].map(|item| Some(item).filter(|item| item.exists()));
+    let merged = merger.merge(base, ours, theirs, "").unwrap_or_else(|| "null".to_owned());
+    Merge { merged, conflicts: merger.conflicts }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::merge::{merge3, merge3_with, Resolution};
+    use crate::{parse, parse_strict};
+
+    fn merge(base: &str, ours: &str, theirs: &str, resolution: Resolution) -> (String, Vec<String>) {
+        let merge = merge3_with(&parse(base).unwrap(), &parse(ours).unwrap(), &parse(theirs).unwrap(), resolution);
+        (merge.get_merged().to_owned(), merge.get_conflicts().iter().map(|conflict| conflict.get_path().to_owned()).collect())
+    }
+
+    #[test]
+    fn merge_changes() {
+        let clean = merge("{\"a\": [1, 2], \"b\": {\"c\": 1, \"d\": 1}, \"e\": 1}", "{\"a\": [1, 3], \"b\": {\"c\": 2, \"d\": 1}, \"e\": 1, \"f\": 1}",
+                          "{\"a\": [0, 2], \"b\": {\"c\": 1}, \"f\": 1, \"g/h\": []}", Resolution::Markers);
+        assert_eq!(clean, ("{\"a\":[0,3],\"b\":{\"c\":2},\"f\":1,\"g/h\":[]}".to_owned(), vec![]));
+
+        let (merged, conflicts) = merge("{\"a\": [1], \"b\": {\"x\": 1}, \"c\": 1}", "{\"a\": [1, 2], \"b\": {\"x\": 2}}", "{\"a\": [], \"b\": {}, \"c\": 2}", Resolution::Markers);
+        assert_eq!(conflicts, ["/a", "/b/x", "/c"]);
+        assert_eq!(merged, "{\"a\":{\"<<<<<<< ours\":[1,2],\"||||||| base\":[1],\">>>>>>> theirs\":[]},\"b\":{\"x\":{\"<<<<<<< ours\":2,\"||||||| base\":1}},\
+                            \"c\":{\"||||||| base\":1,\">>>>>>> theirs\":2}}");
+        assert_eq!(merge("[1, {\"a\": 1}]", "[2, {\"a\": 2}]", "[3, {\"a\": 3}]", Resolution::Ours), ("[2,{\"a\":2}]".to_owned(), vec!["/0".to_owned(), "/1/a".to_owned()]));
+        assert_eq!(merge("{}", "{\"a\": {\"b\": 1}}", "{\"a\": {\"c\": 1}}", Resolution::Theirs).0, "{\"a\":{\"b\":1,\"c\":1}}");
+        assert_eq!(merge("{}", "{\"a\": 1}", "{\"a\": 1.0}", Resolution::Theirs), ("{\"a\":1}".to_owned(), vec![]));
+
+        let merged = merge3(&parse_strict("1").unwrap(), &parse_strict("2").unwrap(), &parse_strict("3").unwrap());
+        assert_eq!(merged.get_conflicts()[0].get_path(), "");
+        assert!(!merged.is_clean());
+    }
+}
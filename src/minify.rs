@@ -0,0 +1,265 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::io;
+use std::io::Write;
+use std::str::from_utf8;
+
+use crate::json_error::{ErrorKind, JsonError};
+use crate::number::is_json_number;
+
+/// Errors returned by [`minify_to`]
+#[derive(Debug)]
+pub enum MinifyError {
+    /// Output could not be written
+    Io(io::Error),
+    /// Source is not valid JSON
+    Json(JsonError),
+}
+
+impl Display for MinifyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            MinifyError::Io(error) => { write!(f, "I/O error: {}", error) }
+            MinifyError::Json(error) => { Display::fmt(error, f) }
+        }
+    }
+}
+
+impl Error for MinifyError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            MinifyError::Io(error) => { Some(error) }
+            MinifyError::Json(error) => { Some(error) }
+        }
+    }
+}
+
+impl From<JsonError> for MinifyError {
+    fn from(error: JsonError) -> Self {
+        MinifyError::Json(error)
+    }
+}
+
+// Validating scanner passing on the source text, except whitespace between tokens, in as few chunks as possible
+struct Minifier<'a, F> {
+    bytes: &'a [u8],
+    index: usize,
+    // Start of the text not passed on yet
+    mark: usize,
+    emit: F,
+}
+
+impl<'a, E: From<JsonError>, F: FnMut(&[u8]) -> Result<(), E>> Minifier<'a, F> {
+    fn error(&self) -> E {
+        JsonError::new(self.bytes, self.index).located(self.bytes).into()
+    }
+
+    fn error_with(&self, kind: ErrorKind) -> E {
+        JsonError::with_kind(self.index, kind).located(self.bytes).into()
+    }
+
+    // Skips whitespace and returns the next byte, passing on the text preceding the whitespace
+    fn peek(&mut self) -> Result<Option<u8>, E> {
+        let start = self.index;
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.bytes.get(self.index) {
+            self.index += 1;
+        }
+        if self.index > start {
+            if start > self.mark {
+                (self.emit)(&self.bytes[self.mark..start])?;
+            }
+            self.mark = self.index;
+        }
+        Ok(self.bytes.get(self.index).copied())
+    }
+
+    fn string(&mut self) -> Result<(), E> {
+        self.index += 1;
+        loop {
+            match self.bytes.get(self.index) {
+                Some(b'"') => {
+                    self.index += 1;
+                    return Ok(());
+                }
+                None => { return Err(self.error_with(ErrorKind::UnterminatedString)); }
+                Some(0x00..=0x1F) => { return Err(self.error()); }
+                Some(b'\\') => {
+                    match self.bytes.get(self.index + 1) {
+                        Some(b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't') => { self.index += 2; }
+                        Some(b'u') if self.bytes.get(self.index + 2..self.index + 6).is_some_and(|hex| hex.iter().all(u8::is_ascii_hexdigit)) => { self.index += 6; }
+                        _ => { return Err(self.error_with(ErrorKind::InvalidEscape)); }
+                    }
+                }
+                Some(_) => { self.index += 1; }
+            }
+        }
+    }
+
+    // Literal or number
+    fn scalar(&mut self) -> Result<(), E> {
+        let start = self.index;
+        while let Some(b'a'..=b'z' | b'0'..=b'9' | b'-' | b'+' | b'.' | b'E') = self.bytes.get(self.index) {
+            self.index += 1;
+        }
+        let token = &self.bytes[start..self.index];
+        if matches!(token.first(), Some(b'-' | b'0'..=b'9')) {
+            if !from_utf8(token).is_ok_and(is_json_number) {
+                self.index = start;
+                return Err(self.error_with(ErrorKind::InvalidNumber));
+            }
+        } else if !matches!(token, b"true" | b"false" | b"null") {
+            // A literal cut short is reported at the end of data
+            if self.index < self.bytes.len() || ![b"true".as_slice(), b"false", b"null"].iter().any(|word| word.starts_with(token)) {
+                self.index = start;
+            }
+            return Err(self.error());
+        }
+        Ok(())
+    }
+
+    // Object key and the following colon
+    fn key(&mut self) -> Result<(), E> {
+        if self.peek()? != Some(b'"') {
+            return Err(self.error());
+        }
+        self.string()?;
+        if self.peek()? != Some(b':') {
+            return Err(self.error());
+        }
+        self.index += 1;
+        Ok(())
+    }
+
+    fn run(mut self) -> Result<(), E> {
+        // Closing brackets of the open containers, so that nesting depth is not limited by the call stack
+        let mut closing = Vec::new();
+        loop {
+            match self.peek()? {
+                Some(b'{') => {
+                    self.index += 1;
+                    if self.peek()? == Some(b'}') {
+                        self.index += 1;
+                    } else {
+                        closing.push(b'}');
+                        self.key()?;
+                        continue;
+                    }
+                }
+                Some(b'[') => {
+                    self.index += 1;
+                    if self.peek()? == Some(b']') {
+                        self.index += 1;
+                    } else {
+                        closing.push(b']');
+                        continue;
+                    }
+                }
+                Some(b'"') => { self.string()?; }
+                _ => { self.scalar()?; }
+            }
+
+            // After a value, close containers until a comma or the end of the source
+            loop {
+                let next = self.peek()?;
+                match closing.last() {
+                    None if next.is_none() => {
+                        return if self.mark < self.index { (self.emit)(&self.bytes[self.mark..self.index]) } else { Ok(()) };
+                    }
+                    Some(&close) if next == Some(close) => {
+                        closing.pop();
+                        self.index += 1;
+                    }
+                    Some(&close) if next == Some(b',') => {
+                        self.index += 1;
+                        if close == b'}' {
+                            self.key()?;
+                        }
+                        break;
+                    }
+                    None => { return Err(self.error_with(ErrorKind::TrailingData)); }
+                    _ => { return Err(self.error()); }
+                }
+            }
+        }
+    }
+}
+
+/// Removes insignificant whitespace from JSON text in a single pass, without building items.
+/// Source text is validated against RFC 8259 as with [`parse_strict`](crate::parse_strict), except that nesting depth is not limited.
+/// Everything else is copied as is, e.g. escape sequences and number formats are kept.
+///
+/// # Arguments
+/// * `source` - JSON text to minify
+///
+/// # Example
+///
+/// ```rust
+/// assert_eq!(jsonic::minify("{\n  \"a\": [1, 2],\n  \"b c\": null\n}\n").unwrap(), "{\"a\":[1,2],\"b c\":null}");
+/// assert_eq!(jsonic::minify("[1, 2,]").unwrap_err().get_index(), 6);
+/// ```
+pub fn minify(source: &str) -> Result<String, JsonError> {
+    let mut output = Vec::with_capacity(source.len());
+    let emit = |text: &[u8]| {
+        output.extend_from_slice(text);
+        Ok::<_, JsonError>(())
+    };
+    Minifier { bytes: source.as_bytes(), index: 0, mark: 0, emit }.run()?;
+    // Only ASCII whitespace was removed
+    Ok(String::from_utf8(output).expect("minified text is valid UTF-8"))
+}
+
+/// Removes insignificant whitespace from JSON text in a single pass, writing the result to `writer`, see [`minify`].
+/// Output is written while validating, so part of it may have been written when an error is returned.
+/// Writes are made for each run of text between whitespace, so `writer` should be buffered.
+///
+/// # Arguments
+/// * `source` - JSON text to minify
+/// * `writer` - Destination of the minified text
+pub fn minify_to<W: Write>(source: &str, mut writer: W) -> Result<(), MinifyError> {
+    let emit = |text: &[u8]| writer.write_all(text).map_err(MinifyError::Io);
+    Minifier { bytes: source.as_bytes(), index: 0, mark: 0, emit }.run()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use crate::json_error::ErrorKind;
+    use crate::minify::{minify, minify_to, MinifyError};
+
+    #[test]
+    fn strip_whitespace() {
+        assert_eq!(minify(" 42 ").unwrap(), "42");
+        assert_eq!(minify("\"a b\"").unwrap(), "\"a b\"");
+        assert_eq!(minify("[ ]").unwrap(), "[]");
+        assert_eq!(minify("{ \"k\" : { } ,\"l\":[ [ ] , -1.5e3 ,true,\"\\u00e9 \\\" x\"] }").unwrap(), "{\"k\":{},\"l\":[[],-1.5e3,true,\"\\u00e9 \\\" x\"]}");
+        assert_eq!(minify(&format!("{}{}", "[".repeat(100_000), "]".repeat(100_000))).unwrap().len(), 200_000);
+    }
+
+    #[test]
+    fn reject_invalid() {
+        for (json, index) in [("", 0), ("[1] [2]", 4), ("[01]", 1), ("{\"a\" 1}", 5), ("{1: 2}", 1), ("[1,]", 3),
+                              ("[,1]", 1), ("{\"a\": 1]", 7), ("[\"\\x\"]", 2), ("[\"a\nb\"]", 3), ("[tru]", 1), ("[1", 2)] {
+            let error = minify(json).unwrap_err();
+            assert_eq!(error.get_index(), index, "{}", json);
+        }
+        let kind = |json: &str| minify(json).unwrap_err().get_kind();
+        assert_eq!(kind("[\"a"), ErrorKind::UnterminatedString);
+        assert_eq!(kind("[\"\\x\"]"), ErrorKind::InvalidEscape);
+        assert_eq!(kind("[-]"), ErrorKind::InvalidNumber);
+        assert_eq!(kind("[1] 2"), ErrorKind::TrailingData);
+        assert_eq!((kind("[fals"), kind("[")), (ErrorKind::UnexpectedEof, ErrorKind::UnexpectedEof));
+        assert_eq!(kind("[x]"), ErrorKind::UnexpectedCharacter);
+    }
+
+    #[test]
+    fn write_minified() {
+        let mut output = Vec::new();
+        minify_to("{ \"a\" : [ 1 , 2 ] }", &mut output).unwrap();
+        assert_eq!(output, b"{\"a\":[1,2]}");
+
+        assert!(matches!(minify_to("[1 2]", Vec::new()), Err(MinifyError::Json(e)) if e.get_index() == 3));
+        let mut small = [0u8; 2];
+        assert!(matches!(minify_to("[1, 2]", &mut small[..]), Err(MinifyError::Io(e)) if e.kind() == io::ErrorKind::WriteZero));
+    }
+}
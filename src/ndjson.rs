@@ -0,0 +1,290 @@
+use std::borrow::Borrow;
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::io;
+use std::io::Write;
+use std::iter::FusedIterator;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::json_error::JsonError;
+use crate::json_item::JsonItem;
+use crate::parse_complete;
+use crate::options::ParseOptions;
+use crate::serialize::write_compact;
+
+/// Error found on a line of NDJSON (newline-delimited JSON) data
+#[derive(Debug)]
+pub struct LineError {
+    line: usize,
+    offset: usize,
+    error: JsonError,
+}
+
+impl LineError {
+    /// Line number, starting at 1
+    pub fn get_line(&self) -> usize {
+        self.line
+    }
+
+    /// Byte offset of the start of the line in source content
+    pub fn get_offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Parsing error, with its index relative to the start of the line
+    pub fn get_error(&self) -> &JsonError {
+        &self.error
+    }
+}
+
+impl Display for LineError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "line {}: {}", self.line, self.error)
+    }
+}
+
+impl Error for LineError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// NDJSON records iterator, see [`parse_lines`]
+pub struct JsonLines<'a> {
+    source: &'a str,
+    offset: usize,
+    line: usize,
+}
+
+impl<'a> Iterator for JsonLines<'a> {
+    type Item = Result<JsonItem, LineError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.offset < self.source.len() {
+            let rest = &self.source[self.offset..];
+            let (text, consumed) = match rest.find('\n') {
+                Some(end) => { (&rest[..end], end + 1) }
+                None => { (rest, rest.len()) }
+            };
+            let offset = self.offset;
+            self.offset += consumed;
+            self.line += 1;
+
+            // Blank lines (e.g. a final newline) are not records
+            if text.bytes().all(|b| b.is_ascii_whitespace()) {
+                continue;
+            }
+            return Some(parse_complete(text, &ParseOptions::JSON).map_err(|error| LineError { line: self.line, offset, error }));
+        }
+        None
+    }
+}
+
+impl<'a> FusedIterator for JsonLines<'a> {}
+
+/// Parses NDJSON (newline-delimited JSON) data, yielding one result per non-blank line.
+/// Each line must hold a complete JSON object or array, optionally surrounded by whitespace (including `\r`).
+///
+/// # Arguments
+/// * `source` - Text content to be parsed
+///
+/// # Example
+///
+/// ```rust
+/// let ndjson = "{\"id\": 1}\n{\"id\": 2}\n";
+///
+/// for record in jsonic::ndjson::parse_lines(ndjson) {
+///     match record {
+///         Ok(parsed) => { println!("id: {:?}", parsed["id"].as_i128()); }
+///         Err(error) => { eprintln!("line {}: {}", error.get_line(), error.get_error()); }
+///     }
+/// }
+/// ```
+pub fn parse_lines(source: &str) -> JsonLines<'_> {
+    JsonLines { source, offset: 0, line: 0 }
+}
+
+/// Validates NDJSON (newline-delimited JSON) data in one pass, returning the errors found, in line order.
+/// An empty result means all records are valid.
+///
+/// # Arguments
+/// * `source` - Text content to be validated
+///
+/// # Example
+///
+/// ```rust
+/// let ndjson = "{\"id\": 1}\n{\"id\": 2,}\n[3]\n{\"id\"\n";
+///
+/// let errors = jsonic::ndjson::validate_lines(ndjson);
+/// assert_eq!(errors.iter().map(|e| e.get_line()).collect::<Vec<_>>(), vec![2, 4]);
+/// assert_eq!(errors[0].get_offset(), 10);
+/// ```
+pub fn validate_lines(source: &str) -> Vec<LineError> {
+    parse_lines(source).filter_map(Result::err).collect()
+}
+
+/// Parses NDJSON (newline-delimited JSON) data across threads with rayon, returning one result per non-blank line,
+/// in input order. Records are parsed as by [`parse_lines`].
+///
+/// # Arguments
+/// * `source` - Text content to be parsed
+///
+/// # Example
+///
+/// ```rust
+/// let ndjson = "{\"id\": 1}\n{\"id\": 2}\n";
+///
+/// let records = jsonic::ndjson::parse_lines_parallel(ndjson);
+/// assert_eq!(records[1].as_ref().unwrap()["id"].as_i128(), Some(2));
+/// ```
+#[cfg(feature = "rayon")]
+pub fn parse_lines_parallel(source: &str) -> Vec<Result<JsonItem, LineError>> {
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    for (index, text) in source.split('\n').enumerate() {
+        if !text.bytes().all(|b| b.is_ascii_whitespace()) {
+            lines.push((index + 1, offset, text));
+        }
+        offset += text.len() + 1;
+    }
+    lines.into_par_iter()
+        .map(|(line, offset, text)| Record(parse_complete(text, &ParseOptions::JSON).map_err(|error| LineError { line, offset, error })))
+        .collect::<Vec<_>>()
+        .into_iter()
+        .map(|record| record.0)
+        .collect()
+}
+
+// Result of a record parsed by a rayon worker, moved back to the calling thread
+#[cfg(feature = "rayon")]
+struct Record(Result<JsonItem, LineError>);
+
+// SAFETY: items only point into the `source` given to `parse_lines_parallel`, which outlives the workers since
+// the call only returns once they are done, and each item is moved to the calling thread rather than shared.
+#[cfg(feature = "rayon")]
+unsafe impl Send for Record {}
+
+/// NDJSON (newline-delimited JSON) writer, serializing each record as compact JSON on its own line
+///
+/// # Example
+///
+/// ```rust
+/// use jsonic::ndjson::JsonLinesWriter;
+///
+/// let parsed = jsonic::parse("[{\"id\": 1}, {\n  \"id\": 2\n}]").unwrap();
+///
+/// let mut writer = JsonLinesWriter::new(Vec::new());
+/// writer.write_all(parsed.elements().unwrap()).unwrap();
+/// assert_eq!(writer.into_inner(), b"{\"id\":1}\n{\"id\":2}\n");
+/// ```
+pub struct JsonLinesWriter<W: Write> {
+    writer: W,
+    buffer: String,
+}
+
+impl<W: Write> JsonLinesWriter<W> {
+    /// Creates a writer on top of `writer`
+    pub fn new(writer: W) -> Self {
+        JsonLinesWriter { writer, buffer: String::new() }
+    }
+
+    /// Writes `item` as one line, then flushes the underlying writer
+    pub fn write(&mut self, item: &JsonItem) -> io::Result<()> {
+        self.buffer.clear();
+        write_compact(item, &mut self.buffer);
+        self.buffer.push('\n');
+        self.writer.write_all(self.buffer.as_bytes())?;
+        self.writer.flush()
+    }
+
+    /// Writes every item of `items` as one line, flushing after each
+    pub fn write_all<I>(&mut self, items: I) -> io::Result<()>
+    where
+        I: IntoIterator,
+        I::Item: Borrow<JsonItem>,
+    {
+        for item in items {
+            self.write(item.borrow())?;
+        }
+        Ok(())
+    }
+
+    /// Returns the underlying writer
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Writes `items` as NDJSON (newline-delimited JSON) to `writer`, flushing after each record.
+/// See [`JsonLinesWriter`].
+///
+/// # Arguments
+/// * `writer` - Destination of the records
+/// * `items` - Records to be written
+pub fn write_lines<W, I>(writer: W, items: I) -> io::Result<()>
+where
+    W: Write,
+    I: IntoIterator,
+    I::Item: Borrow<JsonItem>,
+{
+    JsonLinesWriter::new(writer).write_all(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ndjson::{parse_lines, validate_lines, write_lines, JsonLinesWriter};
+
+    #[test]
+    fn parse_records() {
+        let records = parse_lines("{\"a\":1}\r\n\n  [2, 3]  \n{\"a\":4}").collect::<Vec<_>>();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].as_ref().unwrap()["a"].as_i128(), Some(1));
+        assert_eq!(records[1].as_ref().unwrap()[1].as_i128(), Some(3));
+        assert_eq!(records[2].as_ref().unwrap()["a"].as_i128(), Some(4));
+    }
+
+    #[test]
+    fn validate_records() {
+        let errors = validate_lines("{\"a\":1}\n{\"a\":1} {}\n\n{\"a\":}\n[1]\n");
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].get_line(), 2);
+        assert_eq!(errors[0].get_offset(), 8);
+        assert_eq!(errors[0].get_error().get_index(), 8);
+        assert_eq!(errors[1].get_line(), 4);
+        assert_eq!(errors[1].get_offset(), 20);
+        assert!(validate_lines("").is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn parse_records_in_parallel() {
+        let source = (0..1000).map(|i| if i % 100 == 7 { "{\"bad\"}\n\n".to_owned() } else { format!("{{\"i\": {}}}\r\n", i) }).collect::<String>();
+        let parallel = crate::ndjson::parse_lines_parallel(&source);
+        let sequential = parse_lines(&source).collect::<Vec<_>>();
+        assert_eq!(parallel.len(), sequential.len());
+        for (p, s) in parallel.iter().zip(&sequential) {
+            match (p, s) {
+                (Ok(p), Ok(s)) => { assert_eq!(p["i"].as_i128(), s["i"].as_i128()); }
+                (Err(p), Err(s)) => { assert_eq!((p.get_line(), p.get_offset()), (s.get_line(), s.get_offset())); }
+                _ => { panic!("results differ"); }
+            }
+        }
+        assert_eq!(parallel.iter().filter(|r| r.is_err()).count(), 10);
+    }
+
+    #[test]
+    fn write_records() {
+        let source = "{\"a\": [1,  2]}\n [\"x\\ny\"]\n";
+        let records = parse_lines(source).collect::<Result<Vec<_>, _>>().unwrap();
+        let mut output = Vec::new();
+        write_lines(&mut output, &records).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "{\"a\":[1,2]}\n[\"x\\ny\"]\n");
+
+        let mut writer = JsonLinesWriter::new(Vec::new());
+        writer.write_all(records).unwrap();
+        let written = writer.into_inner();
+        assert_eq!(parse_lines(std::str::from_utf8(&written).unwrap()).filter(Result::is_ok).count(), 2);
+    }
+}
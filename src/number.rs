@@ -0,0 +1,440 @@
+#[cfg(feature = "extended")]
+use crate::options::ParseOptions;
+
+/// A JSON number converted to its native representation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JsonNumberValue {
+    /// Number without fraction or exponent that fits in an `i128`
+    Integer(i128),
+    /// Any other number
+    Float(f64),
+}
+
+/// How floats are compared by [`JsonItem::equals`](crate::json_item::JsonItem::equals) and [`delta_with`](crate::delta::delta_with).
+/// Numbers that are both integers are always compared exactly, other numbers are compared as `f64`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum FloatEquality {
+    /// Floats are equal if their bit patterns are, so `0.0 != -0.0` and a NaN equals itself
+    #[default]
+    Bitwise,
+    /// Floats are equal if they differ by at most the given absolute tolerance
+    Epsilon(f64),
+    /// Floats are equal if at most the given number of representable `f64` values lie between them
+    Ulps(u64),
+}
+
+impl FloatEquality {
+    /// Compares two floats according to this semantic
+    ///
+    /// ```rust
+    /// use jsonic::number::FloatEquality;
+    ///
+    /// assert!(!FloatEquality::Bitwise.eq_f64(0.1 + 0.2, 0.3));
+    /// assert!(FloatEquality::Epsilon(1e-9).eq_f64(0.1 + 0.2, 0.3));
+    /// assert!(FloatEquality::Ulps(1).eq_f64(0.1 + 0.2, 0.3));
+    /// ```
+    pub fn eq_f64(&self, a: f64, b: f64) -> bool {
+        match *self {
+            FloatEquality::Bitwise => { a.to_bits() == b.to_bits() }
+            FloatEquality::Epsilon(epsilon) => { a == b || (a - b).abs() <= epsilon }
+            FloatEquality::Ulps(ulps) => {
+                if a == b {
+                    true
+                } else if a.is_nan() || b.is_nan() || a.is_sign_negative() != b.is_sign_negative() {
+                    false
+                } else {
+                    // Same sign: bit patterns are ordered like the values they encode
+                    a.to_bits().abs_diff(b.to_bits()) <= ulps
+                }
+            }
+        }
+    }
+
+    /// Compares two numbers according to this semantic
+    pub fn eq_number(&self, a: JsonNumberValue, b: JsonNumberValue) -> bool {
+        match (a, b) {
+            (JsonNumberValue::Integer(a), JsonNumberValue::Integer(b)) => { a == b }
+            _ => { self.eq_f64(a.to_f64(), b.to_f64()) }
+        }
+    }
+}
+
+macro_rules! from_primitive {
+    ($variant:ident, $($primitive:ty),*) => {
+        $(
+            impl From<$primitive> for JsonNumberValue {
+                fn from(value: $primitive) -> Self {
+                    JsonNumberValue::$variant(value.into())
+                }
+            }
+        )*
+    };
+}
+
+from_primitive!(Integer, i8, i16, i32, i64, i128, u8, u16, u32, u64);
+from_primitive!(Float, f32, f64);
+
+impl From<isize> for JsonNumberValue {
+    fn from(value: isize) -> Self {
+        JsonNumberValue::Integer(value as i128)
+    }
+}
+
+impl From<usize> for JsonNumberValue {
+    fn from(value: usize) -> Self {
+        JsonNumberValue::Integer(value as i128)
+    }
+}
+
+impl JsonNumberValue {
+    /// Converts the number to `f64`, possibly losing precision
+    pub fn to_f64(self) -> f64 {
+        match self {
+            JsonNumberValue::Integer(integer) => { integer as f64 }
+            JsonNumberValue::Float(float) => { float }
+        }
+    }
+}
+
+/// Parses number text with the same semantics as [`JsonItem::as_i128`](crate::json_item::JsonItem::as_i128)
+/// and [`JsonItem::as_f64`](crate::json_item::JsonItem::as_f64): integers are preferred, floats are used otherwise.
+/// Only numbers of standard JSON are accepted, see [`parse_number_with`] for extended syntaxes.
+/// Returns `None` if the text is not a JSON number, e.g. `inf`, `NaN` or `01`.
+///
+/// ```rust
+/// use jsonic::number::{parse_json_number, JsonNumberValue};
+///
+/// assert_eq!(parse_json_number("-234"), Some(JsonNumberValue::Integer(-234)));
+/// assert_eq!(parse_json_number("4.5e-2"), Some(JsonNumberValue::Float(0.045)));
+/// assert_eq!(parse_json_number("abc"), None);
+/// assert_eq!(parse_json_number("inf"), None);
+/// ```
+pub fn parse_json_number(text: &str) -> Option<JsonNumberValue> {
+    if !is_json_number(text) {
+        return None;
+    }
+    parse_number_text(text)
+}
+
+/// Same as [`parse_json_number`], also accepting the number syntaxes enabled in `options`: a leading `+`, leading
+/// or trailing decimal points, `Infinity` and `NaN` with [`extended_numbers`](ParseOptions::extended_numbers),
+/// and `0x1F` style integers with [`hex_numbers`](ParseOptions::hex_numbers), as in JSON5.
+/// Other spellings, such as Rust's `inf` or `nan`, are always rejected.
+///
+/// ```rust
+/// use jsonic::number::{parse_number_with, JsonNumberValue};
+/// use jsonic::options::ParseOptions;
+///
+/// assert_eq!(parse_number_with("-0x10", &ParseOptions::json5()), Some(JsonNumberValue::Integer(-16)));
+/// assert!(parse_number_with("-Infinity", &ParseOptions::json5()).is_some_and(|number| number.to_f64() == f64::NEG_INFINITY));
+/// assert_eq!(parse_number_with("Infinity", &ParseOptions::new()), None);
+/// assert_eq!(parse_number_with("infinity", &ParseOptions::json5()), None);
+/// ```
+#[cfg(feature = "extended")]
+pub fn parse_number_with(text: &str, options: &ParseOptions) -> Option<JsonNumberValue> {
+    if !is_json_number(text) && !is_extended_number(text, options) {
+        return None;
+    }
+    parse_number_text(text)
+}
+
+// Checks number text against the extended number syntaxes enabled in `options`
+#[cfg(feature = "extended")]
+fn is_extended_number(text: &str, options: &ParseOptions) -> bool {
+    let (plus, unsigned) = match text.as_bytes().first() {
+        Some(b'+') => { (true, &text[1..]) }
+        Some(b'-') => { (false, &text[1..]) }
+        _ => { (false, text) }
+    };
+    let extended = options.syntax.extended_numbers();
+    if plus && !extended {
+        return false;
+    }
+    let digits = |text: &str| text.bytes().all(|b| b.is_ascii_digit());
+    if let Some(hex) = unsigned.strip_prefix("0x").or_else(|| unsigned.strip_prefix("0X")) {
+        return options.syntax.hex_numbers() && !hex.is_empty() && hex.bytes().all(|b| b.is_ascii_hexdigit());
+    }
+    if !extended {
+        return false;
+    }
+    if matches!(unsigned, "Infinity" | "NaN") {
+        return true;
+    }
+    let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+        Some(index) => { (&unsigned[..index], Some(&unsigned[index + 1..])) }
+        None => { (unsigned, None) }
+    };
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    digits(integer) && digits(fraction) && !(integer.is_empty() && fraction.is_empty())
+        && exponent.is_none_or(|exponent| {
+            let exponent = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+            !exponent.is_empty() && digits(exponent)
+        })
+}
+
+// Converts number text checked by the parser, whatever its syntax, preferring integers
+pub(crate) fn parse_number_text(text: &str) -> Option<JsonNumberValue> {
+    if let Some(integer) = parse_integer(text) {
+        return Some(JsonNumberValue::Integer(integer));
+    }
+    text.parse::<f64>().ok().map(JsonNumberValue::Float)
+}
+
+// Checks number text against the strict JSON grammar: `-?(0|[1-9][0-9]*)(\.[0-9]+)?([eE][+-]?[0-9]+)?`
+pub(crate) fn is_json_number(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    let mut index = usize::from(bytes.first() == Some(&b'-'));
+    let digits = |index: &mut usize| {
+        let start = *index;
+        while *index < bytes.len() && bytes[*index].is_ascii_digit() {
+            *index += 1;
+        }
+        *index - start
+    };
+    let integer = index;
+    match digits(&mut index) {
+        0 => { return false; }
+        len if len > 1 && bytes[integer] == b'0' => { return false; }
+        _ => {}
+    }
+    if bytes.get(index) == Some(&b'.') {
+        index += 1;
+        if digits(&mut index) == 0 {
+            return false;
+        }
+    }
+    if matches!(bytes.get(index), Some(b'e' | b'E')) {
+        index += 1;
+        if matches!(bytes.get(index), Some(b'+' | b'-')) {
+            index += 1;
+        }
+        if digits(&mut index) == 0 {
+            return false;
+        }
+    }
+    index == bytes.len()
+}
+
+// Decimal or `0x` prefixed hexadecimal integer, with optional sign
+pub(crate) fn parse_integer(text: &str) -> Option<i128> {
+    if let Ok(integer) = text.parse::<i128>() {
+        return Some(integer);
+    }
+    let (negative, unsigned) = match text.as_bytes().first() {
+        Some(b'-') => { (true, &text[1..]) }
+        Some(b'+') => { (false, &text[1..]) }
+        _ => { (false, text) }
+    };
+    let digits = unsigned.strip_prefix("0x").or_else(|| unsigned.strip_prefix("0X"))?;
+    if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let magnitude = u128::from_str_radix(digits, 16).ok()?;
+    if negative {
+        0_i128.checked_sub_unsigned(magnitude)
+    } else {
+        i128::try_from(magnitude).ok()
+    }
+}
+
+/// Formats a `f64` with the shortest digits that round-trip to the same value, laid out like
+/// ECMAScript's `Number.prototype.toString` (plain notation for exponents in `[-7, 21)`, `1e+21` style otherwise).
+/// As in `JSON.stringify`, non-finite values are formatted as `null` and `-0` as `0`.
+///
+/// ```rust
+/// use jsonic::number::format_f64_shortest;
+///
+/// assert_eq!(format_f64_shortest(0.1), "0.1");
+/// assert_eq!(format_f64_shortest(100.0), "100");
+/// assert_eq!(format_f64_shortest(1e21), "1e+21");
+/// assert_eq!(format_f64_shortest(f64::NAN), "null");
+/// ```
+pub fn format_f64_shortest(value: f64) -> String {
+    let mut output = String::new();
+    write_f64_shortest(value, &mut output);
+    output
+}
+
+// Shortest digits that round-trip to the finite, non-zero `value`, without sign, leading or trailing zeros,
+// and the exponent of the first digit, e.g. `("12345", 3)` for `-1234.5`
+#[cfg(not(feature = "fast-numbers"))]
+fn shortest_digits(value: f64) -> (String, i32) {
+    // `{:e}` yields the shortest round-trip digits, e.g. `-1.2345e3`
+    let scientific = format!("{:e}", value.abs());
+    let (mantissa, exponent) = scientific.split_once('e').unwrap();
+    (mantissa.replace('.', ""), exponent.parse().unwrap())
+}
+
+#[cfg(feature = "fast-numbers")]
+fn shortest_digits(value: f64) -> (String, i32) {
+    // Ryū yields the shortest round-trip digits in plain or scientific notation, e.g. `0.001`, `1234.5` or `1.2345e-20`
+    let mut buffer = ryu::Buffer::new();
+    let text = buffer.format_finite(value.abs());
+    let (mantissa, exponent) = match text.split_once('e') {
+        Some((mantissa, exponent)) => { (mantissa, exponent.parse::<i32>().unwrap()) }
+        None => { (text, 0) }
+    };
+    let point = mantissa.find('.').unwrap_or(mantissa.len()) as i32;
+    let digits = mantissa.replace('.', "");
+    let leading = digits.len() - digits.trim_start_matches('0').len();
+    let digits = digits.trim_matches('0');
+    (digits.to_owned(), exponent + point - 1 - leading as i32)
+}
+
+// Appends `value` to `output` as [`format_f64_shortest`] formats it
+pub(crate) fn write_f64_shortest(value: f64, output: &mut String) {
+    if !value.is_finite() {
+        output.push_str("null");
+        return;
+    }
+    if value == 0.0 {
+        output.push('0');
+        return;
+    }
+
+    let (digits, exponent) = shortest_digits(value);
+    let k = digits.len() as i32;
+    let n = exponent + 1;
+
+    if value < 0.0 {
+        output.push('-');
+    }
+    if k <= n && n <= 21 {
+        output.push_str(&digits);
+        output.extend(std::iter::repeat_n('0', (n - k) as usize));
+    } else if 0 < n && n <= 21 {
+        output.push_str(&digits[..n as usize]);
+        output.push('.');
+        output.push_str(&digits[n as usize..]);
+    } else if -6 < n && n <= 0 {
+        output.push_str("0.");
+        output.extend(std::iter::repeat_n('0', (-n) as usize));
+        output.push_str(&digits);
+    } else {
+        output.push_str(&digits[..1]);
+        if k > 1 {
+            output.push('.');
+            output.push_str(&digits[1..]);
+        }
+        output.push('e');
+        output.push(if n - 1 < 0 { '-' } else { '+' });
+        write_integer((n - 1).abs() as i128, output);
+    }
+}
+
+// Appends the decimal digits of `value` to `output`
+pub(crate) fn write_integer(value: i128, output: &mut String) {
+    #[cfg(feature = "fast-numbers")]
+    output.push_str(itoa::Buffer::new().format(value));
+    #[cfg(not(feature = "fast-numbers"))]
+    output.push_str(&value.to_string());
+}
+
+// Appends a number to `output`, integers as is and floats as [`format_f64_shortest`] formats them
+#[cfg(any(feature = "test-util", feature = "serialize"))]
+pub(crate) fn write_number(value: JsonNumberValue, output: &mut String) {
+    match value {
+        JsonNumberValue::Integer(integer) => { write_integer(integer, output) }
+        JsonNumberValue::Float(float) => { write_f64_shortest(float, output) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::number::{format_f64_shortest, is_json_number, parse_json_number, shortest_digits, write_integer, FloatEquality, JsonNumberValue};
+
+    #[test]
+    fn parse_numbers() {
+        assert_eq!(parse_json_number("0"), Some(JsonNumberValue::Integer(0)));
+        assert_eq!(parse_json_number("-4.2344"), Some(JsonNumberValue::Float(-4.2344)));
+        assert_eq!(parse_json_number("3.7391238e+24"), Some(JsonNumberValue::Float(3.7391238e24)));
+        assert_eq!(parse_json_number(""), None);
+        for rejected in ["-0x10", "inf", "-infinity", "NaN", "nan", "Infinity", "+1", ".5", "01"] {
+            assert_eq!(parse_json_number(rejected), None, "{}", rejected);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "extended")]
+    fn parse_extended_numbers() {
+        use crate::number::parse_number_with;
+        use crate::options::ParseOptions;
+
+        let json5 = ParseOptions::json5();
+        assert_eq!(parse_number_with("-0x10", &json5), Some(JsonNumberValue::Integer(-16)));
+        assert_eq!(parse_number_with("+1", &json5), Some(JsonNumberValue::Integer(1)));
+        assert_eq!(parse_number_with(".5e1", &json5), Some(JsonNumberValue::Float(5.0)));
+        assert_eq!(parse_number_with("5.", &json5), Some(JsonNumberValue::Float(5.0)));
+        assert_eq!(parse_number_with("-Infinity", &json5), Some(JsonNumberValue::Float(f64::NEG_INFINITY)));
+        assert!(parse_number_with("NaN", &json5).is_some_and(|number| number.to_f64().is_nan()));
+        for rejected in ["0x", "0x-1", ".", "1e", "inf", "infinity", "nan", "+NaN.", "1.5.2"] {
+            assert_eq!(parse_number_with(rejected, &json5), None, "{}", rejected);
+        }
+        assert_eq!(parse_number_with("0x1F", &ParseOptions::new().hex_numbers(true)), Some(JsonNumberValue::Integer(31)));
+        for rejected in ["0x1F", "+1", ".5", "Infinity"] {
+            assert_eq!(parse_number_with(rejected, &ParseOptions::new()), None, "{}", rejected);
+        }
+    }
+
+    #[test]
+    fn json_numbers() {
+        for valid in ["0", "-0", "12", "1.5", "-1.5e10", "2E-3", "0.0e+0"] {
+            assert!(is_json_number(valid), "{}", valid);
+        }
+        for invalid in ["", "-", "01", ".5", "5.", "+1", "1e", "1-2", "0x1F", "NaN", "1.5.2"] {
+            assert!(!is_json_number(invalid), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn format_shortest() {
+        let cases = [
+            (1.0, "1"), (-1.5, "-1.5"), (123456789.0, "123456789"), (0.000001, "0.000001"),
+            (0.0000001, "1e-7"), (1.2345e-20, "1.2345e-20"), (1e20, "100000000000000000000"),
+            (-3.3e-21, "-3.3e-21"), (-4.5e-213, "-4.5e-213"), (5e-324, "5e-324"),
+            (f64::MAX, "1.7976931348623157e+308"), (-0.0, "0"), (f64::INFINITY, "null"),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(format_f64_shortest(value), expected);
+        }
+    }
+
+    #[test]
+    fn format_round_trip() {
+        for value in [0.1 + 0.2, 1.0 / 3.0, 2.5e-8, 9007199254740993.0, 4.2344] {
+            assert_eq!(format_f64_shortest(value).parse::<f64>().unwrap(), value);
+        }
+        // Digits are as few as those of the standard library, which are the shortest, whichever implementation is compiled
+        for index in 0..100_000_u64 {
+            let value = f64::from_bits(index.wrapping_mul(0x9E3779B97F4A7C15));
+            if value.is_finite() && value != 0.0 {
+                let reference = format!("{:e}", value.abs());
+                let (mantissa, exponent) = reference.split_once('e').unwrap();
+                let (digits, digits_exponent) = shortest_digits(value);
+                assert_eq!((digits.len(), digits_exponent), (mantissa.replace('.', "").len(), exponent.parse().unwrap()), "{:e}", value);
+                assert_eq!(format_f64_shortest(value).parse::<f64>().unwrap(), value);
+            }
+        }
+        let mut output = String::new();
+        write_integer(i128::MIN, &mut output);
+        assert_eq!(output, i128::MIN.to_string());
+    }
+
+    #[test]
+    fn float_equality() {
+        let sum = 0.1 + 0.2;
+        assert!(FloatEquality::Bitwise.eq_f64(f64::NAN, f64::NAN));
+        assert!(!FloatEquality::Bitwise.eq_f64(0.0, -0.0));
+        assert!(!FloatEquality::Bitwise.eq_f64(sum, 0.3));
+        assert!(FloatEquality::Epsilon(1e-12).eq_f64(sum, 0.3));
+        assert!(!FloatEquality::Epsilon(1e-20).eq_f64(sum, 0.3));
+        assert!(FloatEquality::Epsilon(0.0).eq_f64(f64::INFINITY, f64::INFINITY));
+        assert!(FloatEquality::Ulps(0).eq_f64(0.0, -0.0));
+        assert!(FloatEquality::Ulps(1).eq_f64(sum, 0.3));
+        assert!(!FloatEquality::Ulps(1).eq_f64(1.0, 1.0 + 4.0 * f64::EPSILON));
+        assert!(!FloatEquality::Ulps(u64::MAX).eq_f64(f64::MIN_POSITIVE, -f64::MIN_POSITIVE));
+        assert!(!FloatEquality::Ulps(u64::MAX).eq_f64(f64::NAN, f64::NAN));
+        assert!(FloatEquality::Bitwise.eq_number(JsonNumberValue::Integer(3), JsonNumberValue::Float(3.0)));
+        assert!(!FloatEquality::Epsilon(1.0).eq_number(JsonNumberValue::Integer(3), JsonNumberValue::Integer(4)));
+    }
+}
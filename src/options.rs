@@ -0,0 +1,456 @@
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+use std::sync::Arc;
+
+use crate::document::JsonDocument;
+use crate::json_error::JsonError;
+use crate::json_item::JsonItem;
+use crate::parse_root;
+use crate::plugin::{Recognizer, Recognizers};
+use crate::syntax::Syntax;
+
+const DEFAULT_MAX_DEPTH: usize = 128;
+
+// Caps on the amount of data a single document may hold, unlimited by default
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Limits {
+    pub(crate) input_size: usize,
+    pub(crate) nodes: usize,
+    pub(crate) keys: usize,
+    pub(crate) string_len: usize,
+}
+
+impl Limits {
+    const NONE: Limits = Limits { input_size: usize::MAX, nodes: usize::MAX, keys: usize::MAX, string_len: usize::MAX };
+}
+
+/// How objects holding the same key more than once are handled. Keys are compared as written in the source,
+/// escape sequences included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicateKeys {
+    /// Parsing fails with an [`ErrorKind::DuplicateKey`](crate::json_error::ErrorKind::DuplicateKey) error
+    /// giving the spans of both keys
+    Error,
+    /// The first value is kept
+    FirstWins,
+    /// The last value is kept, at the position of the first key, like `JSON.parse` does
+    #[default]
+    LastWins,
+    /// All values are collected, in order, into an array at the position of the first key.
+    /// The text of this array spans source text from the first to the last value.
+    Collect,
+}
+
+/// Versioned option sets, see [`ParseOptions::preset`]. The behavior of a preset is pinned: it never changes,
+/// even when the defaults and the unversioned presets such as [`ParseOptions::strict`] evolve, so that services can
+/// pin their parsing semantics. Changes are published as new versions of a preset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Preset {
+    /// Strict RFC 8259 JSON: a single value of any type, with no extension, canonical numbers and standard escapes only.
+    /// Nesting is limited to a depth of 128, duplicate keys keep the last value, and no other limit applies.
+    V1Strict,
+    /// JSON5 plus Python's `True`, `False` and `None`, with a single root object or array.
+    /// Nesting is limited to a depth of 128, duplicate keys keep the last value, and no other limit applies.
+    #[cfg(feature = "extended")]
+    V1Lenient,
+}
+
+// Callback reporting the number of bytes consumed, every `interval` bytes
+#[derive(Clone)]
+pub(crate) struct ProgressHook {
+    pub(crate) interval: usize,
+    pub(crate) callback: Arc<dyn Fn(usize) + Send + Sync>,
+}
+
+impl Debug for ProgressHook {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "progress every {} bytes", self.interval)
+    }
+}
+
+/// Parsing options, built by chaining setters from standard JSON ([`ParseOptions::new`]) or from a preset.
+/// Presets and grammar setters require the `extended` feature.
+///
+/// # Example
+///
+#[cfg_attr(feature = "extended", doc = "```rust")]
+#[cfg_attr(not(feature = "extended"), doc = "```rust,ignore")]
+/// use jsonic::options::{ParseOptions, Parser};
+///
+/// let parser = Parser::new(ParseOptions::new().comments(true).hex_numbers(true));
+///
+/// let parsed = parser.parse("[0x10 /* mask */]").unwrap();
+/// assert_eq!(parsed[0].as_i128(), Some(16));
+/// assert!(parser.parse("[0x10,]").is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct ParseOptions {
+    pub(crate) syntax: Syntax,
+    pub(crate) recognizers: Recognizers,
+    pub(crate) max_depth: usize,
+    pub(crate) materialize_depth: usize,
+    pub(crate) limits: Limits,
+    pub(crate) progress: Option<ProgressHook>,
+    pub(crate) duplicate_keys: DuplicateKeys,
+    pub(crate) strict: bool,
+    pub(crate) preserve_layout: bool,
+}
+
+impl ParseOptions {
+    pub(crate) const JSON: ParseOptions = ParseOptions { syntax: Syntax::JSON, recognizers: Recognizers::NONE, max_depth: DEFAULT_MAX_DEPTH, materialize_depth: usize::MAX, limits: Limits::NONE, progress: None, duplicate_keys: DuplicateKeys::LastWins, strict: false, preserve_layout: false };
+    pub(crate) const STRICT: ParseOptions = ParseOptions { syntax: Syntax::JSON, recognizers: Recognizers::NONE, max_depth: DEFAULT_MAX_DEPTH, materialize_depth: usize::MAX, limits: Limits::NONE, progress: None, duplicate_keys: DuplicateKeys::LastWins, strict: true, preserve_layout: false };
+    #[cfg(feature = "extended")]
+    pub(crate) const JSONC: ParseOptions = ParseOptions { syntax: Syntax::JSONC, recognizers: Recognizers::NONE, max_depth: DEFAULT_MAX_DEPTH, materialize_depth: usize::MAX, limits: Limits::NONE, progress: None, duplicate_keys: DuplicateKeys::LastWins, strict: false, preserve_layout: false };
+    #[cfg(feature = "extended")]
+    pub(crate) const JSON5: ParseOptions = ParseOptions { syntax: Syntax::JSON5, recognizers: Recognizers::NONE, max_depth: DEFAULT_MAX_DEPTH, materialize_depth: usize::MAX, limits: Limits::NONE, progress: None, duplicate_keys: DuplicateKeys::LastWins, strict: false, preserve_layout: false };
+    #[cfg(feature = "extended")]
+    pub(crate) const LENIENT: ParseOptions = ParseOptions { syntax: Syntax::LENIENT, recognizers: Recognizers::NONE, max_depth: DEFAULT_MAX_DEPTH, materialize_depth: usize::MAX, limits: Limits::NONE, progress: None, duplicate_keys: DuplicateKeys::LastWins, strict: false, preserve_layout: false };
+
+    /// Options for standard JSON, as used by [`parse`](crate::parse)
+    pub fn new() -> Self {
+        Self::JSON
+    }
+
+    /// Options for strict RFC 8259 JSON, as used by [`parse_strict`](crate::parse_strict)
+    pub fn strict() -> Self {
+        Self::STRICT
+    }
+
+    /// Options for JSON with comments and trailing commas, as used by [`parse_jsonc`](crate::parse_jsonc)
+    #[cfg(feature = "extended")]
+    pub fn jsonc() -> Self {
+        Self::JSONC
+    }
+
+    /// Options for JSON5, as used by [`parse_json5`](crate::parse_json5)
+    #[cfg(feature = "extended")]
+    pub fn json5() -> Self {
+        Self::JSON5
+    }
+
+    /// Options for JSON5 plus Python literals, as used by [`parse_lenient`](crate::parse_lenient)
+    #[cfg(feature = "extended")]
+    pub fn lenient() -> Self {
+        Self::LENIENT
+    }
+
+    /// Options of the versioned `preset`, which can be further customized with setters
+    ///
+    /// ```rust
+    /// use jsonic::options::{ParseOptions, Parser, Preset};
+    ///
+    /// let parser = Parser::new(ParseOptions::preset(Preset::V1Strict).max_input_size(1 << 20));
+    /// assert!(parser.parse("[1.0e3]").is_ok());
+    /// assert!(parser.parse("[1,]").is_err());
+    /// ```
+    pub fn preset(preset: Preset) -> Self {
+        // Settings are spelled out rather than taken from the unversioned presets, which may change
+        match preset {
+            Preset::V1Strict => {
+                ParseOptions {
+                    syntax: Syntax::JSON,
+                    recognizers: Recognizers::NONE,
+                    max_depth: 128,
+                    materialize_depth: usize::MAX,
+                    limits: Limits::NONE,
+                    progress: None,
+                    duplicate_keys: DuplicateKeys::LastWins,
+                    strict: true,
+                    preserve_layout: false,
+                }
+            }
+            #[cfg(feature = "extended")]
+            Preset::V1Lenient => {
+                ParseOptions {
+                    syntax: Syntax {
+                        comments: true,
+                        trailing_commas: true,
+                        single_quotes: true,
+                        unquoted_keys: true,
+                        extended_numbers: true,
+                        hex_numbers: true,
+                        python_literals: true,
+                        extended_whitespace: true,
+                    },
+                    recognizers: Recognizers::NONE,
+                    max_depth: 128,
+                    materialize_depth: usize::MAX,
+                    limits: Limits::NONE,
+                    progress: None,
+                    duplicate_keys: DuplicateKeys::LastWins,
+                    strict: false,
+                    preserve_layout: false,
+                }
+            }
+        }
+    }
+
+    /// Accepts `// line` and `/* block */` comments wherever whitespace is allowed
+    #[cfg(feature = "extended")]
+    pub fn comments(mut self, enabled: bool) -> Self {
+        self.syntax.comments = enabled;
+        self
+    }
+
+    /// Accepts a single `,` before the closing `]` or `}` of non-empty containers
+    #[cfg(feature = "extended")]
+    pub fn trailing_commas(mut self, enabled: bool) -> Self {
+        self.syntax.trailing_commas = enabled;
+        self
+    }
+
+    /// Accepts strings and keys delimited by `'`
+    #[cfg(feature = "extended")]
+    pub fn single_quotes(mut self, enabled: bool) -> Self {
+        self.syntax.single_quotes = enabled;
+        self
+    }
+
+    /// Accepts object keys written as bare identifiers, e.g. `{key: 1}`
+    #[cfg(feature = "extended")]
+    pub fn unquoted_keys(mut self, enabled: bool) -> Self {
+        self.syntax.unquoted_keys = enabled;
+        self
+    }
+
+    /// Accepts numbers with a leading or trailing decimal point, `Infinity` and `NaN`
+    #[cfg(feature = "extended")]
+    pub fn extended_numbers(mut self, enabled: bool) -> Self {
+        self.syntax.extended_numbers = enabled;
+        self
+    }
+
+    /// Accepts `0x1F` style hexadecimal integers
+    #[cfg(feature = "extended")]
+    pub fn hex_numbers(mut self, enabled: bool) -> Self {
+        self.syntax.hex_numbers = enabled;
+        self
+    }
+
+    /// Accepts Python's `True`, `False` and `None` as aliases of `true`, `false` and `null`
+    #[cfg(feature = "extended")]
+    pub fn python_literals(mut self, enabled: bool) -> Self {
+        self.syntax.python_literals = enabled;
+        self
+    }
+
+    /// Accepts vertical tab, form feed, NBSP, BOM and Unicode line/paragraph separators as whitespace
+    #[cfg(feature = "extended")]
+    pub fn extended_whitespace(mut self, enabled: bool) -> Self {
+        self.syntax.extended_whitespace = enabled;
+        self
+    }
+
+    /// Sets the maximum nesting depth of objects and arrays, the root container being at depth 1 (128 by default).
+    /// Deeper data fails with an [`ErrorKind::DepthLimitExceeded`](crate::json_error::ErrorKind::DepthLimitExceeded) error.
+    /// The parser does not recurse, so the limit only bounds memory use: `usize::MAX` accepts any depth.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Sets the depth down to which objects and arrays are built, the root container being at depth 1 (unbounded by default).
+    /// Deeper containers are skipped over and kept as stubs holding their source text, see [`JsonItem::as_stub`].
+    /// Their children are unknown until they are expanded, so [`len`](JsonItem::len), [`elements`](JsonItem::elements),
+    /// [`entries`](JsonItem::entries), [`as_array`](JsonItem::as_array), [`as_object`](JsonItem::as_object) and the
+    /// iterators built on them return `None` for stubs, and indexing into a stub gives a non-existent item. Very deep or very large documents can so be parsed with little memory
+    /// and their parts expanded on demand. Skipped containers are checked as if they were built, syntax, duplicate keys
+    /// and limits included, so that parsing fails on the same documents and stubs expand without error.
+    pub fn materialize_depth(mut self, depth: usize) -> Self {
+        self.materialize_depth = depth.max(1);
+        self
+    }
+
+    /// Sets the maximum size in bytes of the source text.
+    /// Larger input fails with an [`ErrorKind::SizeLimitExceeded`](crate::json_error::ErrorKind::SizeLimitExceeded) error before being parsed.
+    pub fn max_input_size(mut self, bytes: usize) -> Self {
+        self.limits.input_size = bytes;
+        self
+    }
+
+    /// Sets the maximum number of values (objects, arrays and scalars, the root included) in a document.
+    /// Parsing fails with an [`ErrorKind::NodeLimitExceeded`](crate::json_error::ErrorKind::NodeLimitExceeded) error beyond it.
+    pub fn max_nodes(mut self, count: usize) -> Self {
+        self.limits.nodes = count;
+        self
+    }
+
+    /// Sets the maximum number of object members in a document, all objects combined.
+    /// Parsing fails with an [`ErrorKind::KeyLimitExceeded`](crate::json_error::ErrorKind::KeyLimitExceeded) error beyond it.
+    pub fn max_keys(mut self, count: usize) -> Self {
+        self.limits.keys = count;
+        self
+    }
+
+    /// Sets the maximum length in bytes of strings and keys, as written in the source (escape sequences included).
+    /// Longer ones fail with an [`ErrorKind::StringLengthLimitExceeded`](crate::json_error::ErrorKind::StringLengthLimitExceeded) error.
+    pub fn max_string_len(mut self, len: usize) -> Self {
+        self.limits.string_len = len;
+        self
+    }
+
+    /// Sets how objects holding the same key more than once are handled ([`DuplicateKeys::LastWins`] by default)
+    pub fn duplicate_keys(mut self, policy: DuplicateKeys) -> Self {
+        self.duplicate_keys = policy;
+        self
+    }
+
+    /// Records the text separating tokens (whitespace, comments, commas and colons) in documents parsed by
+    /// [`Parser::parse_document`], so that [`JsonDocument::to_json_preserving`] reproduces their source text byte for byte
+    /// and [`apply_delta_preserving`](crate::delta::apply_delta_preserving) keeps the layout of the parts a delta leaves
+    /// unchanged (disabled by default). Without it, both recover the layout from the spans of the items instead,
+    /// which keeps the text of members dropped as duplicate keys. Items parsed by [`Parser::parse`] record nothing.
+    #[cfg(feature = "serialize")]
+    pub fn preserve_layout(mut self, enabled: bool) -> Self {
+        self.preserve_layout = enabled;
+        self
+    }
+
+    /// Registers `callback`, called with the number of bytes consumed each time parsing moves `interval` bytes further,
+    /// and once more when the document is complete, so that tools can show progress on very large inputs.
+    /// Progress is checked between values, so a single long string is reported once parsed.
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use jsonic::options::{ParseOptions, Parser};
+    ///
+    /// let consumed = Arc::new(AtomicUsize::new(0));
+    /// let reported = consumed.clone();
+    /// let parser = Parser::new(ParseOptions::new().on_progress(1024, move |bytes| reported.store(bytes, Ordering::Relaxed)));
+    ///
+    /// let json = format!("[{}0]", "0, ".repeat(10_000));
+    /// parser.parse(&json).unwrap();
+    /// assert_eq!(consumed.load(Ordering::Relaxed), json.len());
+    /// ```
+    pub fn on_progress<F: Fn(usize) + Send + Sync + 'static>(mut self, interval: usize, callback: F) -> Self {
+        self.progress = Some(ProgressHook { interval: interval.max(1), callback: Arc::new(callback) });
+        self
+    }
+
+    /// Registers `recognizer`, called on every scalar to attach user-defined payloads, see [`Recognizer`]
+    pub fn recognizer<R: Recognizer + 'static>(mut self, recognizer: R) -> Self {
+        self.recognizers.push(Arc::new(recognizer));
+        self
+    }
+
+    /// Reads strings such as `"10s"` or `"5 MB"` as quantities of the units in `units`, see [`UnitTable`](crate::quantity::UnitTable)
+    #[cfg(feature = "extended")]
+    pub fn quantities(self, units: crate::quantity::UnitTable) -> Self {
+        self.recognizer(units)
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// JSON parser configured with [`ParseOptions`]
+#[derive(Debug, Clone, Default)]
+pub struct Parser {
+    options: ParseOptions,
+}
+
+impl Parser {
+    /// Creates a parser using `options`
+    pub fn new(options: ParseOptions) -> Self {
+        Parser { options }
+    }
+
+    /// Options used by this parser
+    pub fn options(&self) -> &ParseOptions {
+        &self.options
+    }
+
+    /// Parses JSON data
+    ///
+    /// # Arguments
+    /// * `source` - Text content to be parsed
+    pub fn parse(&self, source: &str) -> Result<JsonItem, JsonError> {
+        parse_root(source, &self.options)
+    }
+
+    /// Parses JSON data into a document owning `source`
+    ///
+    /// # Arguments
+    /// * `source` - Text content to be parsed
+    pub fn parse_document(&self, source: String) -> Result<JsonDocument, JsonError> {
+        JsonDocument::parse(source, &self.options)
+    }
+}
+
+impl From<ParseOptions> for Parser {
+    fn from(options: ParseOptions) -> Self {
+        Parser::new(options)
+    }
+}
+
+#[cfg(all(test, feature = "extended"))]
+mod tests {
+    use crate::json_error::ErrorKind;
+    use crate::options::{ParseOptions, Parser};
+
+    #[test]
+    fn presets_match_free_functions() {
+        let json5 = "{a: 'b', c: [Infinity, 0x1,], /* d */}";
+        assert!(Parser::new(ParseOptions::json5()).parse(json5).is_ok());
+        assert!(Parser::new(ParseOptions::jsonc()).parse(json5).is_err());
+        assert!(Parser::new(ParseOptions::lenient()).parse("[None]").is_ok());
+        assert!(Parser::default().parse("[1,]").is_err());
+    }
+
+    #[test]
+    fn toggles() {
+        let parser = Parser::new(ParseOptions::json5().unquoted_keys(false).single_quotes(false));
+        assert!(parser.parse("{\"a\": [.5, NaN,]} // end").is_ok());
+        assert!(parser.parse("{a: 1}").is_err());
+        assert!(parser.parse("['a']").is_err());
+
+        let parser = Parser::new(ParseOptions::new().trailing_commas(true).python_literals(true));
+        let parsed = parser.parse("[True, False, None,]").unwrap();
+        assert_eq!(parsed[0].as_bool(), Some(true));
+        assert!(parser.parse("[1] ").is_ok());
+        assert!(parser.parse("[1, /* x */ 2]").is_err());
+    }
+
+    #[test]
+    fn limit_depth() {
+        let parser = Parser::new(ParseOptions::new().max_depth(3));
+        assert!(parser.parse("[{\"a\": [1]}, [[]]]").is_ok());
+        let error = parser.parse("[{\"a\": [[1]]}]").unwrap_err();
+        assert_eq!(error.get_kind(), ErrorKind::DepthLimitExceeded);
+        assert_eq!(error.get_index(), 8);
+        assert_eq!(parser.parse("[1,]").unwrap_err().get_kind(), ErrorKind::UnexpectedCharacter);
+    }
+
+    #[test]
+    fn limit_resources() {
+        let json = "{\"name\": \"jsonic\", \"tags\": [\"fast\", \"small\"], \"meta\": {\"v\": 1}}";
+        let limited = |options: ParseOptions| Parser::new(options).parse(json).map_err(|error| (error.get_kind(), error.get_index()));
+        assert!(limited(ParseOptions::new().max_input_size(json.len()).max_nodes(7).max_keys(4).max_string_len(6)).is_ok());
+        assert_eq!(limited(ParseOptions::new().max_input_size(json.len() - 1)), Err((ErrorKind::SizeLimitExceeded, json.len() - 1)));
+        assert_eq!(limited(ParseOptions::new().max_nodes(6)), Err((ErrorKind::NodeLimitExceeded, 60)));
+        assert_eq!(limited(ParseOptions::new().max_keys(3)), Err((ErrorKind::KeyLimitExceeded, 55)));
+        assert_eq!(limited(ParseOptions::new().max_string_len(5)), Err((ErrorKind::StringLengthLimitExceeded, 9)));
+        assert_eq!(limited(ParseOptions::new().max_nodes(0)), Err((ErrorKind::NodeLimitExceeded, 0)));
+    }
+
+    #[test]
+    fn report_progress() {
+        use std::sync::{Arc, Mutex};
+
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = reports.clone();
+        let parser = Parser::new(ParseOptions::new().on_progress(10, move |bytes| sink.lock().unwrap().push(bytes)));
+        parser.parse("{\"a\": [1, 2, 3], \"b\": \"some text\", \"c\": {}}").unwrap();
+        assert_eq!(*reports.lock().unwrap(), vec![11, 33, 43]);
+    }
+
+    #[test]
+    fn parse_owned_document() {
+        let document = Parser::new(ParseOptions::jsonc()).parse_document("{\"a\": 1, // one\n}".to_owned()).unwrap();
+        assert_eq!(document["a"].as_i128(), Some(1));
+    }
+}
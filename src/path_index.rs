@@ -0,0 +1,123 @@
+use crate::json_item::JsonItem;
+use crate::json_type::JsonType;
+
+// Item of the index, its children being keyed by escaped JSON Pointer reference token, in document order
+#[derive(Debug, Clone)]
+struct Node {
+    json_type: JsonType,
+    children: Vec<(String, Node)>,
+}
+
+impl Node {
+    fn new(item: &JsonItem) -> Node {
+        let mut children = Vec::new();
+        if let Some(elements) = item.elements() {
+            children.extend(elements.enumerate().map(|(index, element)| (index.to_string(), Node::new(element))));
+        } else if let Some(entries) = item.entries() {
+            children.extend(entries.map(|(key, value)| (key.as_str().replace('~', "~0").replace('/', "~1"), Node::new(value))));
+        }
+        Node { json_type: item.json_type, children }
+    }
+
+    fn child(&self, token: &str) -> Option<&Node> {
+        self.children.iter().find(|(t, _)| t == token).map(|(_, node)| node)
+    }
+
+    // Node designated by a JSON Pointer
+    fn find(&self, pointer: &str) -> Option<&Node> {
+        if pointer.is_empty() {
+            return Some(self);
+        }
+        pointer.strip_prefix('/')?.split('/').try_fold(self, |node, token| node.child(token))
+    }
+
+    fn collect(&self, path: &str, paths: &mut Vec<(String, JsonType)>) {
+        paths.push((path.to_owned(), self.json_type));
+        for (token, child) in &self.children {
+            child.collect(&format!("{}/{}", path, token), paths);
+        }
+    }
+}
+
+/// Paths of all the items of a document with their types, queried by prefix to complete paths typed by hand,
+/// e.g. in interactive explorers or shell completions, see [`JsonItem::path_index`].
+/// Paths are JSON Pointers (RFC 6901), the empty path designating the root.
+#[derive(Debug, Clone)]
+pub struct PathIndex {
+    root: Node,
+}
+
+impl PathIndex {
+    /// Returns the type of the item at `path`, or `None` if there is no such item
+    pub fn get_type(&self, path: &str) -> Option<JsonType> {
+        self.root.find(path).map(|node| node.json_type)
+    }
+
+    /// Returns the paths completing `prefix` by one reference token, with their types, in document order:
+    /// the children of the item designated by `prefix` up to its last `/`, whose reference token starts
+    /// with the rest of `prefix`. For example, `/us` may complete to `/user` and `/users`, and `/users/` to `/users/0`.
+    /// The empty prefix completes to the children of the root, and a prefix not starting with `/` has no completions.
+    pub fn complete(&self, prefix: &str) -> Vec<(String, JsonType)> {
+        let (parent, partial) = match prefix.rfind('/') {
+            Some(slash) => { (&prefix[..slash], &prefix[slash + 1..]) }
+            None if prefix.is_empty() => { ("", "") }
+            None => { return Vec::new(); }
+        };
+        let children = self.root.find(parent).map_or(&[][..], |node| &node.children[..]);
+        children.iter()
+            .filter(|(token, _)| token.starts_with(partial))
+            .map(|(token, node)| (format!("{}/{}", parent, token), node.json_type))
+            .collect()
+    }
+
+    /// Returns all paths with their types, in document order, parents first
+    pub fn paths(&self) -> Vec<(String, JsonType)> {
+        let mut paths = Vec::new();
+        self.root.collect("", &mut paths);
+        paths
+    }
+}
+
+impl JsonItem {
+    /// Builds the index of the paths of the item and its descendants, relative to the item.
+    /// The index owns its data, so it can outlive the document.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jsonic::json_type::JsonType::{JsonArray, JsonMap, JsonString};
+    ///
+    /// let parsed = jsonic::parse("{\"user\": {\"name\": \"ann\"}, \"users\": [], \"total\": 0}").unwrap();
+    /// let index = parsed.path_index();
+    ///
+    /// assert_eq!(index.complete("/us"), [("/user".to_owned(), JsonMap), ("/users".to_owned(), JsonArray)]);
+    /// assert_eq!(index.complete("/user/"), [("/user/name".to_owned(), JsonString)]);
+    /// assert_eq!(index.get_type("/users"), Some(JsonArray));
+    /// ```
+    pub fn path_index(&self) -> PathIndex {
+        PathIndex { root: Node::new(self) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::json_type::JsonType::{JsonArray, JsonMap, JsonNull, JsonNumber, JsonTrue};
+    use crate::parse;
+
+    #[test]
+    fn complete_paths() {
+        let index = parse("{\"a/b\": [1, {\"c~\": true}], \"ab\": null}").unwrap().path_index();
+        let paths = |completions: Vec<(String, _)>| completions.into_iter().map(|(path, _)| path).collect::<Vec<_>>();
+        assert_eq!(paths(index.complete("")), ["/a~1b", "/ab"]);
+        assert_eq!(paths(index.complete("/a")), ["/a~1b", "/ab"]);
+        assert_eq!(paths(index.complete("/a~")), ["/a~1b"]);
+        assert_eq!(index.complete("/a~1b/"), [("/a~1b/0".to_owned(), JsonNumber), ("/a~1b/1".to_owned(), JsonMap)]);
+        assert_eq!(index.complete("/a~1b/1/c"), [("/a~1b/1/c~0".to_owned(), JsonTrue)]);
+        assert!(index.complete("/x/").is_empty() && index.complete("a").is_empty() && index.complete("/ab/").is_empty());
+
+        assert_eq!((index.get_type(""), index.get_type("/ab"), index.get_type("/a~1b/1/c~0")), (Some(JsonMap), Some(JsonNull), Some(JsonTrue)));
+        assert_eq!((index.get_type("/a~1b/2"), index.get_type("ab")), (None, None));
+        assert_eq!(index.paths().len(), 6);
+        assert_eq!(index.paths()[1], ("/a~1b".to_owned(), JsonArray));
+    }
+}
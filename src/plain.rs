@@ -0,0 +1,102 @@
+use std::collections::BTreeMap;
+
+use crate::json_item::JsonItem;
+use crate::json_type::JsonType::{Empty, JsonArray, JsonFalse, JsonMap, JsonNull, JsonNumber, JsonString, JsonTrue};
+use crate::number::JsonNumberValue;
+use crate::text::unescape;
+
+/// A JSON value made of `std` types only, owning its data, see [`JsonItem::to_plain`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlainValue {
+    Null,
+    Bool(bool),
+    /// Number without fraction or exponent that fits in an `i64`
+    Integer(i64),
+    /// Any other number
+    Float(f64),
+    String(String),
+    Array(Vec<PlainValue>),
+    Object(BTreeMap<String, PlainValue>),
+}
+
+// Decoded string content, or the raw content if it holds a malformed escape sequence
+fn plain_string(raw: &str) -> String {
+    unescape(raw).map_or_else(|| raw.to_owned(), |text| text.into_owned())
+}
+
+impl JsonItem {
+    /// Converts the item and all of its descendants to a [`PlainValue`], which has no lifetime
+    /// and does not depend on jsonic types, e.g. to be passed across API boundaries.
+    /// Strings and keys are decoded, and kept as written if they hold a malformed escape sequence.
+    /// Integers which do not fit in an `i64` become floats. Non-existent items become `null`.
+    /// When a key appears more than once in an object, the last value is kept.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::collections::BTreeMap;
+    /// use jsonic::plain::PlainValue;
+    ///
+    /// let parsed = jsonic::parse("{\"a\": [1, 2.5, \"\\u00e9\"], \"b\": null}").unwrap();
+    /// let plain = parsed.to_plain();
+    ///
+    /// let array = vec![PlainValue::Integer(1), PlainValue::Float(2.5), PlainValue::String("é".to_owned())];
+    /// let expected = BTreeMap::from([("a".to_owned(), PlainValue::Array(array)), ("b".to_owned(), PlainValue::Null)]);
+    /// assert_eq!(plain, PlainValue::Object(expected));
+    /// ```
+    pub fn to_plain(&self) -> PlainValue {
+        match self.json_type {
+            JsonNull | Empty => { PlainValue::Null }
+            JsonTrue => { PlainValue::Bool(true) }
+            JsonFalse => { PlainValue::Bool(false) }
+            JsonNumber => {
+                match self.as_number() {
+                    Some(JsonNumberValue::Integer(integer)) => {
+                        i64::try_from(integer).map_or(PlainValue::Float(integer as f64), PlainValue::Integer)
+                    }
+                    Some(JsonNumberValue::Float(float)) => { PlainValue::Float(float) }
+                    None => { self.as_f64().map_or(PlainValue::Null, PlainValue::Float) }
+                }
+            }
+            JsonString => { PlainValue::String(plain_string(self.slice.as_str())) }
+            JsonArray => { PlainValue::Array(self.elements().into_iter().flatten().map(JsonItem::to_plain).collect()) }
+            JsonMap => {
+                PlainValue::Object(self.entries().into_iter().flatten().map(|(key, value)| (plain_string(key.as_str()), value.to_plain())).collect())
+            }
+        }
+    }
+}
+
+impl From<&JsonItem> for PlainValue {
+    fn from(item: &JsonItem) -> Self {
+        item.to_plain()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse;
+    use crate::plain::PlainValue;
+
+    #[test]
+    fn convert_to_plain() {
+        let parsed = parse("[true, null, -3, 1e2, 170141183460469231731687303715884105727, \"a\\\\tb\", \"\\uZZ\", {}, []]").unwrap();
+        let plain = parsed.to_plain();
+        let PlainValue::Array(elements) = &plain else { panic!("{:?}", plain) };
+        assert_eq!(elements[..4], [PlainValue::Bool(true), PlainValue::Null, PlainValue::Integer(-3), PlainValue::Float(100.0)]);
+        assert_eq!(elements[4], PlainValue::Float(i128::MAX as f64));
+        assert_eq!(elements[5], PlainValue::String("a\\tb".to_owned()));
+        assert_eq!(elements[6], PlainValue::String("\\uZZ".to_owned()));
+        assert_eq!(elements[7..], [PlainValue::Object(Default::default()), PlainValue::Array(Vec::new())]);
+        assert_eq!(parsed[42].to_plain(), PlainValue::Null);
+    }
+
+    #[test]
+    fn convert_keys() {
+        let parsed = parse("{\"b\\u0020\": {\"x\": [1]}, \"a\": 2, \"a\": 3}").unwrap();
+        let PlainValue::Object(entries) = PlainValue::from(&parsed) else { panic!() };
+        assert_eq!(entries.keys().collect::<Vec<_>>(), ["a", "b "]);
+        assert_eq!(entries["a"], PlainValue::Integer(3));
+        assert!(matches!(&entries["b "], PlainValue::Object(inner) if inner["x"] == PlainValue::Array(vec![PlainValue::Integer(1)])));
+    }
+}
@@ -0,0 +1,134 @@
+use std::any::Any;
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+use std::panic::{RefUnwindSafe, UnwindSafe};
+use std::sync::Arc;
+
+use crate::generics::Container::Attached;
+use crate::json_item::JsonItem;
+use crate::json_type::JsonType::{JsonArray, JsonMap};
+
+/// User-defined data attached to a parsed scalar by a [`Recognizer`]
+pub type Payload = Box<dyn Any + Send + Sync + UnwindSafe + RefUnwindSafe>;
+
+// Payload stored in the container slot of a scalar item
+pub(crate) struct Attachment(pub(crate) Payload);
+
+impl Attachment {
+    pub(crate) fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        let payload: &dyn Any = self.0.as_ref();
+        payload.downcast_ref()
+    }
+}
+
+impl Debug for Attachment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str("Payload")
+    }
+}
+
+/// Extension point to handle domain-specific JSON dialects without forking the parser.
+/// Recognizers registered with [`ParseOptions::recognizer`](crate::options::ParseOptions::recognizer) are called,
+/// in registration order, on every scalar (string, number, `true`, `false` or `null`) as it is parsed.
+/// The payload returned by the first one returning `Some` is attached to the item, and can be retrieved with
+/// [`JsonItem::payload`].
+///
+/// This trait is part of the stable API: new methods will only be added with default implementations.
+///
+/// # Example
+///
+/// ```rust
+/// use jsonic::json_item::JsonItem;
+/// use jsonic::options::{ParseOptions, Parser};
+/// use jsonic::plugin::{Payload, Recognizer};
+///
+/// struct Reference(u64);
+///
+/// struct References;
+///
+/// impl Recognizer for References {
+///     fn recognize(&self, item: &JsonItem) -> Option<Payload> {
+///         let id = item.as_unescaped()?.strip_prefix("@ref:")?.parse().ok()?;
+///         Some(Box::new(Reference(id)))
+///     }
+/// }
+///
+/// let parser = Parser::new(ParseOptions::new().recognizer(References));
+/// let parsed = parser.parse("{\"owner\": \"@ref:123\", \"name\": \"@ref\"}").unwrap();
+///
+/// assert_eq!(parsed["owner"].payload::<Reference>().map(|r| r.0), Some(123));
+/// assert!(parsed["name"].payload::<Reference>().is_none());
+/// assert_eq!(parsed["owner"].as_str(), Some("@ref:123"));
+/// ```
+pub trait Recognizer: Send + Sync {
+    /// Returns the payload to attach to the scalar `item`, or `None` to leave it as is
+    fn recognize(&self, item: &JsonItem) -> Option<Payload>;
+}
+
+// Recognizers registered in parse options
+#[derive(Clone, Default)]
+pub(crate) struct Recognizers(Vec<Arc<dyn Recognizer>>);
+
+impl Recognizers {
+    pub(crate) const NONE: Recognizers = Recognizers(Vec::new());
+
+    pub(crate) fn push(&mut self, recognizer: Arc<dyn Recognizer>) {
+        self.0.push(recognizer);
+    }
+
+    // Attaches the payload of the first matching recognizer to scalar items
+    #[inline(always)]
+    pub(crate) fn apply(&self, mut item: JsonItem) -> JsonItem {
+        if self.0.is_empty() || matches!(item.json_type, JsonMap | JsonArray) {
+            return item;
+        }
+        if let Some(payload) = self.0.iter().find_map(|recognizer| recognizer.recognize(&item)) {
+            item.container = Some(Attached(Attachment(payload)));
+        }
+        item
+    }
+}
+
+impl Debug for Recognizers {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{} recognizer(s)", self.0.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::json_item::JsonItem;
+    use crate::options::{ParseOptions, Parser};
+    use crate::plugin::{Payload, Recognizer};
+
+    struct Negative;
+
+    impl Recognizer for Negative {
+        fn recognize(&self, item: &JsonItem) -> Option<Payload> {
+            item.as_i128().filter(|&n| n < 0).map(|n| Box::new(n.unsigned_abs()) as Payload)
+        }
+    }
+
+    struct Tag(&'static str);
+
+    impl Recognizer for Tag {
+        fn recognize(&self, _: &JsonItem) -> Option<Payload> {
+            Some(Box::new(self.0))
+        }
+    }
+
+    #[test]
+    fn attach_payloads() {
+        let parser = Parser::new(ParseOptions::new().recognizer(Negative).recognizer(Tag("scalar")));
+        let parsed = parser.parse("{\"a\": [-3, 4, null], \"b\": {}}").unwrap();
+        assert_eq!(parsed["a"][0].payload::<u128>(), Some(&3));
+        assert_eq!(parsed["a"][0].as_i128(), Some(-3));
+        assert_eq!(parsed["a"][1].payload::<&str>(), Some(&"scalar"));
+        assert_eq!(parsed["a"][2].payload::<&str>(), Some(&"scalar"));
+        assert!(parsed["a"][2].is_null());
+        assert!(parsed["a"][1].payload::<u128>().is_none());
+        assert!(parsed["a"].payload::<&str>().is_none());
+        assert!(parsed["b"].payload::<&str>().is_none());
+        assert_eq!(parsed["a"].elements().unwrap().count(), 3);
+        assert!(crate::parse("[-3]").unwrap()[0].payload::<u128>().is_none());
+    }
+}
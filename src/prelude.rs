@@ -0,0 +1,36 @@
+//! Commonly used items, to be imported at once with `use jsonic::prelude::*`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use jsonic::prelude::*;
+//!
+//! let parsed: JsonItem = parse("{\"a\": [1, null]}").unwrap();
+//! assert_eq!(parsed["a"].get_type(), &JsonType::JsonArray);
+//! assert!(matches!(parsed["a"][1].field_state(), FieldState::Null));
+//! assert_eq!(<u8 as FromJsonItem>::from_item(&parsed["a"][0]), Some(1));
+//! ```
+
+pub use crate::{parse, parse_strict, parse_with_recovery, validate};
+#[cfg(feature = "extended")]
+pub use crate::{parse_json5, parse_jsonc, parse_lenient};
+#[cfg(feature = "serialize")]
+pub use crate::minify::minify;
+#[cfg(feature = "streams")]
+pub use crate::decode::decode;
+pub use crate::document::{parse_file, parse_reader, JsonDocument, ParseJsonExt};
+
+pub use crate::json_item::{FieldState, JsonItem, Update};
+pub use crate::json_type::JsonType;
+pub use crate::key::Key;
+pub use crate::number::{FloatEquality, JsonNumberValue};
+pub use crate::plain::PlainValue;
+pub use crate::json_error::{ErrorKind, JsonError};
+pub use crate::options::{DuplicateKeys, ParseOptions, Parser};
+
+pub use crate::accessors::FromJsonItem;
+pub use crate::coercion::Coercer;
+pub use crate::plugin::Recognizer;
+pub use crate::json_accessors;
+#[cfg(feature = "schema")]
+pub use crate::schema::SchemaRegistry;
@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+
+use crate::json_item::JsonItem;
+use crate::plugin::{Payload, Recognizer};
+
+/// A number with a unit, read from a string such as `"10s"` or `"1.5 GB"` by a [`UnitTable`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quantity {
+    value: f64,
+    unit: String,
+    scale: f64,
+}
+
+impl Quantity {
+    /// Number as written, e.g. `1.5` for `"1.5 GB"`
+    pub fn get_value(&self) -> f64 {
+        self.value
+    }
+
+    /// Unit as written, e.g. `GB` for `"1.5 GB"`
+    pub fn get_unit(&self) -> &str {
+        &self.unit
+    }
+
+    /// Value converted to the base unit of the table, e.g. `1.5e9` for `"1.5 GB"` with [`UnitTable::byte_sizes`]
+    pub fn to_base(&self) -> f64 {
+        self.value * self.scale
+    }
+}
+
+/// Units recognized in quantity strings, each one with its size in a base unit of the caller's choice.
+/// Registered with [`ParseOptions::quantities`](crate::options::ParseOptions::quantities), it attaches a [`Quantity`]
+/// to every string made of a number, an optional space and a unit of the table, retrieved with [`JsonItem::as_quantity`].
+/// Units are case-sensitive, and other strings are left as they are.
+///
+/// # Example
+///
+/// ```rust
+/// use jsonic::options::{ParseOptions, Parser};
+/// use jsonic::quantity::UnitTable;
+///
+/// let units = UnitTable::durations().unit("sprint", 14.0 * 86400.0);
+/// let parser = Parser::new(ParseOptions::jsonc().quantities(units));
+/// let parsed = parser.parse("{\"timeout\": \"1500ms\", \"cycle\": \"2 sprint\", \"name\": \"5 apples\"}").unwrap();
+///
+/// let timeout = parsed["timeout"].as_quantity().unwrap();
+/// assert_eq!((timeout.get_value(), timeout.get_unit(), timeout.to_base()), (1500.0, "ms", 1.5));
+/// assert_eq!(parsed["cycle"].as_quantity().map(|q| q.to_base()), Some(2419200.0));
+/// assert!(parsed["name"].as_quantity().is_none());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct UnitTable {
+    scales: HashMap<String, f64>,
+}
+
+impl UnitTable {
+    /// Creates an empty table
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Table of durations, in seconds: `ns`, `us`, `ms`, `s`, `m`, `h` and `d`
+    pub fn durations() -> Self {
+        Self::new().unit("ns", 1e-9).unit("us", 1e-6).unit("ms", 1e-3).unit("s", 1.0).unit("m", 60.0).unit("h", 3600.0).unit("d", 86400.0)
+    }
+
+    /// Table of sizes, in bytes: `B`, decimal `KB` to `TB` and binary `KiB` to `TiB`
+    pub fn byte_sizes() -> Self {
+        Self::new().unit("B", 1.0).unit("KB", 1e3).unit("MB", 1e6).unit("GB", 1e9).unit("TB", 1e12)
+            .unit("KiB", 1024.0).unit("MiB", 1024.0 * 1024.0).unit("GiB", 1024.0 * 1024.0 * 1024.0).unit("TiB", 1024.0 * 1024.0 * 1024.0 * 1024.0)
+    }
+
+    /// Adds or replaces `unit`, worth `scale` base units
+    pub fn unit(mut self, unit: &str, scale: f64) -> Self {
+        self.scales.insert(unit.to_owned(), scale);
+        self
+    }
+
+    /// Reads `text` as a quantity, returning `None` if it is not a number followed by a unit of the table
+    pub fn read(&self, text: &str) -> Option<Quantity> {
+        let split = text.find(|c: char| !matches!(c, '0'..='9' | '.' | '+' | '-')).unwrap_or(text.len());
+        let (number, unit) = text.split_at(split);
+        let unit = unit.strip_prefix(' ').unwrap_or(unit);
+        let scale = *self.scales.get(unit)?;
+        let value = number.parse::<f64>().ok().filter(|value| value.is_finite())?;
+        Some(Quantity { value, unit: unit.to_owned(), scale })
+    }
+}
+
+impl Recognizer for UnitTable {
+    fn recognize(&self, item: &JsonItem) -> Option<Payload> {
+        Some(Box::new(self.read(&item.as_unescaped()?)?))
+    }
+}
+
+impl JsonItem {
+    /// Returns the quantity read from the item by a [`UnitTable`] registered with
+    /// [`ParseOptions::quantities`](crate::options::ParseOptions::quantities), if any
+    pub fn as_quantity(&self) -> Option<&Quantity> {
+        self.payload::<Quantity>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::options::{ParseOptions, Parser};
+    use crate::quantity::UnitTable;
+
+    #[test]
+    fn read_quantities() {
+        let units = UnitTable::byte_sizes();
+        let quantity = |text: &str| units.read(text).map(|q| (q.get_value(), q.get_unit().to_owned(), q.to_base()));
+        assert_eq!(quantity("5MB"), Some((5.0, "MB".to_owned(), 5e6)));
+        assert_eq!(quantity("-1.5 KiB"), Some((-1.5, "KiB".to_owned(), -1536.0)));
+        for invalid in ["5", "MB", "5mb", "5  MB", "1..2MB", " 5MB", "5 MB ", ""] {
+            assert!(units.read(invalid).is_none(), "{}", invalid);
+        }
+
+        let parser = Parser::new(ParseOptions::new().quantities(UnitTable::durations()));
+        let parsed = parser.parse("[\"10s\", \"2\\u0068\", 10, \"10 s\", \"10x\"]").unwrap();
+        let seconds = parsed.elements().unwrap().map(|item| item.as_quantity().map(|q| q.to_base())).collect::<Vec<_>>();
+        assert_eq!(seconds, [Some(10.0), Some(7200.0), None, Some(10.0), None]);
+        assert_eq!(parsed[0].as_str(), Some("10s"));
+    }
+}
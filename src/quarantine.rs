@@ -0,0 +1,80 @@
+use std::iter::FusedIterator;
+
+use crate::json_error::JsonError;
+use crate::json_item::JsonItem;
+use crate::parse_complete;
+use crate::options::ParseOptions;
+
+/// Successfully parsed documents iterator, see [`quarantine`]
+pub struct Quarantine<I, F> {
+    documents: I,
+    sink: F,
+}
+
+impl<'a, I, F> Iterator for Quarantine<I, F>
+where
+    I: Iterator<Item=&'a str>,
+    F: FnMut(&'a str, JsonError),
+{
+    type Item = JsonItem;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for document in self.documents.by_ref() {
+            match parse_complete(document, &ParseOptions::JSON) {
+                Ok(parsed) => { return Some(parsed); }
+                Err(error) => { (self.sink)(document, error); }
+            }
+        }
+        None
+    }
+}
+
+impl<'a, I, F> FusedIterator for Quarantine<I, F>
+where
+    I: FusedIterator<Item=&'a str>,
+    F: FnMut(&'a str, JsonError),
+{}
+
+/// Parses a stream of documents, yielding the valid ones and routing failures to `sink` ("dead letter" pattern).
+/// A document is valid if it holds a single JSON object or array, optionally surrounded by whitespace.
+///
+/// # Arguments
+/// * `documents` - Text content of each document
+/// * `sink` - Called with the raw text and the error of every invalid document, in stream order
+///
+/// # Example
+///
+/// ```rust
+/// let batch = "{\"id\": 1}\n{\"id\": \n{\"id\": 3}";
+///
+/// let mut rejected = Vec::new();
+/// let ids = jsonic::quarantine::quarantine(batch.lines(), |raw, error| rejected.push((raw, error)))
+///     .filter_map(|parsed| parsed["id"].as_i128())
+///     .collect::<Vec<_>>();
+///
+/// assert_eq!(ids, vec![1, 3]);
+/// assert_eq!(rejected.len(), 1);
+/// assert_eq!(rejected[0].0, "{\"id\": ");
+/// ```
+pub fn quarantine<'a, I, F>(documents: I, sink: F) -> Quarantine<I::IntoIter, F>
+where
+    I: IntoIterator<Item=&'a str>,
+    F: FnMut(&'a str, JsonError),
+{
+    Quarantine { documents: documents.into_iter(), sink }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::quarantine::quarantine;
+
+    #[test]
+    fn route_failures() {
+        let documents = ["[1]", "[1] [2]", "", "{\"a\": true}", "{"];
+        let mut failures = Vec::new();
+        let parsed = quarantine(documents, |raw, error| failures.push((raw, error.get_index()))).collect::<Vec<_>>();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[1]["a"].as_bool(), Some(true));
+        assert_eq!(failures, vec![("[1] [2]", 4), ("", 0), ("{", 1)]);
+    }
+}
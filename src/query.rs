@@ -0,0 +1,259 @@
+use crate::document::JsonDocument;
+use crate::options::ParseOptions;
+use crate::text::escape_into;
+
+/// Key conventions recognized by [`parse_query_with`], both enabled by default
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryOptions {
+    brackets: bool,
+    dots: bool,
+}
+
+impl QueryOptions {
+    /// Options recognizing both bracket and dot conventions
+    pub fn new() -> Self {
+        QueryOptions { brackets: true, dots: true }
+    }
+
+    /// Recognizes `a[b]=1` as a nested object and `a[]=1` as an array element.
+    /// When disabled, brackets are part of key names.
+    pub fn brackets(mut self, enabled: bool) -> Self {
+        self.brackets = enabled;
+        self
+    }
+
+    /// Recognizes `a.b=1` as a nested object. When disabled, dots are part of key names.
+    pub fn dots(mut self, enabled: bool) -> Self {
+        self.dots = enabled;
+        self
+    }
+}
+
+impl Default for QueryOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+enum Node {
+    Value(String),
+    Array(Vec<Node>),
+    Object(Vec<(String, Node)>),
+}
+
+enum Segment {
+    Key(String),
+    Append,
+}
+
+// Decodes `%XX` sequences and `+` signs, replacing invalid UTF-8 with U+FFFD
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'+' => { decoded.push(b' '); }
+            b'%' if bytes.get(index + 1..index + 3).is_some_and(|hex| hex.iter().all(u8::is_ascii_hexdigit)) => {
+                decoded.push(u8::from_str_radix(&text[index + 1..index + 3], 16).unwrap());
+                index += 2;
+            }
+            b => { decoded.push(b); }
+        }
+        index += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+// Byte index of the first separator in `text`, or its length if there is none
+fn separator(text: &str, options: &QueryOptions, skip_first: bool) -> usize {
+    text.char_indices()
+        .skip(usize::from(skip_first))
+        .find(|&(index, c)| (c == '.' && options.dots) || (c == '[' && options.brackets && text[index..].contains(']')))
+        .map_or(text.len(), |(index, _)| index)
+}
+
+// Splits a decoded key into its path, e.g. `a[b][].c` into `a`, `b`, append, `c`
+fn segments(key: &str, options: &QueryOptions) -> Vec<Segment> {
+    // A separator at the very start belongs to the name, e.g. `[x]` and `.x` are plain keys
+    let end = separator(key, options, true);
+    let mut segments = vec![Segment::Key(key[..end].to_owned())];
+    let mut rest = &key[end..];
+    while !rest.is_empty() {
+        if options.brackets && rest.starts_with('[') {
+            if let Some(close) = rest.find(']') {
+                segments.push(match &rest[1..close] {
+                    "" => { Segment::Append }
+                    inner => { Segment::Key(inner.to_owned()) }
+                });
+                rest = &rest[close + 1..];
+                continue;
+            }
+        }
+        let name = if options.dots { rest.strip_prefix('.').unwrap_or(rest) } else { rest };
+        let end = separator(name, options, false);
+        segments.push(Segment::Key(name[..end].to_owned()));
+        rest = &name[end..];
+    }
+    segments
+}
+
+impl Node {
+    // Stores `value` at `path` below this node, which must be an object or an array
+    fn insert(&mut self, path: &[Segment], value: String) {
+        let child = match (self, &path[0]) {
+            (Node::Object(entries), Segment::Key(key)) => {
+                let position = match entries.iter().position(|(k, _)| k == key) {
+                    Some(position) => { position }
+                    None if path.len() == 1 => {
+                        entries.push((key.clone(), Node::Value(value)));
+                        return;
+                    }
+                    None => {
+                        entries.push((key.clone(), Node::Object(Vec::new())));
+                        entries.len() - 1
+                    }
+                };
+                &mut entries[position].1
+            }
+            (Node::Array(elements), Segment::Append) => {
+                if path.len() == 1 {
+                    elements.push(Node::Value(value));
+                    return;
+                }
+                elements.push(Node::Object(Vec::new()));
+                elements.last_mut().unwrap()
+            }
+            (node, _) => {
+                // Shape conflict: an object where an array is expected, or the other way round
+                *node = match path[0] {
+                    Segment::Key(_) => { Node::Object(Vec::new()) }
+                    Segment::Append => { Node::Array(Vec::new()) }
+                };
+                node.insert(path, value);
+                return;
+            }
+        };
+        if path.len() == 1 {
+            // Repeated key: values are gathered into an array
+            match child {
+                Node::Array(elements) => { elements.push(Node::Value(value)); }
+                _ => {
+                    let previous = std::mem::replace(child, Node::Array(Vec::new()));
+                    *child = Node::Array(vec![previous, Node::Value(value)]);
+                }
+            }
+            return;
+        }
+        match (&*child, &path[1]) {
+            (Node::Value(_), Segment::Append) => {
+                let previous = std::mem::replace(child, Node::Array(Vec::new()));
+                *child = Node::Array(vec![previous]);
+            }
+            (Node::Object(_), Segment::Key(_)) | (Node::Array(_), Segment::Append) => {}
+            (_, Segment::Key(_)) => { *child = Node::Object(Vec::new()); }
+            (_, Segment::Append) => { *child = Node::Array(Vec::new()); }
+        }
+        child.insert(&path[1..], value);
+    }
+
+    fn write(&self, output: &mut String) {
+        match self {
+            Node::Value(value) => { escape_into(value, output); }
+            Node::Array(elements) => {
+                output.push('[');
+                for (index, element) in elements.iter().enumerate() {
+                    if index > 0 {
+                        output.push(',');
+                    }
+                    element.write(output);
+                }
+                output.push(']');
+            }
+            Node::Object(entries) => {
+                output.push('{');
+                for (index, (key, value)) in entries.iter().enumerate() {
+                    if index > 0 {
+                        output.push(',');
+                    }
+                    escape_into(key, output);
+                    output.push(':');
+                    value.write(output);
+                }
+                output.push('}');
+            }
+        }
+    }
+}
+
+/// Converts an URL query string or `application/x-www-form-urlencoded` body to a JSON object, see [`parse_query_with`]
+///
+/// # Arguments
+/// * `query` - Query string, with or without its leading `?`
+///
+/// # Example
+///
+/// ```rust
+/// let query = jsonic::query::parse_query("?a=1&b[]=2&b[]=3&c.d=4&e=x+y%21");
+///
+/// assert_eq!(query.source(), "{\"a\":\"1\",\"b\":[\"2\",\"3\"],\"c\":{\"d\":\"4\"},\"e\":\"x y!\"}");
+/// assert_eq!(query["b"][1].as_str(), Some("3"));
+/// ```
+pub fn parse_query(query: &str) -> JsonDocument {
+    parse_query_with(query, &QueryOptions::new())
+}
+
+/// Converts an URL query string or `application/x-www-form-urlencoded` body to a JSON object,
+/// so that query parameters can be handled like JSON bodies.
+/// Keys and values are percent-decoded, and values are kept as strings. Keys are paths into the object:
+/// `a[b]` and `a.b` designate member `b` of object `a`, and `a[]` appends to array `a`.
+/// Repeated keys gather their values into an array, e.g. `a=1&a=2` gives `{"a": ["1", "2"]}`.
+/// When keys conflict, e.g. `a=1&a[b]=2`, the later one replaces the earlier value.
+///
+/// # Arguments
+/// * `query` - Query string, with or without its leading `?`
+/// * `options` - Key conventions to recognize
+pub fn parse_query_with(query: &str, options: &QueryOptions) -> JsonDocument {
+    let mut root = Node::Object(Vec::new());
+    for pair in query.strip_prefix('?').unwrap_or(query).split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        root.insert(&segments(&percent_decode(key), options), percent_decode(value));
+    }
+    let mut source = String::new();
+    root.write(&mut source);
+    JsonDocument::parse(source, &ParseOptions::new().max_depth(usize::MAX)).expect("generated JSON is valid")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::query::{parse_query, parse_query_with, QueryOptions};
+
+    fn convert(query: &str) -> String {
+        parse_query(query).source().to_owned()
+    }
+
+    #[test]
+    fn nest_keys() {
+        assert_eq!(convert(""), "{}");
+        assert_eq!(convert("a=1&a=2&a=3&b"), "{\"a\":[\"1\",\"2\",\"3\"],\"b\":\"\"}");
+        assert_eq!(convert("x[y][z]=1&x[y][w]=2&x.v=3"), "{\"x\":{\"y\":{\"z\":\"1\",\"w\":\"2\"},\"v\":\"3\"}}");
+        assert_eq!(convert("l[][n]=1&l[][n]=2"), "{\"l\":[{\"n\":\"1\"},{\"n\":\"2\"}]}");
+        assert_eq!(convert("a[b].c[]=1&a[b].c[]=2"), "{\"a\":{\"b\":{\"c\":[\"1\",\"2\"]}}}");
+        assert_eq!(convert("a=1&a[]=2"), "{\"a\":[\"1\",\"2\"]}");
+        assert_eq!(convert("a=1&a[b]=2"), "{\"a\":{\"b\":\"2\"}}");
+        assert_eq!(convert("[x]=1&.y=2&z[=3&w]=4"), "{\"[x]\":\"1\",\".y\":\"2\",\"z[\":\"3\",\"w]\":\"4\"}");
+    }
+
+    #[test]
+    fn decode_text() {
+        assert_eq!(convert("q=%22a%2Bb%22+%C3%A9&%61%5B%5D=1"), "{\"q\":\"\\\"a+b\\\" é\",\"a\":[\"1\"]}");
+        assert_eq!(convert("bad=%zz%4&utf=%FF"), "{\"bad\":\"%zz%4\",\"utf\":\"\u{FFFD}\"}");
+    }
+
+    #[test]
+    fn toggle_conventions() {
+        let query = "a[b]=1&c.d=2";
+        assert_eq!(parse_query_with(query, &QueryOptions::new().brackets(false)).source(), "{\"a[b]\":\"1\",\"c\":{\"d\":\"2\"}}");
+        assert_eq!(parse_query_with(query, &QueryOptions::new().dots(false)).source(), "{\"a\":{\"b\":\"1\"},\"c.d\":\"2\"}");
+    }
+}
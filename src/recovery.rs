@@ -0,0 +1,437 @@
+use std::fmt::Write;
+
+use crate::document::JsonDocument;
+use crate::json_error::{ErrorKind, JsonError};
+use crate::number::is_json_number;
+use crate::options::ParseOptions;
+
+// What the scanner expects next
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Expect {
+    Value,
+    Key,
+    Colon,
+    // Comma or closing bracket
+    Next,
+    // Nothing, the root value is complete
+    End,
+}
+
+// Open container, with its closing bracket and whether an element was written yet
+struct Open {
+    close: u8,
+    items: bool,
+}
+
+// Scanner rewriting source text as valid JSON, recording a diagnostic for each repair
+struct Repair<'a> {
+    source: &'a str,
+    bytes: &'a [u8],
+    index: usize,
+    output: String,
+    stack: Vec<Open>,
+    diagnostics: Vec<JsonError>,
+}
+
+fn is_delimiter(byte: u8) -> bool {
+    matches!(byte, b' ' | b'\t' | b'\n' | b'\r' | b'{' | b'}' | b'[' | b']' | b',' | b':' | b'"')
+}
+
+impl<'a> Repair<'a> {
+    // Records an error, unless one was already recorded at the same place by an earlier repair
+    fn diagnose(&mut self, index: usize, kind: ErrorKind) {
+        if self.diagnostics.last().is_some_and(|last| last.get_index() == index) {
+            return;
+        }
+        self.diagnostics.push(JsonError::with_kind(index, kind).located(self.bytes));
+    }
+
+    fn in_object(&self) -> bool {
+        self.stack.last().is_some_and(|open| open.close == b'}')
+    }
+
+    // Writes the comma preceding an array element or an object key, if any
+    fn separate(&mut self) {
+        if let Some(open) = self.stack.last_mut() {
+            if open.items {
+                self.output.push(',');
+            }
+            open.items = true;
+        }
+    }
+
+    fn after_value(&self) -> Expect {
+        if self.stack.is_empty() { Expect::End } else { Expect::Next }
+    }
+
+    fn skip_spaces(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.bytes.get(self.index) {
+            self.index += 1;
+        }
+    }
+
+    // Run of bytes up to the next delimiter, e.g. a number, a literal or a bare word
+    fn token(&mut self) -> (usize, &'a str) {
+        let start = self.index;
+        while self.bytes.get(self.index).is_some_and(|&byte| !is_delimiter(byte)) {
+            self.index += 1;
+        }
+        (start, &self.source[start..self.index])
+    }
+
+    // Copies a string, closing it at the end of its line if unterminated and escaping what cannot appear in JSON strings
+    fn string(&mut self) {
+        let start = self.index;
+        self.index += 1;
+        let mut mark = start;
+        loop {
+            match self.bytes.get(self.index) {
+                Some(b'"') => {
+                    self.index += 1;
+                    self.output.push_str(&self.source[mark..self.index]);
+                    return;
+                }
+                None | Some(b'\n' | b'\r') => {
+                    self.diagnose(start, ErrorKind::UnterminatedString);
+                    self.output.push_str(&self.source[mark..self.index]);
+                    self.output.push('"');
+                    return;
+                }
+                Some(b'\\') => {
+                    match self.bytes.get(self.index + 1) {
+                        Some(b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't') => { self.index += 2; }
+                        Some(b'u') if self.bytes.get(self.index + 2..self.index + 6).is_some_and(|hex| hex.iter().all(u8::is_ascii_hexdigit)) => { self.index += 6; }
+                        _ => {
+                            // Kept as written, with the backslash escaped
+                            self.diagnose(self.index, ErrorKind::InvalidEscape);
+                            self.output.push_str(&self.source[mark..self.index]);
+                            self.output.push_str("\\\\");
+                            self.index += 1;
+                            mark = self.index;
+                        }
+                    }
+                }
+                Some(&byte) if byte < 0x20 => {
+                    self.diagnose(self.index, ErrorKind::UnexpectedCharacter);
+                    self.output.push_str(&self.source[mark..self.index]);
+                    write!(self.output, "\\u{:04x}", byte).unwrap();
+                    self.index += 1;
+                    mark = self.index;
+                }
+                Some(_) => { self.index += 1; }
+            }
+        }
+    }
+
+    // Copies a number or literal, replacing anything else with `null`
+    fn scalar(&mut self) {
+        let (start, token) = self.token();
+        if matches!(token, "true" | "false" | "null") || is_json_number(token) {
+            self.output.push_str(token);
+        } else {
+            let kind = if matches!(token.as_bytes()[0], b'-' | b'0'..=b'9') { ErrorKind::InvalidNumber } else { ErrorKind::UnexpectedCharacter };
+            self.diagnose(start, kind);
+            self.output.push_str("null");
+        }
+    }
+
+    // Writes a bare word as a key, e.g. `{a: 1}`
+    fn bare_key(&mut self) {
+        let (start, token) = self.token();
+        self.diagnose(start, ErrorKind::UnexpectedCharacter);
+        self.output.push('"');
+        for c in token.chars() {
+            match c {
+                '\\' => { self.output.push_str("\\\\"); }
+                c if (c as u32) < 0x20 => { write!(self.output, "\\u{:04x}", c as u32).unwrap(); }
+                c => { self.output.push(c); }
+            }
+        }
+        self.output.push('"');
+    }
+
+    // Closes containers up to the one matching `close`, the current element being complete.
+    // A bracket matching no open container is skipped.
+    fn close(&mut self, close: u8) -> Option<Expect> {
+        let Some(depth) = self.stack.iter().rposition(|open| open.close == close) else {
+            self.diagnose(self.index, ErrorKind::UnexpectedCharacter);
+            self.index += 1;
+            return None;
+        };
+        if depth + 1 < self.stack.len() {
+            self.diagnose(self.index, ErrorKind::UnexpectedCharacter);
+        }
+        while self.stack.len() > depth {
+            self.output.push(self.stack.pop().unwrap().close as char);
+        }
+        self.index += 1;
+        Some(self.after_value())
+    }
+
+    // Writes the comma preceding an array element, member values following their key
+    fn start_value(&mut self) {
+        if !self.in_object() {
+            self.separate();
+        }
+    }
+
+    fn run(&mut self) {
+        let mut expect = Expect::Value;
+        loop {
+            self.skip_spaces();
+            let Some(&byte) = self.bytes.get(self.index) else {
+                self.finish(expect);
+                return;
+            };
+            expect = match expect {
+                Expect::Value => { self.value(byte) }
+                Expect::Key => { self.key(byte) }
+                Expect::Colon => {
+                    if byte == b':' {
+                        self.output.push(':');
+                        self.index += 1;
+                        Expect::Value
+                    } else {
+                        self.diagnose(self.index, ErrorKind::UnexpectedCharacter);
+                        self.output.push(':');
+                        if matches!(byte, b',' | b'}' | b']') {
+                            self.output.push_str("null");
+                            Expect::Next
+                        } else {
+                            Expect::Value
+                        }
+                    }
+                }
+                Expect::Next => {
+                    match byte {
+                        b',' => {
+                            self.index += 1;
+                            if self.in_object() { Expect::Key } else { Expect::Value }
+                        }
+                        b'}' | b']' => { self.close(byte).unwrap_or(expect) }
+                        b':' => {
+                            self.diagnose(self.index, ErrorKind::UnexpectedCharacter);
+                            self.index += 1;
+                            expect
+                        }
+                        _ => {
+                            // Missing comma, written before the next element
+                            self.diagnose(self.index, ErrorKind::UnexpectedCharacter);
+                            if self.in_object() { Expect::Key } else { Expect::Value }
+                        }
+                    }
+                }
+                Expect::End => {
+                    self.diagnose(self.index, ErrorKind::TrailingData);
+                    return;
+                }
+            };
+        }
+    }
+
+    fn value(&mut self, byte: u8) -> Expect {
+        match byte {
+            b'{' | b'[' => {
+                self.start_value();
+                self.output.push(byte as char);
+                self.stack.push(Open { close: if byte == b'{' { b'}' } else { b']' }, items: false });
+                self.index += 1;
+                if byte == b'{' { Expect::Key } else { Expect::Value }
+            }
+            b'"' => {
+                self.start_value();
+                self.string();
+                self.after_value()
+            }
+            b',' | b'}' | b']' if self.in_object() => {
+                // Missing member value
+                self.diagnose(self.index, ErrorKind::UnexpectedCharacter);
+                self.output.push_str("null");
+                Expect::Next
+            }
+            b'}' | b']' if !self.stack.is_empty() => {
+                // Trailing comma, unless the array is empty
+                if self.stack.last().is_some_and(|open| open.items && open.close == byte) {
+                    self.diagnose(self.index, ErrorKind::UnexpectedCharacter);
+                }
+                Expect::Next
+            }
+            b',' | b':' | b'}' | b']' => {
+                self.diagnose(self.index, ErrorKind::UnexpectedCharacter);
+                self.index += 1;
+                Expect::Value
+            }
+            _ => {
+                self.start_value();
+                self.scalar();
+                self.after_value()
+            }
+        }
+    }
+
+    fn key(&mut self, byte: u8) -> Expect {
+        match byte {
+            b'"' => {
+                self.separate();
+                self.string();
+                Expect::Colon
+            }
+            b'}' | b']' => {
+                // Trailing comma, unless the object is empty
+                if byte == b'}' && self.stack.last().is_some_and(|open| open.items) {
+                    self.diagnose(self.index, ErrorKind::UnexpectedCharacter);
+                }
+                Expect::Next
+            }
+            b',' | b':' => {
+                self.diagnose(self.index, ErrorKind::UnexpectedCharacter);
+                self.index += 1;
+                Expect::Key
+            }
+            b'{' | b'[' => {
+                // Missing key, the value is kept under an empty one
+                self.diagnose(self.index, ErrorKind::UnexpectedCharacter);
+                self.separate();
+                self.output.push_str("\"\":");
+                Expect::Value
+            }
+            _ => {
+                self.separate();
+                self.bare_key();
+                Expect::Colon
+            }
+        }
+    }
+
+    // Completes the output at the end of the source
+    fn finish(&mut self, expect: Expect) {
+        if expect == Expect::End {
+            return;
+        }
+        self.diagnose(self.bytes.len(), ErrorKind::UnexpectedEof);
+        match expect {
+            Expect::Value if self.stack.is_empty() || self.in_object() => { self.output.push_str("null"); }
+            Expect::Colon => { self.output.push_str(":null"); }
+            _ => {}
+        }
+        while let Some(open) = self.stack.pop() {
+            self.output.push(open.close as char);
+        }
+    }
+}
+
+/// Parses JSON text, repairing syntax errors instead of stopping at the first one, e.g. for editor tooling or log scrubbing.
+/// Returns a best-effort document along with a diagnostic for each error, in source order; a valid source gives no diagnostics.
+///
+/// Source text is read as strict JSON (RFC 8259), and errors are repaired as follows:
+/// * missing commas and colons are inserted, and trailing or extra commas and colons are dropped
+/// * invalid numbers and unknown words become `null`, as do missing member values; bare-word keys are quoted
+/// * invalid escape sequences are kept as written, and unterminated strings are closed at the end of their line
+/// * mismatched closing brackets close the inner containers, and containers still open at the end of the source are closed
+/// * data following the root value is ignored
+///
+/// The returned document holds the repaired text without insignificant whitespace, so item offsets refer to it rather than to `source`.
+/// Diagnostics locate errors in `source`.
+///
+/// # Arguments
+/// * `source` - JSON text to parse
+///
+/// # Example
+///
+/// ```rust
+/// let (document, diagnostics) = jsonic::parse_with_recovery("{\"a\": [1, 2,], \"b\": tru \"c\": \"x");
+///
+/// assert_eq!(document.source(), "{\"a\":[1,2],\"b\":null,\"c\":\"x\"}");
+/// assert_eq!(diagnostics.iter().map(|d| d.get_index()).collect::<Vec<_>>(), [12, 20, 24, 29, 31]);
+/// ```
+pub fn parse_with_recovery(source: &str) -> (JsonDocument, Vec<JsonError>) {
+    let mut repair = Repair { source, bytes: source.as_bytes(), index: 0, output: String::with_capacity(source.len()), stack: Vec::new(), diagnostics: Vec::new() };
+    repair.run();
+    let document = JsonDocument::parse(repair.output, &ParseOptions::strict().max_depth(usize::MAX)).expect("repaired JSON is valid");
+    (document, repair.diagnostics)
+}
+
+/// Parses JSON text as with [`parse_strict`](crate::parse_strict), but reports all syntax errors rather than the first one only,
+/// e.g. so that a hand-written configuration file can be fixed in one go.
+/// Errors are found by repairing the source as with [`parse_with_recovery`], so an error may hide the ones following it on the same token.
+///
+/// # Arguments
+/// * `source` - JSON text to parse
+///
+/// # Example
+///
+/// ```rust
+/// let errors = jsonic::recovery::parse_collecting_errors("{\"port\": 80 \"host\": localhost, \"tls\": {\"on\": true,}}").unwrap_err();
+/// assert_eq!(errors.iter().map(|e| e.get_column()).collect::<Vec<_>>(), [Some(13), Some(21), Some(51)]);
+///
+/// assert_eq!(jsonic::recovery::parse_collecting_errors("{\"port\": 80}").unwrap()["port"].as_i128(), Some(80));
+/// ```
+pub fn parse_collecting_errors(source: &str) -> Result<JsonDocument, Vec<JsonError>> {
+    let (_, diagnostics) = parse_with_recovery(source);
+    if !diagnostics.is_empty() {
+        return Err(diagnostics);
+    }
+    JsonDocument::parse(source.to_owned(), &ParseOptions::strict()).map_err(|error| vec![error])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::json_error::ErrorKind;
+    use crate::recovery::{parse_collecting_errors, parse_with_recovery};
+
+    fn recover(source: &str) -> (String, Vec<(usize, ErrorKind)>) {
+        let (document, diagnostics) = parse_with_recovery(source);
+        (document.source().to_owned(), diagnostics.iter().map(|d| (d.get_index(), d.get_kind())).collect())
+    }
+
+    #[test]
+    fn keep_valid() {
+        for source in ["{\"a\": [1, -2.5e3, true, null, \"\\u00e9\"], \"b\": {}}", "[]", " 42 ", "\"s\""] {
+            let (document, diagnostics) = parse_with_recovery(source);
+            assert!(diagnostics.is_empty(), "{}", source);
+            assert!(document.equals(&crate::parse_strict(source).unwrap(), Default::default()), "{}", source);
+        }
+    }
+
+    #[test]
+    fn repair_structure() {
+        use ErrorKind::*;
+        assert_eq!(recover("{\"a\": 1 \"b\": 2,}"), ("{\"a\":1,\"b\":2}".to_owned(), vec![(8, UnexpectedCharacter), (15, UnexpectedCharacter)]));
+        assert_eq!(recover("[1 2, , 3]"), ("[1,2,3]".to_owned(), vec![(3, UnexpectedCharacter), (6, UnexpectedCharacter)]));
+        assert_eq!(recover("{\"a\" 1, \"b\":, \"c\": }"), ("{\"a\":1,\"b\":null,\"c\":null}".to_owned(), vec![(5, UnexpectedCharacter), (12, UnexpectedCharacter), (19, UnexpectedCharacter)]));
+        assert_eq!(recover("{a: 1, [2]}"), ("{\"a\":1,\"\":[2]}".to_owned(), vec![(1, UnexpectedCharacter), (7, UnexpectedCharacter)]));
+        assert_eq!(recover("{\"a\": [1, }"), ("{\"a\":[1]}".to_owned(), vec![(10, UnexpectedCharacter)]));
+        assert_eq!(recover("[1]] [2]"), ("[1]".to_owned(), vec![(3, TrailingData)]));
+        assert_eq!(recover("[{\"a\": [1"), ("[{\"a\":[1]}]".to_owned(), vec![(9, UnexpectedEof)]));
+        assert_eq!(recover("{\"a\", \"b\": 1}"), ("{\"a\":null,\"b\":1}".to_owned(), vec![(4, UnexpectedCharacter)]));
+        assert_eq!(recover("{\"a\""), ("{\"a\":null}".to_owned(), vec![(4, UnexpectedEof)]));
+        assert_eq!(recover(""), ("null".to_owned(), vec![(0, UnexpectedEof)]));
+        assert_eq!(recover("}, 1"), ("1".to_owned(), vec![(0, UnexpectedCharacter), (1, UnexpectedCharacter)]));
+    }
+
+    #[test]
+    fn repair_values() {
+        use ErrorKind::*;
+        assert_eq!(recover("[01, -, tru, 1.5]"), ("[null,null,null,1.5]".to_owned(), vec![(1, InvalidNumber), (5, InvalidNumber), (8, UnexpectedCharacter)]));
+        assert_eq!(recover("[\"a\\qb\", \"c\td\"]"), ("[\"a\\\\qb\",\"c\\u0009d\"]".to_owned(), vec![(3, InvalidEscape), (11, UnexpectedCharacter)]));
+        let (document, diagnostics) = parse_with_recovery("{\"k\": \"open\n, \"l\": 1}");
+        assert_eq!(document["k"].as_str(), Some("open"));
+        assert_eq!(document["l"].as_i128(), Some(1));
+        assert_eq!(diagnostics[0].get_kind(), UnterminatedString);
+        assert_eq!((diagnostics[0].get_index(), diagnostics.len()), (6, 1));
+        let (document, _) = parse_with_recovery(&"[".repeat(100_000));
+        assert_eq!(document.source().len(), 200_000);
+    }
+
+    #[test]
+    fn collect_errors() {
+        let document = parse_collecting_errors("{ \"a\": [1, 2] }").unwrap();
+        assert_eq!(document.source(), "{ \"a\": [1, 2] }");
+        let errors = parse_collecting_errors("[1 2,\n \"x\\q\", 0x1,\n {\"k\" 1}").unwrap_err();
+        let located = errors.iter().map(|e| (e.get_line().unwrap(), e.get_column().unwrap(), e.get_kind())).collect::<Vec<_>>();
+        assert_eq!(located, [(1, 4, ErrorKind::UnexpectedCharacter), (2, 4, ErrorKind::InvalidEscape), (2, 9, ErrorKind::InvalidNumber),
+                             (3, 7, ErrorKind::UnexpectedCharacter), (3, 9, ErrorKind::UnexpectedEof)]);
+        let deep = format!("{}{}", "[".repeat(10_000), "]".repeat(10_000));
+        assert_eq!(parse_collecting_errors(&deep).unwrap_err()[0].get_kind(), ErrorKind::DepthLimitExceeded);
+    }
+}
@@ -0,0 +1,44 @@
+// SplitMix64, small deterministic generator used for seeded test data transforms and fixture generation
+pub(crate) struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    // Uniform value in `0..bound`, `bound` must not be 0
+    pub(crate) fn below(&mut self, bound: usize) -> usize {
+        ((self.next_u64() as u128 * bound as u128) >> 64) as usize
+    }
+
+    // Uniform value in `min..=max`
+    #[cfg(feature = "gen")]
+    pub(crate) fn between(&mut self, min: i64, max: i64) -> i64 {
+        let span = (max as i128 - min as i128 + 1) as u128;
+        (min as i128 + ((self.next_u64() as u128 * span) >> 64) as i128) as i64
+    }
+
+    // Uniform value in `[0, 1)`
+    #[cfg(feature = "gen")]
+    pub(crate) fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64
+    }
+
+    // Fisher-Yates shuffle
+    #[cfg(feature = "test-util")]
+    pub(crate) fn shuffle<T>(&mut self, values: &mut [T]) {
+        for index in (1..values.len()).rev() {
+            values.swap(index, self.below(index + 1));
+        }
+    }
+}
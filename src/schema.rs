@@ -0,0 +1,632 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use crate::json_error::{ErrorKind, JsonError};
+use crate::json_item::JsonItem;
+use crate::json_type::JsonType::{Empty, JsonArray, JsonFalse, JsonMap, JsonNull, JsonNumber, JsonString, JsonTrue};
+use crate::key::Key;
+use crate::number::FloatEquality;
+use crate::parse_strict;
+use crate::plain::PlainValue;
+use crate::text::unescape;
+
+// Bits of the instance types accepted by the `type` keyword
+const NULL: u8 = 1;
+const BOOLEAN: u8 = 2;
+const OBJECT: u8 = 4;
+const ARRAY: u8 = 8;
+const NUMBER: u8 = 16;
+const STRING: u8 = 32;
+const INTEGER: u8 = 64;
+
+// Keywords that change validation but are not supported, so that schemas using them are rejected rather than half-applied
+const UNSUPPORTED: &[&str] = &[
+    "pattern", "patternProperties", "propertyNames", "dependencies", "dependentRequired", "dependentSchemas", "if", "then", "else",
+    "contains", "minContains", "maxContains", "prefixItems", "additionalItems", "unevaluatedItems", "unevaluatedProperties",
+    "$dynamicRef", "$recursiveRef",
+];
+
+/// Errors returned by [`SchemaRegistry::compile`]
+#[derive(Debug)]
+pub enum SchemaError {
+    /// A schema is not valid JSON, or a keyword has a malformed value, at the location of the error
+    Json { schema: String, error: JsonError },
+    /// A schema uses a keyword which is not supported
+    Unsupported { schema: String, keyword: String },
+    /// A `$ref` designates a schema or a location which does not exist
+    UnresolvedRef { schema: String, reference: String },
+    /// Two schemas have the same name
+    DuplicateName(String),
+}
+
+impl Display for SchemaError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            SchemaError::Json { schema, error } => { write!(f, "schema '{}': {}", schema, error) }
+            SchemaError::Unsupported { schema, keyword } => { write!(f, "schema '{}': unsupported keyword '{}'", schema, keyword) }
+            SchemaError::UnresolvedRef { schema, reference } => { write!(f, "schema '{}': unresolved reference '{}'", schema, reference) }
+            SchemaError::DuplicateName(name) => { write!(f, "schema '{}' is defined twice", name) }
+        }
+    }
+}
+
+impl Error for SchemaError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SchemaError::Json { error, .. } => { Some(error) }
+            _ => { None }
+        }
+    }
+}
+
+/// A value failing a schema keyword, see [`Validator::validate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    path: String,
+    keyword: &'static str,
+}
+
+impl Violation {
+    /// JSON Pointer of the failing value in the validated document
+    pub fn get_path(&self) -> &str {
+        &self.path
+    }
+
+    /// Name of the failed keyword, `false` for a `false` schema
+    pub fn get_keyword(&self) -> &'static str {
+        self.keyword
+    }
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "value at '{}' fails '{}'", self.path, self.keyword)
+    }
+}
+
+// Compiled keyword, subschemas being indices of nodes in the registry
+#[derive(Debug)]
+enum Keyword {
+    Fail,
+    Type(u8),
+    Enum(Vec<PlainValue>),
+    Const(PlainValue),
+    Minimum(f64),
+    Maximum(f64),
+    ExclusiveMinimum(f64),
+    ExclusiveMaximum(f64),
+    MultipleOf(f64),
+    MinLength(usize),
+    MaxLength(usize),
+    MinItems(usize),
+    MaxItems(usize),
+    UniqueItems,
+    Items(usize),
+    MinProperties(usize),
+    MaxProperties(usize),
+    Required(Vec<String>),
+    Properties { named: Vec<(String, usize)>, additional: Option<usize> },
+    AllOf(Vec<usize>),
+    AnyOf(Vec<usize>),
+    OneOf(Vec<usize>),
+    Not(usize),
+    Ref(usize),
+}
+
+impl Keyword {
+    fn name(&self) -> &'static str {
+        match self {
+            Keyword::Fail => { "false" }
+            Keyword::Type(_) => { "type" }
+            Keyword::Enum(_) => { "enum" }
+            Keyword::Const(_) => { "const" }
+            Keyword::Minimum(_) => { "minimum" }
+            Keyword::Maximum(_) => { "maximum" }
+            Keyword::ExclusiveMinimum(_) => { "exclusiveMinimum" }
+            Keyword::ExclusiveMaximum(_) => { "exclusiveMaximum" }
+            Keyword::MultipleOf(_) => { "multipleOf" }
+            Keyword::MinLength(_) => { "minLength" }
+            Keyword::MaxLength(_) => { "maxLength" }
+            Keyword::MinItems(_) => { "minItems" }
+            Keyword::MaxItems(_) => { "maxItems" }
+            Keyword::UniqueItems => { "uniqueItems" }
+            Keyword::Items(_) => { "items" }
+            Keyword::MinProperties(_) => { "minProperties" }
+            Keyword::MaxProperties(_) => { "maxProperties" }
+            Keyword::Required(_) => { "required" }
+            Keyword::Properties { .. } => { "properties" }
+            Keyword::AllOf(_) => { "allOf" }
+            Keyword::AnyOf(_) => { "anyOf" }
+            Keyword::OneOf(_) => { "oneOf" }
+            Keyword::Not(_) => { "not" }
+            Keyword::Ref(_) => { "$ref" }
+        }
+    }
+}
+
+fn key_text(key: &Key) -> Cow<'_, str> {
+    unescape(key.as_str()).unwrap_or(Cow::Borrowed(key.as_str()))
+}
+
+fn type_bits(item: &JsonItem) -> u8 {
+    match item.json_type {
+        JsonNull => { NULL }
+        JsonTrue | JsonFalse => { BOOLEAN }
+        JsonMap => { OBJECT }
+        JsonArray => { ARRAY }
+        JsonString => { STRING }
+        JsonNumber if item.as_i128().is_some() || item.as_f64().is_some_and(|value| value.fract() == 0.0) => { NUMBER | INTEGER }
+        JsonNumber => { NUMBER }
+        Empty => { 0 }
+    }
+}
+
+// Length of a string in characters, `None` for other values
+fn string_len(item: &JsonItem) -> Option<usize> {
+    (item.json_type == JsonString).then(|| item.as_unescaped().unwrap_or(Cow::Borrowed(item.slice.as_str())).chars().count())
+}
+
+fn is_multiple(value: f64, divisor: f64) -> bool {
+    let quotient = value / divisor;
+    (quotient - quotient.round()).abs() <= 1e-9 * quotient.abs().max(1.0)
+}
+
+// Whether `item` equals `value`, numbers being compared by value (`1` equals `1.0`)
+fn matches(item: &JsonItem, value: &PlainValue) -> bool {
+    match value {
+        PlainValue::Null => { item.json_type == JsonNull }
+        PlainValue::Bool(value) => { item.as_bool() == Some(*value) }
+        PlainValue::Integer(value) => { item.as_i128() == Some(i128::from(*value)) || item.as_f64() == Some(*value as f64) && item.as_i128().is_none() }
+        PlainValue::Float(value) => { item.as_f64() == Some(*value) }
+        PlainValue::String(value) => { item.json_type == JsonString && item.as_unescaped().is_some_and(|text| text == value.as_str()) }
+        PlainValue::Array(values) => {
+            item.elements().is_some_and(|elements| elements.count() == values.len())
+                && item.elements().into_iter().flatten().zip(values).all(|(element, value)| matches(element, value))
+        }
+        PlainValue::Object(values) => {
+            item.entries().is_some_and(|entries| entries.count() == values.len())
+                && item.entries().into_iter().flatten().all(|(key, element)| values.get(key_text(key).as_ref()).is_some_and(|value| matches(element, value)))
+        }
+    }
+}
+
+// Location of a value in the validated document
+enum Segment<'v> {
+    Key(Cow<'v, str>),
+    Index(usize),
+}
+
+fn render(path: &[Segment<'_>]) -> String {
+    let mut pointer = String::new();
+    for segment in path {
+        pointer.push('/');
+        match segment {
+            Segment::Key(key) => { pointer.push_str(&key.replace('~', "~0").replace('/', "~1")); }
+            Segment::Index(index) => { pointer.push_str(&index.to_string()); }
+        }
+    }
+    pointer
+}
+
+// Schema documents being compiled, subschemas being compiled once each from a work list
+struct Compiler<'r> {
+    schemas: &'r [(&'r str, &'r str, JsonItem)],
+    names: HashMap<&'r str, usize>,
+    nodes: Vec<Vec<Keyword>>,
+    compiled: HashMap<*const JsonItem, usize>,
+    pending: Vec<(usize, &'r JsonItem, usize)>,
+}
+
+impl<'r> Compiler<'r> {
+    // Node of a subschema, allocated and queued for compilation on first use
+    fn node(&mut self, schema: usize, item: &'r JsonItem) -> Result<usize, SchemaError> {
+        if !matches!(item.json_type, JsonMap | JsonTrue | JsonFalse) {
+            return Err(self.invalid(schema, item));
+        }
+        if let Some(&node) = self.compiled.get(&(item as *const JsonItem)) {
+            return Ok(node);
+        }
+        self.nodes.push(Vec::new());
+        let node = self.nodes.len() - 1;
+        self.compiled.insert(item, node);
+        self.pending.push((schema, item, node));
+        Ok(node)
+    }
+
+    fn invalid(&self, schema: usize, item: &JsonItem) -> SchemaError {
+        let (name, source, _) = &self.schemas[schema];
+        let bytes = source.as_bytes();
+        let index = item.slice.offset_in(bytes) - usize::from(item.json_type == JsonString);
+        SchemaError::Json { schema: name.to_string(), error: JsonError::with_kind(index, ErrorKind::UnexpectedValue).located(bytes) }
+    }
+
+    fn number(&self, schema: usize, item: &JsonItem) -> Result<f64, SchemaError> {
+        item.as_f64().ok_or_else(|| self.invalid(schema, item))
+    }
+
+    fn count(&self, schema: usize, item: &JsonItem) -> Result<usize, SchemaError> {
+        item.as_i128().and_then(|count| usize::try_from(count).ok()).ok_or_else(|| self.invalid(schema, item))
+    }
+
+    fn string(&self, schema: usize, item: &JsonItem) -> Result<String, SchemaError> {
+        item.as_unescaped().map(Cow::into_owned).ok_or_else(|| self.invalid(schema, item))
+    }
+
+    fn subschemas(&mut self, schema: usize, item: &'r JsonItem) -> Result<Vec<usize>, SchemaError> {
+        match item.elements() {
+            Some(elements) if item.elements().is_some_and(|e| e.count() > 0) => { elements.map(|element| self.node(schema, element)).collect() }
+            _ => { Err(self.invalid(schema, item)) }
+        }
+    }
+
+    fn reference(&mut self, schema: usize, item: &'r JsonItem) -> Result<usize, SchemaError> {
+        let reference = self.string(schema, item)?;
+        let unresolved = || SchemaError::UnresolvedRef { schema: self.schemas[schema].0.to_string(), reference: reference.clone() };
+        let (name, fragment) = reference.split_once('#').unwrap_or((&reference, ""));
+        let target = if name.is_empty() { schema } else { *self.names.get(name).ok_or_else(unresolved)? };
+        let item = self.schemas[target].2.pointer(fragment);
+        if !item.exists() {
+            return Err(unresolved());
+        }
+        self.node(target, item)
+    }
+
+    fn compile(&mut self, schema: usize, item: &'r JsonItem) -> Result<Vec<Keyword>, SchemaError> {
+        let entries = match item.json_type {
+            JsonTrue => { return Ok(Vec::new()); }
+            JsonFalse => { return Ok(vec![Keyword::Fail]); }
+            _ => { item.entries().into_iter().flatten() }
+        };
+        let mut keywords = Vec::new();
+        let (mut named, mut additional) = (None, None);
+        for (key, value) in entries {
+            let keyword = match key.as_str() {
+                "type" => {
+                    let names = match value.elements() {
+                        Some(elements) => { elements.collect::<Vec<_>>() }
+                        None => { vec![value] }
+                    };
+                    let mut bits = 0;
+                    for name in names {
+                        bits |= match name.as_unescaped().as_deref() {
+                            Some("null") => { NULL }
+                            Some("boolean") => { BOOLEAN }
+                            Some("object") => { OBJECT }
+                            Some("array") => { ARRAY }
+                            Some("number") => { NUMBER | INTEGER }
+                            Some("string") => { STRING }
+                            Some("integer") => { INTEGER }
+                            _ => { return Err(self.invalid(schema, name)); }
+                        };
+                    }
+                    Keyword::Type(bits)
+                }
+                "enum" => { Keyword::Enum(value.elements().ok_or_else(|| self.invalid(schema, value))?.map(JsonItem::to_plain).collect()) }
+                "const" => { Keyword::Const(value.to_plain()) }
+                "minimum" => { Keyword::Minimum(self.number(schema, value)?) }
+                "maximum" => { Keyword::Maximum(self.number(schema, value)?) }
+                "exclusiveMinimum" => { Keyword::ExclusiveMinimum(self.number(schema, value)?) }
+                "exclusiveMaximum" => { Keyword::ExclusiveMaximum(self.number(schema, value)?) }
+                "multipleOf" => {
+                    match self.number(schema, value)? {
+                        divisor if divisor > 0.0 => { Keyword::MultipleOf(divisor) }
+                        _ => { return Err(self.invalid(schema, value)); }
+                    }
+                }
+                "minLength" => { Keyword::MinLength(self.count(schema, value)?) }
+                "maxLength" => { Keyword::MaxLength(self.count(schema, value)?) }
+                "minItems" => { Keyword::MinItems(self.count(schema, value)?) }
+                "maxItems" => { Keyword::MaxItems(self.count(schema, value)?) }
+                "minProperties" => { Keyword::MinProperties(self.count(schema, value)?) }
+                "maxProperties" => { Keyword::MaxProperties(self.count(schema, value)?) }
+                "uniqueItems" => {
+                    match value.as_bool() {
+                        Some(true) => { Keyword::UniqueItems }
+                        Some(false) => { continue; }
+                        None => { return Err(self.invalid(schema, value)); }
+                    }
+                }
+                "items" => { Keyword::Items(self.node(schema, value)?) }
+                "required" => {
+                    let names = value.elements().ok_or_else(|| self.invalid(schema, value))?;
+                    Keyword::Required(names.map(|name| self.string(schema, name)).collect::<Result<_, _>>()?)
+                }
+                "properties" => {
+                    let properties = value.entries().ok_or_else(|| self.invalid(schema, value))?;
+                    named = Some(properties.map(|(name, property)| Ok((key_text(name).into_owned(), self.node(schema, property)?))).collect::<Result<Vec<_>, _>>()?);
+                    continue;
+                }
+                "additionalProperties" => {
+                    additional = Some(self.node(schema, value)?);
+                    continue;
+                }
+                "allOf" => { Keyword::AllOf(self.subschemas(schema, value)?) }
+                "anyOf" => { Keyword::AnyOf(self.subschemas(schema, value)?) }
+                "oneOf" => { Keyword::OneOf(self.subschemas(schema, value)?) }
+                "not" => { Keyword::Not(self.node(schema, value)?) }
+                "$ref" => { Keyword::Ref(self.reference(schema, value)?) }
+                name if UNSUPPORTED.contains(&name) => {
+                    return Err(SchemaError::Unsupported { schema: self.schemas[schema].0.to_string(), keyword: name.to_owned() });
+                }
+                // Annotations (e.g. `title`, `format`) and definitions, compiled when referenced
+                _ => { continue; }
+            };
+            keywords.push(keyword);
+        }
+        if named.is_some() || additional.is_some() {
+            keywords.push(Keyword::Properties { named: named.unwrap_or_default(), additional });
+        }
+        Ok(keywords)
+    }
+}
+
+/// Named JSON schemas compiled once, e.g. at startup, to validate documents without parsing schemas again.
+/// References (`$ref`) are resolved at compilation, and may designate locations in the same schema (`#/$defs/item`),
+/// or other schemas of the registry by name (`address` or `address#/$defs/line`). Recursive schemas are supported.
+///
+/// Supported keywords are `type`, `enum`, `const`, `minimum`, `maximum`, `exclusiveMinimum`, `exclusiveMaximum`, `multipleOf`,
+/// `minLength`, `maxLength`, `minItems`, `maxItems`, `uniqueItems`, `items` (single schema), `minProperties`, `maxProperties`,
+/// `required`, `properties`, `additionalProperties`, `allOf`, `anyOf`, `oneOf`, `not` and `$ref`.
+/// Schemas using other keywords affecting validation (e.g. `pattern` or `if`) are rejected, and annotations are ignored.
+///
+/// A registry holds no reference to schema sources, and can be shared between threads.
+///
+/// # Example
+///
+/// ```rust
+/// use jsonic::schema::SchemaRegistry;
+///
+/// let registry = SchemaRegistry::compile([
+///     ("user", "{\"type\": \"object\", \"required\": [\"name\"], \"properties\": {\"name\": {\"type\": \"string\"}, \"tags\": {\"$ref\": \"tags\"}}}"),
+///     ("tags", "{\"type\": \"array\", \"items\": {\"type\": \"string\", \"maxLength\": 8}}"),
+/// ]).unwrap();
+/// let user = registry.validator("user").unwrap();
+///
+/// assert!(user.is_valid(&jsonic::parse("{\"name\": \"ann\", \"tags\": [\"admin\"]}").unwrap()));
+/// let violations = user.validate(&jsonic::parse("{\"tags\": [\"administrator\"]}").unwrap()).unwrap_err();
+/// assert_eq!(violations.iter().map(|v| (v.get_path(), v.get_keyword())).collect::<Vec<_>>(), [("", "required"), ("/tags/0", "maxLength")]);
+/// ```
+#[derive(Debug)]
+pub struct SchemaRegistry {
+    nodes: Vec<Vec<Keyword>>,
+    roots: HashMap<String, usize>,
+}
+
+impl SchemaRegistry {
+    /// Compiles schemas given as `(name, source)` pairs, which may reference one another
+    pub fn compile<'a>(schemas: impl IntoIterator<Item = (&'a str, &'a str)>) -> Result<SchemaRegistry, SchemaError> {
+        let mut parsed = Vec::new();
+        for (name, source) in schemas {
+            let root = parse_strict(source).map_err(|error| SchemaError::Json { schema: name.to_owned(), error })?;
+            parsed.push((name, source, root));
+        }
+        let mut names = HashMap::new();
+        for (index, (name, _, _)) in parsed.iter().enumerate() {
+            if names.insert(*name, index).is_some() {
+                return Err(SchemaError::DuplicateName(name.to_string()));
+            }
+        }
+        let mut compiler = Compiler { schemas: &parsed, names, nodes: Vec::new(), compiled: HashMap::new(), pending: Vec::new() };
+        let mut roots = HashMap::new();
+        for (index, (name, _, root)) in parsed.iter().enumerate() {
+            roots.insert(name.to_string(), compiler.node(index, root)?);
+        }
+        while let Some((schema, item, node)) = compiler.pending.pop() {
+            compiler.nodes[node] = compiler.compile(schema, item)?;
+        }
+        Ok(SchemaRegistry { nodes: compiler.nodes, roots })
+    }
+
+    /// Names of the schemas in the registry, in no particular order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.roots.keys().map(String::as_str)
+    }
+
+    /// Returns the validator of the schema named `name`, or `None` if there is no such schema
+    pub fn validator(&self, name: &str) -> Option<Validator<'_>> {
+        self.roots.get(name).map(|&node| Validator { registry: self, node })
+    }
+
+    // Checks `item` against a node, reporting failures to `violations` if given, or stopping at the first one otherwise.
+    // `hops` counts the subschemas applied to the same value, to stop on reference cycles that never reach a child value.
+    fn check<'v>(&self, node: usize, item: &'v JsonItem, hops: usize, path: &mut Vec<Segment<'v>>, violations: &mut Option<&mut Vec<Violation>>) -> bool {
+        if hops > self.nodes.len() {
+            if let Some(violations) = violations {
+                violations.push(Violation { path: render(path), keyword: "$ref" });
+            }
+            return false;
+        }
+        let mut valid = true;
+        for keyword in &self.nodes[node] {
+            // Keywords applying subschemas report their own failures
+            let (passed, reported) = match keyword {
+                Keyword::Fail => { (false, false) }
+                Keyword::Type(bits) => { (bits & type_bits(item) != 0, false) }
+                Keyword::Enum(values) => { (values.iter().any(|value| matches(item, value)), false) }
+                Keyword::Const(value) => { (matches(item, value), false) }
+                Keyword::Minimum(minimum) => { (item.as_f64().is_none_or(|value| value >= *minimum), false) }
+                Keyword::Maximum(maximum) => { (item.as_f64().is_none_or(|value| value <= *maximum), false) }
+                Keyword::ExclusiveMinimum(minimum) => { (item.as_f64().is_none_or(|value| value > *minimum), false) }
+                Keyword::ExclusiveMaximum(maximum) => { (item.as_f64().is_none_or(|value| value < *maximum), false) }
+                Keyword::MultipleOf(divisor) => { (item.as_f64().is_none_or(|value| is_multiple(value, *divisor)), false) }
+                Keyword::MinLength(min) => { (string_len(item).is_none_or(|len| len >= *min), false) }
+                Keyword::MaxLength(max) => { (string_len(item).is_none_or(|len| len <= *max), false) }
+                Keyword::MinItems(min) => { (item.elements().is_none_or(|elements| elements.count() >= *min), false) }
+                Keyword::MaxItems(max) => { (item.elements().is_none_or(|elements| elements.count() <= *max), false) }
+                Keyword::MinProperties(min) => { (item.entries().is_none_or(|entries| entries.count() >= *min), false) }
+                Keyword::MaxProperties(max) => { (item.entries().is_none_or(|entries| entries.count() <= *max), false) }
+                Keyword::UniqueItems => {
+                    let elements = item.elements().into_iter().flatten().collect::<Vec<_>>();
+                    let unique = elements.iter().enumerate().all(|(index, a)| elements[..index].iter().all(|b| !a.equals(b, FloatEquality::Bitwise)));
+                    (unique, false)
+                }
+                Keyword::Required(names) => {
+                    let present = |name: &String| item.entries().into_iter().flatten().any(|(key, _)| key_text(key) == name.as_str());
+                    (item.json_type != JsonMap || names.iter().all(present), false)
+                }
+                Keyword::Items(items) => {
+                    let mut passed = true;
+                    for (index, element) in item.elements().into_iter().flatten().enumerate() {
+                        path.push(Segment::Index(index));
+                        passed &= self.check(*items, element, 0, path, violations);
+                        path.pop();
+                        if !passed && violations.is_none() {
+                            break;
+                        }
+                    }
+                    (passed, true)
+                }
+                Keyword::Properties { named, additional } => {
+                    let mut passed = true;
+                    for (key, value) in item.entries().into_iter().flatten() {
+                        let name = key_text(key);
+                        let property = named.iter().find(|(n, _)| *n == name).map(|(_, node)| *node);
+                        path.push(Segment::Key(name));
+                        passed &= match (property, additional) {
+                            (Some(property), _) => { self.check(property, value, 0, path, violations) }
+                            (None, Some(additional)) => {
+                                let allowed = self.check(*additional, value, 0, path, &mut None);
+                                if let (false, Some(violations)) = (allowed, violations.as_mut()) {
+                                    violations.push(Violation { path: render(path), keyword: "additionalProperties" });
+                                }
+                                allowed
+                            }
+                            (None, None) => { true }
+                        };
+                        path.pop();
+                        if !passed && violations.is_none() {
+                            break;
+                        }
+                    }
+                    (passed, true)
+                }
+                Keyword::AllOf(nodes) => {
+                    let mut passed = true;
+                    for node in nodes {
+                        passed &= self.check(*node, item, hops + 1, path, violations);
+                        if !passed && violations.is_none() {
+                            break;
+                        }
+                    }
+                    (passed, true)
+                }
+                Keyword::Ref(node) => { (self.check(*node, item, hops + 1, path, violations), true) }
+                Keyword::AnyOf(nodes) => { (nodes.iter().any(|node| self.check(*node, item, hops + 1, path, &mut None)), false) }
+                Keyword::OneOf(nodes) => { (nodes.iter().filter(|node| self.check(**node, item, hops + 1, path, &mut None)).count() == 1, false) }
+                Keyword::Not(node) => { (!self.check(*node, item, hops + 1, path, &mut None), false) }
+            };
+            if !passed {
+                valid = false;
+                match violations {
+                    Some(violations) if !reported => { violations.push(Violation { path: render(path), keyword: keyword.name() }); }
+                    Some(_) => {}
+                    None => { return false; }
+                }
+            }
+        }
+        valid
+    }
+}
+
+/// Validator of one schema of a [`SchemaRegistry`]
+#[derive(Debug, Clone, Copy)]
+pub struct Validator<'a> {
+    registry: &'a SchemaRegistry,
+    node: usize,
+}
+
+impl<'a> Validator<'a> {
+    /// Validates `item`, returning all the violations found if it does not conform to the schema.
+    /// Subschemas of `anyOf`, `oneOf` and `not` are reported as a whole, by the name of the keyword.
+    pub fn validate(&self, item: &JsonItem) -> Result<(), Vec<Violation>> {
+        let mut violations = Vec::new();
+        self.registry.check(self.node, item, 0, &mut Vec::new(), &mut Some(&mut violations));
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    /// Whether `item` conforms to the schema, stopping at the first violation
+    pub fn is_valid(&self, item: &JsonItem) -> bool {
+        self.registry.check(self.node, item, 0, &mut Vec::new(), &mut None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse_strict;
+    use crate::schema::{SchemaError, SchemaRegistry};
+
+    fn violations(registry: &SchemaRegistry, name: &str, json: &str) -> Vec<(String, &'static str)> {
+        let item = parse_strict(json).unwrap();
+        match registry.validator(name).unwrap().validate(&item) {
+            Ok(()) => { Vec::new() }
+            Err(violations) => { violations.into_iter().map(|v| (v.get_path().to_owned(), v.get_keyword())).collect() }
+        }
+    }
+
+    #[test]
+    fn check_keywords() {
+        let registry = SchemaRegistry::compile([
+            ("number", "{\"type\": \"number\", \"minimum\": 0, \"exclusiveMaximum\": 10, \"multipleOf\": 0.1}"),
+            ("text", "{\"type\": [\"string\", \"null\"], \"minLength\": 2, \"maxLength\": 3}"),
+            ("list", "{\"type\": \"array\", \"items\": {\"type\": \"integer\"}, \"minItems\": 1, \"uniqueItems\": true}"),
+            ("choice", "{\"enum\": [1, \"a\", [null], {\"k\": true}]}"),
+            ("object", "{\"properties\": {\"a~b\": {\"const\": 1}}, \"additionalProperties\": false, \"maxProperties\": 2}"),
+            ("combined", "{\"anyOf\": [{\"type\": \"string\"}, {\"minimum\": 5}], \"not\": {\"const\": 7}, \"oneOf\": [{\"type\": \"integer\"}, {\"type\": \"string\", \"maxLength\": 1}]}"),
+        ]).unwrap();
+        assert!(violations(&registry, "number", "0.3").is_empty());
+        assert_eq!(violations(&registry, "number", "10"), [("".to_owned(), "exclusiveMaximum")]);
+        assert_eq!(violations(&registry, "number", "-0.05"), [("".to_owned(), "minimum"), ("".to_owned(), "multipleOf")]);
+        assert_eq!(violations(&registry, "number", "\"1\""), [("".to_owned(), "type")]);
+        assert!(violations(&registry, "text", "\"\\u00e9t\"").is_empty() && violations(&registry, "text", "null").is_empty());
+        assert_eq!(violations(&registry, "text", "\"abcd\""), [("".to_owned(), "maxLength")]);
+        assert!(violations(&registry, "list", "[1, 2.0]").is_empty());
+        assert_eq!(violations(&registry, "list", "[1, 1.5, 1]"), [("/1".to_owned(), "type"), ("".to_owned(), "uniqueItems")]);
+        assert_eq!(violations(&registry, "list", "[]"), [("".to_owned(), "minItems")]);
+        assert!(violations(&registry, "choice", "1.0").is_empty() && violations(&registry, "choice", "{\"k\": true}").is_empty());
+        assert_eq!(violations(&registry, "choice", "[null, null]"), [("".to_owned(), "enum")]);
+        assert!(violations(&registry, "object", "{\"a~b\": 1}").is_empty());
+        assert_eq!(violations(&registry, "object", "{\"a~b\": 2, \"c/d\": 0, \"e\": 0}"),
+                   [("".to_owned(), "maxProperties"), ("/a~0b".to_owned(), "const"), ("/c~1d".to_owned(), "additionalProperties"), ("/e".to_owned(), "additionalProperties")]);
+        assert!(violations(&registry, "combined", "6").is_empty() && violations(&registry, "combined", "\"a\"").is_empty());
+        assert_eq!(violations(&registry, "combined", "7"), [("".to_owned(), "not")]);
+        assert_eq!(violations(&registry, "combined", "4"), [("".to_owned(), "anyOf")]);
+        assert_eq!(violations(&registry, "combined", "\"ab\""), [("".to_owned(), "oneOf")]);
+    }
+
+    #[test]
+    fn resolve_references() {
+        let registry = SchemaRegistry::compile([
+            ("tree", "{\"$defs\": {\"node\": {\"type\": \"object\", \"required\": [\"value\"], \"properties\": {\"children\": {\"type\": \"array\", \"items\": {\"$ref\": \"#/$defs/node\"}}, \"value\": {\"$ref\": \"leaf#/$defs/value\"}}}}, \"$ref\": \"#/$defs/node\"}"),
+            ("leaf", "{\"$defs\": {\"value\": {\"type\": \"integer\"}}}"),
+            ("loop", "{\"allOf\": [{\"$ref\": \"#\"}]}"),
+        ]).unwrap();
+        assert!(violations(&registry, "tree", "{\"value\": 1, \"children\": [{\"value\": 2, \"children\": []}]}").is_empty());
+        assert_eq!(violations(&registry, "tree", "{\"value\": 1, \"children\": [{\"value\": \"x\"}, {}]}"),
+                   [("/children/0/value".to_owned(), "type"), ("/children/1".to_owned(), "required")]);
+        assert!(!registry.validator("loop").unwrap().is_valid(&parse_strict("1").unwrap()));
+        assert_eq!(violations(&registry, "loop", "1"), [("".to_owned(), "$ref")]);
+        assert!(registry.validator("missing").is_none());
+        let mut names = registry.names().collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, ["leaf", "loop", "tree"]);
+    }
+
+    #[test]
+    fn reject_schemas() {
+        let error = |name: &str, source: &str| SchemaRegistry::compile([(name, source)]).unwrap_err();
+        assert!(matches!(error("a", "{\"type\": 1"), SchemaError::Json { schema, .. } if schema == "a"));
+        assert!(matches!(error("a", "{\"type\": \"text\"}"), SchemaError::Json { error, .. } if error.get_index() == 9));
+        assert!(matches!(error("a", "{\"minLength\": -1}"), SchemaError::Json { error, .. } if error.get_index() == 14));
+        assert!(matches!(error("a", "{\"items\": 3}"), SchemaError::Json { .. }));
+        assert!(matches!(error("a", "{\"pattern\": \"^a\"}"), SchemaError::Unsupported { keyword, .. } if keyword == "pattern"));
+        assert!(matches!(error("a", "{\"$ref\": \"b#/x\"}"), SchemaError::UnresolvedRef { reference, .. } if reference == "b#/x"));
+        assert!(matches!(error("a", "{\"$ref\": \"#/$defs/x\"}"), SchemaError::UnresolvedRef { .. }));
+        assert!(matches!(SchemaRegistry::compile([("a", "true"), ("a", "false")]), Err(SchemaError::DuplicateName(name)) if name == "a"));
+    }
+}
@@ -0,0 +1,135 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::iter::FusedIterator;
+
+use crate::json_error::JsonError;
+use crate::json_item::JsonItem;
+use crate::options::ParseOptions;
+use crate::parse_complete;
+
+const RECORD_SEPARATOR: char = '\u{1E}';
+
+/// Error found in a record of a JSON text sequence
+#[derive(Debug)]
+pub struct RecordError {
+    record: usize,
+    offset: usize,
+    error: JsonError,
+}
+
+impl RecordError {
+    /// Record number, starting at 1
+    pub fn get_record(&self) -> usize {
+        self.record
+    }
+
+    /// Byte offset of the start of the record (after its RS) in source content
+    pub fn get_offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Parsing error, with its index relative to the start of the record
+    pub fn get_error(&self) -> &JsonError {
+        &self.error
+    }
+}
+
+impl Display for RecordError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "record {}: {}", self.record, self.error)
+    }
+}
+
+impl Error for RecordError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// JSON text sequence records iterator, see [`parse_seq`]
+pub struct JsonSeq<'a> {
+    source: &'a str,
+    offset: usize,
+    record: usize,
+}
+
+impl<'a> Iterator for JsonSeq<'a> {
+    type Item = Result<JsonItem, RecordError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.offset < self.source.len() {
+            let rest = &self.source[self.offset..];
+            let Some(start) = rest.find(RECORD_SEPARATOR) else {
+                // Trailing data not introduced by an RS
+                self.offset = self.source.len();
+                break;
+            };
+            let rest = &rest[start + 1..];
+            let end = rest.find(RECORD_SEPARATOR).unwrap_or(rest.len());
+            let text = &rest[..end];
+            let offset = self.offset + start + 1;
+            self.offset = offset + end;
+
+            // Consecutive RS delimit no record
+            if text.bytes().all(|b| b.is_ascii_whitespace()) {
+                continue;
+            }
+            self.record += 1;
+            return Some(parse_complete(text, &ParseOptions::JSON).map_err(|error| RecordError { record: self.record, offset, error }));
+        }
+        None
+    }
+}
+
+impl<'a> FusedIterator for JsonSeq<'a> {}
+
+/// Parses a JSON text sequence (RFC 7464, `application/json-seq`), yielding one result per record.
+/// Each record is introduced by an RS (`0x1E`) character and must hold a complete JSON object or array,
+/// optionally surrounded by whitespace (normally a final LF).
+/// As the RFC requires, a malformed record is reported and parsing resumes at the next RS;
+/// data before the first RS and empty records are skipped.
+///
+/// # Arguments
+/// * `source` - Text content to be parsed
+///
+/// # Example
+///
+/// ```rust
+/// let seq = "\u{1E}{\"id\": 1}\n\u{1E}{\"id\": \n\u{1E}{\"id\": 3}\n";
+///
+/// let records = jsonic::seq::parse_seq(seq).collect::<Vec<_>>();
+/// assert_eq!(records.len(), 3);
+/// assert_eq!(records[0].as_ref().unwrap()["id"].as_i128(), Some(1));
+/// assert_eq!(records[1].as_ref().unwrap_err().get_record(), 2);
+/// assert_eq!(records[2].as_ref().unwrap()["id"].as_i128(), Some(3));
+/// ```
+pub fn parse_seq(source: &str) -> JsonSeq<'_> {
+    JsonSeq { source, offset: 0, record: 0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::seq::parse_seq;
+
+    #[test]
+    fn parse_records() {
+        let records = parse_seq("garbage\u{1E}[1]\n\u{1E}\u{1E}\n\u{1E} {\"a\": [2]} \r\n\u{1E}[3]").collect::<Vec<_>>();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].as_ref().unwrap()[0].as_i128(), Some(1));
+        assert_eq!(records[1].as_ref().unwrap()["a"][0].as_i128(), Some(2));
+        assert_eq!(records[2].as_ref().unwrap()[0].as_i128(), Some(3));
+    }
+
+    #[test]
+    fn resynchronize() {
+        let records = parse_seq("\u{1E}[1, \n\u{1E}[2] [3]\n\u{1E}{\"ok\": true}\n").collect::<Vec<_>>();
+        assert_eq!(records.len(), 3);
+        let error = records[0].as_ref().unwrap_err();
+        assert_eq!((error.get_record(), error.get_offset()), (1, 1));
+        let error = records[1].as_ref().unwrap_err();
+        assert_eq!((error.get_record(), error.get_offset(), error.get_error().get_index()), (2, 7, 4));
+        assert_eq!(records[2].as_ref().unwrap()["ok"].as_bool(), Some(true));
+        assert_eq!(parse_seq("").count(), 0);
+        assert_eq!(parse_seq("[1]\n").count(), 0);
+    }
+}
@@ -0,0 +1,670 @@
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::io;
+use std::io::Write;
+
+#[cfg(feature = "extended")]
+use crate::comments::{layout, Layout};
+use crate::document::{JsonDocument, Progress, ProgressWriter};
+use crate::json_item::JsonItem;
+use crate::key::Key;
+use crate::json_type::JsonType::{Empty, JsonArray, JsonFalse, JsonMap, JsonNull, JsonNumber, JsonString, JsonTrue};
+use crate::number::{is_json_number, write_number};
+use crate::text::{escape_into, unescape};
+
+// Checks that raw string content only holds characters and escapes allowed in JSON strings
+fn is_json_string(raw: &str) -> bool {
+    let bytes = raw.as_bytes();
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'"' | 0x00..=0x1F => { return false; }
+            b'\\' => {
+                match bytes.get(index + 1) {
+                    Some(b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't') => { index += 2; }
+                    Some(b'u') if bytes.get(index + 2..index + 6).is_some_and(|hex| hex.iter().all(u8::is_ascii_hexdigit)) => { index += 6; }
+                    _ => { return false; }
+                }
+            }
+            _ => { index += 1; }
+        }
+    }
+    true
+}
+
+/// How strings are escaped in JSON output, see [`JsonItem::to_json_with`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Escaping {
+    /// Escapes only what JSON requires: quotes, backslashes and control characters
+    #[default]
+    Json,
+    /// Also escapes `<`, `>`, `&`, U+2028 and U+2029 as `\uXXXX`, so that the output can be embedded as is
+    /// in HTML `<script>` elements and in JavaScript source, where `</script>` or a raw line separator
+    /// in a string would end it early
+    HtmlSafe,
+}
+
+/// Indentation of pretty-printed JSON, see [`JsonItem::to_json_pretty`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Indent {
+    /// This number of spaces per nesting level
+    Spaces(usize),
+    /// This number of tabs per nesting level
+    Tabs(usize),
+}
+
+impl Default for Indent {
+    fn default() -> Self {
+        Indent::Spaces(2)
+    }
+}
+
+// Writes raw string content as a JSON string, copying it as is when already valid JSON
+pub(crate) fn write_string(raw: &str, output: &mut String) {
+    if is_json_string(raw) {
+        output.push('"');
+        output.push_str(raw);
+        output.push('"');
+    } else {
+        escape_into(&unescape(raw).unwrap_or_else(|| raw.into()), output);
+    }
+}
+
+// Writes raw string content as a JSON string, escaped according to `options`
+fn write_string_with(raw: &str, options: SerializeOptions, output: &mut String) {
+    let start = output.len();
+    write_string(raw, output);
+    apply_escaping(start, options, output);
+}
+
+// Writes text as a JSON string, escaped according to `options`
+pub(crate) fn write_text(text: &str, options: SerializeOptions, output: &mut String) {
+    let start = output.len();
+    escape_into(text, output);
+    apply_escaping(start, options, output);
+}
+
+// Escapes characters of the JSON string written from `start` in `output`, according to `options`
+fn apply_escaping(start: usize, options: SerializeOptions, output: &mut String) {
+    let html_safe = options.escaping == Escaping::HtmlSafe;
+    let line_separators = html_safe || options.escape_line_separators;
+    let escaped = |c: char| {
+        (html_safe && matches!(c, '<' | '>' | '&')) || (line_separators && matches!(c, '\u{2028}' | '\u{2029}'))
+            || (options.ascii_only && !c.is_ascii()) || (options.escape_slashes && c == '/')
+    };
+    if output[start..].contains(escaped) {
+        // Escape sequences already written are copied as is, so that the `/` of `\/` is not escaped twice
+        let text = output.split_off(start);
+        let mut chars = text.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => {
+                    output.push(c);
+                    output.extend(chars.next());
+                }
+                '/' if options.escape_slashes => { output.push_str("\\/") }
+                c if escaped(c) => {
+                    for unit in c.encode_utf16(&mut [0; 2]) {
+                        output.push_str(&format!("\\u{:04x}", unit));
+                    }
+                }
+                c => { output.push(c) }
+            }
+        }
+    }
+}
+
+/// Appends the compact JSON text of `item` to `output`. Source text is copied as is when it is valid JSON,
+/// extended syntax (e.g. JSON5 numbers or single-quoted escapes) is converted to standard JSON.
+/// Non-existent items and non-finite numbers are written as `null`.
+pub(crate) fn write_compact(item: &JsonItem, output: &mut String) {
+    write_compact_with(item, Escaping::Json, output);
+}
+
+/// Output options, built by chaining setters from compact standard JSON ([`SerializeOptions::new`]),
+/// see [`JsonItem::to_json_using`]
+///
+/// # Example
+///
+/// ```rust
+/// use jsonic::{Indent, SerializeOptions};
+///
+/// let parsed = jsonic::parse("{\"b\": 1, \"a\": {\"d\": 2, \"c\": 3}}").unwrap();
+///
+/// let options = SerializeOptions::new().indent(Indent::Spaces(1)).sort_keys(true);
+/// assert_eq!(parsed.to_json_using(&options), "{\n \"a\": {\n  \"c\": 3,\n  \"d\": 2\n },\n \"b\": 1\n}");
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SerializeOptions {
+    escaping: Escaping,
+    // Compact output when there is no indentation
+    pub(crate) indent: Option<Indent>,
+    sort_keys: bool,
+    ascii_only: bool,
+    escape_line_separators: bool,
+    escape_slashes: bool,
+    #[cfg(feature = "extended")]
+    comments: bool,
+}
+
+impl SerializeOptions {
+    /// Options for compact standard JSON, as written by [`to_json`](JsonItem::to_json)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how strings and keys are escaped ([`Escaping::Json`] by default)
+    pub fn escaping(mut self, escaping: Escaping) -> Self {
+        self.escaping = escaping;
+        self
+    }
+
+    /// Pretty-prints output with `indent` per nesting level, see [`to_json_pretty`](JsonItem::to_json_pretty) (compact by default)
+    pub fn indent(mut self, indent: Indent) -> Self {
+        self.indent = Some(indent);
+        self
+    }
+
+    /// Escapes all non-ASCII characters in strings and keys as `\uXXXX`, characters beyond the Basic Multilingual Plane
+    /// as surrogate pairs, for consumers that only handle ASCII (disabled by default)
+    pub fn ascii_only(mut self, enabled: bool) -> Self {
+        self.ascii_only = enabled;
+        self
+    }
+
+    /// Escapes U+2028 and U+2029 as `\u2028` and `\u2029`, which end string literals in JavaScript engines
+    /// predating ES2019 (disabled by default, enabled by [`Escaping::HtmlSafe`])
+    pub fn escape_line_separators(mut self, enabled: bool) -> Self {
+        self.escape_line_separators = enabled;
+        self
+    }
+
+    /// Escapes `/` as `\/`, as some encoders do, so that the output cannot hold `</` (disabled by default)
+    pub fn escape_slashes(mut self, enabled: bool) -> Self {
+        self.escape_slashes = enabled;
+        self
+    }
+
+    /// Writes object members sorted by key, whatever their order in the source, so that output is reproducible
+    /// and diffs well. Keys are compared by their decoded text, character by character (disabled by default).
+    pub fn sort_keys(mut self, enabled: bool) -> Self {
+        self.sort_keys = enabled;
+        self
+    }
+
+    /// Writes the comments of the source text attached to elements and members, see [`JsonItem::comments_of`],
+    /// when pretty-printing: comments preceding an item on lines of their own, comments following it after its comma,
+    /// and comments before a closing bracket on lines of their own. Comments before and after the root item are
+    /// not written, see [`JsonDocument::root_comments`]. Compact output has no comments (disabled by default).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jsonic::{Indent, SerializeOptions};
+    ///
+    /// let settings = jsonic::parse_jsonc("{\"editor.tabSize\": 2, // team style\n/* \"files.eol\": \"\\n\" */}").unwrap();
+    ///
+    /// let options = SerializeOptions::new().indent(Indent::Spaces(4)).comments(true);
+    /// assert_eq!(settings.to_json_using(&options), "{\n    \"editor.tabSize\": 2 // team style\n    /* \"files.eol\": \"\\n\" */\n}");
+    /// ```
+    #[cfg(feature = "extended")]
+    pub fn comments(mut self, enabled: bool) -> Self {
+        self.comments = enabled;
+        self
+    }
+}
+
+fn write_compact_with(item: &JsonItem, escaping: Escaping, output: &mut String) {
+    write_json(item, SerializeOptions::new().escaping(escaping), 0, &mut Output { text: output, writer: None, result: Ok(()) });
+}
+
+pub(crate) const WRITE_CHUNK_SIZE: usize = 64 * 1024;
+
+// Destination of JSON output: text is appended to `text`, and handed over to `writer` if any by chunks,
+// so that large output is never held in memory at once
+pub(crate) struct Output<'o> {
+    pub(crate) text: &'o mut String,
+    pub(crate) writer: Option<&'o mut dyn Write>,
+    // First error returned by the writer, after which text is discarded
+    pub(crate) result: io::Result<()>,
+}
+
+impl Output<'_> {
+    fn flush(&mut self, min_len: usize) {
+        if let Some(writer) = &mut self.writer {
+            if self.text.len() >= min_len {
+                if self.result.is_ok() {
+                    self.result = writer.write_all(self.text.as_bytes());
+                }
+                self.text.clear();
+            }
+        }
+    }
+}
+
+// Writes the JSON text of `item` to `writer`, through a buffer of a few chunks
+fn write_to<W: Write>(item: &JsonItem, options: SerializeOptions, mut writer: W) -> io::Result<()> {
+    let mut text = String::with_capacity(2 * WRITE_CHUNK_SIZE);
+    let mut output = Output { text: &mut text, writer: Some(&mut writer), result: Ok(()) };
+    write_json(item, options, 0, &mut output);
+    output.flush(0);
+    output.result?;
+    writer.flush()
+}
+
+// Starts a new line indented for `depth`, when output is pretty-printed
+pub(crate) fn write_newline(options: SerializeOptions, depth: usize, output: &mut String) {
+    if let Some(indent) = options.indent {
+        output.push('\n');
+        match indent {
+            Indent::Spaces(width) => { output.extend(std::iter::repeat_n(' ', width * depth)) }
+            Indent::Tabs(width) => { output.extend(std::iter::repeat_n('\t', width * depth)) }
+        }
+    }
+}
+
+// Pending output of `write_json`
+enum Work<'i> {
+    // Item to write at a nesting depth
+    Item(&'i JsonItem, usize),
+    Key(&'i Key),
+    // Punctuation or comment
+    Text(&'i str),
+    // Line break indented for a nesting depth, when pretty-printing
+    Newline(usize),
+}
+
+// Writes the JSON text of `item` at `depth`. Nested items are written from a stack of pending work rather than
+// by recursion, so that deep documents do not exhaust the call stack.
+pub(crate) fn write_json(item: &JsonItem, options: SerializeOptions, depth: usize, output: &mut Output<'_>) {
+    let mut stack = vec![Work::Item(item, depth)];
+    while let Some(work) = stack.pop() {
+        match work {
+            Work::Item(item, depth) => { write_item(item, options, depth, output, &mut stack) }
+            Work::Key(key) => { write_string_with(key.as_str(), options, output.text) }
+            Work::Text(text) => { output.text.push_str(text) }
+            Work::Newline(depth) => { write_newline(options, depth, output.text) }
+        }
+        output.flush(WRITE_CHUNK_SIZE);
+    }
+}
+
+// Writes `item` at `depth` if it is a scalar, otherwise its opening bracket, pushing the rest of its text on `stack`
+fn write_item<'i>(item: &'i JsonItem, options: SerializeOptions, depth: usize, output: &mut Output<'_>, stack: &mut Vec<Work<'i>>) {
+    if let Some(stub) = item.as_stub() {
+        // The materialized item holds no stubs, so this recurses once at most
+        return write_json(&stub.materialized(), options, depth, output);
+    }
+    #[cfg(feature = "extended")]
+    if options.comments && options.indent.is_some() {
+        if let Some(layout) = layout(item) {
+            return write_commented(item, layout, options, depth, stack, output);
+        }
+    }
+    let mut pending = Vec::new();
+    match item.json_type {
+        JsonNull | Empty => { output.text.push_str("null") }
+        JsonTrue => { output.text.push_str("true") }
+        JsonFalse => { output.text.push_str("false") }
+        JsonNumber => {
+            let text = item.slice.as_str();
+            if is_json_number(text) {
+                output.text.push_str(text);
+            } else {
+                match item.as_number() {
+                    Some(number) => { write_number(number, output.text) }
+                    None => { output.text.push_str("null") }
+                }
+            }
+        }
+        JsonString => { write_string_with(item.slice.as_str(), options, output.text) }
+        JsonArray => {
+            output.text.push('[');
+            for (index, element) in item.elements().into_iter().flatten().enumerate() {
+                if index > 0 {
+                    pending.push(Work::Text(","));
+                }
+                pending.push(Work::Newline(depth + 1));
+                pending.push(Work::Item(element, depth + 1));
+            }
+            if !pending.is_empty() {
+                pending.push(Work::Newline(depth));
+            }
+            pending.push(Work::Text("]"));
+        }
+        JsonMap => {
+            output.text.push('{');
+            let entries = item.entries().into_iter().flatten();
+            if options.sort_keys {
+                let mut entries = entries.map(|(key, value)| (unescape(key.as_str()).unwrap_or(Cow::Borrowed(key.as_str())), key, value)).collect::<Vec<_>>();
+                entries.sort_by(|(a, _, _), (b, _, _)| a.cmp(b));
+                push_members(entries.into_iter().map(|(_, key, value)| (key, value)), options, depth, &mut pending);
+            } else {
+                push_members(entries, options, depth, &mut pending);
+            }
+            if !pending.is_empty() {
+                pending.push(Work::Newline(depth));
+            }
+            pending.push(Work::Text("}"));
+        }
+    }
+    stack.extend(pending.into_iter().rev());
+}
+
+// Adds the work writing the members of an object at `depth` to `pending`
+fn push_members<'i, I: Iterator<Item = (&'i Key, &'i JsonItem)>>(entries: I, options: SerializeOptions, depth: usize, pending: &mut Vec<Work<'i>>) {
+    for (index, (key, value)) in entries.enumerate() {
+        if index > 0 {
+            pending.push(Work::Text(","));
+        }
+        pending.push(Work::Newline(depth + 1));
+        pending.push(Work::Key(key));
+        pending.push(Work::Text(if options.indent.is_some() { ": " } else { ":" }));
+        pending.push(Work::Item(value, depth + 1));
+    }
+}
+
+// Writes the opening bracket of an object or array, pushing its children with the comments attached to them on
+// `stack`, when pretty-printing
+#[cfg(feature = "extended")]
+fn write_commented<'i>(item: &'i JsonItem, (mut children, inner): Layout<'i>, options: SerializeOptions, depth: usize, stack: &mut Vec<Work<'i>>, output: &mut Output<'_>) {
+    let brackets = if item.json_type == JsonMap { ("{", "}") } else { ("[", "]") };
+    output.text.push_str(brackets.0);
+    if options.sort_keys {
+        children.sort_by_cached_key(|(key, _, _)| key.map(|key| unescape(key.as_str()).unwrap_or(Cow::Borrowed(key.as_str()))));
+    }
+    let mut pending = Vec::new();
+    let mut after: &[&'i str] = &[];
+    for (index, (key, value, comments)) in children.iter().enumerate() {
+        if index > 0 {
+            pending.push(Work::Text(","));
+        }
+        for comment in after {
+            pending.push(Work::Text(" "));
+            pending.push(Work::Text(comment));
+        }
+        for comment in comments.get_before() {
+            pending.push(Work::Newline(depth + 1));
+            pending.push(Work::Text(comment));
+        }
+        pending.push(Work::Newline(depth + 1));
+        if let Some(key) = key {
+            pending.push(Work::Key(key));
+            pending.push(Work::Text(": "));
+        }
+        pending.push(Work::Item(value, depth + 1));
+        after = comments.get_after();
+    }
+    for comment in after {
+        pending.push(Work::Text(" "));
+        pending.push(Work::Text(comment));
+    }
+    for comment in &inner {
+        pending.push(Work::Newline(depth + 1));
+        pending.push(Work::Text(comment));
+    }
+    if !children.is_empty() || !inner.is_empty() {
+        pending.push(Work::Newline(depth));
+    }
+    pending.push(Work::Text(brackets.1));
+    stack.extend(pending.into_iter().rev());
+}
+
+impl JsonItem {
+    /// Returns the compact JSON text of the item, as standard JSON. Non-existent items and non-finite numbers are written as `null`.
+    pub fn to_json(&self) -> String {
+        self.to_json_with(Escaping::Json)
+    }
+
+    /// Same as [`to_json`](JsonItem::to_json), but strings are escaped according to `escaping`
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jsonic::Escaping;
+    ///
+    /// let parsed = jsonic::parse("{\"bio\": \"</script><script>alert(1)</script>\"}").unwrap();
+    ///
+    /// let script = format!("<script>const user = {};</script>", parsed.to_json_with(Escaping::HtmlSafe));
+    /// assert_eq!(script, "<script>const user = {\"bio\":\"\\u003c/script\\u003e\\u003cscript\\u003ealert(1)\\u003c/script\\u003e\"};</script>");
+    /// ```
+    pub fn to_json_with(&self, escaping: Escaping) -> String {
+        let mut output = String::new();
+        write_compact_with(self, escaping, &mut output);
+        output
+    }
+
+    /// Returns the JSON text of the item pretty-printed, as standard JSON: one value per line, indented by `indent`
+    /// per nesting level, with a space after colons, as `JSON.stringify` does. Empty objects and arrays are written `{}` and `[]`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jsonic::Indent;
+    ///
+    /// let parsed = jsonic::parse("{\"name\": \"app\", \"ports\": [80, 443], \"env\": {}}").unwrap();
+    ///
+    /// assert_eq!(parsed.to_json_pretty(Indent::Spaces(2)), "{\n  \"name\": \"app\",\n  \"ports\": [\n    80,\n    443\n  ],\n  \"env\": {}\n}");
+    /// assert_eq!(parsed["ports"].to_json_pretty(Indent::Tabs(1)), "[\n\t80,\n\t443\n]");
+    /// ```
+    pub fn to_json_pretty(&self, indent: Indent) -> String {
+        self.to_json_using(&SerializeOptions::new().indent(indent))
+    }
+
+    /// Returns the JSON text of the item written according to `options`
+    pub fn to_json_using(&self, options: &SerializeOptions) -> String {
+        let mut output = String::new();
+        write_json(self, *options, 0, &mut Output { text: &mut output, writer: None, result: Ok(()) });
+        output
+    }
+
+    /// Writes the compact JSON text of the item to `writer`, see [`to_json`](JsonItem::to_json).
+    /// Text is written by chunks as it is produced, without building the whole of it in memory, so that large documents
+    /// can be written to files or sockets. The writer is flushed once done. Wrapping it in a `BufWriter` is not needed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let parsed = jsonic::parse("{\"a\": [1, 2]}").unwrap();
+    ///
+    /// let mut output = Vec::new();
+    /// parsed.write_json(&mut output).unwrap();
+    /// assert_eq!(output, b"{\"a\":[1,2]}");
+    /// ```
+    pub fn write_json<W: Write>(&self, writer: W) -> io::Result<()> {
+        write_to(self, SerializeOptions::new(), writer)
+    }
+
+    /// Writes the pretty-printed JSON text of the item to `writer`, see [`to_json_pretty`](JsonItem::to_json_pretty)
+    /// and [`write_json`](JsonItem::write_json)
+    pub fn write_json_pretty<W: Write>(&self, writer: W, indent: Indent) -> io::Result<()> {
+        write_to(self, SerializeOptions::new().indent(indent), writer)
+    }
+
+    /// Writes the JSON text of the item to `writer` according to `options`, see [`write_json`](JsonItem::write_json)
+    pub fn write_json_using<W: Write>(&self, writer: W, options: &SerializeOptions) -> io::Result<()> {
+        write_to(self, *options, writer)
+    }
+
+    /// Writes the JSON text of the item to `writer` according to `options` like
+    /// [`write_json_using`](JsonItem::write_json_using), calling `progress` after each chunk written with the number
+    /// of bytes written so far. The size of the output is not known in advance, so no total is reported.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jsonic::SerializeOptions;
+    ///
+    /// let parsed = jsonic::parse("{\"a\": [1, 2]}").unwrap();
+    ///
+    /// let mut written = 0;
+    /// parsed.write_json_with_progress(std::io::sink(), &SerializeOptions::new(), |progress| written = progress.get_processed()).unwrap();
+    /// assert_eq!(written, 11);
+    /// ```
+    pub fn write_json_with_progress<W: Write, F: FnMut(Progress)>(&self, writer: W, options: &SerializeOptions, progress: F) -> io::Result<()> {
+        write_to(self, *options, ProgressWriter::new(writer, None, progress))
+    }
+}
+
+/// Writes the compact JSON text of the item, see [`to_json`](JsonItem::to_json)
+///
+/// ```rust
+/// let parsed = jsonic::parse("{\"a\": [1, 2.50],  \"b\": \"x\"}").unwrap();
+///
+/// assert_eq!(format!("a = {}", parsed["a"]), "a = [1,2.50]");
+/// assert_eq!(parsed.to_string(), "{\"a\":[1,2.50],\"b\":\"x\"}");
+/// ```
+impl Display for JsonItem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str(&self.to_json())
+    }
+}
+
+/// Writes the compact JSON text of the root item, see [`to_json`](JsonItem::to_json)
+impl Display for JsonDocument {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        Display::fmt(self.root(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::serialize::{write_compact, Escaping, Indent, SerializeOptions};
+
+    fn compact(json: &str) -> String {
+        let mut output = String::new();
+        write_compact(&crate::parse(json).unwrap(), &mut output);
+        output
+    }
+
+    #[test]
+    fn write_json() {
+        assert_eq!(compact(" {\n\"a\" : [1, 2.5e3, \"x\\ty\\u00e9\"],\n \"b\": {\"c\": null, \"d\": [true, false, {}, []]}\n} "),
+                   "{\"a\":[1,2.5e3,\"x\\ty\\u00e9\"],\"b\":{\"c\":null,\"d\":[true,false,{},[]]}}");
+    }
+
+    #[test]
+    #[cfg(feature = "extended")]
+    fn write_extended() {
+        let mut output = String::new();
+        write_compact(&crate::parse_json5("{key: 'it\\'s \"q\"', n: [.5, +1, 0x1F, Infinity, 1.]}").unwrap(), &mut output);
+        assert_eq!(output, "{\"key\":\"it's \\\"q\\\"\",\"n\":[0.5,1,31,null,1]}");
+    }
+
+    #[test]
+    fn write_pretty() {
+        let parsed = crate::parse("{\"a\": [1, [], {\"b\": null}], \"c\": {}, \"d\": \"x\"}").unwrap();
+        assert_eq!(parsed.to_json_pretty(Indent::default()), "{\n  \"a\": [\n    1,\n    [],\n    {\n      \"b\": null\n    }\n  ],\n  \"c\": {},\n  \"d\": \"x\"\n}");
+        assert_eq!(parsed["a"].to_json_pretty(Indent::Spaces(0)), "[\n1,\n[],\n{\n\"b\": null\n}\n]");
+        assert_eq!(parsed["a"][2].to_json_pretty(Indent::Tabs(2)), "{\n\t\t\"b\": null\n}");
+        assert_eq!((parsed["d"].to_json_pretty(Indent::Tabs(1)), parsed["x"].to_json_pretty(Indent::Tabs(1))), ("\"x\"".to_owned(), "null".to_owned()));
+        assert_eq!(crate::parse(&parsed.to_json_pretty(Indent::Spaces(4))).unwrap(), parsed);
+    }
+
+    #[test]
+    fn escape_policies() {
+        let parsed = crate::parse("[\"é/\\/\\u00e9 😀\\n\u{2028}<\"]").unwrap();
+        assert_eq!(parsed.to_json_using(&SerializeOptions::new().ascii_only(true)), "[\"\\u00e9/\\/\\u00e9 \\ud83d\\ude00\\n\\u2028<\"]");
+        assert_eq!(parsed.to_json_using(&SerializeOptions::new().escape_slashes(true)), "[\"é\\/\\/\\u00e9 😀\\n\u{2028}<\"]");
+        assert_eq!(parsed.to_json_using(&SerializeOptions::new().escape_line_separators(true)), "[\"é/\\/\\u00e9 😀\\n\\u2028<\"]");
+        let all = SerializeOptions::new().ascii_only(true).escape_slashes(true).escaping(Escaping::HtmlSafe);
+        assert_eq!(parsed.to_json_using(&all), "[\"\\u00e9\\/\\/\\u00e9 \\ud83d\\ude00\\n\\u2028\\u003c\"]");
+        assert_eq!(crate::parse(&parsed.to_json_using(&all)).unwrap(), parsed);
+        let keys = crate::parse("{\"ключ\": 1}").unwrap();
+        assert_eq!(keys.to_json_using(&SerializeOptions::new().ascii_only(true)), "{\"\\u043a\\u043b\\u044e\\u0447\":1}");
+    }
+
+    #[test]
+    fn sort_keys() {
+        let parsed = crate::parse("{\"b\": [{\"z\": 1, \"y\": 2}], \"\\u0061\": true, \"B\": null, \"é\": 0, \"aa\": {}}").unwrap();
+        let sorted = SerializeOptions::new().sort_keys(true);
+        assert_eq!(parsed.to_json_using(&sorted), "{\"B\":null,\"\\u0061\":true,\"aa\":{},\"b\":[{\"y\":2,\"z\":1}],\"é\":0}");
+        assert_eq!(parsed.to_json_using(&SerializeOptions::new()), parsed.to_json());
+        assert_eq!(parsed["b"].to_json_using(&sorted.indent(Indent::Tabs(1))), "[\n\t{\n\t\t\"y\": 2,\n\t\t\"z\": 1\n\t}\n]");
+        let mut output = Vec::new();
+        parsed["b"].write_json_using(&mut output, &sorted.escaping(Escaping::HtmlSafe)).unwrap();
+        assert_eq!(output, b"[{\"y\":2,\"z\":1}]");
+    }
+
+    #[test]
+    fn write_to_writers() {
+        use std::io::{Error, ErrorKind, Result, Write};
+
+        let json = format!("[{}]", (0..100_000).map(|index| format!("{{\"k\": {}}}", index)).collect::<Vec<_>>().join(", "));
+        let parsed = crate::parse(&json).unwrap();
+        let (mut compact, mut pretty) = (Vec::new(), Vec::new());
+        parsed.write_json(&mut compact).unwrap();
+        parsed.write_json_pretty(&mut pretty, Indent::Tabs(1)).unwrap();
+        assert_eq!(String::from_utf8(compact).unwrap(), parsed.to_json());
+        assert_eq!(String::from_utf8(pretty).unwrap(), parsed.to_json_pretty(Indent::Tabs(1)));
+
+        // Writer failing after some chunks, counting the writes attempted
+        struct Failing(usize);
+        impl Write for Failing {
+            fn write(&mut self, buf: &[u8]) -> Result<usize> {
+                self.0 += 1;
+                if self.0 > 2 { Err(Error::new(ErrorKind::BrokenPipe, "closed")) } else { Ok(buf.len()) }
+            }
+            fn flush(&mut self) -> Result<()> {
+                Ok(())
+            }
+        }
+        let mut failing = Failing(0);
+        assert_eq!(parsed.write_json(&mut failing).unwrap_err().kind(), ErrorKind::BrokenPipe);
+        assert_eq!(failing.0, 3);
+
+        let (mut written, mut reports) = (Vec::new(), Vec::new());
+        parsed.write_json_with_progress(&mut written, &SerializeOptions::new(), |progress| reports.push(progress)).unwrap();
+        assert!(reports.len() > 1);
+        assert!(reports.windows(2).all(|w| w[0].get_processed() < w[1].get_processed()));
+        assert_eq!(reports.last().unwrap().get_processed(), written.len() as u64);
+        assert_eq!(reports.last().unwrap().get_total(), None);
+    }
+
+    #[test]
+    fn escape_html() {
+        let parsed = crate::parse("{\"a<b>\": [\"x & y\", \"\\u2028\u{2028}\u{2029}\", \"\\\\u003c\"], \"c\": \"é\\n\"}").unwrap();
+        assert_eq!(parsed.to_json_with(Escaping::HtmlSafe), "{\"a\\u003cb\\u003e\":[\"x \\u0026 y\",\"\\u2028\\u2028\\u2029\",\"\\\\u003c\"],\"c\":\"é\\n\"}");
+        assert_eq!(parsed.to_json(), "{\"a<b>\":[\"x & y\",\"\\u2028\u{2028}\u{2029}\",\"\\\\u003c\"],\"c\":\"é\\n\"}");
+        assert_eq!(parsed["x"].to_json_with(Escaping::HtmlSafe), "null");
+        assert_eq!((parsed["c"].to_string(), parsed["x"].to_string()), ("\"é\\n\"".to_owned(), "null".to_owned()));
+        assert_eq!(crate::document::ParseJsonExt::parse_json("[ 1 ]").unwrap().to_string(), "[1]");
+    }
+
+    #[test]
+    fn write_stubs() {
+        let parsed = crate::options::Parser::new(crate::options::ParseOptions::new().materialize_depth(1)).parse("[{ \"a\" : [ 1 ] }, [\"<\"]]").unwrap();
+        assert_eq!(parsed.to_json_with(Escaping::HtmlSafe), "[{\"a\":[1]},[\"\\u003c\"]]");
+    }
+
+    #[test]
+    fn write_deep() {
+        let json = format!("{}0{}", "[{\"a\":".repeat(100_000), "}]".repeat(100_000));
+        for depth in [usize::MAX, 1] {
+            let parser = crate::options::Parser::new(crate::options::ParseOptions::new().max_depth(usize::MAX).materialize_depth(depth));
+            let parsed = parser.parse(&json).unwrap();
+            assert_eq!(parsed.to_json(), json);
+            assert_eq!(parsed.to_string(), json);
+            let mut written = Vec::new();
+            parsed.write_json(&mut written).unwrap();
+            assert_eq!(written, json.as_bytes());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "extended")]
+    fn write_comments() {
+        let source = "// top\n{\"b\": [1, /* one */ 2 // two\n], /* a */ \"a\": {\n// empty\n}, \"c\": [] /* c */}";
+        let parsed = crate::parse_jsonc(source).unwrap();
+        let options = SerializeOptions::new().indent(Indent::Spaces(2)).comments(true);
+        let pretty = parsed.to_json_using(&options);
+        assert_eq!(pretty, "{\n  \"b\": [\n    1,\n    /* one */\n    2 // two\n  ],\n  /* a */\n  \"a\": {\n    // empty\n  },\n  \"c\": [] /* c */\n}");
+        assert_eq!(crate::parse_jsonc(&pretty).unwrap().to_json_using(&options), pretty);
+        assert_eq!(parsed.to_json_using(&options.sort_keys(true)), "{\n  /* a */\n  \"a\": {\n    // empty\n  },\n  \"b\": [\n    1,\n    /* one */\n    2 // two\n  ],\n  \"c\": [] /* c */\n}");
+        assert_eq!(parsed.to_json_using(&SerializeOptions::new().comments(true)), parsed.to_json());
+        assert_eq!(parsed.to_json_pretty(Indent::Spaces(2)), crate::parse(&parsed.to_json()).unwrap().to_json_pretty(Indent::Spaces(2)));
+
+        let parser = crate::options::Parser::new(crate::options::ParseOptions::jsonc().materialize_depth(1));
+        assert_eq!(parser.parse("[[1, // x\n2]]").unwrap().to_json_using(&options), "[\n  [\n    1, // x\n    2\n  ]\n]");
+    }
+}
@@ -0,0 +1,276 @@
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use crate::json_item::JsonItem;
+use crate::json_type::JsonType::{Empty, JsonArray, JsonFalse, JsonMap, JsonNull, JsonNumber, JsonString, JsonTrue};
+use crate::number::{write_f64_shortest, JsonNumberValue};
+use crate::text::{escape_into, unescape};
+
+// Members of the object `item` with unescaped keys, sorted by UTF-16 code units as in JavaScript, leaving out member `skip` if any.
+// Keys repeated once unescaped, e.g. `"a"` and `"\u0061"`, give an error, since the object has no canonical form.
+fn members<'a>(item: &'a JsonItem, skip: Option<&str>) -> Result<Vec<(Cow<'a, str>, &'a JsonItem)>, SigningError> {
+    let mut members = item.entries().unwrap()
+        .map(|(key, value)| (unescape(key.as_str()).unwrap_or_else(|| key.as_str().into()), value))
+        .filter(|(key, _)| Some(&**key) != skip)
+        .collect::<Vec<_>>();
+    members.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+    match members.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+        Some(pair) => { Err(SigningError::DuplicateKey(pair[0].0.clone().into_owned())) }
+        None => { Ok(members) }
+    }
+}
+
+/// Errors returned for items which cannot be canonicalized or signed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SigningError {
+    /// The item to sign is not an object
+    NotAnObject,
+    /// A number does not fit in an IEEE 754 double, e.g. `1e400`, or is not finite, e.g. `NaN` in JSON5
+    NonFiniteNumber(String),
+    /// Two keys of an object are this key once unescaped, e.g. `"a"` and `"\u0061"`
+    DuplicateKey(String),
+}
+
+impl Display for SigningError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            SigningError::NotAnObject => { write!(f, "not an object") }
+            SigningError::NonFiniteNumber(number) => { write!(f, "number {} has no canonical form", number) }
+            SigningError::DuplicateKey(key) => { write!(f, "duplicate key {:?}", key) }
+        }
+    }
+}
+
+impl Error for SigningError {}
+
+// Writes the canonical form of `item`, leaving out its member `skip` if any
+fn write_canonical(item: &JsonItem, skip: Option<&str>, output: &mut String) -> Result<(), SigningError> {
+    if let Some(stub) = item.as_stub() {
+        return write_canonical(&stub.expanded(), skip, output);
+    }
+    match item.json_type {
+        JsonNull | Empty => { output.push_str("null") }
+        JsonTrue => { output.push_str("true") }
+        JsonFalse => { output.push_str("false") }
+        JsonNumber => {
+            // Numbers are IEEE 754 doubles, written in their shortest ECMAScript form. Other numbers are rejected,
+            // rather than written as `null`, which would give them the canonical form of other data.
+            match item.as_number().map(JsonNumberValue::to_f64) {
+                Some(number) if number.is_finite() => { write_f64_shortest(number, output) }
+                _ => { return Err(SigningError::NonFiniteNumber(item.slice.as_str().to_owned())); }
+            }
+        }
+        JsonString => { escape_into(&item.as_unescaped().unwrap_or_else(|| item.slice.as_str().into()), output) }
+        JsonArray => {
+            output.push('[');
+            for (index, element) in item.elements().unwrap().enumerate() {
+                if index > 0 {
+                    output.push(',');
+                }
+                write_canonical(element, None, output)?;
+            }
+            output.push(']');
+        }
+        JsonMap => {
+            output.push('{');
+            for (index, (key, value)) in members(item, skip)?.iter().enumerate() {
+                if index > 0 {
+                    output.push(',');
+                }
+                escape_into(key, output);
+                output.push(':');
+                write_canonical(value, None, output)?;
+            }
+            output.push('}');
+        }
+    }
+    Ok(())
+}
+
+/// Returns the canonical form of `item`, following the JSON Canonicalization Scheme (RFC 8785): no whitespace,
+/// object members sorted by key, strings with minimal escapes and numbers in their shortest form.
+/// Documents with the same data have the same canonical form whatever their formatting or key order,
+/// so that it can be hashed or signed. As required by RFC 8785, numbers which do not fit in an IEEE 754 double
+/// have no canonical form, and give an error, as do objects holding the same key once unescaped, e.g. `"a"`
+/// and `"\u0061"`, which parsing keeps apart since it compares keys as written.
+///
+/// # Arguments
+/// * `item` - Item to canonicalize
+///
+/// # Example
+///
+/// ```rust
+/// use jsonic::signing::{canonicalize, SigningError};
+///
+/// let parsed = jsonic::parse("{\"b\": [1.50, 1e2], \"a\": \"\\u00e9\"}").unwrap();
+/// assert_eq!(canonicalize(&parsed).unwrap(), "{\"a\":\"é\",\"b\":[1.5,100]}");
+/// assert_eq!(canonicalize(&jsonic::parse("[1e400]").unwrap()), Err(SigningError::NonFiniteNumber("1e400".to_owned())));
+/// ```
+pub fn canonicalize(item: &JsonItem) -> Result<String, SigningError> {
+    let mut output = String::new();
+    write_canonical(item, None, &mut output)?;
+    Ok(output)
+}
+
+impl JsonItem {
+    /// Returns the canonical form of the item, following the JSON Canonicalization Scheme (RFC 8785), see [`canonicalize`].
+    /// Meant for signing and content addressing: equal data gives the same bytes, and so the same hash.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let parsed = jsonic::parse("{\"numbers\": [4.50, 2e-3, 1E30], \"literals\": [null, true], \"string\": \"\\u20ac\\/\"}").unwrap();
+    /// assert_eq!(parsed.to_canonical_json().unwrap(), "{\"literals\":[null,true],\"numbers\":[4.5,0.002,1e+30],\"string\":\"€/\"}");
+    /// assert!(jsonic::parse("{\"a\": 1e400}").unwrap().to_canonical_json().is_err());
+    /// ```
+    pub fn to_canonical_json(&self) -> Result<String, SigningError> {
+        canonicalize(self)
+    }
+}
+
+/// Returns the detached payload of the object `item`, that is the data covered by its signature:
+/// the canonical form of the object without its member `slot`, see [`canonicalize`].
+/// Returns an error if `item` is not an object or has no canonical form.
+///
+/// # Arguments
+/// * `item` - Signed or unsigned object
+/// * `slot` - Key of the member holding the signature
+pub fn signing_payload(item: &JsonItem, slot: &str) -> Result<String, SigningError> {
+    if item.json_type != JsonMap {
+        return Err(SigningError::NotAnObject);
+    }
+    let mut output = String::new();
+    write_canonical(item, Some(slot), &mut output)?;
+    Ok(output)
+}
+
+/// Signs the object `item`: computes its detached payload (see [`signing_payload`]), passes it to `sign`,
+/// and returns the canonical form of the object with the returned signature as string member `slot`.
+/// A signature already in `slot` is replaced, so that signed documents can be signed again.
+/// Returns an error without calling `sign` if `item` is not an object or has no canonical form,
+/// e.g. holds a number out of the range of doubles.
+///
+/// The signed document can be verified with [`verify_with`] after being reformatted or having its members
+/// reordered, as long as its data is unchanged.
+///
+/// # Arguments
+/// * `item` - Object to sign
+/// * `slot` - Key of the member receiving the signature
+/// * `sign` - Function computing the signature of the payload bytes, e.g. hashing then signing them,
+///   and encoding the result as text, e.g. in base64
+///
+/// # Example
+///
+/// ```rust
+/// use jsonic::signing::{sign_with, verify_with};
+///
+/// // Stand-in for a real signature scheme
+/// let checksum = |bytes: &[u8]| bytes.iter().map(|&b| b as u32).sum::<u32>().to_string();
+///
+/// let parsed = jsonic::parse("{\"user\": \"ann\", \"role\": \"admin\"}").unwrap();
+/// let signed = sign_with(&parsed, "sig", checksum).unwrap();
+/// assert_eq!(signed, "{\"role\":\"admin\",\"sig\":\"2399\",\"user\":\"ann\"}");
+///
+/// let received = jsonic::parse("{\n  \"sig\": \"2399\",\n  \"user\": \"ann\",\n  \"role\": \"admin\"\n}").unwrap();
+/// assert!(verify_with(&received, "sig", |payload, signature| checksum(payload) == signature));
+/// ```
+pub fn sign_with<F: FnOnce(&[u8]) -> String>(item: &JsonItem, slot: &str, sign: F) -> Result<String, SigningError> {
+    let signature = sign(signing_payload(item, slot)?.as_bytes());
+    let mut signature_text = String::new();
+    escape_into(&signature, &mut signature_text);
+    let mut members = members(item, Some(slot))?.into_iter().map(|(key, value)| Ok((key, canonicalize(value)?))).collect::<Result<Vec<_>, SigningError>>()?;
+    members.push((slot.into(), signature_text));
+    members.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+
+    let mut output = String::from("{");
+    for (index, (key, value)) in members.iter().enumerate() {
+        if index > 0 {
+            output.push(',');
+        }
+        escape_into(key, &mut output);
+        output.push(':');
+        output.push_str(value);
+    }
+    output.push('}');
+    Ok(output)
+}
+
+/// Verifies the signature in member `slot` of the object `item`, signed by [`sign_with`]: passes the detached
+/// payload and the signature to `verify`, and returns its result. Returns `false` without calling `verify`
+/// if `item` is not an object, if `slot` is not a string member or if the rest of the object has no canonical form.
+///
+/// # Arguments
+/// * `item` - Signed object
+/// * `slot` - Key of the member holding the signature
+/// * `verify` - Function checking the signature against the payload bytes
+pub fn verify_with<F: FnOnce(&[u8], &str) -> bool>(item: &JsonItem, slot: &str, verify: F) -> bool {
+    let member = item.entries().and_then(|mut entries| entries.find(|(key, _)| unescape(key.as_str()).is_some_and(|key| key == slot)));
+    let signature = match member.and_then(|(_, value)| value.as_unescaped()) {
+        Some(signature) => { signature }
+        None => { return false; }
+    };
+    match signing_payload(item, slot) {
+        Ok(payload) => { verify(payload.as_bytes(), &signature) }
+        Err(_) => { false }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::options::{ParseOptions, Parser};
+    use crate::parse;
+    use crate::signing::{canonicalize, sign_with, signing_payload, verify_with, SigningError};
+
+    #[test]
+    fn canonical_form() {
+        let parsed = parse("{\"\\u20ac\": 1, \"\\ud83d\\ude00\": 2, \"\\r\": [-0.0, 1e21, 0.000001, 1e-7, \"\\u0007\\/\"]}").unwrap();
+        assert_eq!(canonicalize(&parsed).unwrap(), "{\"\\r\":[0,1e+21,0.000001,1e-7,\"\\u0007/\"],\"€\":1,\"😀\":2}");
+        assert_eq!(signing_payload(&parsed, "\r"), Ok("{\"€\":1,\"😀\":2}".to_owned()));
+        assert_eq!(signing_payload(&parsed["\\r"], "x"), Err(SigningError::NotAnObject));
+
+        // Example of RFC 8785, section 3.2.2
+        let parsed = parse("{\"numbers\": [333333333.33333329, 1E30, 4.50, 2e-3, 0.000000000000000000000000001], \"string\": \"\\u20ac$\\u000F\\u000aA'\\u0042\\u0022\\u005c\\\\\\\"\\/\", \"literals\": [null, true, false]}").unwrap();
+        assert_eq!(parsed.to_canonical_json().unwrap(), "{\"literals\":[null,true,false],\"numbers\":[333333333.3333333,1e+30,4.5,0.002,1e-27],\"string\":\"€$\\u000f\\nA'B\\\"\\\\\\\\\\\"/\"}");
+        let stubs = Parser::new(ParseOptions::new().materialize_depth(1)).parse("{\"b\": {\"d\": 1, \"c\": [2.0]}, \"a\": []}").unwrap();
+        assert_eq!(stubs.to_canonical_json().unwrap(), "{\"a\":[],\"b\":{\"c\":[2],\"d\":1}}");
+    }
+
+    #[test]
+    fn no_canonical_form() {
+        assert_eq!(canonicalize(&parse("{\"a\": 1e400}").unwrap()), Err(SigningError::NonFiniteNumber("1e400".to_owned())));
+        assert_eq!(canonicalize(&parse("[-1E400]").unwrap()), Err(SigningError::NonFiniteNumber("-1E400".to_owned())));
+        assert_eq!(canonicalize(&parse("{\"a\": null}").unwrap()).unwrap(), "{\"a\":null}");
+        assert_eq!(canonicalize(&parse("[{\"a\": 1, \"\\u0061\": 2}]").unwrap()), Err(SigningError::DuplicateKey("a".to_owned())));
+        assert_eq!(SigningError::DuplicateKey("a".to_owned()).to_string(), "duplicate key \"a\"");
+    }
+
+    #[test]
+    fn sign_and_verify() {
+        let sign = |bytes: &[u8]| String::from_utf8(bytes.to_vec()).unwrap().len().to_string();
+        let signed = sign_with(&parse("{\"b\": {\"y\": 1, \"x\": 2}, \"a\": 0, \"sig\": \"old\"}").unwrap(), "sig", sign).unwrap();
+        assert_eq!(signed, "{\"a\":0,\"b\":{\"x\":2,\"y\":1},\"sig\":\"25\"}");
+        assert_eq!(sign_with(&parse("{}").unwrap(), "s", sign).unwrap(), "{\"s\":\"2\"}");
+
+        let verify = |payload: &[u8], signature: &str| sign(payload) == signature;
+        assert!(verify_with(&parse(&signed).unwrap(), "sig", verify));
+        assert!(verify_with(&parse("{\"\\u0073ig\": \"25\", \"b\": {\"x\": 2, \"y\": 1}, \"a\": 0}").unwrap(), "sig", verify));
+        assert!(!verify_with(&parse("{\"sig\": \"25\", \"b\": {\"x\": 20, \"y\": 1}, \"a\": 0}").unwrap(), "sig", verify));
+        assert!(!verify_with(&parse("{\"sig\": 23, \"a\": 0}").unwrap(), "sig", |_, _| true));
+        assert!(!verify_with(&parse("[]").unwrap(), "sig", |_, _| true));
+    }
+
+    #[test]
+    fn sign_without_canonical_form() {
+        let parsed = parse("{\"a\": 1e400}").unwrap();
+        assert_eq!(sign_with(&parsed, "sig", |_| unreachable!()), Err(SigningError::NonFiniteNumber("1e400".to_owned())));
+        assert_eq!(sign_with(&parse("[]").unwrap(), "sig", |_| unreachable!()), Err(SigningError::NotAnObject));
+        let duplicates = parse("{\"a\": 1, \"\\u0061\": 2, \"sig\": \"x\"}").unwrap();
+        assert_eq!(sign_with(&duplicates, "sig", |_| unreachable!()), Err(SigningError::DuplicateKey("a".to_owned())));
+        assert!(!verify_with(&duplicates, "sig", |_, _| true));
+        assert!(!verify_with(&parse("{\"a\": 1e400, \"sig\": \"x\"}").unwrap(), "sig", |_, _| true));
+        assert!(verify_with(&parse("{\"a\": null, \"sig\": \"x\"}").unwrap(), "sig", |_, _| true));
+        let stubs = Parser::new(ParseOptions::new().materialize_depth(1)).parse("{\"a\": [1e400], \"sig\": \"x\"}").unwrap();
+        assert!(!verify_with(&stubs, "sig", |_, _| true));
+    }
+}
@@ -4,6 +4,7 @@ use std::ptr::null;
 use std::slice::from_raw_parts;
 use std::str::from_utf8_unchecked;
 
+#[derive(Clone, Copy)]
 pub(crate) struct Slice {
     pub(crate) ptr: *const u8,
     pub(crate) len: usize,
@@ -11,17 +12,22 @@ pub(crate) struct Slice {
 
 impl Slice {
     pub(crate) fn from_str(source: &str) -> Slice {
-        return Slice {
+        Slice {
             ptr: source.as_ptr(),
             len: source.len(),
-        };
+        }
     }
 
     pub(crate) fn from_bytes(bytes: &[u8], start: usize, end: usize) -> Slice {
-        return Slice {
+        Slice {
             ptr: unsafe { bytes.as_ptr().byte_add(start) },
             len: end - start,
-        };
+        }
+    }
+
+    // Byte offset of the slice start within `bytes`, which must contain it
+    pub(crate) fn offset_in(&self, bytes: &[u8]) -> usize {
+        self.ptr as usize - bytes.as_ptr() as usize
     }
 
     pub const fn empty() -> Self {
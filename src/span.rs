@@ -0,0 +1,111 @@
+use std::ops::Range;
+
+use crate::document::JsonDocument;
+use crate::json_error::location;
+use crate::json_item::JsonItem;
+use crate::json_type::JsonType::{Empty, JsonString};
+
+/// Location of the text of an item in its source, see [`JsonItem::span`].
+/// Offsets are in bytes, and lines and columns are computed when requested.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span<'a> {
+    source: &'a str,
+    start: usize,
+    end: usize,
+}
+
+impl<'a> Span<'a> {
+    /// Byte offset of the start of the item text
+    pub fn get_start(&self) -> usize {
+        self.start
+    }
+
+    /// Byte offset following the end of the item text
+    pub fn get_end(&self) -> usize {
+        self.end
+    }
+
+    /// Byte range of the item text
+    pub fn get_range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    /// Item text, quotes included for strings
+    pub fn get_text(&self) -> &'a str {
+        &self.source[self.start..self.end]
+    }
+
+    /// Line and column of the start of the item text, both starting at 1, columns being counted in characters
+    pub fn get_start_location(&self) -> (usize, usize) {
+        let (line, column, _) = location(self.source.as_bytes(), self.start);
+        (line, column)
+    }
+
+    /// Line and column of the last character of the item text, see [`get_start_location`](Span::get_start_location)
+    pub fn get_end_location(&self) -> (usize, usize) {
+        let last = self.source[..self.end].char_indices().next_back().map_or(0, |(index, _)| index);
+        let (line, column, _) = location(self.source.as_bytes(), usize::max(last, self.start));
+        (line, column)
+    }
+}
+
+impl JsonItem {
+    /// Returns the location of the item text in `source`, which must be the text the item was parsed from.
+    /// Returns `None` if the item does not exist or does not point into `source`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let source = "{\n  \"name\": \"ann\",\n  \"tags\": [1, 2]\n}";
+    /// let parsed = jsonic::parse(source).unwrap();
+    ///
+    /// let span = parsed["name"].span(source).unwrap();
+    /// assert_eq!((span.get_range(), span.get_text()), (12..17, "\"ann\""));
+    /// assert_eq!(span.get_start_location(), (2, 11));
+    /// assert_eq!(parsed["tags"].span(source).unwrap().get_end_location(), (3, 16));
+    /// assert!(parsed["missing"].span(source).is_none());
+    /// ```
+    pub fn span<'a>(&self, source: &'a str) -> Option<Span<'a>> {
+        if self.json_type == Empty {
+            return None;
+        }
+        let (base, address) = (source.as_ptr() as usize, self.slice.ptr as usize);
+        if address < base || address + self.slice.len > base + source.len() {
+            return None;
+        }
+        let quote = usize::from(self.json_type == JsonString);
+        let start = address - base - quote;
+        Some(Span { source, start, end: start + self.slice.len + 2 * quote })
+    }
+}
+
+impl JsonDocument {
+    /// Returns the location of `item` in the source of the document, if it belongs to it, see [`JsonItem::span`]
+    pub fn span_of(&self, item: &JsonItem) -> Option<Span<'_>> {
+        item.span(self.source())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::document::ParseJsonExt;
+    use crate::parse;
+
+    #[test]
+    fn locate_items() {
+        let source = "[\"é\", {\"k\": \"v\\\"\"},\n 12.5, true]";
+        let parsed = parse(source).unwrap();
+        let spans = parsed.elements().unwrap().map(|item| item.span(source).map(|span| span.get_text())).collect::<Vec<_>>();
+        assert_eq!(spans, [Some("\"é\""), Some("{\"k\": \"v\\\"\"}"), Some("12.5"), Some("true")]);
+        assert_eq!(parsed.span(source).unwrap().get_range(), 0..source.len());
+        assert_eq!(parsed[0].span(source).unwrap().get_end_location(), (1, 4));
+        assert_eq!(parsed[2].span(source).unwrap().get_start_location(), (2, 2));
+        assert_eq!(parsed[1]["k"].span(source).unwrap().get_range(), 13..18);
+
+        let other = String::from(source);
+        assert!(parsed[0].span(&other).is_none());
+        let document = source.parse_json().unwrap();
+        assert_eq!(document.span_of(&document[3]).map(|span| span.get_start()), Some(source.len() - 5));
+        assert!(document.span_of(&parsed[3]).is_none());
+    }
+}
@@ -0,0 +1,82 @@
+use std::iter::FusedIterator;
+
+use crate::json_error::JsonError;
+use crate::json_item::JsonItem;
+use crate::options::ParseOptions;
+use crate::{parse_root_from, shift_index};
+
+/// Concatenated JSON values iterator, see [`parse_stream`]
+pub struct JsonStream<'a> {
+    source: &'a str,
+    offset: usize,
+}
+
+impl<'a> Iterator for JsonStream<'a> {
+    type Item = Result<JsonItem, JsonError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bytes = self.source.as_bytes();
+        while self.offset < bytes.len() && bytes[self.offset].is_ascii_whitespace() {
+            self.offset += 1;
+        }
+        if self.offset >= bytes.len() {
+            return None;
+        }
+        match parse_root_from(self.source, self.offset, &ParseOptions::JSON) {
+            Ok(item) => {
+                self.offset = item.slice.offset_in(bytes) + shift_index(&item);
+                Some(Ok(item))
+            }
+            Err(error) => {
+                // Values are not delimited, so there is no way to resynchronize
+                self.offset = bytes.len();
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+impl<'a> FusedIterator for JsonStream<'a> {}
+
+/// Parses back-to-back JSON values with no delimiter, e.g. `{"a":1}{"b":2}`, as emitted by many streaming APIs
+/// and `jq -c` pipelines. Values must be objects or arrays, and may be separated by whitespace.
+/// Iteration stops after the first error, whose index is relative to the start of `source`.
+///
+/// # Arguments
+/// * `source` - Text content to be parsed
+///
+/// # Example
+///
+/// ```rust
+/// let stream = "{\"a\":1}{\"b\":2} [3]\n";
+///
+/// let values = jsonic::stream::parse_stream(stream).collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!(values.len(), 3);
+/// assert_eq!(values[1]["b"].as_i128(), Some(2));
+/// ```
+pub fn parse_stream(source: &str) -> JsonStream<'_> {
+    JsonStream { source, offset: 0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stream::parse_stream;
+
+    #[test]
+    fn parse_values() {
+        let values = parse_stream("[1][2]\n\t{\"a\": [3]}{}[]").collect::<Vec<_>>();
+        assert_eq!(values.len(), 5);
+        assert_eq!(values[1].as_ref().unwrap()[0].as_i128(), Some(2));
+        assert_eq!(values[2].as_ref().unwrap()["a"][0].as_i128(), Some(3));
+        assert!(values.iter().all(Result::is_ok));
+        assert_eq!(parse_stream("  \n").count(), 0);
+    }
+
+    #[test]
+    fn stop_on_error() {
+        let mut values = parse_stream("{\"a\":1}{\"b\":}{\"c\":3}");
+        assert!(values.next().unwrap().is_ok());
+        assert_eq!(values.next().unwrap().unwrap_err().get_index(), 12);
+        assert!(values.next().is_none());
+    }
+}
@@ -0,0 +1,160 @@
+#[cfg(feature = "serialize")]
+use std::ops::Range;
+use std::panic::{RefUnwindSafe, UnwindSafe};
+use std::sync::Arc;
+
+use crate::generics::Container::Stub as StubContainer;
+use crate::json_error::JsonError;
+use crate::json_item::JsonItem;
+use crate::json_type::JsonType;
+use crate::options::ParseOptions;
+use crate::parse_root_from;
+#[cfg(feature = "serialize")]
+use crate::parse_from_recording;
+
+// Options of the parse which left objects or arrays unparsed, shared by its stubs.
+// They are only read once the parse is over, so that panics cannot leave them in an inconsistent state.
+#[derive(Debug)]
+pub(crate) struct StubOptions(pub(crate) Arc<ParseOptions>);
+
+impl UnwindSafe for StubOptions {}
+
+impl RefUnwindSafe for StubOptions {}
+
+/// Object or array left unparsed by a parse bounded with [`ParseOptions::materialize_depth`], see [`JsonItem::as_stub`]
+#[derive(Debug, Clone, Copy)]
+pub struct Stub<'a> {
+    item: &'a JsonItem,
+    options: &'a ParseOptions,
+}
+
+impl<'a> Stub<'a> {
+    /// Type of the stub, [`JsonType::JsonMap`] or [`JsonType::JsonArray`]
+    pub fn get_type(&self) -> JsonType {
+        self.item.json_type
+    }
+
+    /// Source text of the object or array, brackets included
+    pub fn as_str(&self) -> &'a str {
+        self.item.slice.as_str()
+    }
+
+    /// Parses the object or array with the options of the original parse, so that containers nested deeper than
+    /// the materialized depth below it are stubs in turn. The expanded item borrows the same source text as the stub.
+    /// The text of stubs is checked while parsing, limits included, so that expanding them does not fail.
+    pub fn expand(&self) -> Result<JsonItem, JsonError> {
+        parse_root_from(self.as_str(), 0, self.options)
+    }
+
+    // Expands the stub, whose text was checked while it was skipped over
+    pub(crate) fn expanded(&self) -> JsonItem {
+        self.expand().expect("stubs are checked while parsing")
+    }
+
+    // Expands the stub with all the objects and arrays nested in it, so that the expanded item holds no stubs
+    #[cfg(feature = "serialize")]
+    pub(crate) fn materialized(&self) -> JsonItem {
+        let options = ParseOptions { materialize_depth: usize::MAX, ..self.options.clone() };
+        parse_root_from(self.as_str(), 0, &options).expect("stubs are checked while parsing")
+    }
+
+    // Expands the stub, also returning the byte ranges of the text separating its tokens, relative to the text
+    // of the stub, if the original parse recorded them, see `ParseOptions::preserve_layout`
+    #[cfg(feature = "serialize")]
+    pub(crate) fn expand_recording(&self) -> Result<(JsonItem, Vec<Range<usize>>), JsonError> {
+        let mut separators = Vec::new();
+        let item = parse_from_recording(self.as_str(), 0, self.options, self.options.preserve_layout.then_some(&mut separators))?;
+        Ok((item, separators))
+    }
+}
+
+impl JsonItem {
+    /// Returns the item as a stub if it is an object or array left unparsed by a parse bounded with
+    /// [`ParseOptions::materialize_depth`], `None` otherwise. Accessors to the children of stubs return `None`
+    /// until they are expanded with [`Stub::expand`], but stubs are serialized and compared as expanded.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jsonic::options::{ParseOptions, Parser};
+    ///
+    /// let parser = Parser::new(ParseOptions::new().materialize_depth(2));
+    /// let parsed = parser.parse("{\"users\": [{\"name\": \"ann\", \"roles\": [\"admin\"]}]}").unwrap();
+    ///
+    /// let user = &parsed["users"][0];
+    /// assert!(!user["name"].exists());
+    /// let user = user.as_stub().unwrap().expand().unwrap();
+    /// assert_eq!(user["name"].as_str(), Some("ann"));
+    /// assert_eq!(user["roles"][0].as_str(), Some("admin"));
+    /// ```
+    pub fn as_stub(&self) -> Option<Stub<'_>> {
+        match &self.container {
+            Some(StubContainer(options)) => { Some(Stub { item: self, options: &options.0 }) }
+            _ => { None }
+        }
+    }
+
+    /// Checks if the item is an object or array left unparsed, see [`as_stub`](JsonItem::as_stub)
+    pub fn is_stub(&self) -> bool {
+        self.as_stub().is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::json_error::ErrorKind;
+    use crate::json_type::JsonType::{JsonArray, JsonMap};
+    use crate::options::{DuplicateKeys, ParseOptions, Parser};
+    use crate::parse;
+
+    #[test]
+    fn expand_stubs() {
+        let source = "[{\"a\": [1, {\"b\": \"]}\"}]}, [], {\"c\": {}}, 2]";
+        let parsed = Parser::new(ParseOptions::new().materialize_depth(1)).parse(source).unwrap();
+        assert!(parsed[0].is_stub() && parsed[1].is_stub() && !parsed[3].is_stub());
+        assert_eq!((parsed[0].as_stub().unwrap().get_type(), parsed[1].as_stub().unwrap().get_type()), (JsonMap, JsonArray));
+        assert!(parsed[0].entries().is_none());
+        assert!(parsed[0].as_object().is_none());
+        assert!(parsed[1].elements().is_none());
+        assert!(parsed[1].as_array().is_none());
+        assert_eq!(parsed[1].len(), None);
+        assert!(!parsed[0]["a"].exists());
+
+        let first = parsed[0].as_stub().unwrap().expand().unwrap();
+        assert!(first["a"].is_stub());
+        assert_eq!(first["a"].as_stub().unwrap().expand().unwrap()[1].as_stub().unwrap().expand().unwrap()["b"].as_str(), Some("]}"));
+        assert_eq!(parsed, parse(source).unwrap());
+        assert_ne!(parsed, parse("[{}, [], {\"c\": {}}, 2]").unwrap());
+
+        let deep = format!("[{}{}]", "[".repeat(1000), "]".repeat(1000));
+        let parser = Parser::new(ParseOptions::new().materialize_depth(2).max_depth(1000));
+        assert_eq!(parser.parse(&deep).unwrap_err().get_kind(), ErrorKind::DepthLimitExceeded);
+        assert!(parser.parse(&deep[1..deep.len() - 1]).unwrap()[0][0].is_stub());
+    }
+
+    #[test]
+    fn check_stubs() {
+        let parser = Parser::new(ParseOptions::new().materialize_depth(1));
+        assert!(parser.parse("[[1, 2}]").is_err());
+        assert!(parser.parse("[[\"]\"]").is_err());
+        assert_eq!(parser.parse("[{\"a\" 1}]").unwrap_err().get_expected(), Some("':' after key"));
+        assert_eq!(parser.parse("[[1 2]]").unwrap_err().get_index(), 4);
+        assert!(parser.parse("[[1,, 2]]").is_err());
+        assert!(parser.parse("[[1, 2,]]").is_err());
+        assert!(parser.parse("[[01]]").is_ok() && Parser::new(ParseOptions::strict().materialize_depth(1)).parse("[[01]]").is_err());
+
+        let limited = |options: ParseOptions| Parser::new(options.materialize_depth(1)).parse("[{\"a\": [1, \"bc\"], \"a\": 2}]").map(|_| ()).map_err(|e| e.get_kind());
+        assert_eq!(limited(ParseOptions::new()), Ok(()));
+        assert_eq!(limited(ParseOptions::new().duplicate_keys(DuplicateKeys::Error)).unwrap_err(), ErrorKind::DuplicateKey);
+        assert_eq!(limited(ParseOptions::new().max_nodes(5)).unwrap_err(), ErrorKind::NodeLimitExceeded);
+        assert_eq!(limited(ParseOptions::new().max_keys(1)).unwrap_err(), ErrorKind::KeyLimitExceeded);
+        assert_eq!(limited(ParseOptions::new().max_string_len(1)).unwrap_err(), ErrorKind::StringLengthLimitExceeded);
+        assert_eq!(limited(ParseOptions::new().max_depth(2)).unwrap_err(), ErrorKind::DepthLimitExceeded);
+        #[cfg(feature = "extended")]
+        {
+            let parser = Parser::new(ParseOptions::json5().materialize_depth(1));
+            let parsed = parser.parse("[{a: ']', /* } */ b: [1,],}]").unwrap();
+            assert_eq!(parsed[0].as_stub().unwrap().expand().unwrap()["a"].as_str(), Some("]"));
+        }
+    }
+}
@@ -0,0 +1,107 @@
+const EXTENDED: bool = cfg!(feature = "extended");
+
+/// Grammar extensions accepted on top of standard JSON
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Syntax {
+    /// `// line` and `/* block */` comments wherever whitespace is allowed
+    pub(crate) comments: bool,
+    /// A single `,` before the closing `]` or `}`
+    pub(crate) trailing_commas: bool,
+    /// Strings and keys delimited by `'`
+    pub(crate) single_quotes: bool,
+    /// Object keys written as bare identifiers
+    pub(crate) unquoted_keys: bool,
+    /// Leading/trailing decimal point, `Infinity` and `NaN`
+    pub(crate) extended_numbers: bool,
+    /// `0x1F` style hexadecimal integers
+    pub(crate) hex_numbers: bool,
+    /// Python's `True`, `False` and `None` as aliases of `true`, `false` and `null`
+    pub(crate) python_literals: bool,
+    /// Vertical tab, form feed, NBSP, BOM and Unicode line/paragraph separators as whitespace
+    pub(crate) extended_whitespace: bool,
+}
+
+impl Syntax {
+    /// Standard JSON
+    pub(crate) const JSON: Syntax = Syntax {
+        comments: false,
+        trailing_commas: false,
+        single_quotes: false,
+        unquoted_keys: false,
+        extended_numbers: false,
+        hex_numbers: false,
+        python_literals: false,
+        extended_whitespace: false,
+    };
+
+    /// JSON with comments and trailing commas, as used by VS Code configuration files
+    #[cfg(feature = "extended")]
+    pub(crate) const JSONC: Syntax = Syntax {
+        comments: true,
+        trailing_commas: true,
+        ..Syntax::JSON
+    };
+
+    /// JSON5, see <https://spec.json5.org>
+    #[cfg(feature = "extended")]
+    pub(crate) const JSON5: Syntax = Syntax {
+        comments: true,
+        trailing_commas: true,
+        single_quotes: true,
+        unquoted_keys: true,
+        extended_numbers: true,
+        hex_numbers: true,
+        python_literals: false,
+        extended_whitespace: true,
+    };
+
+    /// JSON5 plus Python literals, to recover data from sloppy `str(dict)` dumps
+    #[cfg(feature = "extended")]
+    pub(crate) const LENIENT: Syntax = Syntax {
+        python_literals: true,
+        ..Syntax::JSON5
+    };
+
+    // Extension checks used by the parser, constant `false` without the `extended` feature so that
+    // the code handling extensions is compiled out
+
+    #[inline(always)]
+    pub(crate) fn comments(&self) -> bool {
+        EXTENDED && self.comments
+    }
+
+    #[inline(always)]
+    pub(crate) fn trailing_commas(&self) -> bool {
+        EXTENDED && self.trailing_commas
+    }
+
+    #[inline(always)]
+    pub(crate) fn single_quotes(&self) -> bool {
+        EXTENDED && self.single_quotes
+    }
+
+    #[inline(always)]
+    pub(crate) fn unquoted_keys(&self) -> bool {
+        EXTENDED && self.unquoted_keys
+    }
+
+    #[inline(always)]
+    pub(crate) fn extended_numbers(&self) -> bool {
+        EXTENDED && self.extended_numbers
+    }
+
+    #[inline(always)]
+    pub(crate) fn hex_numbers(&self) -> bool {
+        EXTENDED && self.hex_numbers
+    }
+
+    #[inline(always)]
+    pub(crate) fn python_literals(&self) -> bool {
+        EXTENDED && self.python_literals
+    }
+
+    #[inline(always)]
+    pub(crate) fn extended_whitespace(&self) -> bool {
+        EXTENDED && self.extended_whitespace
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use crate::document::JsonDocument;
+use crate::json_item::JsonItem;
+use crate::json_type::JsonType::{JsonArray, JsonMap, JsonString};
+use crate::options::{DuplicateKeys, ParseOptions};
+use crate::serialize::write_compact;
+use crate::text::{escape_into, unescape};
+
+/// Errors returned by [`invert_object`] and [`zip_arrays`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TableError {
+    /// The item to invert is not an object
+    NotAnObject,
+    /// The keys or values to zip are not an array
+    NotAnArray,
+    /// The arrays to zip have different lengths
+    LengthMismatch { keys: usize, values: usize },
+    /// The key at this index of the keys array is an object or array
+    InvalidKey(usize),
+    /// The key occurs more than once, with [`DuplicateKeys::Error`]
+    DuplicateKey(String),
+}
+
+impl Display for TableError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            TableError::NotAnObject => { write!(f, "not an object") }
+            TableError::NotAnArray => { write!(f, "not an array") }
+            TableError::LengthMismatch { keys, values } => { write!(f, "{} keys for {} values", keys, values) }
+            TableError::InvalidKey(index) => { write!(f, "key {} is not a scalar", index) }
+            TableError::DuplicateKey(key) => { write!(f, "duplicate key '{}'", key) }
+        }
+    }
+}
+
+impl Error for TableError {}
+
+// Text of a scalar used as a key: strings unescaped, other scalars as compact JSON, e.g. `1` or `true`
+fn key_text(item: &JsonItem) -> Option<String> {
+    match item.json_type {
+        JsonMap | JsonArray => { None }
+        JsonString => { Some(item.as_unescaped().unwrap_or_else(|| item.slice.as_str().into()).into_owned()) }
+        _ => {
+            let mut text = String::new();
+            write_compact(item, &mut text);
+            Some(text)
+        }
+    }
+}
+
+// Builds an object from keys and compact JSON values, in order, resolving repeated keys according to `policy`
+fn build(members: Vec<(String, String)>, policy: DuplicateKeys) -> Result<JsonDocument, TableError> {
+    let mut object: Vec<(String, Vec<String>)> = Vec::with_capacity(members.len());
+    let mut positions = HashMap::with_capacity(members.len());
+    for (key, value) in members {
+        match positions.get(&key) {
+            None => {
+                positions.insert(key.clone(), object.len());
+                object.push((key, vec![value]));
+            }
+            Some(&position) => {
+                let values = &mut object[position].1;
+                match policy {
+                    DuplicateKeys::Error => { return Err(TableError::DuplicateKey(key)); }
+                    DuplicateKeys::FirstWins => {}
+                    DuplicateKeys::LastWins => { values[0] = value; }
+                    DuplicateKeys::Collect => { values.push(value); }
+                }
+            }
+        }
+    }
+
+    let mut source = String::from("{");
+    for (index, (key, values)) in object.iter().enumerate() {
+        if index > 0 {
+            source.push(',');
+        }
+        escape_into(key, &mut source);
+        source.push(':');
+        if policy == DuplicateKeys::Collect {
+            source.push('[');
+            source.push_str(&values.join(","));
+            source.push(']');
+        } else {
+            source.push_str(&values[0]);
+        }
+    }
+    source.push('}');
+    Ok(JsonDocument::parse(source, &ParseOptions::strict().max_depth(usize::MAX)).expect("generated JSON is valid"))
+}
+
+/// Inverts a lookup table: returns an object whose keys are the scalar values of the object `item`, and whose values
+/// are the matching keys of `item`, as strings. Members whose values are objects or arrays are left out.
+/// String values become keys as they read, other scalars as their JSON text, e.g. `1` or `true`.
+///
+/// Keys shared by several values are handled according to `collisions`, as duplicate keys are by the parser:
+/// [`DuplicateKeys::Collect`] gathers all matching keys of `item` in arrays, for every key of the result.
+///
+/// # Arguments
+/// * `item` - Object to invert
+/// * `collisions` - How values occurring more than once are handled
+///
+/// # Example
+///
+/// ```rust
+/// use jsonic::options::DuplicateKeys;
+/// use jsonic::table::invert_object;
+///
+/// let codes = jsonic::parse("{\"fr\": \"EUR\", \"de\": \"EUR\", \"us\": \"USD\"}").unwrap();
+///
+/// assert_eq!(invert_object(&codes, DuplicateKeys::FirstWins).unwrap().source(), "{\"EUR\":\"fr\",\"USD\":\"us\"}");
+/// assert_eq!(invert_object(&codes, DuplicateKeys::Collect).unwrap().source(), "{\"EUR\":[\"fr\",\"de\"],\"USD\":[\"us\"]}");
+/// assert!(invert_object(&codes, DuplicateKeys::Error).is_err());
+/// ```
+pub fn invert_object(item: &JsonItem, collisions: DuplicateKeys) -> Result<JsonDocument, TableError> {
+    let entries = item.entries().ok_or(TableError::NotAnObject)?;
+    let members = entries.filter_map(|(key, value)| {
+        let mut text = String::new();
+        escape_into(&unescape(key.as_str()).unwrap_or_else(|| key.as_str().into()), &mut text);
+        key_text(value).map(|value| (value, text))
+    });
+    build(members.collect(), collisions)
+}
+
+/// Builds an object from an array of keys and an array of values of the same length, pairing them by index,
+/// like `Object.fromEntries` in JavaScript. Keys are read as by [`invert_object`], and later keys replace earlier ones.
+///
+/// # Arguments
+/// * `keys` - Array of scalar keys
+/// * `values` - Array of values
+///
+/// # Example
+///
+/// ```rust
+/// use jsonic::table::zip_arrays;
+///
+/// let parsed = jsonic::parse("{\"columns\": [\"id\", \"name\"], \"row\": [7, {\"first\": \"ann\"}]}").unwrap();
+///
+/// let record = zip_arrays(&parsed["columns"], &parsed["row"]).unwrap();
+/// assert_eq!(record["name"]["first"].as_str(), Some("ann"));
+/// assert_eq!(record.source(), "{\"id\":7,\"name\":{\"first\":\"ann\"}}");
+/// ```
+pub fn zip_arrays(keys: &JsonItem, values: &JsonItem) -> Result<JsonDocument, TableError> {
+    let (keys, values) = match (keys.elements(), values.elements()) {
+        (Some(keys), Some(values)) => { (keys.collect::<Vec<_>>(), values.collect::<Vec<_>>()) }
+        _ => { return Err(TableError::NotAnArray); }
+    };
+    if keys.len() != values.len() {
+        return Err(TableError::LengthMismatch { keys: keys.len(), values: values.len() });
+    }
+    let mut members = Vec::with_capacity(keys.len());
+    for (index, (key, value)) in keys.into_iter().zip(values).enumerate() {
+        let mut text = String::new();
+        write_compact(value, &mut text);
+        members.push((key_text(key).ok_or(TableError::InvalidKey(index))?, text));
+    }
+    build(members, DuplicateKeys::LastWins)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::options::DuplicateKeys;
+    use crate::parse;
+    use crate::table::{invert_object, zip_arrays, TableError};
+
+    #[test]
+    fn invert_tables() {
+        let parsed = parse("{\"a\": 1, \"b\\\"\": true, \"c\": \"x\\u0041\", \"d\": [1], \"e\": 1, \"f\": null}").unwrap();
+        assert_eq!(invert_object(&parsed, DuplicateKeys::LastWins).unwrap().source(), "{\"1\":\"e\",\"true\":\"b\\\"\",\"xA\":\"c\",\"null\":\"f\"}");
+        assert_eq!(invert_object(&parsed, DuplicateKeys::Collect).unwrap()["1"][1].as_str(), Some("e"));
+        assert_eq!(invert_object(&parsed, DuplicateKeys::Error).unwrap_err(), TableError::DuplicateKey("1".to_owned()));
+        assert_eq!(invert_object(&parse("{}").unwrap(), DuplicateKeys::Error).unwrap().source(), "{}");
+        assert_eq!(invert_object(&parsed["d"], DuplicateKeys::Error).unwrap_err(), TableError::NotAnObject);
+    }
+
+    #[test]
+    fn zip_tables() {
+        let parsed = parse("[[\"a\", 2, \"a\"], [1, [2], 3], [{}], []]").unwrap();
+        assert_eq!(zip_arrays(&parsed[0], &parsed[1]).unwrap().source(), "{\"a\":3,\"2\":[2]}");
+        assert_eq!(zip_arrays(&parsed[3], &parsed[3]).unwrap().source(), "{}");
+        assert_eq!(zip_arrays(&parsed[0], &parsed[3]).unwrap_err().to_string(), "3 keys for 0 values");
+        assert_eq!(zip_arrays(&parsed[2], &parsed[2]).unwrap_err(), TableError::InvalidKey(0));
+        assert_eq!(zip_arrays(&parsed[0], &parsed[2][0]).unwrap_err(), TableError::NotAnArray);
+    }
+}
@@ -0,0 +1,118 @@
+use std::borrow::Cow;
+
+use crate::json_item::{JsonItem, EMPTY_ITEM};
+use crate::json_type::JsonType::{JsonMap, JsonString};
+use crate::text::unescape;
+
+/// Encoding of enum-like values, named as in serde, see [`JsonItem::variant`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tagging<'t> {
+    /// The variant name is a member of the object holding the variant data, e.g. `{"type": "circle", "radius": 1}`
+    /// with tag `type`. The payload is the object itself, tag included.
+    Internal { tag: &'t str },
+    /// The variant name and data are two members of an object, e.g. `{"t": "circle", "c": {"radius": 1}}`
+    /// with tag `t` and content `c`. The content is absent for variants without data.
+    Adjacent { tag: &'t str, content: &'t str },
+    /// The variant name is the only key of an object holding the data, e.g. `{"circle": {"radius": 1}}`,
+    /// or a string for variants without data, e.g. `"empty"`
+    External,
+}
+
+/// Variant of an enum-like value, see [`JsonItem::variant`]
+#[derive(Debug, Clone)]
+pub struct Variant<'a> {
+    name: Cow<'a, str>,
+    payload: &'a JsonItem,
+}
+
+impl<'a> Variant<'a> {
+    /// Name of the variant, with escape sequences decoded
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Data of the variant, which does not exist for variants without data
+    pub fn get_payload(&self) -> &'a JsonItem {
+        self.payload
+    }
+}
+
+// Value of a string member of an object, unescaped
+fn string_member<'a>(item: &'a JsonItem, key: &str) -> Option<Cow<'a, str>> {
+    item.get(key).and_then(JsonItem::as_unescaped)
+}
+
+impl JsonItem {
+    /// Identifies the variant of an enum-like value encoded according to `tagging`, returning its name and data,
+    /// or `None` if the item does not follow the encoding, e.g. when the tag is missing or is not a string.
+    /// Only the tag is read, so that dispatch on large values is cheap.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jsonic::tagged::Tagging;
+    ///
+    /// let shapes = jsonic::parse("[{\"kind\": \"circle\", \"r\": 2}, {\"kind\": \"square\", \"side\": 3}]").unwrap();
+    /// for shape in shapes.elements().unwrap() {
+    ///     let variant = shape.variant(Tagging::Internal { tag: "kind" }).unwrap();
+    ///     let area = match variant.get_name() {
+    ///         "circle" => { std::f64::consts::PI * variant.get_payload()["r"].as_f64().unwrap().powi(2) }
+    ///         "square" => { variant.get_payload()["side"].as_f64().unwrap().powi(2) }
+    ///         _ => { unreachable!() }
+    ///     };
+    ///     assert!(area >= 9.0);
+    /// }
+    ///
+    /// let event = jsonic::parse("{\"Resize\": {\"width\": 80}}").unwrap();
+    /// let variant = event.variant(Tagging::External).unwrap();
+    /// assert_eq!((variant.get_name(), variant.get_payload()["width"].as_i128()), ("Resize", Some(80)));
+    /// ```
+    pub fn variant(&self, tagging: Tagging) -> Option<Variant<'_>> {
+        match tagging {
+            Tagging::Internal { tag } => {
+                Some(Variant { name: string_member(self, tag)?, payload: self })
+            }
+            Tagging::Adjacent { tag, content } => {
+                Some(Variant { name: string_member(self, tag)?, payload: &self[content] })
+            }
+            Tagging::External => {
+                match self.json_type {
+                    JsonString => { Some(Variant { name: self.as_unescaped()?, payload: &EMPTY_ITEM }) }
+                    JsonMap if self.len() == Some(1) => {
+                        let (key, payload) = self.entries()?.next()?;
+                        let name = unescape(key.as_str()).unwrap_or(Cow::Borrowed(key.as_str()));
+                        Some(Variant { name, payload })
+                    }
+                    _ => { None }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse;
+    use crate::tagged::Tagging;
+
+    #[test]
+    fn identify_variants() {
+        let parsed = parse("[{\"t\": \"a\\u0062\", \"c\": [1]}, {\"t\": \"unit\"}, {\"t\": 1, \"c\": 2}, {\"x\\ty\": null}, \"unit\", {\"a\": 1, \"b\": 2}, 3]").unwrap();
+        let adjacent = Tagging::Adjacent { tag: "t", content: "c" };
+        let variant = parsed[0].variant(adjacent).unwrap();
+        assert_eq!((variant.get_name(), variant.get_payload()[0].as_i128()), ("ab", Some(1)));
+        assert!(!parsed[1].variant(adjacent).unwrap().get_payload().exists());
+        assert!(parsed[2].variant(adjacent).is_none() && parsed[4].variant(adjacent).is_none());
+
+        let internal = parsed[1].variant(Tagging::Internal { tag: "t" }).unwrap();
+        assert_eq!((internal.get_name(), internal.get_payload()), ("unit", &parsed[1]));
+        assert!(parsed[3].variant(Tagging::Internal { tag: "t" }).is_none());
+
+        let external = parsed[3].variant(Tagging::External).unwrap();
+        assert_eq!(external.get_name(), "x\ty");
+        assert!(external.get_payload().is_null());
+        let unit = parsed[4].variant(Tagging::External).unwrap();
+        assert!(unit.get_name() == "unit" && !unit.get_payload().exists());
+        assert!(parsed[5].variant(Tagging::External).is_none() && parsed[6].variant(Tagging::External).is_none());
+    }
+}
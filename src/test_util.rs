@@ -0,0 +1,230 @@
+use std::env;
+use std::fs;
+use std::mem::take;
+use std::path::Path;
+
+use crate::generics::Container::{Array, Attached, MapBTree, MapVec, Stub};
+use crate::json_item::JsonItem;
+use crate::json_type::JsonType::{Empty, JsonArray, JsonFalse, JsonMap, JsonNull, JsonNumber, JsonString, JsonTrue};
+use crate::number::write_number;
+use crate::parse;
+use crate::rng::Rng;
+use crate::text::{escape_into, unescape};
+
+/// Environment variable that makes [`assert_matches_file`] write golden files instead of comparing against them
+pub const UPDATE_GOLDEN_VAR: &str = "JSONIC_UPDATE_GOLDEN";
+
+impl JsonItem {
+    /// Shuffles the elements of every array in the tree, deterministically for a given `seed`.
+    /// Useful to check that code does not depend on element order where it should not.
+    ///
+    /// ```rust
+    /// let mut parsed = jsonic::parse("[1, 2, 3, 4, 5, 6, 7, 8]").unwrap();
+    /// parsed.shuffle_arrays(42);
+    /// assert_eq!(parsed.elements().unwrap().count(), 8);
+    /// ```
+    pub fn shuffle_arrays(&mut self, seed: u64) {
+        self.reorder(&mut Rng::new(seed), true, false);
+    }
+
+    /// Shuffles the entries of every object in the tree, deterministically for a given `seed`, changing the order
+    /// [`entries`](JsonItem::entries) yields them in. Key lookups keep working, although large objects then use linear lookups.
+    /// Useful to check that code does not depend on key order.
+    pub fn randomize_key_order(&mut self, seed: u64) {
+        self.reorder(&mut Rng::new(seed), false, true);
+    }
+
+    fn reorder(&mut self, rng: &mut Rng, arrays: bool, maps: bool) {
+        if let Some(MapBTree(map)) = &mut self.container {
+            if maps {
+                self.container = Some(MapVec(take(map).into_iter().collect()));
+            }
+        }
+        match &mut self.container {
+            Some(Array(array)) => {
+                if arrays {
+                    rng.shuffle(array);
+                }
+                array.iter_mut().for_each(|item| item.reorder(rng, arrays, maps));
+            }
+            Some(MapVec(map)) => {
+                if maps {
+                    rng.shuffle(map);
+                }
+                map.iter_mut().for_each(|(_, item)| item.reorder(rng, arrays, maps));
+            }
+            Some(MapBTree(map)) => {
+                map.values_mut().for_each(|item| item.reorder(rng, arrays, maps));
+            }
+            Some(Attached(_) | Stub(_)) | None => {}
+        }
+    }
+}
+
+/// Asserts that `item` matches the JSON document stored in the golden file at `path`.
+/// Both are compared in canonical form: pretty-printed with sorted keys, decoded string escapes and normalized numbers,
+/// so golden files may be hand-edited freely.
+///
+/// When the [`UPDATE_GOLDEN_VAR`] environment variable is set (e.g. `JSONIC_UPDATE_GOLDEN=1 cargo test`),
+/// the golden file is (re)written with the canonical form of `item` instead.
+///
+/// # Panics
+/// If the golden file is missing or unreadable, is not valid JSON, or does not match `item`.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// let parsed = jsonic::parse("{\"b\": [1, 2.50], \"a\": null}").unwrap();
+/// jsonic::test_util::assert_matches_file(&parsed, "tests/golden/report.json");
+/// ```
+#[track_caller]
+pub fn assert_matches_file<P: AsRef<Path>>(item: &JsonItem, path: P) {
+    assert_matches_file_impl(item, path.as_ref(), env::var_os(UPDATE_GOLDEN_VAR).is_some());
+}
+
+#[track_caller]
+fn assert_matches_file_impl(item: &JsonItem, path: &Path, update: bool) {
+    let actual = canonical(item);
+    if update {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).unwrap_or_else(|error| panic!("cannot create {}: {}", parent.display(), error));
+        }
+        fs::write(path, &actual).unwrap_or_else(|error| panic!("cannot write golden file {}: {}", path.display(), error));
+        return;
+    }
+    let golden = fs::read_to_string(path)
+        .unwrap_or_else(|error| panic!("cannot read golden file {} ({}), set {}=1 to create it", path.display(), error, UPDATE_GOLDEN_VAR));
+    let expected = parse(&golden)
+        .map(|parsed| canonical(&parsed))
+        .unwrap_or_else(|error| panic!("invalid golden file {}: {}", path.display(), error));
+    assert!(actual == expected, "JSON does not match golden file {}, set {}=1 to update it\n--- expected\n{}--- actual\n{}",
+            path.display(), UPDATE_GOLDEN_VAR, expected, actual);
+}
+
+// Pretty-printed form with sorted keys, decoded escapes and normalized numbers, ending with a newline
+fn canonical(item: &JsonItem) -> String {
+    let mut output = String::new();
+    write_canonical(item, 0, &mut output);
+    output.push('\n');
+    output
+}
+
+fn write_canonical(item: &JsonItem, depth: usize, output: &mut String) {
+    let indent = |output: &mut String, depth: usize| output.extend(std::iter::repeat_n("  ", depth));
+    match item.json_type {
+        JsonNull | Empty => { output.push_str("null") }
+        JsonTrue => { output.push_str("true") }
+        JsonFalse => { output.push_str("false") }
+        JsonNumber => {
+            match item.as_number() {
+                Some(number) => { write_number(number, output) }
+                None => { output.push_str(item.slice.as_str()) }
+            }
+        }
+        JsonString => { escape_into(&item.as_unescaped().unwrap_or_else(|| item.slice.as_str().into()), output) }
+        JsonArray => {
+            let elements = item.elements().unwrap().collect::<Vec<_>>();
+            if elements.is_empty() {
+                output.push_str("[]");
+                return;
+            }
+            output.push_str("[\n");
+            for (index, element) in elements.iter().enumerate() {
+                if index > 0 {
+                    output.push_str(",\n");
+                }
+                indent(output, depth + 1);
+                write_canonical(element, depth + 1, output);
+            }
+            output.push('\n');
+            indent(output, depth);
+            output.push(']');
+        }
+        JsonMap => {
+            let mut entries = item.entries().unwrap()
+                .map(|(key, value)| (unescape(key.as_str()).unwrap_or_else(|| key.as_str().into()), value))
+                .collect::<Vec<_>>();
+            if entries.is_empty() {
+                output.push_str("{}");
+                return;
+            }
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            output.push_str("{\n");
+            for (index, (key, value)) in entries.iter().enumerate() {
+                if index > 0 {
+                    output.push_str(",\n");
+                }
+                indent(output, depth + 1);
+                escape_into(key, output);
+                output.push_str(": ");
+                write_canonical(value, depth + 1, output);
+            }
+            output.push('\n');
+            indent(output, depth);
+            output.push('}');
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env::temp_dir;
+    use std::fs;
+    use std::process::id;
+
+    use crate::parse;
+    use crate::test_util::{assert_matches_file_impl, canonical};
+
+    fn collect_ints(json: &str, seed: u64, keys: bool) -> Vec<i128> {
+        let mut parsed = parse(json).unwrap();
+        if keys {
+            parsed.randomize_key_order(seed);
+            parsed.entries().unwrap().map(|(_, v)| v.as_i128().unwrap()).collect()
+        } else {
+            parsed.shuffle_arrays(seed);
+            parsed.elements().unwrap().map(|v| v.as_i128().unwrap()).collect()
+        }
+    }
+
+    #[test]
+    fn shuffle_deterministically() {
+        let json = format!("[{}]", (0..50).map(|i| i.to_string()).collect::<Vec<_>>().join(","));
+        let shuffled = collect_ints(&json, 7, false);
+        assert_eq!(shuffled, collect_ints(&json, 7, false));
+        assert_ne!(shuffled, collect_ints(&json, 8, false));
+        let mut sorted = shuffled.clone();
+        sorted.sort();
+        assert_eq!(sorted, (0..50).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn randomize_keys() {
+        let json = format!("{{{}}}", (0..100).map(|i| format!("\"k{}\": {}", i, i)).collect::<Vec<_>>().join(","));
+        let values = collect_ints(&json, 3, true);
+        assert_eq!(values, collect_ints(&json, 3, true));
+        assert_eq!(values.len(), 100);
+
+        let mut parsed = parse(&json).unwrap();
+        parsed.randomize_key_order(3);
+        assert_eq!(parsed["k42"].as_i128(), Some(42));
+    }
+
+    #[test]
+    fn canonical_form() {
+        let parsed = parse("{\"b\": [1, 2.50, 1e2, \"\\u00e9\\n\"], \"a\": {}, \"c\": [], \"\\u0061a\": null}").unwrap();
+        assert_eq!(canonical(&parsed), "{\n  \"a\": {},\n  \"aa\": null,\n  \"b\": [\n    1,\n    2.5,\n    100,\n    \"\u{e9}\\n\"\n  ],\n  \"c\": []\n}\n");
+    }
+
+    #[test]
+    fn golden_files() {
+        let path = temp_dir().join(format!("jsonic-golden-{}", id())).join("golden.json");
+        let parsed = parse("{\"b\": [1, 2], \"a\": true}").unwrap();
+        assert_matches_file_impl(&parsed, &path, true);
+        assert_matches_file_impl(&parse("{\"a\":true,\"b\":[1,2.0e0]}").unwrap(), &path, false);
+
+        fs::write(&path, "{\"a\": true, \"b\": [2, 1]}").unwrap();
+        let result = std::panic::catch_unwind(|| assert_matches_file_impl(&parsed, &path, false));
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,138 @@
+use std::borrow::Cow;
+#[cfg(any(feature = "test-util", feature = "gen", feature = "serialize"))]
+use std::fmt::Write;
+
+#[cfg(feature = "grapheme")]
+use unicode_segmentation::UnicodeSegmentation;
+
+// Byte length of the character or escape sequence starting at `index` in raw string content
+#[inline(always)]
+fn unit_len(raw: &str, index: usize) -> usize {
+    let bytes = raw.as_bytes();
+    if bytes[index] != b'\\' {
+        return raw[index..].chars().next().map_or(1, char::len_utf8);
+    }
+    let len = match bytes.get(index + 1) {
+        None => { 1 }
+        Some(b'u') => {
+            // Keep surrogate pairs together
+            let high = matches!(bytes.get(index + 2..index + 4), Some([b'd' | b'D', b'8'..=b'9' | b'a'..=b'b' | b'A'..=b'B']));
+            let low = matches!(bytes.get(index + 6..index + 10), Some([b'\\', b'u', b'd' | b'D', b'c'..=b'f' | b'C'..=b'F']));
+            if high && low { 12 } else { 6 }
+        }
+        Some(_) => { 1 + raw[index + 1..].chars().next().map_or(1, char::len_utf8) }
+    };
+    usize::min(len, bytes.len() - index)
+}
+
+/// Longest prefix of raw string content holding at most `max_chars` characters,
+/// never splitting a UTF-8 sequence or an escape sequence (nor a grapheme cluster with the `grapheme` feature)
+pub(crate) fn truncate(raw: &str, max_chars: usize) -> &str {
+    let mut index = 0;
+    let mut count = 0;
+    while index < raw.len() && count < max_chars {
+        index += unit_len(raw, index);
+        count += 1;
+    }
+
+    #[cfg(feature = "grapheme")]
+    if index < raw.len() {
+        let floor = raw.grapheme_indices(true).map(|(i, _)| i).take_while(|&i| i <= index).last().unwrap_or(0);
+        if floor < index {
+            // Grapheme boundaries may fall inside escape sequences, step back to the last full one
+            let mut boundary = 0;
+            while boundary + unit_len(raw, boundary) <= floor {
+                boundary += unit_len(raw, boundary);
+            }
+            index = boundary;
+        }
+    }
+
+    &raw[..index]
+}
+
+#[inline(always)]
+fn hex_value(digits: &[u8]) -> Option<u32> {
+    let mut value = 0;
+    for &digit in digits {
+        value = value * 16 + (digit as char).to_digit(16)?;
+    }
+    Some(value)
+}
+
+/// Decodes escape sequences of raw string content: JSON escapes, plus the JSON5 ones
+/// (`\'`, `\v`, `\0`, `\xHH`, line continuations and escaped arbitrary characters).
+/// Unpaired surrogates are replaced with U+FFFD, malformed `\u`/`\x` escapes make decoding fail.
+pub(crate) fn unescape(raw: &str) -> Option<Cow<'_, str>> {
+    let Some(first) = raw.find('\\') else {
+        return Some(Cow::Borrowed(raw));
+    };
+    let bytes = raw.as_bytes();
+    let mut output = String::with_capacity(raw.len());
+    output.push_str(&raw[..first]);
+    let mut index = first;
+    while index < bytes.len() {
+        if bytes[index] != b'\\' {
+            let next = raw[index..].find('\\').map_or(raw.len(), |i| index + i);
+            output.push_str(&raw[index..next]);
+            index = next;
+            continue;
+        }
+        let escaped = raw[index + 1..].chars().next()?;
+        index += 1 + escaped.len_utf8();
+        match escaped {
+            'b' => { output.push('\u{8}') }
+            'f' => { output.push('\u{c}') }
+            'n' => { output.push('\n') }
+            'r' => { output.push('\r') }
+            't' => { output.push('\t') }
+            'v' => { output.push('\u{b}') }
+            '0' => { output.push('\0') }
+            'x' => {
+                let value = hex_value(bytes.get(index..index + 2)?)?;
+                output.push(char::from_u32(value)?);
+                index += 2;
+            }
+            'u' => {
+                let mut value = hex_value(bytes.get(index..index + 4)?)?;
+                index += 4;
+                if (0xD800..0xDC00).contains(&value) && bytes.get(index..index + 2) == Some(b"\\u") {
+                    if let Some(low @ 0xDC00..=0xDFFF) = bytes.get(index + 2..index + 6).and_then(hex_value) {
+                        value = 0x10000 + ((value - 0xD800) << 10) + (low - 0xDC00);
+                        index += 6;
+                    }
+                }
+                output.push(char::from_u32(value).unwrap_or(char::REPLACEMENT_CHARACTER));
+            }
+            // Line continuations
+            '\n' | '\u{2028}' | '\u{2029}' => {}
+            '\r' => {
+                if bytes.get(index) == Some(&b'\n') {
+                    index += 1;
+                }
+            }
+            other => { output.push(other) }
+        }
+    }
+    Some(Cow::Owned(output))
+}
+
+/// Appends `text` to `output` as a JSON string literal, quotes included
+#[cfg(any(feature = "test-util", feature = "gen", feature = "serialize"))]
+pub(crate) fn escape_into(text: &str, output: &mut String) {
+    output.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => { output.push_str("\\\"") }
+            '\\' => { output.push_str("\\\\") }
+            '\n' => { output.push_str("\\n") }
+            '\r' => { output.push_str("\\r") }
+            '\t' => { output.push_str("\\t") }
+            '\u{8}' => { output.push_str("\\b") }
+            '\u{c}' => { output.push_str("\\f") }
+            c if (c as u32) < 0x20 => { write!(output, "\\u{:04x}", c as u32).unwrap() }
+            c => { output.push(c) }
+        }
+    }
+    output.push('"');
+}
@@ -0,0 +1,193 @@
+use std::collections::BTreeSet;
+use std::ops::Deref;
+use std::sync::Mutex;
+
+use crate::document::JsonDocument;
+use crate::json_item::JsonItem;
+use crate::key::Key;
+
+// Appends a JSON Pointer reference token to `path`
+pub(crate) fn push_token(path: &mut String, token: &str) {
+    path.push('/');
+    for c in token.chars() {
+        match c {
+            '~' => { path.push_str("~0"); }
+            '/' => { path.push_str("~1"); }
+            c => { path.push(c); }
+        }
+    }
+}
+
+/// View over an item of a [`JsonDocument`] which records its path in the document when the item is read,
+/// see [`PathRecorder::tracked`]. Navigating does not count as reading: only dereferencing the view does,
+/// e.g. calling a [`JsonItem`] method on it.
+#[derive(Clone)]
+pub struct Tracked<'a> {
+    item: &'a JsonItem,
+    path: String,
+    touched: &'a Mutex<BTreeSet<String>>,
+}
+
+impl<'a> Tracked<'a> {
+    fn child(&self, item: &'a JsonItem, token: &str) -> Tracked<'a> {
+        let mut path = self.path.clone();
+        push_token(&mut path, token);
+        Tracked { item, path, touched: self.touched }
+    }
+
+    /// Member of an object, matched as with indexing by `&str`
+    pub fn get(&self, key: &str) -> Tracked<'a> {
+        self.child(&self.item[key], key)
+    }
+
+    /// Element of an array
+    pub fn at(&self, index: usize) -> Tracked<'a> {
+        self.child(&self.item[index], &index.to_string())
+    }
+
+    /// Descendant designated by a JSON Pointer (RFC 6901) relative to this item
+    pub fn pointer(&self, pointer: &str) -> Tracked<'a> {
+        Tracked { item: self.item.pointer(pointer), path: format!("{}{}", self.path, pointer), touched: self.touched }
+    }
+
+    /// Iterator over the elements of an array, or `None` if the item is not an array
+    pub fn elements(&self) -> Option<impl Iterator<Item = Tracked<'a>> + '_> {
+        Some(self.item.elements()?.enumerate().map(|(index, element)| self.child(element, &index.to_string())))
+    }
+
+    /// Iterator over the members of an object, or `None` if the item is not an object
+    pub fn entries(&self) -> Option<impl Iterator<Item = (&'a Key, Tracked<'a>)> + '_> {
+        Some(self.item.entries()?.map(|(key, value)| (key, self.child(value, key.as_str()))))
+    }
+
+    /// JSON Pointer of the item in the document
+    pub fn get_path(&self) -> &str {
+        &self.path
+    }
+
+    /// Reads the item, recording its path
+    pub fn item(&self) -> &'a JsonItem {
+        let mut touched = self.touched.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if !touched.contains(&self.path) {
+            touched.insert(self.path.clone());
+        }
+        self.item
+    }
+}
+
+impl Deref for Tracked<'_> {
+    type Target = JsonItem;
+
+    fn deref(&self) -> &Self::Target {
+        self.item()
+    }
+}
+
+/// Recorder of the paths of a [`JsonDocument`] read through its tracked views, e.g. to find the fields
+/// of a payload that consumers never use. Documents record nothing unless a recorder is created for them.
+///
+/// # Example
+///
+/// ```rust
+/// use jsonic::document::ParseJsonExt;
+/// use jsonic::tracking::PathRecorder;
+///
+/// let document = "{\"id\": 1, \"user\": {\"name\": \"ann\", \"age\": 30}, \"tags\": [\"a\"]}".parse_json().unwrap();
+/// let recorder = PathRecorder::new(&document);
+/// let root = recorder.tracked();
+///
+/// assert_eq!(root.get("user").get("name").as_str(), Some("ann"));
+/// assert_eq!(root.get("tags").elements().unwrap().count(), 1);
+///
+/// assert_eq!(recorder.touched_paths(), ["/user/name"]);
+/// assert_eq!(recorder.untouched_paths(), ["/id", "/tags/0", "/user/age"]);
+/// ```
+pub struct PathRecorder<'a> {
+    document: &'a JsonDocument,
+    touched: Mutex<BTreeSet<String>>,
+}
+
+impl<'a> PathRecorder<'a> {
+    /// Creates a recorder for `document`, with no path read yet
+    pub fn new(document: &'a JsonDocument) -> Self {
+        PathRecorder { document, touched: Mutex::default() }
+    }
+
+    /// Returns a view over the root item of the document recording which paths are read through it.
+    /// Reads made through the document itself are not recorded.
+    /// Views can be used from several threads, and all record to this recorder.
+    pub fn tracked(&self) -> Tracked<'_> {
+        Tracked { item: self.document.root(), path: String::new(), touched: &self.touched }
+    }
+
+    /// JSON Pointers of the items read through [`tracked`](PathRecorder::tracked) views, sorted,
+    /// including the ones of non-existent items
+    pub fn touched_paths(&self) -> Vec<String> {
+        self.touched.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).iter().cloned().collect()
+    }
+
+    /// JSON Pointers of the scalars and empty containers never read through [`tracked`](PathRecorder::tracked) views, sorted.
+    /// Reading a container counts as reading everything it contains.
+    pub fn untouched_paths(&self) -> Vec<String> {
+        let touched = self.touched.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        // The path is read, or one of its ancestors is
+        let covered = |path: &str| touched.contains(path) || path.match_indices('/').any(|(index, _)| touched.contains(&path[..index]));
+        let mut untouched = Vec::new();
+        let mut pending = vec![(String::new(), self.document.root())];
+        while let Some((path, item)) = pending.pop() {
+            let mut leaf = true;
+            if let Some(elements) = item.elements() {
+                for (index, element) in elements.enumerate() {
+                    leaf = false;
+                    pending.push((format!("{}/{}", path, index), element));
+                }
+            } else if let Some(entries) = item.entries() {
+                for (key, value) in entries {
+                    leaf = false;
+                    let mut child = path.clone();
+                    push_token(&mut child, key.as_str());
+                    pending.push((child, value));
+                }
+            }
+            if leaf && !covered(&path) {
+                untouched.push(path);
+            }
+        }
+        untouched.sort();
+        untouched
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use crate::document::ParseJsonExt;
+    use crate::tracking::PathRecorder;
+
+    #[test]
+    fn record_reads() {
+        let document = "{\"a/b\": {\"c\": [1, 2]}, \"d~\": null, \"e\": {}}".parse_json().unwrap();
+        let recorder = PathRecorder::new(&document);
+        let root = recorder.tracked();
+        let nested = root.get("a/b").pointer("/c/1");
+        assert_eq!(nested.get_path(), "/a~1b/c/1");
+        assert!(recorder.touched_paths().is_empty());
+        assert_eq!(nested.as_i128(), Some(2));
+        assert!(!root.get("missing").at(3).exists());
+        assert_eq!(recorder.touched_paths(), ["/a~1b/c/1", "/missing/3"]);
+        assert_eq!(recorder.untouched_paths(), ["/a~1b/c/0", "/d~0", "/e"]);
+
+        for (key, value) in root.entries().unwrap() {
+            if key.as_str() == "e" {
+                assert!(value.item().entries().is_some());
+            }
+        }
+        thread::scope(|scope| {
+            scope.spawn(|| root.get("a/b").get("c").exists());
+        });
+        assert_eq!(recorder.untouched_paths(), ["/d~0"]);
+        assert_eq!(recorder.touched_paths().len(), 4);
+        assert!(PathRecorder::new(&document).touched_paths().is_empty());
+    }
+}
@@ -0,0 +1,177 @@
+use std::time::Duration;
+
+use crate::json_item::JsonItem;
+use crate::json_type::JsonType::{JsonNumber, JsonString};
+
+// Duration units, in seconds
+const DURATION_UNITS: [(&str, f64); 8] = [
+    ("ns", 1e-9), ("us", 1e-6), ("µs", 1e-6), ("ms", 1e-3), ("s", 1.0), ("m", 60.0), ("h", 3600.0), ("d", 86400.0),
+];
+
+// Size units, in bytes
+const BYTE_SIZE_UNITS: [(&str, f64); 11] = [
+    ("B", 1.0), ("KB", 1e3), ("MB", 1e6), ("GB", 1e9), ("TB", 1e12), ("PB", 1e15),
+    ("KiB", 1024.0), ("MiB", 1048576.0), ("GiB", 1073741824.0), ("TiB", 1099511627776.0), ("PiB", 1125899906842624.0),
+];
+
+// Splits `text` after its leading unsigned decimal number, returning `None` if it does not start with one
+fn split_number(text: &str) -> Option<(f64, &str)> {
+    let split = text.find(|c: char| !matches!(c, '0'..='9' | '.')).unwrap_or(text.len());
+    let (number, rest) = text.split_at(split);
+    if number.starts_with('.') || number.ends_with('.') {
+        return None;
+    }
+    Some((number.parse::<f64>().ok()?, rest))
+}
+
+// Size of `unit` in `units`
+fn scale(units: &[(&str, f64)], unit: &str) -> Option<f64> {
+    units.iter().find(|(name, _)| *name == unit).map(|(_, scale)| *scale)
+}
+
+/// Reads a duration written as a number of seconds (e.g. `"90"` or `"0.5"`), or as a sequence of numbers
+/// each followed by a unit among `ns`, `us` (or `µs`), `ms`, `s`, `m`, `h` and `d` (e.g. `"1500ms"` or `"1h30m"`).
+/// Returns `None` if `text` is not such a duration.
+///
+/// # Arguments
+///
+/// * `text` - Text of the duration, without surrounding whitespace
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+/// use jsonic::units::parse_duration;
+///
+/// assert_eq!(parse_duration("1h30m"), Some(Duration::from_secs(5400)));
+/// assert_eq!(parse_duration("1.5"), Some(Duration::from_millis(1500)));
+/// assert_eq!(parse_duration("1h 30m"), None);
+/// ```
+pub fn parse_duration(text: &str) -> Option<Duration> {
+    let (value, mut rest) = split_number(text)?;
+    if rest.is_empty() {
+        return Duration::try_from_secs_f64(value).ok();
+    }
+    let mut total = Duration::ZERO;
+    let mut value = value;
+    loop {
+        let split = rest.find(|c: char| c.is_ascii_digit() || c == '.').unwrap_or(rest.len());
+        let (unit, next) = rest.split_at(split);
+        total = total.checked_add(Duration::try_from_secs_f64(value * scale(&DURATION_UNITS, unit)?).ok()?)?;
+        if next.is_empty() {
+            return Some(total);
+        }
+        (value, rest) = split_number(next)?;
+    }
+}
+
+/// Reads a size in bytes written as a number of bytes (e.g. `"512"`), or as a number followed by an optional space
+/// and a unit among `B`, decimal `KB` to `PB` and binary `KiB` to `PiB` (e.g. `"10MiB"` or `"1.5 GB"`).
+/// Units are case-sensitive. Returns `None` if `text` is not such a size, or if it is not a whole number of bytes
+/// fitting in a `u64`.
+///
+/// # Arguments
+///
+/// * `text` - Text of the size, without surrounding whitespace
+///
+/// # Example
+///
+/// ```rust
+/// use jsonic::units::parse_byte_size;
+///
+/// assert_eq!(parse_byte_size("10MiB"), Some(10 * 1024 * 1024));
+/// assert_eq!(parse_byte_size("1.5 KB"), Some(1500));
+/// assert_eq!(parse_byte_size("0.5B"), None);
+/// ```
+pub fn parse_byte_size(text: &str) -> Option<u64> {
+    let (value, unit) = split_number(text)?;
+    let bytes = if unit.is_empty() { value } else { value * scale(&BYTE_SIZE_UNITS, unit.strip_prefix(' ').unwrap_or(unit))? };
+    if bytes.fract() != 0.0 || bytes >= u64::MAX as f64 {
+        return None;
+    }
+    Some(bytes as u64)
+}
+
+impl JsonItem {
+    /// Returns the duration held by the item: a non-negative number of seconds, or a string read with [`parse_duration`].
+    /// Returns `None` for other items.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    ///
+    /// let parsed = jsonic::parse("{\"timeout\": \"1500ms\", \"ttl\": \"1h30m\", \"retry\": 2.5}").unwrap();
+    /// assert_eq!(parsed["timeout"].as_duration(), Some(Duration::from_millis(1500)));
+    /// assert_eq!(parsed["ttl"].as_duration(), Some(Duration::from_secs(5400)));
+    /// assert_eq!(parsed["retry"].as_duration(), Some(Duration::from_millis(2500)));
+    /// ```
+    pub fn as_duration(&self) -> Option<Duration> {
+        match self.json_type {
+            JsonNumber => { Duration::try_from_secs_f64(self.as_f64()?).ok() }
+            JsonString => { parse_duration(&self.as_unescaped()?) }
+            _ => { None }
+        }
+    }
+
+    /// Returns the size in bytes held by the item: a non-negative integer, or a string read with [`parse_byte_size`].
+    /// Returns `None` for other items.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let parsed = jsonic::parse("{\"cache\": \"10MiB\", \"chunk\": 4096, \"name\": \"10 apples\"}").unwrap();
+    /// assert_eq!(parsed["cache"].as_byte_size(), Some(10485760));
+    /// assert_eq!(parsed["chunk"].as_byte_size(), Some(4096));
+    /// assert_eq!(parsed["name"].as_byte_size(), None);
+    /// ```
+    pub fn as_byte_size(&self) -> Option<u64> {
+        match self.json_type {
+            JsonNumber => { self.as_i128().and_then(|bytes| u64::try_from(bytes).ok()) }
+            JsonString => { parse_byte_size(&self.as_unescaped()?) }
+            _ => { None }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{parse, parse_strict};
+    use crate::units::{parse_byte_size, parse_duration};
+
+    #[test]
+    fn read_durations() {
+        assert_eq!(parse_duration("1500ms"), Some(Duration::from_millis(1500)));
+        assert_eq!(parse_duration("2d1h1m1s"), Some(Duration::from_secs(2 * 86400 + 3661)));
+        assert_eq!(parse_duration("1.5h"), Some(Duration::from_secs(5400)));
+        assert_eq!(parse_duration("10us5ns"), Some(Duration::from_nanos(10005)));
+        assert_eq!(parse_duration("3µs"), Some(Duration::from_micros(3)));
+        assert_eq!(parse_duration("0"), Some(Duration::ZERO));
+        for invalid in ["", "h", "1x", "1h30", "-1s", "1.s", ".5s", "1 h", "1h ", "1e3", "1hh"] {
+            assert!(parse_duration(invalid).is_none(), "{}", invalid);
+        }
+
+        let parsed = parse("[60, \"60\", -1, \"1\\u0068\", true, []]").unwrap();
+        let durations = parsed.elements().unwrap().map(|item| item.as_duration()).collect::<Vec<_>>();
+        let minute = Some(Duration::from_secs(60));
+        assert_eq!(durations, [minute, minute, None, Some(Duration::from_secs(3600)), None, None]);
+        assert_eq!(parse_strict("1e-3").unwrap().as_duration(), Some(Duration::from_millis(1)));
+    }
+
+    #[test]
+    fn read_byte_sizes() {
+        assert_eq!(parse_byte_size("512"), Some(512));
+        assert_eq!(parse_byte_size("2 KiB"), Some(2048));
+        assert_eq!(parse_byte_size("3GB"), Some(3_000_000_000));
+        assert_eq!(parse_byte_size("1PiB"), Some(1 << 50));
+        for invalid in ["", "MB", "5mb", "5  MB", "-5B", "1.2345KB", "20000000PB", "5 ", "5MB "] {
+            assert!(parse_byte_size(invalid).is_none(), "{}", invalid);
+        }
+
+        let parsed = parse("[1024, \"1024\", 1.5, -1, \"1\\u004biB\", null]").unwrap();
+        let sizes = parsed.elements().unwrap().map(|item| item.as_byte_size()).collect::<Vec<_>>();
+        assert_eq!(sizes, [Some(1024), Some(1024), None, None, Some(1024), None]);
+    }
+}
@@ -0,0 +1,368 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::io;
+use std::io::Write;
+
+use crate::json_item::JsonItem;
+use crate::number::{write_number, JsonNumberValue};
+use crate::serialize::{write_json, write_newline, write_text, Output, SerializeOptions, WRITE_CHUNK_SIZE};
+
+/// Errors returned by [`JsonWriter`]
+#[derive(Debug)]
+pub enum WriteError {
+    /// The underlying writer failed
+    Io(io::Error),
+    /// The call does not fit the document written so far, e.g. a value where an object key is expected
+    Unexpected { found: &'static str, expected: &'static str },
+    /// The number is not finite, which JSON cannot represent
+    InvalidNumber,
+}
+
+impl Display for WriteError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            WriteError::Io(error) => { write!(f, "I/O error: {}", error) }
+            WriteError::Unexpected { found, expected } => { write!(f, "unexpected {}, expecting {}", found, expected) }
+            WriteError::InvalidNumber => { write!(f, "non-finite number") }
+        }
+    }
+}
+
+impl Error for WriteError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            WriteError::Io(error) => { Some(error) }
+            _ => { None }
+        }
+    }
+}
+
+impl From<io::Error> for WriteError {
+    fn from(error: io::Error) -> Self {
+        WriteError::Io(error)
+    }
+}
+
+// Container being written, with the number of members written so far
+enum Frame {
+    Object { entries: usize, has_key: bool },
+    Array { elements: usize },
+}
+
+/// Streaming JSON serializer, writing standard JSON to an [`io::Write`] as values are emitted,
+/// without building a tree, so that producers can emit outputs of any size.
+/// Output is compact, or written according to [`SerializeOptions`] with [`with_options`](JsonWriter::with_options).
+///
+/// Calls are checked against the structure of the document: a value or container must be preceded by a key in objects,
+/// keys are only accepted in objects, [`end`](JsonWriter::end) closes the innermost open container, and a single root value
+/// is accepted. Misplaced calls fail with [`WriteError::Unexpected`] and write nothing.
+/// [`finish`](JsonWriter::finish) checks that the document is complete and flushes it.
+/// Wrap the destination in a [`ProgressWriter`](crate::document::ProgressWriter) to report progress as output is written.
+///
+/// # Example
+///
+/// ```rust
+/// use jsonic::writer::JsonWriter;
+///
+/// let mut writer = JsonWriter::new(Vec::new());
+/// writer.begin_object().unwrap();
+/// writer.key("ids").unwrap();
+/// writer.begin_array().unwrap();
+/// for id in 1..=3 {
+///     writer.value_number(id).unwrap();
+/// }
+/// writer.end().unwrap();
+/// writer.key("name").unwrap();
+/// writer.value_str("a \"b\"").unwrap();
+/// assert!(writer.value_null().is_err());
+/// writer.end().unwrap();
+///
+/// assert_eq!(writer.finish().unwrap(), b"{\"ids\":[1,2,3],\"name\":\"a \\\"b\\\"\"}");
+/// ```
+pub struct JsonWriter<W: Write> {
+    writer: W,
+    options: SerializeOptions,
+    buffer: String,
+    stack: Vec<Frame>,
+    complete: bool,
+}
+
+impl<W: Write> JsonWriter<W> {
+    /// Creates a JSON writer writing to `writer`. Output is buffered by chunks, so wrapping it in a `BufWriter` is not needed.
+    pub fn new(writer: W) -> Self {
+        Self::with_options(writer, SerializeOptions::new())
+    }
+
+    /// Creates a JSON writer writing to `writer` according to `options`, e.g. escaping strings so that the output can be
+    /// embedded in HTML. Object keys are written in the order they are emitted, so only items written by
+    /// [`value_item`](JsonWriter::value_item) have their keys sorted.
+    ///
+    /// ```rust
+    /// use jsonic::{Escaping, Indent, SerializeOptions};
+    /// use jsonic::writer::JsonWriter;
+    ///
+    /// let mut writer = JsonWriter::with_options(Vec::new(), SerializeOptions::new().escaping(Escaping::HtmlSafe).indent(Indent::Spaces(2)));
+    /// writer.begin_object().unwrap();
+    /// writer.key("bio").unwrap();
+    /// writer.value_str("</script>").unwrap();
+    /// writer.end().unwrap();
+    ///
+    /// assert_eq!(writer.finish().unwrap(), b"{\n  \"bio\": \"\\u003c/script\\u003e\"\n}");
+    /// ```
+    pub fn with_options(writer: W, options: SerializeOptions) -> Self {
+        JsonWriter { writer, options, buffer: String::with_capacity(2 * WRITE_CHUNK_SIZE), stack: Vec::new(), complete: false }
+    }
+
+    // What may be written next, to report misplaced calls
+    fn expected(&self) -> &'static str {
+        match self.stack.last() {
+            None if self.complete => { "the end of output" }
+            None => { "a value" }
+            Some(Frame::Object { has_key: false, .. }) => { "a key or the end of the object" }
+            Some(Frame::Object { has_key: true, .. }) => { "a value" }
+            Some(Frame::Array { .. }) => { "a value or the end of the array" }
+        }
+    }
+
+    // Checks that a value may be written, and writes the separator preceding it
+    fn begin_value(&mut self, found: &'static str) -> Result<(), WriteError> {
+        match self.stack.last_mut() {
+            None if !self.complete => {}
+            Some(Frame::Object { has_key: has_key @ true, .. }) => { *has_key = false; }
+            Some(Frame::Array { elements }) => {
+                if *elements > 0 {
+                    self.buffer.push(',');
+                }
+                *elements += 1;
+                write_newline(self.options, self.stack.len(), &mut self.buffer);
+            }
+            _ => { return Err(WriteError::Unexpected { found, expected: self.expected() }); }
+        }
+        Ok(())
+    }
+
+    // Marks the root value as complete if the value just written is the root, and hands over a chunk of output if one is ready
+    fn end_value(&mut self) -> Result<(), WriteError> {
+        if self.stack.is_empty() {
+            self.complete = true;
+        }
+        if self.buffer.len() >= WRITE_CHUNK_SIZE {
+            self.writer.write_all(self.buffer.as_bytes())?;
+            self.buffer.clear();
+        }
+        Ok(())
+    }
+
+    /// Opens an object, closed by [`end`](JsonWriter::end)
+    pub fn begin_object(&mut self) -> Result<(), WriteError> {
+        self.begin_value("object")?;
+        self.buffer.push('{');
+        self.stack.push(Frame::Object { entries: 0, has_key: false });
+        Ok(())
+    }
+
+    /// Opens an array, closed by [`end`](JsonWriter::end)
+    pub fn begin_array(&mut self) -> Result<(), WriteError> {
+        self.begin_value("array")?;
+        self.buffer.push('[');
+        self.stack.push(Frame::Array { elements: 0 });
+        Ok(())
+    }
+
+    /// Closes the innermost open object or array
+    pub fn end(&mut self) -> Result<(), WriteError> {
+        let (closing, members) = match self.stack.last() {
+            Some(Frame::Object { entries, has_key: false }) => { ('}', *entries) }
+            Some(Frame::Array { elements }) => { (']', *elements) }
+            _ => { return Err(WriteError::Unexpected { found: "end", expected: self.expected() }); }
+        };
+        self.stack.pop();
+        if members > 0 {
+            write_newline(self.options, self.stack.len(), &mut self.buffer);
+        }
+        self.buffer.push(closing);
+        self.end_value()
+    }
+
+    /// Writes the key of the next object member, escaped as needed
+    pub fn key(&mut self, key: &str) -> Result<(), WriteError> {
+        match self.stack.last_mut() {
+            Some(Frame::Object { entries, has_key: has_key @ false }) => {
+                if *entries > 0 {
+                    self.buffer.push(',');
+                }
+                *entries += 1;
+                *has_key = true;
+            }
+            _ => { return Err(WriteError::Unexpected { found: "key", expected: self.expected() }); }
+        }
+        write_newline(self.options, self.stack.len(), &mut self.buffer);
+        write_text(key, self.options, &mut self.buffer);
+        self.buffer.push_str(if self.options.indent.is_some() { ": " } else { ":" });
+        Ok(())
+    }
+
+    /// Writes a string value, escaped as needed
+    pub fn value_str(&mut self, value: &str) -> Result<(), WriteError> {
+        self.begin_value("string")?;
+        write_text(value, self.options, &mut self.buffer);
+        self.end_value()
+    }
+
+    /// Writes a number value, integers as is and floats in their shortest form. Fails with [`WriteError::InvalidNumber`]
+    /// for non-finite floats.
+    pub fn value_number<N: Into<JsonNumberValue>>(&mut self, value: N) -> Result<(), WriteError> {
+        let value = value.into();
+        if matches!(value, JsonNumberValue::Float(float) if !float.is_finite()) {
+            return Err(WriteError::InvalidNumber);
+        }
+        self.begin_value("number")?;
+        write_number(value, &mut self.buffer);
+        self.end_value()
+    }
+
+    /// Writes a boolean value
+    pub fn value_bool(&mut self, value: bool) -> Result<(), WriteError> {
+        self.begin_value("boolean")?;
+        self.buffer.push_str(if value { "true" } else { "false" });
+        self.end_value()
+    }
+
+    /// Writes a `null` value
+    pub fn value_null(&mut self) -> Result<(), WriteError> {
+        self.begin_value("null")?;
+        self.buffer.push_str("null");
+        self.end_value()
+    }
+
+    /// Writes a parsed item as a value, see [`to_json_using`](JsonItem::to_json_using)
+    pub fn value_item(&mut self, item: &JsonItem) -> Result<(), WriteError> {
+        self.begin_value("value")?;
+        write_json(item, self.options, self.stack.len(), &mut Output { text: &mut self.buffer, writer: None, result: Ok(()) });
+        self.end_value()
+    }
+
+    /// Checks that the document is complete, i.e. that a root value was written and all containers are closed,
+    /// then writes pending output, flushes the writer and returns it
+    pub fn finish(mut self) -> Result<W, WriteError> {
+        if !self.complete {
+            return Err(WriteError::Unexpected { found: "end of output", expected: self.expected() });
+        }
+        self.writer.write_all(self.buffer.as_bytes())?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::document::ProgressWriter;
+    use crate::number::JsonNumberValue;
+    use crate::serialize::{Escaping, Indent, SerializeOptions};
+    use crate::writer::{JsonWriter, WriteError};
+
+    fn unexpected(result: Result<(), WriteError>) -> String {
+        match result {
+            Err(error @ WriteError::Unexpected { .. }) => { error.to_string() }
+            _ => { panic!("call accepted") }
+        }
+    }
+
+    #[test]
+    fn write_events() {
+        let mut writer = JsonWriter::new(Vec::new());
+        writer.begin_array().unwrap();
+        writer.value_number(-1.5).unwrap();
+        writer.value_number(JsonNumberValue::Integer(i128::MAX)).unwrap();
+        writer.value_bool(true).unwrap();
+        writer.begin_object().unwrap();
+        writer.end().unwrap();
+        writer.begin_object().unwrap();
+        writer.key("\n").unwrap();
+        writer.begin_array().unwrap();
+        writer.end().unwrap();
+        writer.key("b").unwrap();
+        writer.value_item(&crate::parse("{\"c\": [1, 2.50]}").unwrap()).unwrap();
+        writer.end().unwrap();
+        writer.value_null().unwrap();
+        writer.end().unwrap();
+        let output = writer.finish().unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), format!("[-1.5,{},true,{{}},{{\"\\n\":[],\"b\":{{\"c\":[1,2.50]}}}},null]", i128::MAX));
+
+        let mut writer = JsonWriter::new(Vec::new());
+        writer.value_str("x").unwrap();
+        assert_eq!(writer.finish().unwrap(), b"\"x\"");
+    }
+
+    #[test]
+    fn write_with_options() {
+        let options = SerializeOptions::new().indent(Indent::Tabs(1)).sort_keys(true).escaping(Escaping::HtmlSafe);
+        let mut writer = JsonWriter::with_options(Vec::new(), options);
+        writer.begin_array().unwrap();
+        writer.begin_object().unwrap();
+        writer.key("<b>").unwrap();
+        writer.value_item(&crate::parse("{\"z\": [1], \"y\": \"&\"}").unwrap()).unwrap();
+        writer.key("c").unwrap();
+        writer.begin_array().unwrap();
+        writer.end().unwrap();
+        writer.end().unwrap();
+        writer.value_null().unwrap();
+        writer.end().unwrap();
+        let output = String::from_utf8(writer.finish().unwrap()).unwrap();
+        let expected = crate::parse("[{\"<b>\": {\"z\": [1], \"y\": \"&\"}, \"c\": []}, null]").unwrap().to_json_using(&options);
+        assert_eq!(output, expected);
+        assert!(output.starts_with("[\n\t{\n\t\t\"\\u003cb\\u003e\": {\n\t\t\t\"y\": \"\\u0026\""));
+    }
+
+    #[test]
+    fn report_progress() {
+        let mut reports = Vec::new();
+        let mut writer = JsonWriter::new(ProgressWriter::new(Vec::new(), Some(200_001), |progress| reports.push(progress)));
+        writer.begin_array().unwrap();
+        for _ in 0..100_000 {
+            writer.value_number(1).unwrap();
+        }
+        writer.end().unwrap();
+        let output = writer.finish().unwrap().into_inner();
+        assert_eq!(output.len(), 200_001);
+        assert!(reports.len() > 1);
+        assert_eq!(reports.last().unwrap().get_processed(), 200_001);
+        assert_eq!(reports.last().unwrap().get_total(), Some(200_001));
+    }
+
+    #[test]
+    fn check_events() {
+        let mut writer = JsonWriter::new(Vec::new());
+        assert_eq!(unexpected(writer.end()), "unexpected end, expecting a value");
+        assert_eq!(unexpected(writer.key("a")), "unexpected key, expecting a value");
+        writer.begin_object().unwrap();
+        assert_eq!(unexpected(writer.value_bool(true)), "unexpected boolean, expecting a key or the end of the object");
+        writer.key("a").unwrap();
+        assert_eq!(unexpected(writer.key("b")), "unexpected key, expecting a value");
+        assert_eq!(unexpected(writer.end()), "unexpected end, expecting a value");
+        assert!(matches!(writer.value_number(f64::NAN), Err(WriteError::InvalidNumber)));
+        writer.begin_array().unwrap();
+        assert_eq!(unexpected(writer.key("c")), "unexpected key, expecting a value or the end of the array");
+        writer.end().unwrap();
+        writer.end().unwrap();
+        assert_eq!(unexpected(writer.begin_array()), "unexpected array, expecting the end of output");
+        assert_eq!(writer.finish().unwrap(), b"{\"a\":[]}");
+
+        let mut writer = JsonWriter::new(Vec::new());
+        writer.begin_array().unwrap();
+        assert_eq!(writer.finish().unwrap_err().to_string(), "unexpected end of output, expecting a value or the end of the array");
+        assert!(JsonWriter::new(Vec::new()).finish().is_err());
+    }
+
+    #[test]
+    fn write_large_output() {
+        let mut writer = JsonWriter::new(Vec::new());
+        writer.begin_array().unwrap();
+        for index in 0..100_000 {
+            writer.value_number(index).unwrap();
+        }
+        writer.end().unwrap();
+        let output = writer.finish().unwrap();
+        assert_eq!(crate::parse(std::str::from_utf8(&output).unwrap()).unwrap()[99_999].as_i128(), Some(99_999));
+    }
+}